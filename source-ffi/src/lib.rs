@@ -0,0 +1,328 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A C interface to the API objects of `radicle-source`, for radicle clients
+//! that are not written in Rust.
+//!
+//! A repository is opened with [`rad_repository_open`], and the
+//! [`Tree`](radicle_source::Tree), [`Blob`](radicle_source::Blob) and
+//! [`Diff`](radicle_source::Diff) objects are returned as NUL-terminated JSON
+//! strings, wrapped in [`api::Versioned`]. Every string returned must be
+//! released with [`rad_string_free`], and every repository with
+//! [`rad_repository_free`].
+//!
+//! Revisions are given as revspecs, e.g. `master`, `v0.1.0` or a commit id,
+//! with `NULL` meaning `HEAD`. On failure, functions return `NULL` and the
+//! reason can be retrieved with [`rad_last_error`].
+//!
+//! A header can be generated with `cbindgen --config cbindgen.toml`.
+
+use std::{
+    cell::RefCell,
+    error,
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    str::FromStr as _,
+};
+
+use serde::Serialize;
+
+use radicle_surf::vcs::git::{Browser, Repository, Rev};
+
+use radicle_source::api;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+/// A repository opened with [`rad_repository_open`].
+pub struct RadRepository(Repository);
+
+/// Open the git repository at `path`.
+///
+/// Returns `NULL` on failure.
+///
+/// # Safety
+///
+/// `path` must be a valid, NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rad_repository_open(path: *const c_char) -> *mut RadRepository {
+    let open = || -> Result<RadRepository> {
+        let path = to_str(path)?.ok_or("the path must not be NULL")?;
+        Ok(RadRepository(Repository::new(path)?))
+    };
+
+    match catch_panic(open) {
+        Ok(repository) => Box::into_raw(Box::new(repository)),
+        Err(err) => {
+            set_last_error(&*err);
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Release a repository opened with [`rad_repository_open`].
+///
+/// # Safety
+///
+/// `repository` must have been returned by [`rad_repository_open`] and not
+/// released already. It may be `NULL`.
+#[no_mangle]
+pub unsafe extern "C" fn rad_repository_free(repository: *mut RadRepository) {
+    if !repository.is_null() {
+        drop(Box::from_raw(repository));
+    }
+}
+
+/// List the directory `prefix` at `rev` as a JSON
+/// [`Tree`](radicle_source::Tree), where a `NULL` `prefix` is the root of the
+/// repository.
+///
+/// # Safety
+///
+/// `repository` must be a live repository from [`rad_repository_open`], and
+/// `rev` and `prefix` must be `NULL` or valid, NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn rad_tree(
+    repository: *const RadRepository,
+    rev: *const c_char,
+    prefix: *const c_char,
+) -> *mut c_char {
+    json(|| {
+        let mut browser = browser(repository, rev)?;
+        let prefix = to_str(prefix)?.map(str::to_string);
+        Ok(api::tree::<String>(&mut browser, None, prefix, 0, None)?)
+    })
+}
+
+/// Get the file at `path` at `rev` as a JSON [`Blob`](radicle_source::Blob).
+///
+/// # Safety
+///
+/// `repository` must be a live repository from [`rad_repository_open`],
+/// `rev` must be `NULL` or a valid, NUL-terminated string, and `path` must be
+/// a valid, NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rad_blob(
+    repository: *const RadRepository,
+    rev: *const c_char,
+    path: *const c_char,
+) -> *mut c_char {
+    json(|| {
+        let mut browser = browser(repository, rev)?;
+        let path = to_str(path)?.ok_or("the path must not be NULL")?;
+        Ok(api::blob::<String>(&mut browser, None, path)?)
+    })
+}
+
+/// Compute the JSON [`Diff`](radicle_source::Diff) from `from` to `to`, where a
+/// `NULL` `from` gives the changes introduced by `to` itself.
+///
+/// # Safety
+///
+/// `repository` must be a live repository from [`rad_repository_open`], and
+/// `from` and `to` must be `NULL` or valid, NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn rad_diff(
+    repository: *const RadRepository,
+    from: *const c_char,
+    to: *const c_char,
+) -> *mut c_char {
+    json(|| {
+        let head = browser(repository, to)?;
+        let to = head.get().first().id;
+        let from = match to_str(from)? {
+            Some(_) => Some(browser(repository, from)?.get().first().id),
+            None => None,
+        };
+        Ok(api::diff(&head, from, to)?)
+    })
+}
+
+/// Get the reason the last function called on this thread failed, or `NULL`
+/// if none has.
+///
+/// The string must be released with [`rad_string_free`].
+#[no_mangle]
+pub extern "C" fn rad_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .clone()
+            .map_or(ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Release a string returned by one of the functions of this crate.
+///
+/// # Safety
+///
+/// `string` must have been returned by one of the functions of this crate
+/// and not released already. It may be `NULL`.
+#[no_mangle]
+pub unsafe extern "C" fn rad_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Serialize the result of `f`, one of the [`api::Versioned`] objects, as a
+/// JSON string, recording the error and returning `NULL` if it fails.
+fn json<T, F>(f: F) -> *mut c_char
+where
+    T: Serialize,
+    F: FnOnce() -> Result<T>,
+{
+    let result = catch_panic(f).and_then(|object| {
+        let json = serde_json::to_string(&object)?;
+        Ok(CString::new(json)?)
+    });
+
+    match result {
+        Ok(json) => json.into_raw(),
+        Err(err) => {
+            set_last_error(&*err);
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Run `f`, turning a panic into an error so that it does not unwind across
+/// the C boundary, which is undefined behaviour.
+fn catch_panic<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        Err(format!("panicked: {}", message).into())
+    })
+}
+
+/// Create a [`Browser`] for `repository` at the revspec `rev`, or at `HEAD`
+/// if it is `NULL`.
+unsafe fn browser<'a>(repository: *const RadRepository, rev: *const c_char) -> Result<Browser<'a>> {
+    let repository = repository
+        .as_ref()
+        .ok_or("the repository must not be NULL")?;
+    let rev = Rev::from_str(to_str(rev)?.unwrap_or("HEAD"))?;
+    Ok(Browser::new(&repository.0, rev)?)
+}
+
+/// Borrow `string` as a `str`, where `NULL` is `None`.
+unsafe fn to_str<'a>(string: *const c_char) -> Result<Option<&'a str>> {
+    if string.is_null() {
+        Ok(None)
+    } else {
+        Ok(Some(CStr::from_ptr(string).to_str()?))
+    }
+}
+
+fn set_last_error(err: &dyn error::Error) {
+    let message = CString::new(err.to_string().replace('\0', " ")).ok();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{CStr, CString};
+
+    use radicle_surf::vcs::git::fixture::Fixture;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// Take ownership of the JSON string returned by one of the functions.
+    unsafe fn take(string: *mut c_char) -> Value {
+        assert!(!string.is_null(), "{}", last_error().unwrap_or_default());
+        let json = serde_json::from_str(CStr::from_ptr(string).to_str().unwrap()).unwrap();
+        rad_string_free(string);
+        json
+    }
+
+    fn last_error() -> Option<String> {
+        let error = rad_last_error();
+        if error.is_null() {
+            return None;
+        }
+        let message = unsafe { CStr::from_ptr(error) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { rad_string_free(error) };
+        Some(message)
+    }
+
+    #[test]
+    fn smoke() -> std::result::Result<(), Box<dyn error::Error>> {
+        let fixture = Fixture::new()?;
+        fixture
+            .commit("master", "Initial commit")
+            .file("README.md", "Hello\n")
+            .write()?;
+        fixture
+            .commit("master", "Say more")
+            .file("README.md", "Hello, world\n")
+            .write()?;
+
+        let path = CString::new(fixture.path().to_str().unwrap())?;
+        let master = CString::new("master")?;
+        let readme = CString::new("README.md")?;
+
+        unsafe {
+            let repository = rad_repository_open(path.as_ptr());
+            assert!(!repository.is_null(), "{:?}", last_error());
+
+            let tree = take(rad_tree(repository, master.as_ptr(), ptr::null()));
+            assert_eq!(tree["version"], api::VERSION);
+            assert_eq!(tree["data"]["entries"][0]["path"], "README.md");
+
+            let blob = take(rad_blob(repository, master.as_ptr(), readme.as_ptr()));
+            assert_eq!(blob["version"], api::VERSION);
+            assert_eq!(blob["data"]["path"], "README.md");
+            assert_eq!(blob["data"]["content"], "Hello, world\n");
+
+            let diff = take(rad_diff(repository, ptr::null(), master.as_ptr()));
+            assert_eq!(diff["version"], api::VERSION);
+            assert!(!diff["data"]["from"].is_null());
+
+            let missing = CString::new("missing")?;
+            assert!(rad_blob(repository, master.as_ptr(), missing.as_ptr()).is_null());
+            assert!(last_error().is_some());
+
+            rad_repository_free(repository);
+        }
+
+        assert!(unsafe { rad_repository_open(readme.as_ptr()) }.is_null());
+        assert!(last_error().is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn panics_are_errors() {
+        let result = json::<(), _>(|| panic!("oh no"));
+        assert!(result.is_null());
+        assert_eq!(last_error().as_deref(), Some("panicked: oh no"));
+    }
+}
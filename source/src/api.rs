@@ -0,0 +1,318 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Versioning of the objects returned from the API.
+//!
+//! The objects of this crate, such as [`crate::Blob`], [`crate::Tree`],
+//! [`crate::Commit`], and [`crate::Diff`], are the wire format shared by the
+//! radicle front-ends. Wrapping a response in [`Versioned`] tags it with the
+//! [`VERSION`] of that format, so that clients can tell when it changes.
+//!
+//! The functions of this module build the API objects in the same way as
+//! their counterparts at the root of the crate, e.g. [`blob`] and
+//! [`crate::blob`], and return them [`Versioned`].
+//!
+//! The [`Etag`] of an object identifies its contents, so that web layers can
+//! answer conditional requests for it.
+
+use serde::Serialize;
+
+use radicle_surf::{
+    git::{self, RefScope},
+    vcs::git::Browser,
+};
+
+use crate::{
+    branch::{self, Branch},
+    commit::{self, Commit, Commits},
+    diff::{self, Diff},
+    error::Error,
+    object::{self, Blob, BlobContent, Info, Tree},
+    revision::Revision,
+    tag::{self, Tag},
+};
+
+/// The version of the wire format of the API objects. It is bumped whenever
+/// their serialization changes in a way that is not backwards compatible.
+pub const VERSION: u32 = 1;
+
+/// An API object, tagged with the [`VERSION`] of its wire format.
+///
+/// It serializes as `{ "version": 1, "data": ... }`.
+#[derive(Clone, Debug, Serialize)]
+pub struct Versioned<T> {
+    /// The [`VERSION`] the `data` is serialized in.
+    pub version: u32,
+    /// The API object.
+    pub data: T,
+}
+
+impl<T> From<T> for Versioned<T> {
+    fn from(data: T) -> Self {
+        Self {
+            version: VERSION,
+            data,
+        }
+    }
+}
+
+/// Get the versioned [`Blob`] at `path`, see [`object::blob`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the blob cannot be found.
+pub fn blob<P>(
+    browser: &mut Browser,
+    maybe_revision: Option<Revision<P>>,
+    path: &str,
+) -> Result<Versioned<Blob>, Error>
+where
+    P: ToString,
+{
+    object::blob(browser, maybe_revision, path).map(Versioned::from)
+}
+
+/// Get the versioned [`Tree`] at `maybe_prefix`, see [`object::tree`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the tree cannot be found.
+pub fn tree<P>(
+    browser: &mut Browser<'_>,
+    maybe_revision: Option<Revision<P>>,
+    maybe_prefix: Option<String>,
+    offset: usize,
+    limit: Option<usize>,
+) -> Result<Versioned<Tree>, Error>
+where
+    P: ToString,
+{
+    object::tree(browser, maybe_revision, maybe_prefix, offset, limit).map(Versioned::from)
+}
+
+/// Get the versioned [`Commit`] `sha1`, see [`commit::commit`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the commit cannot be found.
+pub fn commit(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Versioned<Commit>, Error> {
+    commit::commit(browser, sha1).map(Versioned::from)
+}
+
+/// Get the versioned [`Commits`] of `maybe_revision`, see
+/// [`commit::commits`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the revision cannot be found.
+pub fn commits<P>(
+    browser: &mut Browser<'_>,
+    maybe_revision: Option<Revision<P>>,
+) -> Result<Versioned<Commits>, Error>
+where
+    P: ToString,
+{
+    commit::commits(browser, maybe_revision).map(Versioned::from)
+}
+
+/// Get the versioned [`Branch`]es, see [`branch::branches`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the branches cannot be listed.
+pub fn branches(browser: &Browser<'_>, filter: RefScope) -> Result<Versioned<Vec<Branch>>, Error> {
+    branch::branches(browser, filter).map(Versioned::from)
+}
+
+/// Get the versioned [`Tag`]s, see [`tag::tags`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the tags cannot be listed.
+pub fn tags(browser: &Browser<'_>) -> Result<Versioned<Vec<Tag>>, Error> {
+    tag::tags(browser).map(Versioned::from)
+}
+
+/// Get the versioned [`Diff`] from `from` to `to`, see [`diff::diff`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the diff cannot be computed.
+pub fn diff(
+    browser: &Browser<'_>,
+    from: Option<git2::Oid>,
+    to: git2::Oid,
+) -> Result<Versioned<Diff>, Error> {
+    diff::diff(browser, from, to).map(Versioned::from)
+}
+
+/// An API object with an [entity tag](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/ETag),
+/// which changes whenever the object does, or the [`VERSION`] of its wire
+/// format does.
+pub trait Etag {
+    /// The entity tag of the object, quoted as it appears in an `ETag`
+    /// header.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error`] if the digest of the object fails.
+    fn etag(&self) -> Result<String, Error>;
+}
+
+impl<T: Etag> Etag for Versioned<T> {
+    fn etag(&self) -> Result<String, Error> {
+        self.data.etag()
+    }
+}
+
+impl Etag for Blob {
+    fn etag(&self) -> Result<String, Error> {
+        let (kind, content) = match &self.content {
+            BlobContent::Plain(text) => ("plain", text.as_bytes()),
+            BlobContent::Html(html) => ("html", html.as_bytes()),
+            BlobContent::Binary(bytes) => ("binary", bytes.as_slice()),
+        };
+        digest(&[
+            self.path.as_bytes(),
+            last_commit(&self.info).as_bytes(),
+            kind.as_bytes(),
+            content,
+        ])
+    }
+}
+
+impl Etag for Tree {
+    fn etag(&self) -> Result<String, Error> {
+        let mut parts = vec![
+            self.path.clone(),
+            self.total.to_string(),
+            last_commit(&self.info),
+        ];
+        for entry in &self.entries {
+            parts.push(entry.path.clone());
+            parts.push(last_commit(&entry.info));
+        }
+        digest(&parts.iter().map(String::as_bytes).collect::<Vec<_>>())
+    }
+}
+
+impl Etag for Commit {
+    fn etag(&self) -> Result<String, Error> {
+        let branches = self
+            .branches
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        digest(&[self.header.sha1.to_string().as_bytes(), branches.as_bytes()])
+    }
+}
+
+impl Etag for Diff {
+    fn etag(&self) -> Result<String, Error> {
+        let from = self.from.map(String::from).unwrap_or_default();
+        digest(&[from.as_bytes(), String::from(self.to).as_bytes()])
+    }
+}
+
+/// The id of the last commit of an object, or an empty string.
+fn last_commit(info: &Info) -> String {
+    info.last_commit
+        .as_ref()
+        .map(|header| header.sha1.to_string())
+        .unwrap_or_default()
+}
+
+/// Digest the `parts` of an object, along with the [`VERSION`], into a quoted
+/// entity tag. The parts are separated by a NUL byte, so that moving bytes
+/// from one to the next changes the digest.
+fn digest(parts: &[&[u8]]) -> Result<String, Error> {
+    let mut bytes = VERSION.to_string().into_bytes();
+    for part in parts {
+        bytes.push(0);
+        bytes.extend_from_slice(part);
+    }
+    let oid =
+        git2::Oid::hash_object(git2::ObjectType::Blob, &bytes).map_err(git::error::Error::from)?;
+    Ok(format!("\"{}\"", oid))
+}
+
+#[cfg(test)]
+mod tests {
+    use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser};
+
+    use super::*;
+
+    #[test]
+    fn versioned_objects() -> Result<(), Error> {
+        let fixture = Fixture::new()?;
+        fixture
+            .commit("master", "Initial commit")
+            .file("README.md", "Hello\n")
+            .write()?;
+        let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+        let blob = blob::<String>(&mut browser, None, "README.md")?;
+        assert_eq!(blob.version, VERSION);
+        assert!(matches!(&blob.data.content, BlobContent::Plain(content) if content == "Hello\n"));
+
+        let json = serde_json::to_value(&blob).expect("the blob serializes");
+        assert_eq!(json["version"], VERSION);
+        assert_eq!(json["data"]["path"], "README.md");
+
+        // The envelope has the etag of the object it wraps.
+        assert_eq!(blob.etag()?, blob.data.etag()?);
+
+        let tree = tree::<String>(&mut browser, None, None, 0, None)?;
+        assert_eq!(tree.version, VERSION);
+        assert_eq!(tree.data.entries.len(), 1);
+
+        let branches = branches(&browser, RefScope::Local)?;
+        assert_eq!(branches.version, VERSION);
+        assert_eq!(branches.data.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn etags_follow_the_contents() -> Result<(), Error> {
+        let fixture = Fixture::new()?;
+        fixture
+            .commit("master", "Initial commit")
+            .file("README.md", "Hello\n")
+            .write()?;
+        let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+        let before = blob::<String>(&mut browser, None, "README.md")?.etag()?;
+        assert!(before.starts_with('"') && before.ends_with('"'));
+        assert_eq!(
+            blob::<String>(&mut browser, None, "README.md")?.etag()?,
+            before
+        );
+
+        fixture
+            .commit("master", "Say more")
+            .file("README.md", "Hello, world\n")
+            .write()?;
+        let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+        assert_ne!(
+            blob::<String>(&mut browser, None, "README.md")?.etag()?,
+            before
+        );
+
+        Ok(())
+    }
+}
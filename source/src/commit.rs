@@ -27,7 +27,13 @@ use radicle_surf::{
     vcs::git::{self, Browser, Rev},
 };
 
-use crate::{branch::Branch, error::Error, person::Person, revision::Revision};
+use crate::{
+    branch::Branch,
+    error::Error,
+    person::Person,
+    revision::Revision,
+    verification::{self, Verification},
+};
 
 /// Commit statistics.
 #[derive(Clone, Serialize)]
@@ -38,6 +44,56 @@ pub struct Stats {
     pub deletions: u64,
 }
 
+impl From<&diff::Diff> for Stats {
+    fn from(diff: &diff::Diff) -> Self {
+        let mut deletions = 0;
+        let mut additions = 0;
+
+        for file in &diff.modified {
+            if let diff::FileDiff::Plain { ref hunks } = file.diff {
+                for hunk in hunks.iter() {
+                    for line in &hunk.lines {
+                        match line {
+                            diff::LineDiff::Addition { .. } => additions += 1,
+                            diff::LineDiff::Deletion { .. } => deletions += 1,
+                            _ => {},
+                        }
+                    }
+                }
+            }
+        }
+
+        for file in &diff.created {
+            if let diff::FileDiff::Plain { ref hunks } = file.diff {
+                for hunk in hunks.iter() {
+                    for line in &hunk.lines {
+                        if let diff::LineDiff::Addition { .. } = line {
+                            additions += 1
+                        }
+                    }
+                }
+            }
+        }
+
+        for file in &diff.deleted {
+            if let diff::FileDiff::Plain { ref hunks } = file.diff {
+                for hunk in hunks.iter() {
+                    for line in &hunk.lines {
+                        if let diff::LineDiff::Deletion { .. } = line {
+                            deletions += 1
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            additions,
+            deletions,
+        }
+    }
+}
+
 /// Representation of a changeset between two revs.
 #[derive(Clone, Serialize)]
 pub struct Commit {
@@ -68,6 +124,18 @@ pub struct Header {
     /// The recorded time of the committer signature. This is a convenience
     /// alias until we expose the actual author and commiter signatures.
     pub committer_time: git2::Time,
+    /// The ids of the parents of the commit.
+    pub parents: Vec<git2::Oid>,
+    /// The change statistics for the commit, if they were computed. See
+    /// [`Header::with_details`].
+    ///
+    /// The headers of trees, blobs, and commit listings leave these out, since
+    /// they would take a diff per header; use [`header`] to fetch them for a
+    /// single commit.
+    pub stats: Option<Stats>,
+    /// The verification of the commit signature, if it was checked. See
+    /// [`Header::with_details`].
+    pub verification: Option<Verification>,
 }
 
 impl Header {
@@ -80,6 +148,27 @@ impl Header {
             .unwrap_or(&self.message)
             .trim()
     }
+
+    /// Builds the [`Header`] for `commit`, along with its [`Stats`] and the
+    /// [`Verification`] of its signature.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error`] if computing the diff of the commit or extracting
+    /// its signature fails.
+    pub fn with_details(browser: &Browser<'_>, commit: &git::Commit) -> Result<Self, Error> {
+        let diff = if let Some(parent) = commit.parents.first() {
+            browser.diff(*parent, commit.id)?
+        } else {
+            browser.initial_diff(commit.id)?
+        };
+
+        Ok(Self {
+            stats: Some(Stats::from(&diff)),
+            verification: Some(verification::verify(browser, commit)?),
+            ..Self::from(commit)
+        })
+    }
 }
 
 impl From<&git::Commit> for Header {
@@ -97,6 +186,9 @@ impl From<&git::Commit> for Header {
                 email: commit.committer.email.clone(),
             },
             committer_time: commit.committer.time,
+            parents: commit.parents.clone(),
+            stats: None,
+            verification: None,
         }
     }
 }
@@ -106,13 +198,23 @@ impl Serialize for Header {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Header", 6)?;
+        let mut state = serializer.serialize_struct("Header", 9)?;
         state.serialize_field("sha1", &self.sha1.to_string())?;
         state.serialize_field("author", &self.author)?;
         state.serialize_field("summary", &self.summary)?;
         state.serialize_field("description", &self.description())?;
         state.serialize_field("committer", &self.committer)?;
         state.serialize_field("committerTime", &self.committer_time.seconds())?;
+        state.serialize_field(
+            "parents",
+            &self
+                .parents
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        )?;
+        state.serialize_field("stats", &self.stats)?;
+        state.serialize_field("verification", &self.verification)?;
         state.end()
     }
 }
@@ -144,65 +246,30 @@ pub fn commit(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Commit, Erro
         browser.initial_diff(sha1)?
     };
 
-    let mut deletions = 0;
-    let mut additions = 0;
-
-    for file in &diff.modified {
-        if let diff::FileDiff::Plain { ref hunks } = file.diff {
-            for hunk in hunks.iter() {
-                for line in &hunk.lines {
-                    match line {
-                        diff::LineDiff::Addition { .. } => additions += 1,
-                        diff::LineDiff::Deletion { .. } => deletions += 1,
-                        _ => {},
-                    }
-                }
-            }
-        }
-    }
-
-    for file in &diff.created {
-        if let diff::FileDiff::Plain { ref hunks } = file.diff {
-            for hunk in hunks.iter() {
-                for line in &hunk.lines {
-                    if let diff::LineDiff::Addition { .. } = line {
-                        additions += 1
-                    }
-                }
-            }
-        }
-    }
-
-    for file in &diff.deleted {
-        if let diff::FileDiff::Plain { ref hunks } = file.diff {
-            for hunk in hunks.iter() {
-                for line in &hunk.lines {
-                    if let diff::LineDiff::Deletion { .. } = line {
-                        deletions += 1
-                    }
-                }
-            }
-        }
-    }
-
     let branches = browser
         .revision_branches(sha1)?
         .into_iter()
         .map(Branch::from)
         .collect();
 
+    // The stats are part of the `Commit` itself, so they are not repeated in
+    // its header.
+    let stats = Stats::from(&diff);
+    let header = Header {
+        verification: Some(verification::verify(browser, commit)?),
+        ..Header::from(commit)
+    };
+
     Ok(Commit {
-        header: Header::from(commit),
-        stats: Stats {
-            additions,
-            deletions,
-        },
+        header,
+        stats,
         diff,
         branches,
     })
 }
 
-/// Retrieves the [`Header`] for the given `sha1`.
+/// Retrieves the [`Header`] for the given `sha1`, including its [`Stats`] and
+/// [`Verification`].
 ///
 /// # Errors
 ///
@@ -214,7 +281,7 @@ pub fn header(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Header, Erro
     let history = browser.get();
     let commit = history.first();
 
-    Ok(Header::from(commit))
+    Header::with_details(browser, commit)
 }
 
 /// Retrieves the [`Commit`] history for the given `revision`.
@@ -0,0 +1,100 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use serde::Serialize;
+
+use radicle_surf::{diff, vcs::git::Browser};
+
+use crate::{commit::Stats, error::Error, oid::Oid};
+
+/// Representation of the changeset between two commits.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diff {
+    /// The commit the changeset starts from, or `None` if it is the changeset
+    /// of an initial commit.
+    pub from: Option<Oid>,
+    /// The commit the changeset ends at.
+    pub to: Oid,
+    /// The change statistics of the changeset.
+    pub stats: Stats,
+    /// The changeset itself.
+    pub diff: diff::Diff,
+}
+
+/// Retrieves the [`Diff`] between the commits `from` and `to`, or the one
+/// introduced by `to` when `from` is `None`, i.e. the diff from its first
+/// parent, or from nothing if it is an initial commit.
+///
+/// # Errors
+///
+/// Will return [`Error`] if either commit doesn't exist or the surf
+/// interaction fails.
+pub fn diff(browser: &Browser<'_>, from: Option<git2::Oid>, to: git2::Oid) -> Result<Diff, Error> {
+    let from = match from {
+        Some(from) => Some(from),
+        None => browser.parents(to)?.first().map(|parent| parent.id),
+    };
+    let diff = match from {
+        Some(from) => browser.diff(from, to)?,
+        None => browser.initial_diff(to)?,
+    };
+
+    Ok(Diff {
+        from: from.map(Oid),
+        to: Oid(to),
+        stats: Stats::from(&diff),
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser};
+
+    use super::*;
+
+    #[test]
+    fn introduced_by_a_commit() -> Result<(), Error> {
+        let fixture = Fixture::new()?;
+        let first = fixture
+            .commit("master", "Initial commit")
+            .file("README.md", "Hello\n")
+            .file("src/lib.rs", "")
+            .write()?;
+        let second = fixture
+            .commit("master", "Say more")
+            .file("README.md", "Hello, world\n")
+            .write()?;
+        let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+        // Only the changes of the commit, not the whole tree.
+        let introduced = diff(&browser, None, second)?;
+        assert_eq!(introduced.from.map(git2::Oid::from), Some(first));
+        assert!(introduced.diff.created.is_empty());
+        assert_eq!(introduced.diff.modified.len(), 1);
+        let stats = introduced.stats;
+        assert_eq!((stats.additions, stats.deletions), (1, 1));
+
+        // An initial commit introduces all of its files.
+        let initial = diff(&browser, None, first)?;
+        assert!(initial.from.is_none());
+        assert_eq!(initial.diff.created.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Metadata of documentation files, so that listings can show the titles of
+//! the documents in a project rather than their file names.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::object::{Blob, BlobContent};
+
+/// The markup languages that documentation is recognised in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Markup {
+    /// Markdown, e.g. `README.md`.
+    Markdown,
+    /// AsciiDoc, e.g. `guide.adoc`.
+    AsciiDoc,
+    /// reStructuredText, e.g. `index.rst`.
+    ReStructuredText,
+}
+
+impl Markup {
+    /// Recognise the markup of a file by the extension of its `path`.
+    #[must_use]
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+        match extension.to_lowercase().as_str() {
+            "md" | "markdown" | "mdown" | "mkd" => Some(Self::Markdown),
+            "adoc" | "asciidoc" | "asc" => Some(Self::AsciiDoc),
+            "rst" => Some(Self::ReStructuredText),
+            _ => None,
+        }
+    }
+}
+
+/// The YAML front matter of a document, between two `---` lines at its very
+/// start.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FrontMatter {
+    /// The front matter as it is written, without the `---` lines.
+    pub raw: String,
+    /// The top-level `key: value` pairs of the front matter, with quotes
+    /// around the values removed. Nested values and lists are left out, see
+    /// `raw` for those.
+    pub fields: BTreeMap<String, String>,
+}
+
+/// The metadata of a documentation file, see [`doc`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Doc {
+    /// The markup the document is written in.
+    pub markup: Markup,
+    /// The title of the document, taken from the `title` field of its front
+    /// matter or else from its first top-level heading.
+    pub title: Option<String>,
+    /// The front matter of the document, if it has any.
+    pub front_matter: Option<FrontMatter>,
+}
+
+/// Returns the [`Doc`] metadata of `blob`, or `None` if it is not a text file
+/// in one of the [`Markup`] languages.
+#[must_use]
+pub fn doc(blob: &Blob) -> Option<Doc> {
+    let markup = Markup::from_path(&blob.path)?;
+    match &blob.content {
+        BlobContent::Plain(text) => Some(parse(markup, text)),
+        BlobContent::Html(_) | BlobContent::Binary(_) => None,
+    }
+}
+
+/// Parse the [`Doc`] metadata of `text`, written in `markup`.
+#[must_use]
+pub fn parse(markup: Markup, text: &str) -> Doc {
+    let text = text.trim_start_matches('\u{feff}');
+    let (front_matter, body) = match front_matter(text) {
+        Some((front_matter, body)) => (Some(front_matter), body),
+        None => (None, text),
+    };
+    let title = front_matter
+        .as_ref()
+        .and_then(|front_matter| front_matter.fields.get("title").cloned())
+        .filter(|title| !title.is_empty())
+        .or_else(|| match markup {
+            Markup::Markdown => markdown_title(body),
+            Markup::AsciiDoc => asciidoc_title(body),
+            Markup::ReStructuredText => rst_title(body),
+        });
+    Doc {
+        markup,
+        title,
+        front_matter,
+    }
+}
+
+/// Split the front matter off the start of `text`, if it has any.
+fn front_matter(text: &str) -> Option<(FrontMatter, &str)> {
+    let mut lines = text.split_inclusive('\n');
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+
+    let start = text.find('\n')? + 1;
+    let mut end = start;
+    for line in lines {
+        if line.trim_end() == "---" || line.trim_end() == "..." {
+            let raw = &text[start..end];
+            let fields = raw
+                .lines()
+                .filter(|line| !line.starts_with(char::is_whitespace) && !line.starts_with('#'))
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| (key.trim().to_string(), unquote(value.trim()).to_string()))
+                .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+                .collect();
+            let front_matter = FrontMatter {
+                raw: raw.to_string(),
+                fields,
+            };
+            return Some((front_matter, &text[end + line.len()..]));
+        }
+        end += line.len();
+    }
+    None
+}
+
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(value)
+}
+
+/// The first level one heading, either `# Title` or `Title` underlined with
+/// `=`, outside of fenced code blocks.
+fn markdown_title(body: &str) -> Option<String> {
+    let mut fence: Option<&str> = None;
+    let mut previous: Option<&str> = None;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            previous = None;
+            continue;
+        }
+
+        if let Some(title) = trimmed.strip_prefix("# ") {
+            return Some(title.trim().trim_end_matches('#').trim().to_string());
+        }
+        if let Some(title) = previous {
+            if !trimmed.is_empty() && trimmed.chars().all(|c| c == '=') {
+                return Some(title.to_string());
+            }
+        }
+        previous = Some(trimmed).filter(|line| !line.is_empty());
+    }
+    None
+}
+
+/// The document title, written as `= Title`.
+fn asciidoc_title(body: &str) -> Option<String> {
+    body.lines()
+        .map(str::trim_end)
+        // The title comes before anything but comments and attributes.
+        .find(|line| !line.is_empty() && !line.starts_with("//") && !line.starts_with(':'))
+        .and_then(|line| line.strip_prefix("= "))
+        .map(|title| title.trim().to_string())
+}
+
+/// The first section title, i.e. a line underlined with a punctuation
+/// character that is repeated at least as long as the title.
+fn rst_title(body: &str) -> Option<String> {
+    let lines = body.lines().map(str::trim_end).collect::<Vec<_>>();
+    let is_adornment = |line: &str| {
+        let mut chars = line.chars();
+        match chars.next() {
+            Some(first) if first.is_ascii_punctuation() => chars.all(|c| c == first),
+            _ => false,
+        }
+    };
+    lines.windows(2).find_map(|pair| {
+        let (title, underline) = (pair[0].trim(), pair[1]);
+        (!title.is_empty()
+            && !is_adornment(title)
+            && is_adornment(underline)
+            && underline.chars().count() >= title.chars().count())
+        .then(|| title.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn front_matter_terminators_and_quotes() {
+        let dashes =
+            "---\ntitle: \"Hello: world\"\nauthor: 'Ada'\ntags:\n  - docs\n---\n# Heading\n";
+        let doc = parse(Markup::Markdown, dashes);
+        assert_eq!(doc.title.as_deref(), Some("Hello: world"));
+        let front_matter = doc.front_matter.unwrap();
+        assert_eq!(
+            front_matter.fields.get("author").map(String::as_str),
+            Some("Ada")
+        );
+        assert!(!front_matter.fields.contains_key("tags"));
+        assert!(front_matter.raw.contains("  - docs"));
+
+        let dots = "---\ntitle: Dots\n...\n# Heading\n";
+        assert_eq!(parse(Markup::Markdown, dots).title.as_deref(), Some("Dots"));
+
+        // An empty title falls back to the first heading.
+        let empty = "---\ntitle: ''\n---\n# Heading\n";
+        assert_eq!(
+            parse(Markup::Markdown, empty).title.as_deref(),
+            Some("Heading")
+        );
+
+        // Without a closing line there is no front matter.
+        let unclosed = "---\ntitle: Open\n# Heading\n";
+        let doc = parse(Markup::Markdown, unclosed);
+        assert_eq!(doc.front_matter, None);
+        assert_eq!(doc.title.as_deref(), Some("Heading"));
+    }
+
+    #[test]
+    fn markdown_skips_fenced_code() {
+        let text = "```sh\n# not a heading\n```\n~~~\n# nor this\n~~~\n# Title #\n";
+        assert_eq!(
+            parse(Markup::Markdown, text).title.as_deref(),
+            Some("Title")
+        );
+
+        let unclosed = "```\n# inside\n";
+        assert_eq!(parse(Markup::Markdown, unclosed).title, None);
+    }
+
+    #[test]
+    fn markdown_setext_headings() {
+        let text = "Subtitle\n--------\n\nTitle\n=====\n";
+        assert_eq!(
+            parse(Markup::Markdown, text).title.as_deref(),
+            Some("Title")
+        );
+
+        // The underline has to follow the title directly.
+        let text = "Paragraph\n\n=====\n";
+        assert_eq!(parse(Markup::Markdown, text).title, None);
+    }
+
+    #[test]
+    fn asciidoc_headers() {
+        let text = "// A comment\n:toc: left\n:icons: font\n\n= User Guide\nAda Lovelace\n";
+        assert_eq!(
+            parse(Markup::AsciiDoc, text).title.as_deref(),
+            Some("User Guide")
+        );
+
+        // The title has to come before any other content.
+        let text = "Some text.\n\n= Too Late\n";
+        assert_eq!(parse(Markup::AsciiDoc, text).title, None);
+    }
+
+    #[test]
+    fn rst_adornments() {
+        let overline = "=========\n Project\n=========\n\nIntro\n-----\n";
+        assert_eq!(
+            parse(Markup::ReStructuredText, overline).title.as_deref(),
+            Some("Project")
+        );
+
+        let underline = ".. comment\n\nInstall\n~~~~~~~\n";
+        assert_eq!(
+            parse(Markup::ReStructuredText, underline).title.as_deref(),
+            Some("Install")
+        );
+
+        // An underline shorter than the title is not an adornment.
+        let short = "Too short\n===\n";
+        assert_eq!(parse(Markup::ReStructuredText, short).title, None);
+    }
+
+    #[test]
+    fn unknown_extensions() {
+        assert_eq!(Markup::from_path("README.MD"), Some(Markup::Markdown));
+        assert_eq!(Markup::from_path("docs/guide.adoc"), Some(Markup::AsciiDoc));
+        assert_eq!(
+            Markup::from_path("index.rst"),
+            Some(Markup::ReStructuredText)
+        );
+        assert_eq!(Markup::from_path("notes.txt"), None);
+        assert_eq!(Markup::from_path("Makefile"), None);
+        assert_eq!(Markup::from_path("archive.md.gz"), None);
+    }
+}
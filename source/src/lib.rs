@@ -21,12 +21,21 @@
 /// re-exports the package under the `surf` alias.
 pub use radicle_surf as surf;
 
+pub mod api;
+pub use api::{Etag, Versioned};
+
 pub mod branch;
 pub use branch::{branches, local_state, Branch, LocalState};
 
 pub mod commit;
 pub use commit::{commit, commits, Commit};
 
+pub mod diff;
+pub use diff::{diff, Diff};
+
+pub mod docs;
+pub use docs::Doc;
+
 pub mod error;
 pub use error::Error;
 
@@ -45,7 +54,10 @@ pub use revision::Revision;
 #[cfg(feature = "syntax")]
 pub mod syntax;
 #[cfg(feature = "syntax")]
-pub use syntax::SYNTAX_SET;
+pub use syntax::{themes, SYNTAX_SET};
 
 pub mod tag;
 pub use tag::{tags, Tag};
+
+pub mod verification;
+pub use verification::Verification;
@@ -181,6 +181,17 @@ fn content(content: &[u8]) -> BlobContent {
 pub mod highlighting {
     use super::*;
 
+    /// How the content of a [`Blob`] is highlighted.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Options<'a> {
+        /// The name of the theme to highlight with, one of
+        /// [`syntax::themes`]. The content is left plain when it is `None`.
+        pub theme: Option<&'a str>,
+        /// Whether each line is wrapped in a `<span class="line">` with the
+        /// id `L<n>`, so that lines can be linked to.
+        pub line_anchors: bool,
+    }
+
     /// Returns the [`Blob`] for a file at `revision` under `path`.
     ///
     /// # Errors
@@ -193,26 +204,88 @@ pub mod highlighting {
         path: &str,
         theme: Option<&str>,
     ) -> Result<Blob, Error>
+    where
+        P: ToString,
+    {
+        let options = Options {
+            theme,
+            ..Options::default()
+        };
+        blob_with(browser, maybe_revision, path, options)
+    }
+
+    /// Returns the [`Blob`] for a file at `revision` under `path`, highlighted
+    /// according to the `options`.
+    ///
+    /// The content is [`BlobContent::Html`] when the theme and the syntax of
+    /// the file are known, and [`BlobContent::Plain`] otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error`] if the project doesn't exist or a surf interaction
+    /// fails.
+    pub fn blob_with<P>(
+        browser: &mut Browser,
+        maybe_revision: Option<Revision<P>>,
+        path: &str,
+        options: Options<'_>,
+    ) -> Result<Blob, Error>
     where
         P: ToString,
     {
         make_blob(browser, maybe_revision, path, |contents| {
-            content(path, contents, theme)
+            content(path, contents, options)
         })
     }
 
-    /// Return a [`BlobContent`] given a file path, content and theme. Attempts
-    /// to perform syntax highlighting when the theme is `Some`.
-    fn content(path: &str, content: &[u8], theme_name: Option<&str>) -> BlobContent {
+    /// Return a [`BlobContent`] given a file path, content and options.
+    /// Attempts to perform syntax highlighting when the theme is `Some`.
+    fn content(path: &str, content: &[u8], options: Options<'_>) -> BlobContent {
         let content = match str::from_utf8(content) {
             Ok(content) => content,
             Err(_) => return BlobContent::Binary(content.to_owned()),
         };
 
-        match theme_name {
+        match options.theme {
             None => BlobContent::Plain(content.to_owned()),
-            Some(theme) => syntax::highlight(path, content, theme)
+            Some(theme) => syntax::highlight_lines(path, content, theme, options.line_anchors)
                 .map_or_else(|| BlobContent::Plain(content.to_owned()), BlobContent::Html),
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser};
+
+        use super::*;
+
+        #[test]
+        fn options() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "Initial commit")
+                .file("src/main.rs", "fn main() {}\n")
+                .file("README", "Hello\n")
+                .write()?;
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let anchored = Options {
+                theme: Some("base16-ocean.dark"),
+                line_anchors: true,
+            };
+
+            let blob = blob_with::<String>(&mut browser, None, "src/main.rs", anchored)?;
+            match blob.content {
+                BlobContent::Html(html) => assert!(html.contains("id=\"L1\"")),
+                _ => panic!("expected the content to be highlighted"),
+            }
+
+            // Without a theme, or a known syntax, the content is left plain.
+            let plain = blob_with::<String>(&mut browser, None, "src/main.rs", Options::default())?;
+            assert!(plain.content == BlobContent::Plain("fn main() {}\n".to_string()));
+            let readme = blob_with::<String>(&mut browser, None, "README", anchored)?;
+            assert!(readme.content == BlobContent::Plain("Hello\n".to_string()));
+
+            Ok(())
+        }
+    }
 }
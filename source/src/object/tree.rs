@@ -40,6 +40,9 @@ pub struct Tree {
     pub path: String,
     /// Entries listed in that tree result.
     pub entries: Vec<TreeEntry>,
+    /// Number of entries in the tree, including those not listed in
+    /// `entries` because of pagination.
+    pub total: usize,
     /// Extra info for the tree object.
     pub info: Info,
 }
@@ -49,9 +52,10 @@ impl Serialize for Tree {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Tree", 3)?;
+        let mut state = serializer.serialize_struct("Tree", 4)?;
         state.serialize_field("path", &self.path)?;
         state.serialize_field("entries", &self.entries)?;
+        state.serialize_field("total", &self.total)?;
         state.serialize_field("info", &self.info)?;
         state.end()
     }
@@ -80,6 +84,14 @@ impl Serialize for TreeEntry {
 
 /// Retrieve the [`Tree`] for the given `revision` and directory `prefix`.
 ///
+/// The entries are listed trees first, then blobs, each in name order. Only
+/// the entries from `offset` up to `limit` of them are returned, where `None`
+/// means all remaining entries. [`Tree::total`] is the number of entries
+/// before this pagination.
+///
+/// The last commit of the tree and of each returned entry is found with a
+/// single walk of the history.
+///
 /// # Errors
 ///
 /// Will return [`Error`] if any of the surf interactions fail.
@@ -87,6 +99,8 @@ pub fn tree<P>(
     browser: &mut Browser<'_>,
     maybe_revision: Option<Revision<P>>,
     maybe_prefix: Option<String>,
+    offset: usize,
+    limit: Option<usize>,
 ) -> Result<Tree, Error>
 where
     P: ToString,
@@ -115,7 +129,7 @@ where
     let mut prefix_contents = prefix_dir.list_directory();
     prefix_contents.sort();
 
-    let entries_results: Result<Vec<TreeEntry>, Error> = prefix_contents
+    let mut entries = prefix_contents
         .iter()
         .map(|(label, system_type)| {
             let entry_path = if path.is_root() {
@@ -125,8 +139,6 @@ where
                 p.push(label.clone());
                 p
             };
-            let mut commit_path = file_system::Path::root();
-            commit_path.append(entry_path.clone());
 
             let info = Info {
                 name: label.to_string(),
@@ -137,25 +149,63 @@ where
                 last_commit: None,
             };
 
-            Ok(TreeEntry {
-                info,
-                path: entry_path.to_string(),
-            })
+            (
+                entry_path,
+                TreeEntry {
+                    info,
+                    path: String::new(),
+                },
+            )
         })
-        .collect();
-
-    let mut entries = entries_results?;
+        .collect::<Vec<_>>();
 
     // We want to ensure that in the response Tree entries come first. `Ord` being
     // derived on the enum ensures Variant declaration order.
     //
     // https://doc.rust-lang.org/std/cmp/trait.Ord.html#derivable
-    entries.sort_by(|a, b| a.info.object_type.cmp(&b.info.object_type));
+    entries.sort_by(|(_, a), (_, b)| a.info.object_type.cmp(&b.info.object_type));
+
+    let total = entries.len();
+    let entries = entries
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+
+    // The last commits are looked up by their path from the root, with the
+    // tree itself last.
+    let mut commit_paths = entries
+        .iter()
+        .map(|(entry_path, _)| {
+            let mut commit_path = file_system::Path::root();
+            commit_path.append(entry_path.clone());
+            commit_path
+        })
+        .collect::<Vec<_>>();
+    if !path.is_root() {
+        let mut commit_path = file_system::Path::root();
+        commit_path.append(path.clone());
+        commit_paths.push(commit_path);
+    }
+    let mut last_commits = browser
+        .last_commits(&commit_paths)?
+        .into_iter()
+        .map(|commit| commit.map(|commit| commit::Header::from(&commit)));
+
+    let entries = entries
+        .into_iter()
+        .zip(last_commits.by_ref())
+        .map(|((entry_path, mut entry), last_commit)| {
+            entry.path = entry_path.to_string();
+            entry.info.last_commit = last_commit;
+            entry
+        })
+        .collect();
 
     let last_commit = if path.is_root() {
         Some(commit::Header::from(browser.get().first()))
     } else {
-        None
+        last_commits.next().flatten()
     };
     let name = if path.is_root() {
         "".into()
@@ -172,6 +222,7 @@ where
     Ok(Tree {
         path: prefix,
         entries,
+        total,
         info,
     })
 }
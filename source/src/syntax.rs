@@ -54,34 +54,90 @@ lazy_static::lazy_static! {
         }
         builder.build()
     };
+
+    // The themes are loaded once for the same reason.
+    static ref THEME_SET: ThemeSet = ThemeSet::load_defaults();
+}
+
+/// The names of the themes that content can be highlighted with.
+pub fn themes() -> impl Iterator<Item = &'static str> {
+    THEME_SET.themes.keys().map(String::as_str)
 }
 
 /// Return a [`BlobContent`] given a file path, content and theme. Attempts to
 /// perform syntax highlighting when the theme is `Some`.
 pub fn highlight(path: &str, content: &str, theme_name: &str) -> Option<String> {
+    highlight_lines(path, content, theme_name, false)
+}
+
+/// Highlight `content` as HTML, choosing the syntax from the extension of
+/// `path`. Returns `None` if the syntax or the theme is unknown.
+///
+/// With `line_anchors`, each line is wrapped in a `<span class="line">` with
+/// the id `L<n>`, counting from 1, so that lines can be linked to.
+pub fn highlight_lines(
+    path: &str,
+    content: &str,
+    theme_name: &str,
+    line_anchors: bool,
+) -> Option<String> {
     let syntax = path::Path::new(path)
         .extension()
         .and_then(std::ffi::OsStr::to_str)
-        .and_then(|ext| SYNTAX_SET.find_syntax_by_extension(ext));
-
-    let ts = ThemeSet::load_defaults();
-    let theme = ts.themes.get(theme_name);
-
-    match (syntax, theme) {
-        (Some(syntax), Some(theme)) => {
-            let mut highlighter = HighlightLines::new(syntax, theme);
-            let mut html = String::with_capacity(content.len());
-
-            for line in LinesWithEndings::from(content) {
-                let regions = highlighter.highlight(line, &SYNTAX_SET);
-                syntect::html::append_highlighted_html_for_styled_line(
-                    &regions[..],
-                    syntect::html::IncludeBackground::No,
-                    &mut html,
-                );
-            }
-            Some(html)
-        },
-        _ => None,
+        .and_then(|ext| SYNTAX_SET.find_syntax_by_extension(ext))?;
+    let theme = THEME_SET.themes.get(theme_name)?;
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut html = String::with_capacity(content.len());
+
+    for (index, line) in LinesWithEndings::from(content).enumerate() {
+        if line_anchors {
+            html.push_str(&format!("<span class=\"line\" id=\"L{}\">", index + 1));
+        }
+        let regions = highlighter.highlight(line, &SYNTAX_SET);
+        syntect::html::append_highlighted_html_for_styled_line(
+            &regions[..],
+            syntect::html::IncludeBackground::No,
+            &mut html,
+        );
+        if line_anchors {
+            html.push_str("</span>");
+        }
+    }
+    Some(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEME: &str = "base16-ocean.dark";
+    const SOURCE: &str = "fn main() {}\nlet banana = 1;\n";
+
+    #[test]
+    fn themes_are_listed() {
+        assert!(themes().any(|theme| theme == THEME));
+    }
+
+    #[test]
+    fn line_anchors() {
+        let html = highlight_lines("src/main.rs", SOURCE, THEME, true).unwrap();
+        assert!(html.starts_with("<span class=\"line\" id=\"L1\">"));
+        assert!(html.contains("<span class=\"line\" id=\"L2\">"));
+        assert_eq!(html.matches("class=\"line\"").count(), 2);
+        assert!(html.ends_with("</span>"));
+
+        let plain = highlight("src/main.rs", SOURCE, THEME).unwrap();
+        assert!(!plain.contains("class=\"line\""));
+        assert_eq!(
+            Some(plain),
+            highlight_lines("src/main.rs", SOURCE, THEME, false)
+        );
+    }
+
+    #[test]
+    fn unknown_syntax_or_theme() {
+        assert_eq!(highlight_lines("README", SOURCE, THEME, true), None);
+        assert_eq!(highlight_lines("src/main.rs", SOURCE, "banana", true), None);
     }
 }
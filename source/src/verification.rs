@@ -0,0 +1,290 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Verification of commit signatures.
+//!
+//! Like `git verify-commit`, the signatures are checked by running `gpg`
+//! against the keyring of the user running the process.
+
+use std::{
+    io::Write as _,
+    process::{Child, Command, Stdio},
+};
+
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+use radicle_surf::vcs::git::{Browser, Commit};
+
+use crate::error::Error;
+
+/// The number of `gpg` processes [`verify_all`] runs at once.
+const CONCURRENT_GPG: usize = 8;
+
+/// The outcome of verifying the signature of a commit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum Verification {
+    /// The commit is not signed.
+    Unsigned,
+    /// The signature is valid and made by `key`, which is trusted.
+    Good {
+        /// The id of the signing key.
+        key: String,
+    },
+    /// The signature is valid and made by `key`, but the key is not trusted,
+    /// e.g. because nobody trusted has certified it.
+    Untrusted {
+        /// The id of the signing key.
+        key: String,
+    },
+    /// The signature was made by `key`, but either the signature or the key
+    /// has expired.
+    Expired {
+        /// The id of the signing key.
+        key: String,
+    },
+    /// The signature was made by `key`, which has been revoked.
+    Revoked {
+        /// The id of the signing key.
+        key: String,
+    },
+    /// The signature was made by `key`, but does not match the commit.
+    Bad {
+        /// The id of the signing key.
+        key: String,
+    },
+    /// The signature was made by `key`, which is not in the keyring.
+    UnknownKey {
+        /// The id of the signing key.
+        key: String,
+    },
+    /// The commit is signed, but the signature could not be checked, e.g.
+    /// because `gpg` is not installed.
+    Unverified,
+}
+
+/// Verify the signature of `commit`.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the signature could not be extracted from the
+/// commit. Failing to run `gpg` is reported as
+/// [`Verification::Unverified`] instead.
+pub fn verify(browser: &Browser<'_>, commit: &Commit) -> Result<Verification, Error> {
+    let verification = verify_all(browser, std::slice::from_ref(commit))?
+        .into_iter()
+        .next();
+    Ok(verification.unwrap_or(Verification::Unverified))
+}
+
+/// Verify the signatures of `commits`, in the same way as [`verify`], but
+/// checking several of them at once.
+///
+/// # Errors
+///
+/// See [`verify`].
+pub fn verify_all(browser: &Browser<'_>, commits: &[Commit]) -> Result<Vec<Verification>, Error> {
+    let mut verifications = Vec::with_capacity(commits.len());
+    for chunk in commits.chunks(CONCURRENT_GPG) {
+        let running = chunk
+            .iter()
+            .map(|commit| {
+                let signed = browser.extract_signed_data(commit, None)?;
+                Ok(signed.map(|(signature, data)| Gpg::spawn(signature.as_ref(), &data)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        verifications.extend(running.into_iter().map(|gpg| match gpg {
+            None => Verification::Unsigned,
+            Some(gpg) => gpg.and_then(Gpg::wait).unwrap_or(Verification::Unverified),
+        }));
+    }
+    Ok(verifications)
+}
+
+/// A running `gpg --verify` of a detached signature.
+struct Gpg {
+    child: Child,
+    // gpg only reads one of the signature and the data from stdin, so the
+    // signature is handed over as a file. The file is created exclusively,
+    // readable only by the current user, and removed when it is dropped.
+    _signature: NamedTempFile,
+}
+
+impl Gpg {
+    /// Start verifying the detached `signature` of `data`, returning `None`
+    /// if `gpg` could not be run.
+    fn spawn(signature: &[u8], data: &[u8]) -> Option<Self> {
+        let mut signature_file = NamedTempFile::new().ok()?;
+        signature_file.write_all(signature).ok()?;
+        signature_file.flush().ok()?;
+
+        let mut child = Command::new("gpg")
+            .args(["--batch", "--no-tty", "--status-fd=1", "--verify"])
+            .arg(signature_file.path())
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        // Closing stdin once the data is written tells gpg it is complete.
+        let written = match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(data).is_ok(),
+            None => false,
+        };
+        if !written {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+
+        Some(Self {
+            child,
+            _signature: signature_file,
+        })
+    }
+
+    /// Wait for `gpg` to finish, returning `None` if it did not report on the
+    /// signature.
+    fn wait(self) -> Option<Verification> {
+        let output = self.child.wait_with_output().ok()?;
+        parse_status(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Interpret the `[GNUPG:]` status lines printed by `gpg --status-fd`.
+///
+/// gpg reports one of `GOODSIG`, `EXPSIG`, `EXPKEYSIG`, `REVKEYSIG`, `BADSIG`
+/// or `ERRSIG` for a signature. A `GOODSIG` is only good if the signature is
+/// also `VALIDSIG`, and the key is trusted, see the `TRUST_*` lines.
+fn parse_status(status: &str) -> Option<Verification> {
+    let mut outcome = None;
+    let mut good = None;
+    let mut valid = false;
+    let mut trusted = false;
+
+    for line in status.lines() {
+        let mut words = match line.strip_prefix("[GNUPG:] ") {
+            Some(line) => line.split_whitespace(),
+            None => continue,
+        };
+        let keyword = words.next();
+        let key = words.next().map(str::to_string);
+        let verification = match (keyword, key) {
+            (Some("GOODSIG"), Some(key)) => {
+                good = Some(key);
+                continue;
+            },
+            (Some("VALIDSIG"), _) => {
+                valid = true;
+                continue;
+            },
+            (Some("TRUST_MARGINAL" | "TRUST_FULLY" | "TRUST_ULTIMATE"), _) => {
+                trusted = true;
+                continue;
+            },
+            (Some("EXPSIG" | "EXPKEYSIG"), Some(key)) => Verification::Expired { key },
+            (Some("REVKEYSIG"), Some(key)) => Verification::Revoked { key },
+            (Some("BADSIG"), Some(key)) => Verification::Bad { key },
+            (Some("ERRSIG" | "NO_PUBKEY"), Some(key)) => Verification::UnknownKey { key },
+            _ => continue,
+        };
+        outcome.get_or_insert(verification);
+    }
+
+    match (outcome, good) {
+        (Some(outcome), _) => Some(outcome),
+        (None, Some(key)) if valid && trusted => Some(Verification::Good { key }),
+        (None, Some(key)) if valid => Some(Verification::Untrusted { key }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "AAAABBBBCCCCDDDD";
+
+    fn status(lines: &[&str]) -> String {
+        lines
+            .iter()
+            .map(|line| format!("[GNUPG:] {}\n", line))
+            .collect()
+    }
+
+    fn key() -> String {
+        KEY.to_string()
+    }
+
+    #[test]
+    fn good_needs_validity_and_trust() {
+        let good = format!("GOODSIG {} Test <t@e.st>", KEY);
+        let valid = "VALIDSIG 0123456789ABCDEF 2021-01-01 1609459200 0";
+
+        assert_eq!(
+            parse_status(&status(&["NEWSIG", &good, valid, "TRUST_FULLY 0 pgp"])),
+            Some(Verification::Good { key: key() })
+        );
+        assert_eq!(
+            parse_status(&status(&["NEWSIG", &good, valid, "TRUST_UNDEFINED 0 pgp"])),
+            Some(Verification::Untrusted { key: key() })
+        );
+        assert_eq!(
+            parse_status(&status(&["NEWSIG", &good, "TRUST_ULTIMATE"])),
+            None
+        );
+    }
+
+    #[test]
+    fn expired_and_revoked_keys() {
+        let valid = "VALIDSIG 0123456789ABCDEF 2021-01-01 1609459200 0";
+        for keyword in &["EXPSIG", "EXPKEYSIG"] {
+            let line = format!("{} {} Test <t@e.st>", keyword, KEY);
+            assert_eq!(
+                parse_status(&status(&["NEWSIG", &line, valid])),
+                Some(Verification::Expired { key: key() })
+            );
+        }
+
+        let revoked = format!("REVKEYSIG {} Test <t@e.st>", KEY);
+        assert_eq!(
+            parse_status(&status(&["NEWSIG", &revoked, valid, "TRUST_FULLY 0 pgp"])),
+            Some(Verification::Revoked { key: key() })
+        );
+    }
+
+    #[test]
+    fn bad_and_unknown_signatures() {
+        let bad = format!("BADSIG {} Test <t@e.st>", KEY);
+        assert_eq!(
+            parse_status(&status(&["NEWSIG", &bad])),
+            Some(Verification::Bad { key: key() })
+        );
+
+        let errsig = format!("ERRSIG {} 22 8 00 1609459200 9 -", KEY);
+        let no_pubkey = format!("NO_PUBKEY {}", KEY);
+        assert_eq!(
+            parse_status(&status(&["NEWSIG", &errsig, &no_pubkey])),
+            Some(Verification::UnknownKey { key: key() })
+        );
+
+        assert_eq!(parse_status("gpg: no valid OpenPGP data found.\n"), None);
+    }
+}
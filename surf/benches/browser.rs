@@ -0,0 +1,92 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser, Oid, Repository};
+
+/// Generate a repository with a linear history of `commits` commits on
+/// `master`, spread over `files` files in nested directories, where each
+/// commit modifies one file.
+fn synthetic(commits: usize, files: usize) -> Fixture {
+    let fixture = Fixture::new().expect("Could not create fixture repository");
+    for i in 0..commits {
+        let path = format!("dir-{}/sub-{}/file-{}.txt", i % 7, i % 3, i % files);
+        fixture
+            .commit("master", &format!("commit {}", i))
+            .file(&path, format!("line {}\n", i).repeat(i % 50 + 1))
+            .write()
+            .expect("Could not write fixture commit");
+    }
+    fixture
+}
+
+fn platinum() -> Repository {
+    Repository::new("./data/git-platinum")
+        .expect("Could not retrieve ./data/git-platinum as git repository")
+}
+
+fn snapshot(c: &mut Criterion) {
+    let platinum = platinum();
+    let large = synthetic(1000, 200);
+
+    let mut group = c.benchmark_group("Snapshot");
+    for (name, repo) in [
+        ("git-platinum", &platinum),
+        ("synthetic", large.repository()),
+    ]
+    .iter()
+    {
+        let browser =
+            Browser::new(*repo, Branch::local("master")).expect("Could not initialise Browser");
+        group.bench_function(BenchmarkId::new("get_directory", name), |b| {
+            b.iter(|| browser.get_directory())
+        });
+    }
+}
+
+fn history(c: &mut Criterion) {
+    let platinum = platinum();
+    let large = synthetic(1000, 200);
+
+    let mut group = c.benchmark_group("History");
+    for (name, repo) in [
+        ("git-platinum", &platinum),
+        ("synthetic", large.repository()),
+    ]
+    .iter()
+    {
+        group.bench_function(BenchmarkId::new("new", name), |b| {
+            b.iter(|| Browser::new(*repo, Branch::local("master")))
+        });
+    }
+}
+
+fn diff(c: &mut Criterion) {
+    let platinum = platinum();
+    let browser =
+        Browser::new(&platinum, Branch::local("master")).expect("Could not initialise Browser");
+    let from = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3").expect("Invalid Oid");
+    let to = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02").expect("Invalid Oid");
+
+    let mut group = c.benchmark_group("Diff");
+    group.bench_function("git-platinum root to master", |b| {
+        b.iter(|| browser.diff(from, to))
+    });
+}
+
+criterion_group!(benches, snapshot, history, diff);
+criterion_main!(benches);
@@ -20,7 +20,6 @@ extern crate radicle_surf;
 use std::{env::Args, time::Instant};
 
 use git2::Oid;
-use nonempty::NonEmpty;
 
 use radicle_surf::{
     diff::Diff,
@@ -99,7 +98,7 @@ fn set_browser_history(browser: &mut git::Browser, commit_id: &str) -> Result<()
         Some(commit) => commit,
         None => return Err(format!("Git commit not found: {}", commit_id)),
     };
-    browser.set(History(NonEmpty::new(commit)));
+    browser.set(History::new(commit));
     Ok(())
 }
 
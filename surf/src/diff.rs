@@ -50,6 +50,9 @@ impl Default for Diff {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CreateFile {
     pub path: Path,
+    /// The mode of the new file, e.g. `100755` for an executable.
+    #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_mode"))]
+    pub mode: git2::FileMode,
     pub diff: FileDiff,
 }
 
@@ -57,6 +60,9 @@ pub struct CreateFile {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DeleteFile {
     pub path: Path,
+    /// The mode the file had before it was deleted.
+    #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_mode"))]
+    pub mode: git2::FileMode,
     pub diff: FileDiff,
 }
 
@@ -106,6 +112,44 @@ pub struct ModifiedFile {
     pub eof: Option<EofNewLine>,
 }
 
+/// The kind of change that was made to a file, see [`FileSummary`].
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(tag = "type", rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileStatus {
+    Created,
+    Deleted,
+    Modified,
+    #[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+    Moved {
+        old_path: Path,
+    },
+    #[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+    Copied {
+        old_path: Path,
+    },
+    TypeChanged,
+}
+
+/// A summary of the changes to one file, which only counts the lines that
+/// were added and deleted rather than keeping the hunks around.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSummary {
+    pub path: Path,
+    pub status: FileStatus,
+    pub additions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
 /// A set of changes belonging to one file.
 #[cfg_attr(
     feature = "serialize",
@@ -114,13 +158,123 @@ pub struct ModifiedFile {
 )]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FileDiff {
-    Binary,
+    /// The file is binary, so only its blobs are given. A side is `None` when
+    /// the file does not exist on that side of the diff.
+    #[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+    Binary {
+        old: Option<BinaryFile>,
+        new: Option<BinaryFile>,
+    },
+    #[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+    Plain { hunks: Hunks },
+    /// The commit that a submodule points to has changed. When the submodule
+    /// is created or deleted, the missing side is the zero `Oid`.
+    #[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+    Submodule {
+        #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_oid"))]
+        old: git2::Oid,
+        #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_oid"))]
+        new: git2::Oid,
+    },
+    /// The type of the file has changed, e.g. a file became a symlink or a
+    /// submodule.
     #[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
-    Plain {
-        hunks: Hunks,
+    TypeChange {
+        #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_mode"))]
+        old: git2::FileMode,
+        #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_mode"))]
+        new: git2::FileMode,
     },
 }
 
+/// The blob of one side of a binary [`FileDiff`].
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryFile {
+    #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_oid"))]
+    pub id: git2::Oid,
+    /// The size of the blob in bytes.
+    pub size: u64,
+}
+
+/// Serializes the mode in the octal form that git uses, e.g. `100644`.
+#[cfg(feature = "serialize")]
+fn serialize_mode<S>(mode: &git2::FileMode, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&format!("{:o}", i32::from(*mode)))
+}
+
+#[cfg(feature = "serialize")]
+fn serialize_oid<S>(oid: &git2::Oid, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&oid.to_string())
+}
+
+impl FileDiff {
+    /// Render the changes as the body of a `git diff` patch, i.e. the `---`
+    /// and `+++` file headers followed by the hunks.
+    ///
+    /// A `None` path means that the file does not exist on that side of the
+    /// diff, which is rendered as `/dev/null`. Nothing is rendered if there
+    /// are no hunks. A [`FileDiff::TypeChange`] is rendered as the `old mode`
+    /// and `new mode` lines of its header instead.
+    pub fn to_patch(&self, old_path: Option<&Path>, new_path: Option<&Path>) -> String {
+        let old = patch_path("a", old_path);
+        let new = patch_path("b", new_path);
+        match self {
+            FileDiff::Binary { .. } => format!("Binary files {} and {} differ\n", old, new),
+            FileDiff::Plain { hunks } if hunks.0.is_empty() => String::new(),
+            FileDiff::Plain { hunks } => {
+                let mut patch = format!("--- {}\n+++ {}\n", old, new);
+                for hunk in hunks.iter() {
+                    hunk.write_patch(&mut patch);
+                }
+                patch
+            },
+            FileDiff::Submodule {
+                old: old_id,
+                new: new_id,
+            } => {
+                let mut patch = format!("--- {}\n+++ {}\n", old, new);
+                match (old_path, new_path) {
+                    (None, _) => patch.push_str("@@ -0,0 +1 @@\n"),
+                    (_, None) => patch.push_str("@@ -1 +0,0 @@\n"),
+                    _ => patch.push_str("@@ -1 +1 @@\n"),
+                }
+                if old_path.is_some() {
+                    patch.push_str(&format!("-Subproject commit {}\n", old_id));
+                }
+                if new_path.is_some() {
+                    patch.push_str(&format!("+Subproject commit {}\n", new_id));
+                }
+                patch
+            },
+            // Only the modes are known, which git gives in the extended
+            // header of the file.
+            FileDiff::TypeChange { old, new } => format!(
+                "old mode {:o}\nnew mode {:o}\n",
+                i32::from(*old),
+                i32::from(*new)
+            ),
+        }
+    }
+}
+
+fn patch_path(prefix: &str, path: Option<&Path>) -> String {
+    match path {
+        Some(path) => format!("{}/{}", prefix, path),
+        None => "/dev/null".to_string(),
+    }
+}
+
 /// A set of line changes.
 #[cfg_attr(
     feature = "serialize",
@@ -185,6 +339,40 @@ impl TryFrom<git2::Patch<'_>> for Hunks {
     }
 }
 
+impl Hunk {
+    /// Parse the start and length of the original side of the hunk from its
+    /// header, i.e. `-start,length` in `@@ -start,length +start,length @@`.
+    pub(crate) fn old_range(&self) -> Option<(u32, u32)> {
+        let header = std::str::from_utf8(&self.header.0).ok()?;
+        let range = header.strip_prefix("@@ -")?.split(' ').next()?;
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    }
+
+    fn write_patch(&self, patch: &mut String) {
+        push_line(patch, "", &self.header);
+        for line in &self.lines {
+            match line {
+                LineDiff::Addition { line, .. } => push_line(patch, "+", line),
+                LineDiff::Deletion { line, .. } => push_line(patch, "-", line),
+                LineDiff::Context { line, .. } => push_line(patch, " ", line),
+            }
+        }
+    }
+}
+
+/// Push the `line` with its `origin` marker, taking care of the marker `git`
+/// uses for a line without a trailing newline.
+fn push_line(patch: &mut String, origin: &str, line: &Line) {
+    patch.push_str(origin);
+    patch.push_str(&String::from_utf8_lossy(&line.0));
+    if !line.0.ends_with(b"\n") {
+        patch.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
 /// The content of a single line.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Line(pub(crate) Vec<u8>);
@@ -274,6 +462,83 @@ impl Diff {
         }
     }
 
+    /// Keep only the changes to the files whose path satisfies `f`. A moved or
+    /// copied file is kept if either its old or new path does.
+    pub(crate) fn retain<F>(&mut self, f: F)
+    where
+        F: Fn(&Path) -> bool,
+    {
+        self.created.retain(|created| f(&created.path));
+        self.deleted.retain(|deleted| f(&deleted.path));
+        self.moved
+            .retain(|moved| f(&moved.old_path) || f(&moved.new_path));
+        self.copied
+            .retain(|copied| f(&copied.old_path) || f(&copied.new_path));
+        self.modified.retain(|modified| f(&modified.path));
+    }
+
+    /// Render the `Diff` as the text of a `git diff`, which can then be
+    /// applied with `git apply`.
+    ///
+    /// The files are ordered by their path. Since the `Diff` does not keep
+    /// track of blob ids, the `index` lines are left out of the headers.
+    pub fn to_unified_string(&self) -> String {
+        let mut files: Vec<(String, String)> = Vec::new();
+
+        for CreateFile { path, mode, diff } in &self.created {
+            let header = format!(
+                "diff --git {} {}\nnew file mode {:o}\n",
+                patch_path("a", Some(path)),
+                patch_path("b", Some(path)),
+                i32::from(*mode)
+            );
+            files.push((path.to_string(), header + &diff.to_patch(None, Some(path))));
+        }
+        for DeleteFile { path, mode, diff } in &self.deleted {
+            let header = format!(
+                "diff --git {} {}\ndeleted file mode {:o}\n",
+                patch_path("a", Some(path)),
+                patch_path("b", Some(path)),
+                i32::from(*mode)
+            );
+            files.push((path.to_string(), header + &diff.to_patch(Some(path), None)));
+        }
+        for MoveFile { old_path, new_path } in &self.moved {
+            let header = format!(
+                "diff --git {} {}\nsimilarity index 100%\nrename from {}\nrename to {}\n",
+                patch_path("a", Some(old_path)),
+                patch_path("b", Some(new_path)),
+                old_path,
+                new_path
+            );
+            files.push((new_path.to_string(), header));
+        }
+        for CopyFile { old_path, new_path } in &self.copied {
+            let header = format!(
+                "diff --git {} {}\nsimilarity index 100%\ncopy from {}\ncopy to {}\n",
+                patch_path("a", Some(old_path)),
+                patch_path("b", Some(new_path)),
+                old_path,
+                new_path
+            );
+            files.push((new_path.to_string(), header));
+        }
+        for ModifiedFile { path, diff, .. } in &self.modified {
+            let header = format!(
+                "diff --git {} {}\n",
+                patch_path("a", Some(path)),
+                patch_path("b", Some(path))
+            );
+            files.push((
+                path.to_string(),
+                header + &diff.to_patch(Some(path), Some(path)),
+            ));
+        }
+
+        files.sort_by(|(left, _), (right, _)| left.cmp(right));
+        files.into_iter().map(|(_, patch)| patch).collect()
+    }
+
     // TODO: Direction of comparison is not obvious with this signature.
     // For now using conventional approach with the right being "newer".
     #[allow(clippy::self_named_constructors)]
@@ -350,6 +615,7 @@ impl Diff {
 
                                 diff.add_created_file(
                                     path,
+                                    git2::FileMode::Blob,
                                     FileDiff::Plain {
                                         hunks: Hunks::default(),
                                     },
@@ -372,6 +638,7 @@ impl Diff {
                                 diff.add_created_files(new_entry, parent_path);
                                 diff.add_deleted_file(
                                     path,
+                                    git2::FileMode::Blob,
                                     FileDiff::Plain {
                                         hunks: Hunks::default(),
                                     },
@@ -491,22 +758,39 @@ impl Diff {
         self.copied.push(CopyFile { old_path, new_path });
     }
 
-    pub(crate) fn add_modified_binary_file(&mut self, path: Path) {
+    pub(crate) fn add_modified_binary_file(&mut self, path: Path, diff: FileDiff) {
+        self.modified.push(ModifiedFile {
+            path,
+            diff,
+            eof: None,
+        });
+    }
+
+    pub(crate) fn add_modified_submodule(&mut self, path: Path, diff: FileDiff) {
         self.modified.push(ModifiedFile {
             path,
-            diff: FileDiff::Binary,
+            diff,
             eof: None,
         });
     }
 
-    pub(crate) fn add_created_file(&mut self, path: Path, diff: FileDiff) {
-        self.created.push(CreateFile { path, diff });
+    pub(crate) fn add_type_changed_file(&mut self, path: Path, diff: FileDiff) {
+        self.modified.push(ModifiedFile {
+            path,
+            diff,
+            eof: None,
+        });
+    }
+
+    pub(crate) fn add_created_file(&mut self, path: Path, mode: git2::FileMode, diff: FileDiff) {
+        self.created.push(CreateFile { path, mode, diff });
     }
 
     fn add_created_files(&mut self, dc: &DirectoryContents, parent_path: &Rc<RefCell<Path>>) {
         let mut new_files: Vec<CreateFile> =
             Diff::collect_files_from_entry(dc, parent_path, |path| CreateFile {
                 path,
+                mode: git2::FileMode::Blob,
                 diff: FileDiff::Plain {
                     hunks: Hunks::default(),
                 },
@@ -514,14 +798,15 @@ impl Diff {
         self.created.append(&mut new_files);
     }
 
-    pub(crate) fn add_deleted_file(&mut self, path: Path, diff: FileDiff) {
-        self.deleted.push(DeleteFile { path, diff });
+    pub(crate) fn add_deleted_file(&mut self, path: Path, mode: git2::FileMode, diff: FileDiff) {
+        self.deleted.push(DeleteFile { path, mode, diff });
     }
 
     fn add_deleted_files(&mut self, dc: &DirectoryContents, parent_path: &Rc<RefCell<Path>>) {
         let mut new_files: Vec<DeleteFile> =
             Diff::collect_files_from_entry(dc, parent_path, |path| DeleteFile {
                 path,
+                mode: git2::FileMode::Blob,
                 diff: FileDiff::Plain {
                     hunks: Hunks::default(),
                 },
@@ -550,6 +835,7 @@ mod tests {
         let expected_diff = Diff {
             created: vec![CreateFile {
                 path: Path::with_root(&[unsound::label::new("banana.rs")]),
+                mode: git2::FileMode::Blob,
                 diff: FileDiff::Plain {
                     hunks: Hunks::default(),
                 },
@@ -576,6 +862,7 @@ mod tests {
             created: vec![],
             deleted: vec![DeleteFile {
                 path: Path::with_root(&[unsound::label::new("banana.rs")]),
+                mode: git2::FileMode::Blob,
                 diff: FileDiff::Plain {
                     hunks: Hunks::default(),
                 },
@@ -648,6 +935,7 @@ mod tests {
                     unsound::label::new("src"),
                     unsound::label::new("banana.rs"),
                 ]),
+                mode: git2::FileMode::Blob,
                 diff: FileDiff::Plain {
                     hunks: Hunks::default(),
                 },
@@ -680,6 +968,7 @@ mod tests {
                     unsound::label::new("src"),
                     unsound::label::new("banana.rs"),
                 ]),
+                mode: git2::FileMode::Blob,
                 diff: FileDiff::Plain {
                     hunks: Hunks::default(),
                 },
@@ -764,4 +1053,63 @@ mod tests {
         assert_eq!(diff, expected_diff)
     }
     */
+
+    #[test]
+    fn test_unified_string() {
+        let diff = Diff {
+            created: vec![CreateFile {
+                path: unsound::path::new("src/banana.rs"),
+                mode: git2::FileMode::Blob,
+                diff: FileDiff::Plain {
+                    hunks: vec![Hunk {
+                        header: Line::from(b"@@ -0,0 +1 @@\n".to_vec()),
+                        lines: vec![LineDiff::addition(b"use banana;\n".to_vec(), 1)],
+                    }]
+                    .into(),
+                },
+            }],
+            deleted: vec![],
+            moved: vec![MoveFile {
+                old_path: unsound::path::new("mod.rs"),
+                new_path: unsound::path::new("lib.rs"),
+            }],
+            copied: vec![],
+            modified: vec![ModifiedFile {
+                path: unsound::path::new("README.md"),
+                diff: FileDiff::Plain {
+                    hunks: vec![Hunk {
+                        header: Line::from(b"@@ -1 +1 @@\n".to_vec()),
+                        lines: vec![
+                            LineDiff::deletion(b"banana\n".to_vec(), 1),
+                            LineDiff::addition(b"bananas".to_vec(), 1),
+                        ],
+                    }]
+                    .into(),
+                },
+                eof: Some(EofNewLine::NewMissing),
+            }],
+        };
+
+        let expected = "\
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-banana
++bananas
+\\ No newline at end of file
+diff --git a/mod.rs b/lib.rs
+similarity index 100%
+rename from mod.rs
+rename to lib.rs
+diff --git a/src/banana.rs b/src/banana.rs
+new file mode 100644
+--- /dev/null
++++ b/src/banana.rs
+@@ -0,0 +1 @@
++use banana;
+";
+
+        assert_eq!(diff.to_unified_string(), expected)
+    }
 }
@@ -18,7 +18,7 @@
 use std::convert::TryFrom;
 
 use crate::{
-    diff::{self, Diff, EofNewLine, Hunk, Hunks, Line, LineDiff},
+    diff::{self, Diff, EofNewLine, FileStatus, FileSummary, Hunk, Hunks, Line, LineDiff},
     file_system::Path,
 };
 
@@ -83,6 +83,203 @@ impl<'a> TryFrom<git2::DiffLine<'a>> for LineDiff {
     }
 }
 
+/// Collect the hunks of a patch, noting whether either side of the patch is
+/// missing a newline at the end of the file.
+fn hunks_and_eof(patch: &git2::Patch) -> Result<(Vec<Hunk>, Option<EofNewLine>), error::Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut old_missing_eof = false;
+    let mut new_missing_eof = false;
+
+    for h in 0..patch.num_hunks() {
+        let (hunk, hunk_lines) = patch.hunk(h)?;
+        let header = Line(hunk.header().to_owned());
+        let mut lines: Vec<LineDiff> = Vec::new();
+
+        for l in 0..hunk_lines {
+            let line = patch.line_in_hunk(h, l)?;
+            match line.origin_value() {
+                git2::DiffLineType::ContextEOFNL => {
+                    new_missing_eof = true;
+                    old_missing_eof = true;
+                    continue;
+                },
+                git2::DiffLineType::AddEOFNL => {
+                    old_missing_eof = true;
+                    continue;
+                },
+                git2::DiffLineType::DeleteEOFNL => {
+                    new_missing_eof = true;
+                    continue;
+                },
+                _ => {},
+            }
+            let line = LineDiff::try_from(line)?;
+            lines.push(line);
+        }
+        hunks.push(Hunk { header, lines });
+    }
+    let eof = match (old_missing_eof, new_missing_eof) {
+        (true, true) => Some(EofNewLine::BothMissing),
+        (true, false) => Some(EofNewLine::OldMissing),
+        (false, true) => Some(EofNewLine::NewMissing),
+        (false, false) => None,
+    };
+    Ok((hunks, eof))
+}
+
+/// Get the [`diff::FileDiff::Submodule`] for a delta if either side of it is a
+/// gitlink, i.e. the commit that a submodule points to.
+fn submodule(delta: &git2::DiffDelta) -> Option<diff::FileDiff> {
+    let is_gitlink = |file: git2::DiffFile| file.mode() == git2::FileMode::Commit;
+    if is_gitlink(delta.old_file()) || is_gitlink(delta.new_file()) {
+        Some(diff::FileDiff::Submodule {
+            old: delta.old_file().id(),
+            new: delta.new_file().id(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Get the [`diff::FileDiff::Binary`] for a delta, describing the blobs on
+/// each side of it.
+fn binary(delta: &git2::DiffDelta) -> diff::FileDiff {
+    let blob = |file: git2::DiffFile| {
+        if file.exists() {
+            Some(diff::BinaryFile {
+                id: file.id(),
+                size: file.size(),
+            })
+        } else {
+            None
+        }
+    };
+    diff::FileDiff::Binary {
+        old: blob(delta.old_file()),
+        new: blob(delta.new_file()),
+    }
+}
+
+/// Get the [`diff::FileDiff::TypeChange`] for a delta if the type of the file
+/// differs between both sides of it, e.g. a file that became a symlink.
+///
+/// Changing whether a file is executable does not change its type.
+fn type_change(delta: &git2::DiffDelta) -> Option<diff::FileDiff> {
+    use git2::FileMode::{Blob, BlobExecutable};
+
+    let old = delta.old_file().mode();
+    let new = delta.new_file().mode();
+    match (old, new) {
+        (Blob, BlobExecutable) | (BlobExecutable, Blob) => None,
+        (old, new) if old != new => Some(diff::FileDiff::TypeChange { old, new }),
+        _ => None,
+    }
+}
+
+/// Compute the changes between two versions of a file's contents, where
+/// `None` means that the file does not exist on that side.
+pub(crate) fn diff_buffers(
+    path: &Path,
+    old: Option<&[u8]>,
+    new: Option<&[u8]>,
+) -> Result<(diff::FileDiff, Option<EofNewLine>), error::Diff> {
+    let name = std::path::PathBuf::from(path.to_string());
+    let patch = git2::Patch::from_buffers(
+        old.unwrap_or_default(),
+        old.map(|_| name.as_path()),
+        new.unwrap_or_default(),
+        new.map(|_| name.as_path()),
+        None,
+    )?;
+
+    let delta = patch.delta();
+    if delta.flags().is_binary() {
+        let blob = |file: git2::DiffFile, buffer: &[u8]| diff::BinaryFile {
+            id: file.id(),
+            size: buffer.len() as u64,
+        };
+        let diff = diff::FileDiff::Binary {
+            old: old.map(|buffer| blob(delta.old_file(), buffer)),
+            new: new.map(|buffer| blob(delta.new_file(), buffer)),
+        };
+        return Ok((diff, None));
+    }
+    let (hunks, eof) = hunks_and_eof(&patch)?;
+    Ok((
+        diff::FileDiff::Plain {
+            hunks: hunks.into(),
+        },
+        eof,
+    ))
+}
+
+/// The path of a changed file in the diff along with how it changed, where the
+/// path is the new one for a file that was moved or copied.
+fn path_and_status(delta: &git2::DiffDelta) -> Result<(Path, FileStatus), error::Diff> {
+    use git2::Delta;
+
+    let to_path = |file: git2::DiffFile| -> Result<Path, error::Diff> {
+        let path = file.path().ok_or(error::Diff::PathUnavailable)?;
+        Ok(Path::try_from(path.to_path_buf())?)
+    };
+
+    Ok(match delta.status() {
+        Delta::Added => (to_path(delta.new_file())?, FileStatus::Created),
+        Delta::Deleted => (to_path(delta.old_file())?, FileStatus::Deleted),
+        Delta::Modified => (to_path(delta.new_file())?, FileStatus::Modified),
+        Delta::Typechange => (to_path(delta.new_file())?, FileStatus::TypeChanged),
+        Delta::Renamed => (
+            to_path(delta.new_file())?,
+            FileStatus::Moved {
+                old_path: to_path(delta.old_file())?,
+            },
+        ),
+        Delta::Copied => (
+            to_path(delta.new_file())?,
+            FileStatus::Copied {
+                old_path: to_path(delta.old_file())?,
+            },
+        ),
+        status => return Err(error::Diff::DeltaUnhandled(status)),
+    })
+}
+
+/// List the path and status of each file in the diff, without looking at the
+/// contents of the files.
+pub(crate) fn paths(git_diff: &git2::Diff) -> Result<Vec<(Path, FileStatus)>, error::Diff> {
+    git_diff
+        .deltas()
+        .map(|delta| path_and_status(&delta))
+        .collect()
+}
+
+/// Summarise the changes of each file in the diff, counting the lines that
+/// were added and deleted without collecting the hunks.
+pub(crate) fn summary(git_diff: &git2::Diff) -> Result<Vec<FileSummary>, error::Diff> {
+    let mut summaries = Vec::with_capacity(git_diff.deltas().len());
+    for (idx, delta) in git_diff.deltas().enumerate() {
+        let (path, status) = path_and_status(&delta)?;
+
+        let (additions, deletions) = match git2::Patch::from_diff(git_diff, idx)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            },
+            None => (0, 0),
+        };
+
+        summaries.push(FileSummary {
+            path,
+            status,
+            additions,
+            deletions,
+            binary: delta.flags().is_binary(),
+        });
+    }
+
+    Ok(summaries)
+}
+
 impl<'a> TryFrom<git2::Diff<'a>> for Diff {
     type Error = error::Diff;
 
@@ -97,11 +294,17 @@ impl<'a> TryFrom<git2::Diff<'a>> for Diff {
                     let diff_file = delta.new_file();
                     let path = diff_file.path().ok_or(error::Diff::PathUnavailable)?;
                     let path = Path::try_from(path.to_path_buf())?;
+                    let mode = diff_file.mode();
 
                     let patch = Patch::from_diff(&git_diff, idx)?;
-                    if let Some(patch) = patch {
+                    if let Some(submodule) = submodule(&delta) {
+                        diff.add_created_file(path, mode, submodule);
+                    } else if delta.flags().is_binary() {
+                        diff.add_created_file(path, mode, binary(&delta));
+                    } else if let Some(patch) = patch {
                         diff.add_created_file(
                             path,
+                            mode,
                             diff::FileDiff::Plain {
                                 hunks: Hunks::try_from(patch)?,
                             },
@@ -109,6 +312,7 @@ impl<'a> TryFrom<git2::Diff<'a>> for Diff {
                     } else {
                         diff.add_created_file(
                             path,
+                            mode,
                             diff::FileDiff::Plain {
                                 hunks: Hunks::default(),
                             },
@@ -119,11 +323,17 @@ impl<'a> TryFrom<git2::Diff<'a>> for Diff {
                     let diff_file = delta.old_file();
                     let path = diff_file.path().ok_or(error::Diff::PathUnavailable)?;
                     let path = Path::try_from(path.to_path_buf())?;
+                    let mode = diff_file.mode();
 
                     let patch = Patch::from_diff(&git_diff, idx)?;
-                    if let Some(patch) = patch {
+                    if let Some(submodule) = submodule(&delta) {
+                        diff.add_deleted_file(path, mode, submodule);
+                    } else if delta.flags().is_binary() {
+                        diff.add_deleted_file(path, mode, binary(&delta));
+                    } else if let Some(patch) = patch {
                         diff.add_deleted_file(
                             path,
+                            mode,
                             diff::FileDiff::Plain {
                                 hunks: Hunks::try_from(patch)?,
                             },
@@ -131,6 +341,7 @@ impl<'a> TryFrom<git2::Diff<'a>> for Diff {
                     } else {
                         diff.add_deleted_file(
                             path,
+                            mode,
                             diff::FileDiff::Plain {
                                 hunks: Hunks::default(),
                             },
@@ -144,52 +355,36 @@ impl<'a> TryFrom<git2::Diff<'a>> for Diff {
 
                     let patch = Patch::from_diff(&git_diff, idx)?;
 
-                    if let Some(patch) = patch {
-                        let mut hunks: Vec<Hunk> = Vec::new();
-                        let mut old_missing_eof = false;
-                        let mut new_missing_eof = false;
-
-                        for h in 0..patch.num_hunks() {
-                            let (hunk, hunk_lines) = patch.hunk(h)?;
-                            let header = Line(hunk.header().to_owned());
-                            let mut lines: Vec<LineDiff> = Vec::new();
-
-                            for l in 0..hunk_lines {
-                                let line = patch.line_in_hunk(h, l)?;
-                                match line.origin_value() {
-                                    git2::DiffLineType::ContextEOFNL => {
-                                        new_missing_eof = true;
-                                        old_missing_eof = true;
-                                        continue;
-                                    },
-                                    git2::DiffLineType::AddEOFNL => {
-                                        old_missing_eof = true;
-                                        continue;
-                                    },
-                                    git2::DiffLineType::DeleteEOFNL => {
-                                        new_missing_eof = true;
-                                        continue;
-                                    },
-                                    _ => {},
-                                }
-                                let line = LineDiff::try_from(line)?;
-                                lines.push(line);
-                            }
-                            hunks.push(Hunk { header, lines });
-                        }
-                        let eof = match (old_missing_eof, new_missing_eof) {
-                            (true, true) => Some(EofNewLine::BothMissing),
-                            (true, false) => Some(EofNewLine::OldMissing),
-                            (false, true) => Some(EofNewLine::NewMissing),
-                            (false, false) => None,
-                        };
+                    if let Some(type_change) = type_change(&delta) {
+                        diff.add_type_changed_file(path, type_change);
+                    } else if let Some(submodule) = submodule(&delta) {
+                        diff.add_modified_submodule(path, submodule);
+                    } else if delta.flags().is_binary() {
+                        diff.add_modified_binary_file(path, binary(&delta));
+                    } else if let Some(patch) = patch {
+                        let (hunks, eof) = hunks_and_eof(&patch)?;
                         diff.add_modified_file(path, hunks, eof);
                     } else if diff_file.is_binary() {
-                        diff.add_modified_binary_file(path);
+                        diff.add_modified_binary_file(path, binary(&delta));
                     } else {
                         return Err(error::Diff::PatchUnavailable(path));
                     }
                 },
+                Delta::Typechange => {
+                    let path = delta
+                        .new_file()
+                        .path()
+                        .ok_or(error::Diff::PathUnavailable)?;
+                    let path = Path::try_from(path.to_path_buf())?;
+
+                    diff.add_type_changed_file(
+                        path,
+                        diff::FileDiff::TypeChange {
+                            old: delta.old_file().mode(),
+                            new: delta.new_file().mode(),
+                        },
+                    );
+                },
                 Delta::Renamed => {
                     let old = delta
                         .old_file()
@@ -268,6 +463,63 @@ index f89e4c0..7c56eb7 100644
         assert_eq!(diff.modified[0].eof, None);
     }
 
+    #[test]
+    fn test_submodule() {
+        let buf = r#"
+diff --git a/vendor/banana b/vendor/banana
+index 1111111111111111111111111111111111111111..2222222222222222222222222222222222222222 160000
+--- a/vendor/banana
++++ b/vendor/banana
+@@ -1 +1 @@
+-Subproject commit 1111111111111111111111111111111111111111
++Subproject commit 2222222222222222222222222222222222222222
+"#;
+        let diff = git2::Diff::from_buffer(buf.as_bytes()).unwrap();
+        let diff = Diff::try_from(diff).unwrap();
+        assert_eq!(
+            diff.modified[0].diff,
+            diff::FileDiff::Submodule {
+                old: git2::Oid::from_str("1111111111111111111111111111111111111111").unwrap(),
+                new: git2::Oid::from_str("2222222222222222222222222222222222222222").unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_type_change() {
+        let buf = r#"
+diff --git a/link b/link
+old mode 100644
+new mode 120000
+index 1111111..2222222
+--- a/link
++++ b/link
+@@ -1 +1 @@
+-hello
++target
+\ No newline at end of file
+"#;
+        let diff = git2::Diff::from_buffer(buf.as_bytes()).unwrap();
+        let diff = Diff::try_from(diff).unwrap();
+        assert_eq!(
+            diff.modified[0].diff,
+            diff::FileDiff::TypeChange {
+                old: git2::FileMode::Blob,
+                new: git2::FileMode::Link,
+            }
+        );
+        assert_eq!(
+            diff.modified[0]
+                .diff
+                .to_patch(Some(&diff.modified[0].path), Some(&diff.modified[0].path)),
+            "old mode 100644\nnew mode 120000\n"
+        );
+        assert_eq!(
+            diff.to_unified_string(),
+            "diff --git a/link b/link\nold mode 100644\nnew mode 120000\n"
+        );
+    }
+
     // TODO(xphoniex): uncomment once libgit2 has fixed the bug
     //#[test]
     fn test_old_missing_eof_newline() {
@@ -110,9 +110,22 @@
 //! );
 //! ```
 
+#[cfg(any(test, feature = "proptest"))]
+pub(crate) mod arbitrary;
+pub mod attributes;
 pub mod directory;
+pub mod encoding;
 mod error;
-pub use error::Error;
+pub use error::{ApplyError, BuildError, Error};
+pub mod languages;
+pub mod license;
+#[cfg(feature = "media")]
+pub mod media;
+pub mod owners;
 mod path;
+pub mod pathspec;
+pub use pathspec::Pathspec;
+pub(crate) mod pattern;
+pub mod readme;
 
 pub use self::{directory::*, path::*};
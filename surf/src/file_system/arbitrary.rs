@@ -0,0 +1,99 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! [`Arbitrary`] instances for the file system types, for fuzzing
+//! file system invariants with [`proptest`].
+//!
+//! These are only available when the `proptest` feature is enabled. The
+//! strategies they are built from are also used by the crate's own tests.
+
+use crate::file_system::{unsound, Label, Path};
+use proptest::{collection, prelude::*};
+
+/// Generate a [`Label`] of printable ASCII characters.
+pub(crate) fn label() -> impl Strategy<Value = Label> {
+    // ASCII regex, excluding '/' because of posix file paths
+    "[ -.|0-~]+".prop_map(|label| unsound::label::new(&label))
+}
+
+/// Generate a [`Path`] of up to `max_depth` labels following the first one.
+pub(crate) fn path(max_depth: usize) -> impl Strategy<Value = Path> {
+    (label(), collection::vec(label(), 0..max_depth))
+        .prop_map(|(label, labels)| Path((label, labels).into()))
+}
+
+#[cfg(feature = "proptest")]
+mod instances {
+    use super::{label, path};
+    use crate::file_system::{Directory, DirectoryBuilder, File, Label, Path};
+    use proptest::{collection, prelude::*};
+
+    /// The maximum number of labels following the first one in a [`Path`].
+    const MAX_DEPTH: usize = 5;
+    /// The maximum number of files in a [`Directory`].
+    const MAX_FILES: usize = 10;
+    /// The maximum size of a [`File`].
+    const MAX_SIZE: usize = 256;
+
+    impl Arbitrary for Label {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            label().boxed()
+        }
+    }
+
+    impl Arbitrary for Path {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            path(MAX_DEPTH).boxed()
+        }
+    }
+
+    impl Arbitrary for File {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            collection::vec(any::<u8>(), 0..MAX_SIZE)
+                .prop_map(|contents| File::new(&contents))
+                .boxed()
+        }
+    }
+
+    impl Arbitrary for Directory {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            collection::vec((any::<Path>(), any::<File>()), 0..MAX_FILES)
+                .prop_map(|files| {
+                    let mut builder = DirectoryBuilder::new();
+                    for (path, file) in files {
+                        builder.insert_file(path, &file.contents);
+                    }
+                    builder
+                        .build()
+                        .expect("no directories were inserted, so none can be empty")
+                })
+                .boxed()
+        }
+    }
+}
@@ -0,0 +1,430 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Interpreting the `.gitattributes` files of a [`Directory`], so that
+//! browsing can collapse generated files, archives can leave out the files
+//! marked `export-ignore`, and files can be read as they would be checked out.
+//!
+//! See [`Directory::attributes_for`] and [`Directory::file_contents`].
+
+use crate::file_system::{encoding::Encoding, pattern, Directory, Path};
+use regex::Regex;
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+};
+
+/// The name of the files that attributes are read from.
+pub const GITATTRIBUTES: &str = ".gitattributes";
+
+/// The state of an attribute, for a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    /// The attribute is set, written as `attr`.
+    Set,
+    /// The attribute is unset, written as `-attr`.
+    Unset,
+    /// The attribute is set to a value, written as `attr=value`.
+    Value(String),
+}
+
+/// The attributes of a path, by name. Attributes that are not specified for
+/// the path are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attributes(BTreeMap<String, State>);
+
+impl Attributes {
+    /// The state of the attribute `name`, or `None` if it is unspecified.
+    pub fn get(&self, name: &str) -> Option<&State> {
+        self.0.get(name)
+    }
+
+    /// Whether the attribute `name` is set, or set to `true`.
+    pub fn is_set(&self, name: &str) -> bool {
+        match self.get(name) {
+            Some(State::Set) => true,
+            Some(State::Value(value)) => value == "true",
+            _ => false,
+        }
+    }
+
+    /// Iterate over the attributes that are specified, by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &State)> {
+        self.0.iter().map(|(name, state)| (name.as_str(), state))
+    }
+
+    /// Whether the path is marked as `binary`.
+    pub fn is_binary(&self) -> bool {
+        self.is_set("binary")
+    }
+
+    /// Whether the path is marked as generated, with `linguist-generated`.
+    pub fn is_generated(&self) -> bool {
+        self.is_set("linguist-generated")
+    }
+
+    /// Whether the path is left out of archives, with `export-ignore`.
+    pub fn is_export_ignored(&self) -> bool {
+        self.is_set("export-ignore")
+    }
+
+    /// The encoding the path is checked out in, given by the
+    /// `working-tree-encoding` attribute, if it is one that is recognised.
+    pub fn working_tree_encoding(&self) -> Option<Encoding> {
+        match self.get("working-tree-encoding")? {
+            State::Value(name) => Encoding::from_name(name),
+            _ => None,
+        }
+    }
+
+    fn apply(&mut self, (name, state): &(String, Option<State>)) {
+        match state {
+            Some(state) => self.0.insert(name.clone(), state.clone()),
+            None => self.0.remove(name),
+        };
+    }
+}
+
+/// How the contents of a file are read with [`Directory::file_contents`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    /// The contents as they are stored.
+    #[default]
+    Raw,
+    /// The contents as git would check them out, applying the `eol` and
+    /// `working-tree-encoding` attributes of the file. Line endings are
+    /// converted to CRLF for text files with `eol=crlf`, and UTF-8 contents
+    /// are re-encoded in the `working-tree-encoding`, if it can represent
+    /// them.
+    Text,
+}
+
+/// The rules of a single `.gitattributes` file.
+#[derive(Debug, Clone, Default)]
+pub struct GitAttributes {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Regex,
+    /// The attributes, where `None` is unspecified, written as `!attr`.
+    attributes: Vec<(String, Option<State>)>,
+}
+
+impl GitAttributes {
+    /// Parse the `contents` of a `.gitattributes` file.
+    ///
+    /// Blank lines and comments, starting with `#`, are skipped, as are macro
+    /// definitions and the lines whose pattern cannot be understood. The
+    /// built-in `binary` macro is expanded to `-diff -merge -text`.
+    pub fn parse(contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                let pattern = words
+                    .next()
+                    .filter(|word| !word.starts_with('#') && !word.starts_with("[attr]"))?;
+                let mut attributes = vec![];
+                for word in words {
+                    let attribute = if let Some(name) = word.strip_prefix('-') {
+                        (name.to_string(), Some(State::Unset))
+                    } else if let Some(name) = word.strip_prefix('!') {
+                        (name.to_string(), None)
+                    } else if let Some((name, value)) = word.split_once('=') {
+                        (name.to_string(), Some(State::Value(value.to_string())))
+                    } else {
+                        (word.to_string(), Some(State::Set))
+                    };
+                    if attribute == ("binary".to_string(), Some(State::Set)) {
+                        for name in &["diff", "merge", "text"] {
+                            attributes.push((name.to_string(), Some(State::Unset)));
+                        }
+                    }
+                    attributes.push(attribute);
+                }
+                Some(Rule {
+                    pattern: pattern::to_regex(pattern, false)?,
+                    attributes,
+                })
+            })
+            .collect();
+        GitAttributes { rules }
+    }
+
+    /// Apply the rules that match `path`, relative to the directory of the
+    /// file, in order, so that the later rules win.
+    fn apply(&self, path: &str, attributes: &mut Attributes) {
+        for rule in &self.rules {
+            if rule.pattern.is_match(path) {
+                rule.attributes
+                    .iter()
+                    .for_each(|attribute| attributes.apply(attribute));
+            }
+        }
+    }
+}
+
+impl Directory {
+    /// Get the [`Attributes`] of the file or directory at `path`, as given by
+    /// the `.gitattributes` files of this `Directory`.
+    ///
+    /// The `.gitattributes` files in the directories leading to `path` are
+    /// applied from the outermost to the innermost, so that the rules of a
+    /// nested file win.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{attributes::State, unsound, DirectoryBuilder};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut builder = DirectoryBuilder::new();
+    /// builder
+    ///     .insert_file(
+    ///         unsound::path::new(".gitattributes"),
+    ///         b"*.lock linguist-generated\n*.png binary\n/tests export-ignore\n",
+    ///     )
+    ///     .insert_file(unsound::path::new("Cargo.lock"), b"")
+    ///     .insert_file(unsound::path::new("logo.png"), b"")
+    ///     .insert_file(unsound::path::new("tests/it.rs"), b"");
+    /// let directory = builder.build()?;
+    ///
+    /// assert!(directory.attributes_for(&unsound::path::new("~/Cargo.lock")).is_generated());
+    ///
+    /// let logo = directory.attributes_for(&unsound::path::new("~/logo.png"));
+    /// assert!(logo.is_binary());
+    /// assert_eq!(logo.get("diff"), Some(&State::Unset));
+    ///
+    /// // Attributes of a directory are not inherited by its files.
+    /// assert!(directory.attributes_for(&unsound::path::new("~/tests")).is_export_ignored());
+    /// assert!(!directory.attributes_for(&unsound::path::new("~/tests/it.rs")).is_export_ignored());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn attributes_for(&self, path: &Path) -> Attributes {
+        self.attributes_with(path, &mut HashMap::new())
+    }
+
+    /// Get the contents of the file at `path`, converted according to
+    /// `conversion`, or `None` if there is no such file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{attributes::Conversion, unsound, DirectoryBuilder};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut builder = DirectoryBuilder::new();
+    /// builder
+    ///     .insert_file(unsound::path::new(".gitattributes"), b"*.bat text eol=crlf\n")
+    ///     .insert_file(unsound::path::new("run.bat"), b"echo hi\r\necho bye\n");
+    /// let directory = builder.build()?;
+    ///
+    /// let run = unsound::path::new("run.bat");
+    /// assert_eq!(
+    ///     directory.file_contents(run.clone(), Conversion::Raw),
+    ///     Some(b"echo hi\r\necho bye\n".to_vec())
+    /// );
+    /// assert_eq!(
+    ///     directory.file_contents(run, Conversion::Text),
+    ///     Some(b"echo hi\r\necho bye\r\n".to_vec())
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn file_contents(&self, path: Path, conversion: Conversion) -> Option<Vec<u8>> {
+        let file = self.find_file(path.clone())?;
+        match conversion {
+            Conversion::Raw => Some(file.contents),
+            Conversion::Text => Some(to_working_tree(&self.attributes_for(&path), file.contents)),
+        }
+    }
+
+    /// Get the [`Attributes`] of `path`, as in [`Directory::attributes_for`],
+    /// keeping the `.gitattributes` files that are parsed in `parsed`, by the
+    /// path of their directory, so that they can be reused for other paths.
+    pub(crate) fn attributes_with(
+        &self,
+        path: &Path,
+        parsed: &mut HashMap<String, Option<GitAttributes>>,
+    ) -> Attributes {
+        let labels = path
+            .iter()
+            .filter(|label| !label.is_root())
+            .map(|label| label.as_str())
+            .collect::<Vec<_>>();
+
+        let mut attributes = Attributes::default();
+        for depth in 0..labels.len() {
+            let (directory, relative) = labels.split_at(depth);
+            let directory = directory.join("/");
+            let rules = parsed.entry(directory).or_insert_with_key(|directory| {
+                let location = if directory.is_empty() {
+                    GITATTRIBUTES.to_string()
+                } else {
+                    format!("{}/{}", directory, GITATTRIBUTES)
+                };
+                let file = self.find_file(Path::try_from(location.as_str()).ok()?)?;
+                Some(GitAttributes::parse(&String::from_utf8_lossy(
+                    &file.contents,
+                )))
+            });
+            if let Some(rules) = rules {
+                rules.apply(&relative.join("/"), &mut attributes);
+            }
+        }
+        attributes
+    }
+}
+
+/// Convert `contents` as git does when checking them out, first converting
+/// the line endings and then the encoding.
+fn to_working_tree(attributes: &Attributes, contents: Vec<u8>) -> Vec<u8> {
+    let is_binary = contents.contains(&0);
+    let is_text = match attributes.get("text") {
+        Some(State::Unset) => false,
+        Some(State::Value(value)) if value == "auto" => !is_binary,
+        Some(_) => true,
+        // Setting `eol` implies `text`.
+        None => attributes.get("eol").is_some(),
+    };
+    let crlf = matches!(attributes.get("eol"), Some(State::Value(eol)) if eol == "crlf");
+
+    let contents = if is_text && crlf {
+        let mut converted = Vec::with_capacity(contents.len());
+        for (i, byte) in contents.iter().enumerate() {
+            if *byte == b'\n' && (i == 0 || contents[i - 1] != b'\r') {
+                converted.push(b'\r');
+            }
+            converted.push(*byte);
+        }
+        converted
+    } else {
+        contents
+    };
+
+    let encoding = match attributes.get("working-tree-encoding") {
+        Some(State::Value(name)) => Encoding::from_name(name).map(|encoding| {
+            // Like iconv, git writes a byte order mark for plain `UTF-16`.
+            let name = name.to_ascii_uppercase();
+            (encoding, name == "UTF-16" || name.ends_with("-BOM"))
+        }),
+        _ => None,
+    };
+    match (encoding, std::str::from_utf8(&contents)) {
+        (Some((encoding, bom)), Ok(text)) => match encoding.encode(text) {
+            Some(encoded) if bom => [encoding.bom(), &encoded].concat(),
+            Some(encoded) => encoded,
+            None => contents,
+        },
+        _ => contents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system::{unsound, DirectoryBuilder};
+
+    #[test]
+    fn states_and_precedence() {
+        let rules = GitAttributes::parse(
+            "# defaults\n\
+             * text=auto eol=lf\n\
+             *.sh -text\n\
+             vendor/** linguist-vendored\n\
+             vendor/keep.sh !eol\n",
+        );
+        let attributes = |path: &str| {
+            let mut attributes = Attributes::default();
+            rules.apply(path, &mut attributes);
+            attributes
+        };
+
+        let readme = attributes("README");
+        assert_eq!(readme.get("text"), Some(&State::Value("auto".to_string())));
+        assert!(!readme.is_set("text"));
+
+        let script = attributes("vendor/keep.sh");
+        assert_eq!(script.get("text"), Some(&State::Unset));
+        assert_eq!(script.get("eol"), None);
+        assert!(script.is_set("linguist-vendored"));
+    }
+
+    #[test]
+    fn nested_files_win() {
+        let mut builder = DirectoryBuilder::new();
+        builder
+            .insert_file(
+                unsound::path::new(".gitattributes"),
+                b"*.json linguist-generated",
+            )
+            .insert_file(
+                unsound::path::new("api/.gitattributes"),
+                b"*.json -linguist-generated",
+            )
+            .insert_file(unsound::path::new("api/schema.json"), b"{}")
+            .insert_file(unsound::path::new("data.json"), b"{}");
+        let directory = builder.build().unwrap();
+
+        assert!(directory
+            .attributes_for(&unsound::path::new("~/data.json"))
+            .is_generated());
+        assert_eq!(
+            directory
+                .attributes_for(&unsound::path::new("~/api/schema.json"))
+                .get("linguist-generated"),
+            Some(&State::Unset)
+        );
+    }
+
+    #[test]
+    fn text_conversion() {
+        let rules = GitAttributes::parse(
+            "*.txt text eol=crlf\n\
+             *.bin -text eol=crlf\n\
+             *.auto text=auto eol=crlf\n\
+             *.utf16 working-tree-encoding=UTF-16\n\
+             *.latin1 working-tree-encoding=ISO-8859-1\n",
+        );
+        let convert = |path: &str, contents: &[u8]| {
+            let mut attributes = Attributes::default();
+            rules.apply(path, &mut attributes);
+            to_working_tree(&attributes, contents.to_vec())
+        };
+
+        assert_eq!(convert("a.txt", b"a\nb\r\n"), b"a\r\nb\r\n");
+        assert_eq!(convert("a.bin", b"a\nb"), b"a\nb");
+        assert_eq!(convert("a.auto", b"a\0\nb"), b"a\0\nb");
+        assert_eq!(convert("a.auto", b"a\nb"), b"a\r\nb");
+        assert_eq!(convert("README", b"a\nb"), b"a\nb");
+
+        assert_eq!(
+            convert("a.utf16", "hé".as_bytes()),
+            [0xff, 0xfe, b'h', 0, 0xe9, 0]
+        );
+        assert_eq!(convert("a.latin1", "hé".as_bytes()), b"h\xe9");
+        // Characters that Latin-1 cannot represent are left as UTF-8.
+        assert_eq!(convert("a.latin1", "h€".as_bytes()), "h€".as_bytes());
+    }
+}
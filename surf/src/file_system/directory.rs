@@ -25,7 +25,25 @@
 //! an identifier of what type of [`DirectoryContents`] one is viewing when
 //! [listing](#method.list_directory) a directory.
 
-use crate::{file_system::path::*, tree::*};
+use crate::{
+    diff::{
+        self,
+        CopyFile,
+        CreateFile,
+        DeleteFile,
+        Diff,
+        FileDiff,
+        Hunks,
+        LineDiff,
+        ModifiedFile,
+        MoveFile,
+    },
+    file_system::{
+        error::{ApplyError, BuildError},
+        path::*,
+    },
+    tree::*,
+};
 use nonempty::NonEmpty;
 use std::{
     collections::{hash_map::DefaultHasher, HashMap},
@@ -365,6 +383,56 @@ impl Directory {
         self.sub_directories.find_node(path.0).cloned()
     }
 
+    /// Find a [`File`] given its path as a `/`-separated string, e.g.
+    /// `src/lib.rs`, without building a [`Path`] or cloning the `File`. A
+    /// leading `~/`, for the root, and empty segments are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{Directory, File};
+    /// use radicle_surf::file_system::unsound;
+    ///
+    /// let file = File::new(b"module Banana ...");
+    ///
+    /// let mut directory = Directory::root();
+    /// directory.insert_file(unsound::path::new("foo/bar/baz.rs"), file.clone());
+    ///
+    /// assert_eq!(directory.find("foo/bar/baz.rs"), Some(&file));
+    /// assert_eq!(directory.find("~/foo/bar/baz.rs"), Some(&file));
+    ///
+    /// // Directories are not files
+    /// assert_eq!(directory.find("foo/bar"), None);
+    /// assert_eq!(directory.find("foo/bar/qux.rs"), None);
+    /// ```
+    pub fn find(&self, path: &str) -> Option<&File> {
+        let path = path.strip_prefix("~/").unwrap_or(path);
+        self.find_segments(path.split('/').filter(|segment| !segment.is_empty()))
+    }
+
+    /// Find a [`File`] given the segments of its path, as in
+    /// [`Directory::find`].
+    pub fn find_segments<'a, I>(&self, segments: I) -> Option<&File>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        match self
+            .sub_directories
+            .find_by(segments, |label: &Label| label.as_str())?
+        {
+            SubTree::Node { value, .. } => Some(value),
+            SubTree::Branch { .. } => None,
+        }
+    }
+
+    /// Iterate over all the files in this `Directory` and its
+    /// sub-directories, along with their paths, ordered by their paths.
+    pub(crate) fn iter_files(&self) -> impl Iterator<Item = (Path, &File)> + '_ {
+        self.sub_directories
+            .iter_entries()
+            .map(|(labels, file)| (Path(labels.map(|label| label.clone())), file))
+    }
+
     /// Find a `Directory` in the directory given the [`Path`] to the
     /// `Directory`.
     ///
@@ -504,25 +572,439 @@ impl Directory {
         }
     }
 
-    pub(crate) fn from_hash_map(files: HashMap<Path, NonEmpty<(Label, File)>>) -> Self {
-        let mut directory: Self = Directory::root();
+    /// Apply the changes of a [`Diff`] to the `Directory`, producing a new
+    /// `Directory` and leaving this one untouched.
+    ///
+    /// The paths in the `Diff` are taken to be relative to this `Directory`.
+    /// Copies and moves are applied first, followed by deletions,
+    /// modifications, and finally creations. Changes to submodules are
+    /// skipped, since a `Directory` does not contain them.
+    ///
+    /// # Errors
+    ///
+    /// * [`ApplyError::NotFound`] if a copied, moved, deleted, or modified file
+    ///   does not exist.
+    /// * [`ApplyError::AlreadyExists`] if a created file already exists.
+    /// * [`ApplyError::Conflict`] if the lines of a hunk do not match the file.
+    /// * [`ApplyError::Binary`] if a binary file is created or modified.
+    /// * [`ApplyError::TypeChange`] if the type of a file is changed.
+    /// * [`ApplyError::InvalidHunk`] if a hunk header cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::diff::{CreateFile, Diff, FileDiff, Hunk, LineDiff, ModifiedFile};
+    /// use radicle_surf::file_system::{Directory, File};
+    /// use radicle_surf::file_system::unsound;
+    ///
+    /// let mut directory = Directory::root();
+    /// directory.insert_file(unsound::path::new("README.md"), File::new(b"hello\nworld\n"));
+    ///
+    /// let mut diff = Diff::new();
+    /// diff.modified.push(ModifiedFile {
+    ///     path: unsound::path::new("README.md"),
+    ///     diff: FileDiff::Plain {
+    ///         hunks: vec![Hunk {
+    ///             header: b"@@ -2 +2 @@\n".to_vec().into(),
+    ///             lines: vec![
+    ///                 LineDiff::deletion(b"world\n".to_vec(), 2),
+    ///                 LineDiff::addition(b"banana\n".to_vec(), 2),
+    ///             ],
+    ///         }]
+    ///         .into(),
+    ///     },
+    ///     eof: None,
+    /// });
+    /// diff.created.push(CreateFile {
+    ///     path: unsound::path::new("src/lib.rs"),
+    ///     mode: git2::FileMode::Blob,
+    ///     diff: FileDiff::Plain {
+    ///         hunks: vec![Hunk {
+    ///             header: b"@@ -0,0 +1 @@\n".to_vec().into(),
+    ///             lines: vec![LineDiff::addition(b"pub mod banana;\n".to_vec(), 1)],
+    ///         }]
+    ///         .into(),
+    ///     },
+    /// });
+    ///
+    /// let patched = directory.apply(&diff).expect("failed to apply diff");
+    ///
+    /// assert_eq!(
+    ///     patched.find_file(unsound::path::new("README.md")),
+    ///     Some(File::new(b"hello\nbanana\n"))
+    /// );
+    /// assert_eq!(
+    ///     patched.find_file(unsound::path::new("src/lib.rs")),
+    ///     Some(File::new(b"pub mod banana;\n"))
+    /// );
+    ///
+    /// // The original directory is left as it was
+    /// assert_eq!(
+    ///     directory.find_file(unsound::path::new("README.md")),
+    ///     Some(File::new(b"hello\nworld\n"))
+    /// );
+    /// ```
+    pub fn apply(&self, diff: &Diff) -> Result<Self, ApplyError> {
+        let mut files = self.files();
+
+        for CopyFile { old_path, new_path } in &diff.copied {
+            let file = files
+                .get(&relative(old_path)?)
+                .cloned()
+                .ok_or_else(|| ApplyError::NotFound(old_path.clone()))?;
+            files.insert(relative(new_path)?, file);
+        }
+        for MoveFile { old_path, new_path } in &diff.moved {
+            let file = files
+                .remove(&relative(old_path)?)
+                .ok_or_else(|| ApplyError::NotFound(old_path.clone()))?;
+            files.insert(relative(new_path)?, file);
+        }
+        for DeleteFile { path, diff, .. } in &diff.deleted {
+            if let FileDiff::Submodule { .. } = diff {
+                continue;
+            }
+            files
+                .remove(&relative(path)?)
+                .ok_or_else(|| ApplyError::NotFound(path.clone()))?;
+        }
+        for ModifiedFile { path, diff, .. } in &diff.modified {
+            if let FileDiff::Submodule { .. } = diff {
+                continue;
+            }
+            let file = files
+                .get_mut(&relative(path)?)
+                .ok_or_else(|| ApplyError::NotFound(path.clone()))?;
+            match diff {
+                FileDiff::Binary { .. } => return Err(ApplyError::Binary(path.clone())),
+                FileDiff::TypeChange { .. } => return Err(ApplyError::TypeChange(path.clone())),
+                FileDiff::Submodule { .. } => {},
+                FileDiff::Plain { hunks } => {
+                    *file = File::new(&patch_contents(path, &file.contents, hunks)?);
+                },
+            }
+        }
+        for CreateFile { path, diff, .. } in &diff.created {
+            let key = relative(path)?;
+            if files.contains_key(&key) {
+                return Err(ApplyError::AlreadyExists(path.clone()));
+            }
+            match diff {
+                FileDiff::Binary { .. } => return Err(ApplyError::Binary(path.clone())),
+                FileDiff::TypeChange { .. } => return Err(ApplyError::TypeChange(path.clone())),
+                FileDiff::Submodule { .. } => {},
+                FileDiff::Plain { hunks } => {
+                    files.insert(key, File::new(&patch_contents(path, &[], hunks)?));
+                },
+            }
+        }
+
+        let mut directory = Directory {
+            current: self.current.clone(),
+            sub_directories: Forest::root(),
+        };
+        for (path, file) in files {
+            directory.insert_file(path, file);
+        }
+        Ok(directory)
+    }
+
+    /// Compute the [`Diff`] from this `Directory` to the `other` one, by
+    /// comparing the contents of the files found at the same paths.
+    ///
+    /// Unlike [`Diff::diff`], the hunks of the changed files are computed, so
+    /// any two snapshots can be compared, e.g. the same revision in two
+    /// namespaces. Moved and copied files are not detected, so they are
+    /// reported as created and deleted files. The paths in the `Diff` are
+    /// ordered and, like the paths of a git diff, start from the root
+    /// [`Label`].
+    ///
+    /// # Errors
+    ///
+    /// * [`diff::git::error::Diff`](crate::diff::git::error::Diff) if the
+    ///   changes of a file could not be computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::diff::{FileDiff, Hunk, LineDiff};
+    /// use radicle_surf::file_system::{Directory, File, Path};
+    /// use radicle_surf::file_system::unsound;
+    ///
+    /// let mut old = Directory::root();
+    /// old.insert_file(unsound::path::new("README.md"), File::new(b"hello\nworld\n"));
+    /// old.insert_file(unsound::path::new("src/main.rs"), File::new(b"fn main() {}\n"));
+    ///
+    /// let mut new = Directory::root();
+    /// new.insert_file(unsound::path::new("README.md"), File::new(b"hello\nbanana\n"));
+    ///
+    /// let diff = old.diff(&new).expect("failed to diff");
+    ///
+    /// assert_eq!(diff.deleted[0].path, Path::with_root(&[
+    ///     unsound::label::new("src"),
+    ///     unsound::label::new("main.rs"),
+    /// ]));
+    /// assert_eq!(diff.modified[0].path, Path::with_root(&[unsound::label::new("README.md")]));
+    /// assert_eq!(
+    ///     diff.modified[0].diff,
+    ///     FileDiff::Plain {
+    ///         hunks: vec![Hunk {
+    ///             header: b"@@ -1,2 +1,2 @@\n".to_vec().into(),
+    ///             lines: vec![
+    ///                 LineDiff::context(b"hello\n".to_vec(), 1, 1),
+    ///                 LineDiff::deletion(b"world\n".to_vec(), 2),
+    ///                 LineDiff::addition(b"banana\n".to_vec(), 2),
+    ///             ],
+    ///         }]
+    ///         .into(),
+    ///     }
+    /// );
+    ///
+    /// // Applying the diff gets us back to the new snapshot
+    /// assert_eq!(old.apply(&diff), Ok(new));
+    /// ```
+    pub fn diff(&self, other: &Directory) -> Result<Diff, diff::git::error::Diff> {
+        let old = self.files();
+        let new = other.files();
 
-        for (path, files) in files.into_iter() {
-            for (file_name, file) in files.into_iter() {
-                let file_path = if path.is_root() {
-                    Path::new(file_name)
-                } else {
-                    let mut new_path = path.clone();
-                    new_path.push(file_name);
-                    new_path
-                };
+        let mut paths = old.keys().chain(new.keys()).collect::<Vec<_>>();
+        paths.sort_by_cached_key(|path| path.to_string());
+        paths.dedup();
+
+        let mut result = Diff::new();
+        for path in paths {
+            let old = old.get(path).map(|file| file.contents.as_slice());
+            let new = new.get(path).map(|file| file.contents.as_slice());
+            if old == new {
+                continue;
+            }
 
-                directory.insert_file(file_path, file)
+            let (diff, eof) = diff::git::diff_buffers(path, old, new)?;
+            let path = Path::with_root(&path.iter().cloned().collect::<Vec<_>>());
+            match (old, new) {
+                (None, _) => result.add_created_file(path, git2::FileMode::Blob, diff),
+                (_, None) => result.add_deleted_file(path, git2::FileMode::Blob, diff),
+                _ => result.modified.push(ModifiedFile { path, diff, eof }),
             }
         }
 
-        directory
+        Ok(result)
     }
+
+    /// Get all the files reachable from this `Directory`, keyed by their path
+    /// relative to it.
+    fn files(&self) -> HashMap<Path, File> {
+        let mut files = HashMap::new();
+        let mut stack = vec![(vec![], self.clone())];
+        while let Some((prefix, directory)) = stack.pop() {
+            for entry in directory.iter() {
+                match entry {
+                    DirectoryContents::File { name, file } => {
+                        let mut path = prefix.clone();
+                        path.push(name);
+                        files.insert(
+                            Path(NonEmpty::from_vec(path).expect("path is non-empty")),
+                            file,
+                        );
+                    },
+                    DirectoryContents::Directory(sub_directory) => {
+                        let mut path = prefix.clone();
+                        path.push(sub_directory.current());
+                        stack.push((path, sub_directory));
+                    },
+                }
+            }
+        }
+        files
+    }
+
+    /// Give the `Directory` a new name, where the root [`Label`] makes it the
+    /// root directory.
+    pub(crate) fn relabel(self, label: Label) -> Self {
+        let current = if label.is_root() {
+            Location::Root
+        } else {
+            Location::SubDirectory(label)
+        };
+        Directory { current, ..self }
+    }
+}
+
+/// A builder for a [`Directory`], which takes the full paths of files and
+/// directories rather than grouping files by their parent directory.
+///
+/// Files are inserted into the tree of the `Directory` as they are given, so
+/// that it can be built in a single pass, e.g. while walking a git tree.
+///
+/// Since a `Directory` cannot be empty, every directory inserted via
+/// [`DirectoryBuilder::insert_dir`] must end up with at least one file
+/// beneath it by the time [`DirectoryBuilder::build`] is called.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::file_system::{DirectoryBuilder, File, SystemType};
+/// use radicle_surf::file_system::unsound;
+///
+/// let directory = DirectoryBuilder::new()
+///     .insert_file(unsound::path::new("README.md"), b"Radicle Surfing")
+///     .insert_dir(unsound::path::new("src"))
+///     .insert_file(unsound::path::new("src/lib.rs"), b"pub mod diff;")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(
+///     directory.list_directory(),
+///     vec![
+///         SystemType::file(unsound::label::new("README.md")),
+///         SystemType::directory(unsound::label::new("src")),
+///     ]
+/// );
+/// assert_eq!(
+///     directory.find_file(unsound::path::new("src/lib.rs")),
+///     Some(File::new(b"pub mod diff;"))
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct DirectoryBuilder {
+    directories: Vec<Path>,
+    directory: Directory,
+}
+
+impl Default for DirectoryBuilder {
+    fn default() -> Self {
+        DirectoryBuilder {
+            directories: vec![],
+            directory: Directory::root(),
+        }
+    }
+}
+
+impl DirectoryBuilder {
+    /// Create a builder for an empty root [`Directory`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a file with the given `contents` at `path`, where `path` is the
+    /// file name inclusive. Inserting at the same path twice keeps the last
+    /// contents.
+    pub fn insert_file(&mut self, path: Path, contents: &[u8]) -> &mut Self {
+        self.insert(path, File::new(contents))
+    }
+
+    /// Insert the `file` at `path`, where `path` is the file name inclusive,
+    /// in the same way as [`DirectoryBuilder::insert_file`].
+    pub fn insert(&mut self, path: Path, file: File) -> &mut Self {
+        self.directory.insert_file(path, file);
+        self
+    }
+
+    /// Insert a directory at `path`. Any missing parent directories of a file
+    /// are created anyway, so this is only needed to assert that a directory
+    /// exists.
+    pub fn insert_dir(&mut self, path: Path) -> &mut Self {
+        self.directories.push(path);
+        self
+    }
+
+    /// Build the [`Directory`].
+    ///
+    /// # Errors
+    ///
+    /// * [`BuildError::EmptyDirectory`] if an inserted directory does not
+    ///   contain any files.
+    pub fn build(&self) -> Result<Directory, BuildError> {
+        self.clone().finish()
+    }
+
+    /// Build the [`Directory`], in the same way as [`DirectoryBuilder::build`],
+    /// except that the builder is consumed rather than copied.
+    pub fn finish(self) -> Result<Directory, BuildError> {
+        let sub_directories = &self.directory.sub_directories;
+        if let Some(empty) = self
+            .directories
+            .iter()
+            .find(|directory| sub_directories.find_branch(directory.0.clone()).is_none())
+        {
+            return Err(BuildError::EmptyDirectory(empty.clone()));
+        }
+        Ok(self.directory)
+    }
+}
+
+/// Strip any root labels from a `Path` found in a [`Diff`], so that it is
+/// relative to the `Directory` it is being applied to.
+fn relative(path: &Path) -> Result<Path, ApplyError> {
+    NonEmpty::from_vec(
+        path.iter()
+            .filter(|label| !label.is_root())
+            .cloned()
+            .collect(),
+    )
+    .map(Path)
+    .ok_or_else(|| ApplyError::NotFound(path.clone()))
+}
+
+/// Apply the `hunks` to the `contents` of the file found at `path`.
+fn patch_contents(path: &Path, contents: &[u8], hunks: &Hunks) -> Result<Vec<u8>, ApplyError> {
+    let old = contents
+        .split_inclusive(|b| *b == b'\n')
+        .collect::<Vec<_>>();
+    let mut new = Vec::with_capacity(contents.len());
+    // The index of the next line in `old` that has not been copied over.
+    let mut next = 0;
+
+    for hunk in hunks.iter() {
+        let (start, len) = hunk.old_range().ok_or_else(|| ApplyError::InvalidHunk {
+            path: path.clone(),
+            header: String::from_utf8_lossy(&hunk.header.0).into_owned(),
+        })?;
+        // A hunk without any original lines starts *after* the given line.
+        let at = if len == 0 {
+            start
+        } else {
+            start.saturating_sub(1)
+        } as usize;
+        if at < next || at > old.len() {
+            return Err(ApplyError::Conflict {
+                path: path.clone(),
+                line: start,
+            });
+        }
+        for line in &old[next..at] {
+            new.extend_from_slice(line);
+        }
+        next = at;
+
+        for line in &hunk.lines {
+            let (expected, line_num, keep) = match line {
+                LineDiff::Addition { line, .. } => {
+                    new.extend_from_slice(&line.0);
+                    continue;
+                },
+                LineDiff::Deletion { line, line_num } => (line, *line_num, false),
+                LineDiff::Context {
+                    line, line_num_old, ..
+                } => (line, *line_num_old, true),
+            };
+            if old.get(next) != Some(&expected.0.as_slice()) {
+                return Err(ApplyError::Conflict {
+                    path: path.clone(),
+                    line: line_num,
+                });
+            }
+            if keep {
+                new.extend_from_slice(&expected.0);
+            }
+            next += 1;
+        }
+    }
+
+    for line in &old[next..] {
+        new.extend_from_slice(line);
+    }
+    Ok(new)
 }
 
 #[cfg(test)]
@@ -613,6 +1095,43 @@ pub mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod builder {
+        use crate::file_system::{unsound, BuildError, DirectoryBuilder, File};
+
+        #[test]
+        fn nested_files() {
+            let directory = DirectoryBuilder::new()
+                .insert_file(unsound::path::new("foo/bar/baz.rs"), b"baz")
+                .insert_file(unsound::path::new("foo/quux.rs"), b"quux")
+                .insert_dir(unsound::path::new("foo/bar"))
+                .build()
+                .unwrap();
+
+            assert_eq!(
+                directory.find_file(unsound::path::new("foo/bar/baz.rs")),
+                Some(File::new(b"baz"))
+            );
+            assert_eq!(
+                directory.find_file(unsound::path::new("foo/quux.rs")),
+                Some(File::new(b"quux"))
+            );
+        }
+
+        #[test]
+        fn empty_directory() {
+            let result = DirectoryBuilder::new()
+                .insert_file(unsound::path::new("foo/bar"), b"bar")
+                .insert_dir(unsound::path::new("foo/bar"))
+                .build();
+
+            assert_eq!(
+                result,
+                Err(BuildError::EmptyDirectory(unsound::path::new("foo/bar")))
+            );
+        }
+    }
+
     #[cfg(test)]
     mod properties {
         use crate::file_system::{unsound, *};
@@ -637,22 +1156,9 @@ pub mod tests {
             assert!(prop_all_directories_and_files(directory_map))
         }
 
-        fn label_strategy() -> impl Strategy<Value = Label> {
-            // ASCII regex, excluding '/' because of posix file paths
-            "[ -.|0-~]+".prop_map(|label| unsound::label::new(&label))
-        }
-
-        fn path_strategy(max_size: usize) -> impl Strategy<Value = Path> {
-            (
-                label_strategy(),
-                collection::vec(label_strategy(), 0..max_size),
-            )
-                .prop_map(|(label, labels)| Path((label, labels).into()))
-        }
-
         fn file_strategy() -> impl Strategy<Value = (Label, File)> {
             // ASCII regex, see: https://catonmat.net/my-favorite-regex
-            (label_strategy(), "[ -~]*")
+            (arbitrary::label(), "[ -~]*")
                 .prop_map(|(name, contents)| (name, File::new(contents.as_bytes())))
         }
 
@@ -662,7 +1168,7 @@ pub mod tests {
             map_size: usize,
         ) -> impl Strategy<Value = HashMap<Path, NonEmpty<(Label, File)>>> {
             collection::hash_map(
-                path_strategy(path_size),
+                arbitrary::path(path_size),
                 collection::vec(file_strategy(), 1..n_files).prop_map(|files| {
                     NonEmpty::from_slice(&files).expect("Strategy generated files of length 0")
                 }),
@@ -686,7 +1192,15 @@ pub mod tests {
                 new_directory_map.insert(path.clone(), files);
             }
 
-            let directory = Directory::from_hash_map(new_directory_map.clone());
+            let mut builder = DirectoryBuilder::new();
+            for (path, files) in new_directory_map.iter() {
+                for (file_name, file) in files.iter() {
+                    let mut file_path = path.clone();
+                    file_path.push(file_name.clone());
+                    builder.insert(file_path, file.clone());
+                }
+            }
+            let directory = builder.finish().expect("no directories were inserted");
 
             for (directory_path, files) in new_directory_map {
                 for (file_name, _) in files.iter() {
@@ -708,6 +1222,14 @@ pub mod tests {
             true
         }
 
+        #[cfg(feature = "proptest")]
+        proptest! {
+            #[test]
+            fn prop_diff_with_self_is_empty(directory in any::<Directory>()) {
+                prop_assert_eq!(directory.diff(&directory).unwrap(), crate::diff::Diff::new());
+            }
+        }
+
         #[test]
         fn test_file_name_is_same_as_root() {
             // This test ensures that if the name is the same the root of the
@@ -0,0 +1,199 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Detecting the encoding of file contents and converting them to UTF-8, so
+//! that files written in UTF-16 or Latin-1 can be displayed.
+//!
+//! # Examples
+//!
+//! ```
+//! use radicle_surf::file_system::encoding::{self, Encoding};
+//!
+//! let utf16 = [0xff, 0xfe, b'h', 0, b'i', 0];
+//! assert_eq!(encoding::to_utf8(&utf16), (Encoding::Utf16Le, "hi".into()));
+//!
+//! let latin1 = b"caf\xe9";
+//! assert_eq!(encoding::to_utf8(latin1), (Encoding::Latin1, "café".into()));
+//! ```
+
+use std::{borrow::Cow, convert::TryFrom};
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16BE_BOM: &[u8] = &[0xfe, 0xff];
+
+/// The encodings that are recognised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, with or without a byte order mark.
+    Utf8,
+    /// Little-endian UTF-16.
+    Utf16Le,
+    /// Big-endian UTF-16.
+    Utf16Be,
+    /// ISO-8859-1, where every byte is the code point of the same value.
+    Latin1,
+}
+
+impl Encoding {
+    /// Guess the encoding of `bytes`.
+    ///
+    /// A byte order mark decides the encoding, otherwise `bytes` are UTF-16 if
+    /// every other byte is zero, as it is for mostly ASCII text, and UTF-8 if
+    /// they are valid UTF-8. Anything else is taken to be Latin-1, which any
+    /// sequence of bytes is.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            Self::Utf8
+        } else if bytes.starts_with(UTF16LE_BOM) {
+            Self::Utf16Le
+        } else if bytes.starts_with(UTF16BE_BOM) {
+            Self::Utf16Be
+        } else if let Some(utf16) = detect_utf16(bytes) {
+            utf16
+        } else if std::str::from_utf8(bytes).is_ok() {
+            Self::Utf8
+        } else {
+            Self::Latin1
+        }
+    }
+
+    /// Look up an encoding by a name such as the ones used for the
+    /// `working-tree-encoding` attribute, e.g. `UTF-16LE` or `ISO-8859-1`.
+    /// The name is matched without regard to case, and a `-BOM` suffix is
+    /// ignored.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        let name = name.strip_suffix("-BOM").unwrap_or(&name);
+        match name {
+            "UTF-8" | "UTF8" => Some(Self::Utf8),
+            "UTF-16" | "UTF16" | "UTF-16LE" | "UTF16LE" => Some(Self::Utf16Le),
+            "UTF-16BE" | "UTF16BE" => Some(Self::Utf16Be),
+            "ISO-8859-1" | "ISO8859-1" | "LATIN1" | "LATIN-1" => Some(Self::Latin1),
+            _ => None,
+        }
+    }
+
+    /// Convert `bytes` in this encoding to UTF-8, dropping a byte order mark.
+    /// Sequences that are invalid in this encoding are replaced with `U+FFFD`,
+    /// the replacement character.
+    pub fn decode(self, bytes: &[u8]) -> Cow<'_, str> {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes)),
+            Self::Utf16Le => decode_utf16(bytes.strip_prefix(UTF16LE_BOM).unwrap_or(bytes), |b| {
+                u16::from_le_bytes(b)
+            }),
+            Self::Utf16Be => decode_utf16(bytes.strip_prefix(UTF16BE_BOM).unwrap_or(bytes), |b| {
+                u16::from_be_bytes(b)
+            }),
+            Self::Latin1 => bytes.iter().map(|byte| char::from(*byte)).collect(),
+        }
+    }
+
+    /// Convert `text` to this encoding, without a byte order mark, or `None`
+    /// if it has characters the encoding cannot represent.
+    pub fn encode(self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Utf8 => Some(text.as_bytes().to_vec()),
+            Self::Utf16Le => Some(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Self::Utf16Be => Some(text.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            Self::Latin1 => text
+                .chars()
+                .map(|c| u8::try_from(u32::from(c)).ok())
+                .collect(),
+        }
+    }
+
+    /// The byte order mark of the encoding.
+    pub fn bom(self) -> &'static [u8] {
+        match self {
+            Self::Utf8 => UTF8_BOM,
+            Self::Utf16Le => UTF16LE_BOM,
+            Self::Utf16Be => UTF16BE_BOM,
+            Self::Latin1 => &[],
+        }
+    }
+}
+
+/// Detect the encoding of `bytes` and convert them to UTF-8, see
+/// [`Encoding::detect`] and [`Encoding::decode`].
+pub fn to_utf8(bytes: &[u8]) -> (Encoding, Cow<'_, str>) {
+    let encoding = Encoding::detect(bytes);
+    (encoding, encoding.decode(bytes))
+}
+
+/// Recognise UTF-16 without a byte order mark by the zero bytes of the code
+/// units below 256, which are either all in the even or all in the odd
+/// positions, and make up more than half of the code units.
+fn detect_utf16(bytes: &[u8]) -> Option<Encoding> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let zeros = |offset: usize| {
+        bytes
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|byte| **byte == 0)
+            .count()
+    };
+    let (even, odd) = (zeros(0), zeros(1));
+    let units = bytes.len() / 2;
+    if odd * 2 > units && even == 0 {
+        Some(Encoding::Utf16Le)
+    } else if even * 2 > units && odd == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16(bytes: &[u8], unit: impl Fn([u8; 2]) -> u16) -> Cow<'static, str> {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    let mut text = char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect::<String>();
+    // A dangling byte is not a code unit.
+    if !bytes.len().is_multiple_of(2) {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    Cow::Owned(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_without_bom() {
+        assert_eq!(Encoding::detect(b"plain"), Encoding::Utf8);
+        assert_eq!(Encoding::detect(&[b'h', 0, b'i', 0]), Encoding::Utf16Le);
+        assert_eq!(Encoding::detect(&[0, b'h', 0, b'i']), Encoding::Utf16Be);
+        assert_eq!(Encoding::detect(b"na\xefve"), Encoding::Latin1);
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "naïve 🏄";
+        for encoding in &[Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be] {
+            let bytes = encoding.encode(text).unwrap();
+            assert_eq!(encoding.decode(&bytes), text);
+        }
+        assert_eq!(Encoding::Latin1.encode(text), None);
+        assert_eq!(Encoding::Utf16Le.decode(&[b'h', 0, b'i']), "h\u{FFFD}");
+    }
+}
@@ -19,8 +19,12 @@
 //!
 //! These errors occur due to [`Label`](super::path::Label) and
 //! [`Path`](super::path::Path) parsing when using their respective `TryFrom`
-//! instances.
+//! instances, when applying a [`Diff`](crate::diff::Diff) to a
+//! [`Directory`](super::directory::Directory), or when building a
+//! [`Directory`](super::directory::Directory) with a
+//! [`DirectoryBuilder`](super::directory::DirectoryBuilder).
 
+use super::path::Path;
 use std::ffi::OsStr;
 use thiserror::Error;
 
@@ -35,6 +39,14 @@ pub(crate) fn label_invalid_utf8(item: &OsStr) -> Error {
     })
 }
 
+/// Build an [`Error::Label(LabelError::InvalidUTF8)`] from the bytes of a
+/// name.
+pub(crate) fn label_invalid_utf8_bytes(item: &[u8]) -> Error {
+    Error::Label(LabelError::InvalidUTF8 {
+        label: String::from_utf8_lossy(item).into(),
+    })
+}
+
 /// Build an [`Error::Label(LabelError::ContainsSlash)`] from a [`str`]
 pub(crate) fn label_has_slash(item: &str) -> Error {
     Error::Label(LabelError::ContainsSlash { label: item.into() })
@@ -51,6 +63,10 @@ pub enum Error {
     /// A `PathError` specific error for parsing a [`Path`](super::path::Path).
     #[error(transparent)]
     Path(#[from] PathError),
+    /// A pattern of a [`Pathspec`](super::Pathspec) is empty, or only `/`s,
+    /// so it cannot match any path.
+    #[error("the pattern '{0}' is empty")]
+    EmptyPattern(String),
 }
 
 /// Parse errors for when parsing a string to a [`Path`](super::path::Path).
@@ -77,3 +93,50 @@ pub enum LabelError {
     #[error("label is empty")]
     Empty,
 }
+
+/// Errors for when applying a [`Diff`](crate::diff::Diff) to a
+/// [`Directory`](super::directory::Directory).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum ApplyError {
+    /// A file that the diff expects to exist is missing from the directory.
+    #[error("the file '{0}' does not exist")]
+    NotFound(Path),
+    /// A file that the diff creates already exists in the directory.
+    #[error("the file '{0}' already exists")]
+    AlreadyExists(Path),
+    /// The lines that a hunk expects to find in the file do not match.
+    #[error("the changes to '{path}' do not apply at line {line}")]
+    Conflict {
+        /// The path of the file being changed.
+        path: Path,
+        /// The line number, in the original file, where the mismatch occurred.
+        line: u32,
+    },
+    /// The diff contains changes to a binary file, but not the contents of
+    /// the changes.
+    #[error("the changes to the binary file '{0}' cannot be applied")]
+    Binary(Path),
+    /// The diff changes the type of a file, e.g. into a symlink, but not the
+    /// contents of the change.
+    #[error("the type change of '{0}' cannot be applied")]
+    TypeChange(Path),
+    /// The header of a hunk could not be parsed to find where it applies.
+    #[error("the hunk header '{header}' for '{path}' is invalid")]
+    InvalidHunk {
+        /// The path of the file being changed.
+        path: Path,
+        /// The header of the hunk.
+        header: String,
+    },
+}
+
+/// Errors for when building a [`Directory`](super::directory::Directory) with
+/// a [`DirectoryBuilder`](super::directory::DirectoryBuilder).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// A directory was inserted, but no files were inserted beneath it.
+    #[error("the directory '{0}' is empty")]
+    EmptyDirectory(Path),
+}
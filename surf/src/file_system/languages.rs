@@ -0,0 +1,337 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Detecting the languages of the files of a [`Directory`], to compute how
+//! much of a snapshot each language makes up.
+//!
+//! See [`Directory::language_breakdown`].
+
+use crate::file_system::{
+    attributes::{Attributes, State},
+    Directory,
+    Label,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// The kind of a language, which decides whether it counts towards a
+/// [`Directory::language_breakdown`] by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A programming language, which is counted.
+    Programming,
+    /// A markup language, such as HTML, which is counted.
+    Markup,
+    /// A data format, such as JSON, which is not counted.
+    Data,
+    /// Prose, such as Markdown, which is not counted.
+    Prose,
+}
+
+/// The languages, by the extensions of their files.
+const EXTENSIONS: &[(&str, &str, Kind)] = &[
+    ("c", "C", Kind::Programming),
+    ("cc", "C++", Kind::Programming),
+    ("clj", "Clojure", Kind::Programming),
+    ("cpp", "C++", Kind::Programming),
+    ("cs", "C#", Kind::Programming),
+    ("css", "CSS", Kind::Markup),
+    ("cxx", "C++", Kind::Programming),
+    ("dart", "Dart", Kind::Programming),
+    ("el", "Emacs Lisp", Kind::Programming),
+    ("elm", "Elm", Kind::Programming),
+    ("erl", "Erlang", Kind::Programming),
+    ("ex", "Elixir", Kind::Programming),
+    ("exs", "Elixir", Kind::Programming),
+    ("fs", "F#", Kind::Programming),
+    ("go", "Go", Kind::Programming),
+    ("h", "C", Kind::Programming),
+    ("hpp", "C++", Kind::Programming),
+    ("hs", "Haskell", Kind::Programming),
+    ("htm", "HTML", Kind::Markup),
+    ("html", "HTML", Kind::Markup),
+    ("java", "Java", Kind::Programming),
+    ("js", "JavaScript", Kind::Programming),
+    ("json", "JSON", Kind::Data),
+    ("jsx", "JavaScript", Kind::Programming),
+    ("kt", "Kotlin", Kind::Programming),
+    ("lua", "Lua", Kind::Programming),
+    ("m", "Objective-C", Kind::Programming),
+    ("md", "Markdown", Kind::Prose),
+    ("ml", "OCaml", Kind::Programming),
+    ("nix", "Nix", Kind::Programming),
+    ("php", "PHP", Kind::Programming),
+    ("pl", "Perl", Kind::Programming),
+    ("py", "Python", Kind::Programming),
+    ("r", "R", Kind::Programming),
+    ("rb", "Ruby", Kind::Programming),
+    ("rkt", "Racket", Kind::Programming),
+    ("rs", "Rust", Kind::Programming),
+    ("rst", "reStructuredText", Kind::Prose),
+    ("sass", "Sass", Kind::Markup),
+    ("scala", "Scala", Kind::Programming),
+    ("scm", "Scheme", Kind::Programming),
+    ("scss", "SCSS", Kind::Markup),
+    ("sh", "Shell", Kind::Programming),
+    ("sol", "Solidity", Kind::Programming),
+    ("sql", "SQL", Kind::Data),
+    ("svelte", "Svelte", Kind::Markup),
+    ("swift", "Swift", Kind::Programming),
+    ("tex", "TeX", Kind::Markup),
+    ("toml", "TOML", Kind::Data),
+    ("ts", "TypeScript", Kind::Programming),
+    ("tsx", "TypeScript", Kind::Programming),
+    ("txt", "Text", Kind::Prose),
+    ("vue", "Vue", Kind::Markup),
+    ("xml", "XML", Kind::Data),
+    ("yaml", "YAML", Kind::Data),
+    ("yml", "YAML", Kind::Data),
+    ("zig", "Zig", Kind::Programming),
+];
+
+/// The languages, by the names of their files.
+const FILENAMES: &[(&str, &str, Kind)] = &[
+    ("CMakeLists.txt", "CMake", Kind::Programming),
+    ("Dockerfile", "Dockerfile", Kind::Programming),
+    ("GNUmakefile", "Makefile", Kind::Programming),
+    ("Makefile", "Makefile", Kind::Programming),
+    ("Rakefile", "Ruby", Kind::Programming),
+    ("makefile", "Makefile", Kind::Programming),
+];
+
+/// The languages, by the interpreters named in the `#!` line of a script.
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("bash", "Shell"),
+    ("node", "JavaScript"),
+    ("perl", "Perl"),
+    ("python", "Python"),
+    ("python3", "Python"),
+    ("ruby", "Ruby"),
+    ("runghc", "Haskell"),
+    ("sh", "Shell"),
+    ("zsh", "Shell"),
+];
+
+/// The directories whose files are vendored, rather than part of the project.
+const VENDORED: &[&str] = &[
+    "Godeps",
+    "bower_components",
+    "node_modules",
+    "third_party",
+    "vendor",
+];
+
+/// The directories whose files are documentation.
+const DOCUMENTATION: &[&str] = &["Documentation", "doc", "docs"];
+
+/// The number of bytes that are checked for a NUL byte, to tell whether a
+/// file is binary, the same as `git`.
+const BINARY_CHECK_LEN: usize = 8000;
+
+/// Detect the language of the file called `name`, given its `contents`.
+///
+/// The language is found from, in order, the name of the file, its
+/// extension, and the interpreter of its `#!` line. Binary files have no
+/// language.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::file_system::languages::{detect, Kind};
+///
+/// assert_eq!(detect("main.rs", b"fn main() {}"), Some(("Rust", Kind::Programming)));
+/// assert_eq!(detect("Makefile", b"all:"), Some(("Makefile", Kind::Programming)));
+/// assert_eq!(detect("build", b"#!/usr/bin/env bash\n"), Some(("Shell", Kind::Programming)));
+/// assert_eq!(detect("util.h", b"class Util {};"), Some(("C++", Kind::Programming)));
+/// assert_eq!(detect("logo.png", b"\x89PNG\x00"), None);
+/// ```
+pub fn detect(name: &str, contents: &[u8]) -> Option<(&'static str, Kind)> {
+    if is_binary(contents) {
+        return None;
+    }
+
+    let lookup = |table: &[(&str, &'static str, Kind)], key: &str| {
+        table
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, language, kind)| (*language, *kind))
+    };
+    if let Some(found) = lookup(FILENAMES, name) {
+        return Some(found);
+    }
+
+    match name.rsplit_once('.') {
+        // A header is C, unless it has C++ in it.
+        Some((_, "h")) if is_cpp(contents) => Some(("C++", Kind::Programming)),
+        Some((stem, extension)) if !stem.is_empty() => {
+            lookup(EXTENSIONS, &extension.to_lowercase())
+        },
+        _ => interpreter(contents),
+    }
+}
+
+fn is_binary(contents: &[u8]) -> bool {
+    contents[..contents.len().min(BINARY_CHECK_LEN)].contains(&0)
+}
+
+fn is_cpp(contents: &[u8]) -> bool {
+    let contents = String::from_utf8_lossy(contents);
+    [
+        "class ",
+        "namespace ",
+        "template<",
+        "template <",
+        "#include <iostream>",
+    ]
+    .iter()
+    .any(|marker| contents.contains(marker))
+}
+
+/// Find the language of a script from the interpreter in its `#!` line.
+fn interpreter(contents: &[u8]) -> Option<(&'static str, Kind)> {
+    let line = contents.split(|byte| *byte == b'\n').next()?;
+    let line = std::str::from_utf8(line.strip_prefix(b"#!")?).ok()?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-'))?;
+    }
+    INTERPRETERS
+        .iter()
+        .find(|(name, _)| *name == program)
+        .map(|(_, language)| (*language, Kind::Programming))
+}
+
+/// Whether the attribute `name` is explicitly set (`Some(true)`), unset
+/// (`Some(false)`), or unspecified (`None`).
+fn flag(attributes: &Attributes, name: &str) -> Option<bool> {
+    match attributes.get(name)? {
+        State::Set => Some(true),
+        State::Unset => Some(false),
+        State::Value(value) => Some(value == "true"),
+    }
+}
+
+impl Directory {
+    /// Compute the number of bytes of each language in this `Directory`, for
+    /// showing what a project is written in.
+    ///
+    /// The language of each file is found with [`detect`]. By default only
+    /// programming and markup languages are counted, and the files that are
+    /// vendored, generated, or documentation are left out, as found from
+    /// their paths. The
+    /// [linguist overrides](https://github.com/github/linguist/blob/master/docs/overrides.md)
+    /// of the `.gitattributes` files are honoured: `linguist-language`,
+    /// `linguist-vendored`, `linguist-generated`, `linguist-documentation`,
+    /// and `linguist-detectable`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{unsound, DirectoryBuilder};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut builder = DirectoryBuilder::new();
+    /// builder
+    ///     .insert_file(unsound::path::new(".gitattributes"), b"*.inc linguist-language=PHP\n")
+    ///     .insert_file(unsound::path::new("src/main.rs"), b"fn main() {}")
+    ///     .insert_file(unsound::path::new("src/page.inc"), b"<?php ?>")
+    ///     .insert_file(unsound::path::new("vendor/lib.rs"), b"pub fn lib() {}")
+    ///     .insert_file(unsound::path::new("README.md"), b"# Hello");
+    /// let directory = builder.build()?;
+    ///
+    /// let breakdown = directory.language_breakdown();
+    /// assert_eq!(breakdown.get("Rust"), Some(&12));
+    /// assert_eq!(breakdown.get("PHP"), Some(&8));
+    /// assert_eq!(breakdown.len(), 2);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn language_breakdown(&self) -> BTreeMap<String, usize> {
+        let mut parsed = HashMap::new();
+        let mut breakdown = BTreeMap::new();
+
+        for (path, file) in self.iter_files() {
+            let attributes = self.attributes_with(&path, &mut parsed);
+            let (parents, name) = path.split_last();
+
+            let (language, included) = match attributes.get("linguist-language") {
+                Some(State::Value(language)) => (language.clone(), true),
+                _ => match detect(&name, &file.contents) {
+                    Some((language, kind)) => (
+                        language.to_string(),
+                        matches!(kind, Kind::Programming | Kind::Markup),
+                    ),
+                    None => continue,
+                },
+            };
+
+            let excluded = flag(&attributes, "linguist-vendored")
+                .unwrap_or_else(|| in_directory(&parents, VENDORED))
+                || flag(&attributes, "linguist-generated")
+                    .unwrap_or_else(|| name.ends_with(".min.js"))
+                || flag(&attributes, "linguist-documentation")
+                    .unwrap_or_else(|| in_directory(&parents, DOCUMENTATION));
+            let included = flag(&attributes, "linguist-detectable").unwrap_or(included);
+
+            if included && !excluded {
+                *breakdown.entry(language).or_insert(0) += file.size;
+            }
+        }
+        breakdown
+    }
+}
+
+/// Whether one of the `parents` of a file is one of the `directories`.
+fn in_directory(parents: &[Label], directories: &[&str]) -> bool {
+    parents
+        .iter()
+        .any(|label| directories.contains(&label.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file_system::{unsound, DirectoryBuilder};
+
+    #[test]
+    fn path_heuristics_and_overrides() {
+        let mut builder = DirectoryBuilder::new();
+        builder
+            .insert_file(
+                unsound::path::new(".gitattributes"),
+                b"vendor/ours/** -linguist-vendored\n*.json linguist-detectable\n",
+            )
+            .insert_file(unsound::path::new("app.js"), b"let a;")
+            .insert_file(unsound::path::new("app.min.js"), b"let a;let b;")
+            .insert_file(unsound::path::new("docs/example.py"), b"pass")
+            .insert_file(unsound::path::new("vendor/theirs/lib.py"), b"pass")
+            .insert_file(unsound::path::new("vendor/ours/lib.py"), b"import os")
+            .insert_file(unsound::path::new("package.json"), b"{}")
+            .insert_file(unsound::path::new("data.yml"), b"a: 1");
+        let breakdown = builder.build().unwrap().language_breakdown();
+
+        assert_eq!(
+            breakdown.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("JSON".to_string(), 2),
+                ("JavaScript".to_string(), 6),
+                ("Python".to_string(), 9),
+            ]
+        );
+    }
+}
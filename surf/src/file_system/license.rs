@@ -0,0 +1,125 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Finding the license of a [`Directory`].
+//!
+//! See [`Directory::license`], and, with the `spdx` feature,
+//! `License::spdx` for classifying it.
+
+use crate::file_system::{Directory, File, Label, Path, SystemType};
+
+#[cfg(feature = "spdx")]
+mod spdx;
+#[cfg(feature = "spdx")]
+pub use spdx::Spdx;
+
+/// The names of license files, without their extensions, in the order they
+/// are preferred.
+const NAMES: &[&str] = &["license", "licence", "copying", "unlicense"];
+
+/// The extensions that a license file may have.
+const EXTENSIONS: &[&str] = &["md", "markdown", "txt", "rst"];
+
+/// The license file of a [`Directory`], see [`Directory::license`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct License {
+    /// The name of the license file, as it is written in the `Directory`.
+    pub name: Label,
+    /// The contents of the license file.
+    pub file: File,
+}
+
+/// Where the file called `name` comes in the order of preference of license
+/// files, or `None` if it is not a license file.
+///
+/// A license file is one of the [`NAMES`], in any case, and may be followed
+/// by one of the [`EXTENSIONS`] or by a `-` or `.` and a suffix, as in
+/// `LICENSE-MIT` or `COPYING.LESSER`.
+fn preference(name: &str) -> Option<(usize, bool)> {
+    let lowercase = name.to_lowercase();
+    NAMES.iter().enumerate().find_map(|(index, license)| {
+        let rest = lowercase.strip_prefix(license)?;
+        let plain = rest.is_empty() || EXTENSIONS.iter().any(|ext| rest == format!(".{}", ext));
+        let suffixed = rest.starts_with('-') || rest.starts_with('.');
+        if plain || suffixed {
+            Some((index, !plain))
+        } else {
+            None
+        }
+    })
+}
+
+impl Directory {
+    /// Find the license file of this `Directory`, among the files directly in
+    /// it, such as `LICENSE`, `COPYING.md`, or `LICENSE-MIT`.
+    ///
+    /// If there are several, a `LICENSE` is preferred over a `COPYING`, and a
+    /// file without a suffix over one with, such as `COPYING` over
+    /// `COPYING.LESSER`, and then the one whose name comes first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{unsound, DirectoryBuilder};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut builder = DirectoryBuilder::new();
+    /// builder
+    ///     .insert_file(unsound::path::new("COPYING"), b"GNU GENERAL PUBLIC LICENSE")
+    ///     .insert_file(unsound::path::new("LICENSE-MIT"), b"MIT License")
+    ///     .insert_file(unsound::path::new("LICENSE.txt"), b"Apache License");
+    /// let directory = builder.build()?;
+    ///
+    /// let license = directory.license().expect("missing license");
+    /// assert_eq!(license.name, unsound::label::new("LICENSE.txt"));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn license(&self) -> Option<License> {
+        let (_, name) = self
+            .list_directory()
+            .into_iter()
+            .filter_map(|entry| match entry {
+                (name, SystemType::File) => Some((preference(&name)?, name)),
+                (_, SystemType::Directory) => None,
+            })
+            .min()?;
+
+        Some(License {
+            file: self.find_file(Path::new(name.clone()))?,
+            name,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(preference("LICENSE"), Some((0, false)));
+        assert_eq!(preference("License.md"), Some((0, false)));
+        assert_eq!(preference("LICENSE-APACHE"), Some((0, true)));
+        assert_eq!(preference("COPYING.LESSER"), Some((2, true)));
+        assert_eq!(preference("UNLICENSE"), Some((3, false)));
+        assert_eq!(preference("LICENSES"), None);
+        assert_eq!(preference("licensing.md"), None);
+    }
+}
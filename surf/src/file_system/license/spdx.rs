@@ -0,0 +1,270 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Classifying a [`License`] against excerpts of the
+//! [SPDX license templates](https://spdx.org/licenses/).
+
+use super::License;
+use std::collections::HashSet;
+
+/// The SPDX identifier of a [`License`], see [`License::spdx`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spdx {
+    /// The SPDX identifier, such as `MIT` or `GPL-3.0`.
+    pub id: &'static str,
+    /// How much of the license template was found in the license file, from
+    /// `0.0` to `1.0`.
+    pub confidence: f32,
+}
+
+/// The lowest confidence a license is classified with.
+const THRESHOLD: f32 = 0.8;
+
+/// How far below the best confidence a longer, more specific, template may
+/// be and still be picked, such as `BSD-3-Clause` over `BSD-2-Clause`.
+const SPECIFICITY: f32 = 0.1;
+
+/// The licenses, by their SPDX identifiers, and the text that identifies
+/// them. The texts leave out the parts that vary between projects, such as
+/// the copyright line, and are cut short for the longer licenses.
+const TEMPLATES: &[(&str, &str)] = &[
+    (
+        "0BSD",
+        "Permission to use, copy, modify, and/or distribute this software for any purpose with \
+         or without fee is hereby granted. THE SOFTWARE IS PROVIDED \"AS IS\" AND THE AUTHOR \
+         DISCLAIMS ALL WARRANTIES WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES \
+         OF MERCHANTABILITY AND FITNESS.",
+    ),
+    (
+        "AGPL-3.0",
+        "GNU AFFERO GENERAL PUBLIC LICENSE Version 3, 19 November 2007 Preamble The GNU Affero \
+         General Public License is a free, copyleft license for software and other kinds of \
+         works, specifically designed to ensure cooperation with the community in the case of \
+         network server software.",
+    ),
+    (
+        "Apache-2.0",
+        "Apache License Version 2.0, January 2004 TERMS AND CONDITIONS FOR USE, REPRODUCTION, \
+         AND DISTRIBUTION 1. Definitions. \"License\" shall mean the terms and conditions for \
+         use, reproduction, and distribution as defined by Sections 1 through 9 of this \
+         document.",
+    ),
+    (
+        "BSD-2-Clause",
+        "Redistribution and use in source and binary forms, with or without modification, are \
+         permitted provided that the following conditions are met: 1. Redistributions of source \
+         code must retain the above copyright notice, this list of conditions and the following \
+         disclaimer. 2. Redistributions in binary form must reproduce the above copyright \
+         notice, this list of conditions and the following disclaimer in the documentation \
+         and/or other materials provided with the distribution.",
+    ),
+    (
+        "BSD-3-Clause",
+        "Redistribution and use in source and binary forms, with or without modification, are \
+         permitted provided that the following conditions are met: 1. Redistributions of source \
+         code must retain the above copyright notice, this list of conditions and the following \
+         disclaimer. 2. Redistributions in binary form must reproduce the above copyright \
+         notice, this list of conditions and the following disclaimer in the documentation \
+         and/or other materials provided with the distribution. 3. Neither the name of the \
+         copyright holder nor the names of its contributors may be used to endorse or promote \
+         products derived from this software without specific prior written permission.",
+    ),
+    (
+        "CC0-1.0",
+        "Creative Commons Legal Code CC0 1.0 Universal CREATIVE COMMONS CORPORATION IS NOT A LAW \
+         FIRM AND DOES NOT PROVIDE LEGAL SERVICES. DISTRIBUTION OF THIS DOCUMENT DOES NOT CREATE \
+         AN ATTORNEY-CLIENT RELATIONSHIP.",
+    ),
+    (
+        "GPL-2.0",
+        "GNU GENERAL PUBLIC LICENSE Version 2, June 1991 Preamble The licenses for most software \
+         are designed to take away your freedom to share and change it. By contrast, the GNU \
+         General Public License is intended to guarantee your freedom to share and change free \
+         software--to make sure the software is free for all its users.",
+    ),
+    (
+        "GPL-3.0",
+        "GNU GENERAL PUBLIC LICENSE Version 3, 29 June 2007 Preamble The GNU General Public \
+         License is a free, copyleft license for software and other kinds of works.",
+    ),
+    (
+        "ISC",
+        "Permission to use, copy, modify, and/or distribute this software for any purpose with \
+         or without fee is hereby granted, provided that the above copyright notice and this \
+         permission notice appear in all copies. THE SOFTWARE IS PROVIDED \"AS IS\" AND THE \
+         AUTHOR DISCLAIMS ALL WARRANTIES WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED \
+         WARRANTIES OF MERCHANTABILITY AND FITNESS.",
+    ),
+    (
+        "LGPL-2.1",
+        "GNU LESSER GENERAL PUBLIC LICENSE Version 2.1, February 1999 Preamble The licenses for \
+         most software are designed to take away your freedom to share and change it. By \
+         contrast, the GNU General Public Licenses are intended to guarantee your freedom to \
+         share and change free software--to make sure the software is free for all its users. \
+         This license, the Lesser General Public License, applies to some specially designated \
+         software packages--typically libraries--of the Free Software Foundation and other \
+         authors who decide to use it.",
+    ),
+    (
+        "LGPL-3.0",
+        "GNU LESSER GENERAL PUBLIC LICENSE Version 3, 29 June 2007 This version of the GNU Lesser \
+         General Public License incorporates the terms and conditions of version 3 of the GNU \
+         General Public License, supplemented by the additional permissions listed below.",
+    ),
+    (
+        "MIT",
+        "Permission is hereby granted, free of charge, to any person obtaining a copy of this \
+         software and associated documentation files (the \"Software\"), to deal in the Software \
+         without restriction, including without limitation the rights to use, copy, modify, \
+         merge, publish, distribute, sublicense, and/or sell copies of the Software, and to \
+         permit persons to whom the Software is furnished to do so, subject to the following \
+         conditions: The above copyright notice and this permission notice shall be included in \
+         all copies or substantial portions of the Software. THE SOFTWARE IS PROVIDED \"AS IS\", \
+         WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE \
+         WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.",
+    ),
+    (
+        "MPL-2.0",
+        "Mozilla Public License Version 2.0 1. Definitions 1.1. \"Contributor\" means each \
+         individual or legal entity that creates, contributes to the creation of, or owns \
+         Covered Software.",
+    ),
+    (
+        "Unlicense",
+        "This is free and unencumbered software released into the public domain. Anyone is free \
+         to copy, modify, publish, use, compile, sell, or distribute this software, either in \
+         source code form or as a compiled binary, for any purpose, commercial or \
+         non-commercial, and by any means.",
+    ),
+];
+
+/// The pairs of consecutive words of `text`, ignoring case and punctuation.
+fn bigrams(text: &str) -> HashSet<(String, String)> {
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    words
+        .windows(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect()
+}
+
+impl License {
+    /// Classify the license against the templates of common licenses,
+    /// returning the [`Spdx`] identifier that matches best, or `None` if none
+    /// match well enough.
+    ///
+    /// The confidence is the share of the template that is found in the
+    /// license file. When templates match almost equally well, the longer one
+    /// is picked, since it is the more specific.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{unsound, DirectoryBuilder};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut builder = DirectoryBuilder::new();
+    /// builder.insert_file(
+    ///     unsound::path::new("COPYING"),
+    ///     b"                    GNU GENERAL PUBLIC LICENSE
+    ///                        Version 3, 29 June 2007
+    ///
+    ///  Copyright (C) 2007 Free Software Foundation, Inc. <https://fsf.org/>
+    ///  Everyone is permitted to copy and distribute verbatim copies
+    ///  of this license document, but changing it is not allowed.
+    ///
+    ///                             Preamble
+    ///
+    ///   The GNU General Public License is a free, copyleft license for
+    /// software and other kinds of works.",
+    /// );
+    /// let license = builder.build()?.license().expect("missing license");
+    ///
+    /// let spdx = license.spdx().expect("unknown license");
+    /// assert_eq!(spdx.id, "GPL-3.0");
+    /// assert!(spdx.confidence > 0.9);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spdx(&self) -> Option<Spdx> {
+        let text = bigrams(&String::from_utf8_lossy(&self.file.contents));
+        let candidates = TEMPLATES
+            .iter()
+            .map(|(id, template)| {
+                let template = bigrams(template);
+                let found = template.intersection(&text).count();
+                let confidence = found as f32 / template.len() as f32;
+                (id, template.len(), confidence)
+            })
+            .filter(|(_, _, confidence)| *confidence >= THRESHOLD)
+            .collect::<Vec<_>>();
+
+        let best = candidates
+            .iter()
+            .map(|(_, _, confidence)| *confidence)
+            .fold(0.0, f32::max);
+        candidates
+            .into_iter()
+            .filter(|(_, _, confidence)| *confidence >= best - SPECIFICITY)
+            .max_by_key(|(_, len, _)| *len)
+            .map(|(id, _, confidence)| Spdx { id, confidence })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system::{unsound, File};
+
+    fn classify(text: &str) -> Option<&'static str> {
+        License {
+            name: unsound::label::new("LICENSE"),
+            file: File::new(text.as_bytes()),
+        }
+        .spdx()
+        .map(|spdx| spdx.id)
+    }
+
+    #[test]
+    fn templates_classify_themselves() {
+        for (id, template) in TEMPLATES {
+            assert_eq!(classify(template), Some(*id));
+        }
+    }
+
+    #[test]
+    fn bsd_variants() {
+        let bsd3 = TEMPLATES
+            .iter()
+            .find(|(id, _)| *id == "BSD-3-Clause")
+            .unwrap()
+            .1
+            .replace("the copyright holder", "Acme Corporation");
+        assert_eq!(classify(&bsd3), Some("BSD-3-Clause"));
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(classify("All rights reserved."), None);
+        assert_eq!(classify(""), None);
+    }
+}
@@ -0,0 +1,269 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Recognising images from the header of their contents, so that a file can
+//! be shown as a preview rather than offered as a download.
+//!
+//! See [`File::media_info`].
+
+use crate::file_system::File;
+
+/// The formats of the images that are recognised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Portable Network Graphics.
+    Png,
+    /// JPEG, in a JFIF or Exif container.
+    Jpeg,
+    /// Graphics Interchange Format.
+    Gif,
+    /// Windows bitmap.
+    Bmp,
+    /// WebP, lossy or lossless.
+    Webp,
+    /// Scalable Vector Graphics.
+    Svg,
+}
+
+impl Format {
+    /// The MIME type of the format, e.g. `image/png`.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Bmp => "image/bmp",
+            Self::Webp => "image/webp",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// What is known about an image, see [`File::media_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaInfo {
+    /// The format of the image.
+    pub format: Format,
+    /// The width and height of the image in pixels, if they could be read
+    /// from its header. They are never read for [`Format::Svg`].
+    pub dimensions: Option<(u32, u32)>,
+    /// The size of the file in bytes.
+    pub size: usize,
+}
+
+impl MediaInfo {
+    /// Recognise the image whose contents start with `header`, and whose size
+    /// is `size`, or `None` if it is not an image in one of the [`Format`]s.
+    ///
+    /// Only the header is looked at, so the first few kilobytes of a blob are
+    /// enough, except for JPEGs with large metadata before their frame, whose
+    /// dimensions are then `None`.
+    pub fn from_header(header: &[u8], size: usize) -> Option<Self> {
+        let (format, dimensions) = if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+            // The IHDR chunk comes first, after its length and type.
+            (Format::Png, be32(header, 16).zip(be32(header, 20)))
+        } else if header.starts_with(b"\xff\xd8\xff") {
+            (Format::Jpeg, jpeg_dimensions(header))
+        } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+            let dimensions = le16(header, 6).zip(le16(header, 8));
+            (Format::Gif, dimensions.map(|(w, h)| (w.into(), h.into())))
+        } else if header.starts_with(b"BM") {
+            // Bitmaps stored top-down have a negative height.
+            let dimensions = le32(header, 18).zip(le32(header, 22));
+            let dimensions = dimensions.map(|(w, h)| (w, (h as i32).unsigned_abs()));
+            (Format::Bmp, dimensions)
+        } else if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WEBP") {
+            (Format::Webp, webp_dimensions(header))
+        } else if is_svg(header) {
+            (Format::Svg, None)
+        } else {
+            return None;
+        };
+        Some(MediaInfo {
+            format,
+            dimensions,
+            size,
+        })
+    }
+}
+
+impl File {
+    /// Recognise the `File` as an image, see [`MediaInfo::from_header`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{media::Format, File};
+    ///
+    /// // The signature and IHDR chunk of a 3x2 PNG.
+    /// let png = File::new(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x03\0\0\0\x02\x08\x06\0\0\0");
+    /// let info = png.media_info().unwrap();
+    /// assert_eq!(info.format, Format::Png);
+    /// assert_eq!(info.format.mime_type(), "image/png");
+    /// assert_eq!(info.dimensions, Some((3, 2)));
+    /// assert_eq!(info.size, png.size());
+    ///
+    /// assert_eq!(File::new(b"fn main() {}").media_info(), None);
+    /// ```
+    pub fn media_info(&self) -> Option<MediaInfo> {
+        MediaInfo::from_header(&self.contents, self.size)
+    }
+}
+
+fn be16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*bytes.get(at)?, *bytes.get(at + 1)?]))
+}
+
+fn le16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes([*bytes.get(at)?, *bytes.get(at + 1)?]))
+}
+
+fn be32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes([
+        *bytes.get(at)?,
+        *bytes.get(at + 1)?,
+        *bytes.get(at + 2)?,
+        *bytes.get(at + 3)?,
+    ]))
+}
+
+fn le32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes([
+        *bytes.get(at)?,
+        *bytes.get(at + 1)?,
+        *bytes.get(at + 2)?,
+        *bytes.get(at + 3)?,
+    ]))
+}
+
+/// Find the dimensions in the start of frame segment of a JPEG, skipping the
+/// segments before it.
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut at = 2;
+    loop {
+        if *bytes.get(at)? != 0xff {
+            return None;
+        }
+        let marker = *bytes.get(at + 1)?;
+        match marker {
+            // Padding before a marker.
+            0xff => at += 1,
+            // The start of frame markers, other than the ones for Huffman
+            // tables, arithmetic coding, and the JPEG extensions.
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                let height = be16(bytes, at + 5)?;
+                let width = be16(bytes, at + 7)?;
+                return Some((width.into(), height.into()));
+            },
+            _ => at += 2 + usize::from(be16(bytes, at + 2)?),
+        }
+    }
+}
+
+/// Find the dimensions in the first chunk of a WebP, which depends on whether
+/// it is lossy, lossless, or extended.
+fn webp_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let chunk = bytes.get(12..16)?;
+    let data = 20;
+    match chunk {
+        b"VP8 " => {
+            // After the frame tag and start code, 14 bits for each.
+            let width = le16(bytes, data + 6)? & 0x3fff;
+            let height = le16(bytes, data + 8)? & 0x3fff;
+            Some((width.into(), height.into()))
+        },
+        b"VP8L" => {
+            // After the signature byte, 14 bits for each, minus one.
+            let bits = le32(bytes, data + 1)?;
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        },
+        b"VP8X" => {
+            // After the flags, 24 bits for each, minus one.
+            let u24 = |at: usize| match bytes.get(at..at + 3)? {
+                [a, b, c] => Some(u32::from_le_bytes([*a, *b, *c, 0])),
+                _ => None,
+            };
+            let width = u24(data + 4)?;
+            let height = u24(data + 7)?;
+            Some((width + 1, height + 1))
+        },
+        _ => None,
+    }
+}
+
+/// Whether the text at the start of `bytes` is an SVG document, i.e. an `svg`
+/// element, possibly after an XML declaration, comments, and a doctype.
+fn is_svg(bytes: &[u8]) -> bool {
+    let header = &bytes[..bytes.len().min(1024)];
+    let header = String::from_utf8_lossy(header);
+    let header = header.trim_start_matches('\u{feff}').trim_start();
+    (header.starts_with("<?xml")
+        || header.starts_with("<!--")
+        || header.starts_with("<svg")
+        || header.starts_with("<!DOCTYPE svg"))
+        && header.contains("<svg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        let gif = b"GIF89a\x0a\x00\x14\x00";
+        assert_eq!(
+            MediaInfo::from_header(gif, 100),
+            Some(MediaInfo {
+                format: Format::Gif,
+                dimensions: Some((10, 20)),
+                size: 100,
+            })
+        );
+
+        let mut bmp = b"BM".to_vec();
+        bmp.resize(18, 0);
+        bmp.extend_from_slice(&4u32.to_le_bytes());
+        bmp.extend_from_slice(&(-3i32).to_le_bytes());
+        let bmp = MediaInfo::from_header(&bmp, 0).unwrap();
+        assert_eq!(bmp.dimensions, Some((4, 3)));
+
+        // A JFIF segment before the baseline frame.
+        let jpeg = b"\xff\xd8\xff\xe0\x00\x04JF\xff\xc0\x00\x11\x08\x00\x02\x00\x05";
+        let jpeg = MediaInfo::from_header(jpeg, 0).unwrap();
+        assert_eq!(jpeg.format, Format::Jpeg);
+        assert_eq!(jpeg.dimensions, Some((5, 2)));
+
+        // A JPEG cut off before its frame is still recognised.
+        let jpeg = MediaInfo::from_header(b"\xff\xd8\xff\xe1\x10\x00", 0).unwrap();
+        assert_eq!(jpeg.dimensions, None);
+
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8L\0\0\0\0\x2f".to_vec();
+        webp.extend_from_slice(&(7u32 | (8u32 << 14)).to_le_bytes());
+        let webp = MediaInfo::from_header(&webp, 0).unwrap();
+        assert_eq!(webp.dimensions, Some((8, 9)));
+
+        let svg = b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+        assert_eq!(
+            MediaInfo::from_header(svg, 0).map(|info| info.format),
+            Some(Format::Svg)
+        );
+        assert_eq!(
+            MediaInfo::from_header(b"<?xml version=\"1.0\"?><html/>", 0),
+            None
+        );
+    }
+}
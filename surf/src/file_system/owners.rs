@@ -0,0 +1,166 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parsing a `CODEOWNERS` file, to find who owns the files of a
+//! [`Directory`].
+//!
+//! The syntax follows the one of GitHub and GitLab: each line is a pattern,
+//! in the style of `.gitignore`, followed by the owners of the files that
+//! match it. When several patterns match a file, the last one wins.
+//!
+//! # Examples
+//!
+//! ```
+//! use radicle_surf::file_system::{owners::CodeOwners, unsound};
+//!
+//! let owners = CodeOwners::parse(
+//!     "# The default owners\n\
+//!      *       @core\n\
+//!      /docs/  @docs @core\n\
+//!      *.rs    @rustaceans\n",
+//! );
+//!
+//! assert_eq!(owners.owners_for(&unsound::path::new("~/README.md")), ["@core"]);
+//! assert_eq!(owners.owners_for(&unsound::path::new("~/docs/intro.md")), ["@docs", "@core"]);
+//! assert_eq!(owners.owners_for(&unsound::path::new("~/docs/src/main.rs")), ["@rustaceans"]);
+//! ```
+
+use crate::file_system::{pattern, Directory, Path};
+use regex::Regex;
+use std::convert::TryFrom;
+
+/// The paths that a `CODEOWNERS` file is looked up at, in order.
+pub const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// The rules of a `CODEOWNERS` file.
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Regex,
+    owners: Vec<String>,
+}
+
+impl CodeOwners {
+    /// Parse the `contents` of a `CODEOWNERS` file.
+    ///
+    /// Blank lines and comments, starting with `#`, are skipped, as are the
+    /// lines whose pattern cannot be understood.
+    pub fn parse(contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .filter_map(|line| {
+                let line = match line.find(" #") {
+                    Some(comment) => &line[..comment],
+                    None => line,
+                };
+                let mut words = line.split_whitespace();
+                let pattern = words.next().filter(|word| !word.starts_with('#'))?;
+                Some(Rule {
+                    pattern: pattern::to_regex(pattern, true)?,
+                    owners: words.map(String::from).collect(),
+                })
+            })
+            .collect();
+        CodeOwners { rules }
+    }
+
+    /// Find the `CODEOWNERS` file of the `directory`, at one of the
+    /// [`LOCATIONS`], and parse it.
+    ///
+    /// Returns `None` if there is no `CODEOWNERS` file.
+    pub fn from_directory(directory: &Directory) -> Option<Self> {
+        LOCATIONS.iter().find_map(|location| {
+            let file = directory.find_file(Path::try_from(*location).ok()?)?;
+            Some(Self::parse(&String::from_utf8_lossy(&file.contents)))
+        })
+    }
+
+    /// The owners of the file or directory at `path`, which are empty if no
+    /// rule matches it, or if the rule that matches has no owners.
+    pub fn owners_for(&self, path: &Path) -> &[String] {
+        let path = path.to_string();
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.is_match(&path))
+            .map_or(&[], |rule| &rule.owners)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system::{unsound, DirectoryBuilder};
+
+    fn owners(contents: &str, path: &str) -> Vec<String> {
+        CodeOwners::parse(contents)
+            .owners_for(&unsound::path::new(path))
+            .to_vec()
+    }
+
+    #[test]
+    fn patterns() {
+        let rules = "*.js @js\n\
+                     build/logs/ @logs\n\
+                     /apps/ @apps\n\
+                     docs/*.md @docs\n\
+                     **/vendor @vendor\n\
+                     lib/**/test.?s @tests # inline comment\n";
+
+        assert_eq!(owners(rules, "~/src/app.js"), ["@js"]);
+        assert_eq!(owners(rules, "~/build/logs/today/out.txt"), ["@logs"]);
+        assert!(owners(rules, "~/build/logs").is_empty());
+        assert_eq!(owners(rules, "~/apps/main.rs"), ["@apps"]);
+        assert!(owners(rules, "~/src/apps/main.rs").is_empty());
+        assert_eq!(owners(rules, "~/docs/intro.md"), ["@docs"]);
+        assert!(owners(rules, "~/docs/guide/intro.md").is_empty());
+        assert_eq!(owners(rules, "~/a/b/vendor/c.txt"), ["@vendor"]);
+        assert_eq!(owners(rules, "~/lib/test.ts"), ["@tests"]);
+        assert_eq!(owners(rules, "~/lib/a/b/test.js"), ["@tests"]);
+    }
+
+    #[test]
+    fn last_rule_wins() {
+        let rules = "* @everyone\n/private/ @admin\n/private/public.txt\n";
+
+        assert_eq!(owners(rules, "~/README"), ["@everyone"]);
+        assert_eq!(owners(rules, "~/private/key"), ["@admin"]);
+        assert!(owners(rules, "~/private/public.txt").is_empty());
+    }
+
+    #[test]
+    fn from_directory() {
+        let mut builder = DirectoryBuilder::new();
+        builder
+            .insert_file(unsound::path::new("docs/CODEOWNERS"), b"* @docs")
+            .insert_file(unsound::path::new(".github/CODEOWNERS"), b"* @github");
+        let directory = builder.build().unwrap();
+        let owners = CodeOwners::from_directory(&directory).unwrap();
+        assert_eq!(
+            owners.owners_for(&unsound::path::new("~/lib.rs")),
+            ["@github"]
+        );
+
+        let mut builder = DirectoryBuilder::new();
+        builder.insert_file(unsound::path::new("README"), b"");
+        assert!(CodeOwners::from_directory(&builder.build().unwrap()).is_none());
+    }
+}
@@ -19,7 +19,14 @@ use std::fmt::Write as _;
 
 use crate::{file_system::error, nonempty::split_last};
 use nonempty::NonEmpty;
-use std::{convert::TryFrom, ffi::CString, fmt, ops::Deref, path, str::FromStr};
+use std::{
+    convert::TryFrom,
+    ffi::CString,
+    fmt,
+    ops::Deref,
+    path,
+    str::{self, FromStr},
+};
 
 #[cfg(feature = "serialize")]
 use serde::{Serialize, Serializer};
@@ -83,6 +90,49 @@ impl Label {
     pub fn is_root(&self) -> bool {
         *self == Self::root()
     }
+
+    /// Create a `Label` from the `bytes` of a name, such as the name of an
+    /// entry in a git tree, where a name that is not valid UTF-8 is handled
+    /// according to the `policy`. Returns `None` if the name is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{Label, NonUtf8Policy};
+    ///
+    /// let name = b"caf\xe9.txt";
+    /// assert!(Label::from_bytes(name, NonUtf8Policy::Error).is_err());
+    /// assert_eq!(Label::from_bytes(name, NonUtf8Policy::Skip), Ok(None));
+    ///
+    /// let lossy = Label::from_bytes(name, NonUtf8Policy::Lossy)?.unwrap();
+    /// assert_eq!(lossy.to_string(), "caf\u{FFFD}.txt");
+    /// # Ok::<(), radicle_surf::file_system::Error>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8], policy: NonUtf8Policy) -> Result<Option<Self>, error::Error> {
+        match str::from_utf8(bytes) {
+            Ok(name) => Label::try_from(name).map(Some),
+            Err(_) => match policy {
+                NonUtf8Policy::Error => Err(error::label_invalid_utf8_bytes(bytes)),
+                NonUtf8Policy::Lossy => Label::try_from(&*String::from_utf8_lossy(bytes)).map(Some),
+                NonUtf8Policy::Skip => Ok(None),
+            },
+        }
+    }
+}
+
+/// What to do with a file or directory name that is not valid UTF-8, which
+/// a [`Label`] cannot hold.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NonUtf8Policy {
+    /// Fail with an [`Error`](error::Error).
+    #[default]
+    Error,
+    /// Replace the invalid parts of the name with `U+FFFD`, the replacement
+    /// character. Two names that only differ in their invalid parts end up
+    /// the same.
+    Lossy,
+    /// Leave the file or directory out.
+    Skip,
 }
 
 impl fmt::Display for Label {
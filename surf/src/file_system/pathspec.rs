@@ -0,0 +1,179 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Selecting the paths of a repository by the patterns they match, in the
+//! spirit of `git log -- <pathspec>`.
+//!
+//! # Examples
+//!
+//! ```
+//! use radicle_surf::file_system::{unsound, Pathspec};
+//!
+//! // Everything under `src/`, except for its tests.
+//! let pathspec = Pathspec::default().include("src")?.exclude("src/**/tests")?;
+//!
+//! assert!(pathspec.matches(&unsound::path::new("~/src/lib.rs")));
+//! assert!(pathspec.matches(&unsound::path::new("~/src/git")));
+//! assert!(!pathspec.matches(&unsound::path::new("~/src/git/tests/mod.rs")));
+//! assert!(!pathspec.matches(&unsound::path::new("~/README.md")));
+//!
+//! // A `Path` only matches itself, even if it looks like a pattern.
+//! let pathspec = Pathspec::from(unsound::path::new("~/docs/*.md"));
+//! assert!(pathspec.matches(&unsound::path::new("~/docs/*.md")));
+//! assert!(!pathspec.matches(&unsound::path::new("~/docs/README.md")));
+//!
+//! // An empty pattern would match nothing.
+//! assert!(Pathspec::default().include("").is_err());
+//! # Ok::<(), radicle_surf::file_system::Error>(())
+//! ```
+
+use crate::file_system::{pattern, Error, Path};
+use regex::Regex;
+
+/// A set of patterns that paths are included by and excluded by.
+///
+/// A path matches if it matches any of the included patterns, or there are
+/// none, and none of the excluded patterns. The patterns follow the syntax of
+/// `.gitignore` files, always relative to the root of the repository: `*`
+/// does not match a `/`, `**` matches any number of directories, and a
+/// pattern matching a directory also matches everything beneath it. A `\`
+/// makes the character after it literal, e.g. `\*`.
+///
+/// A single [`Path`] converts into a `Pathspec` including just that path,
+/// taken literally, or everything for the root.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Pathspec {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl From<Path> for Pathspec {
+    fn from(path: Path) -> Self {
+        Self::from(&path)
+    }
+}
+
+impl From<&Path> for Pathspec {
+    fn from(path: &Path) -> Self {
+        if path.is_root() {
+            Self::default()
+        } else {
+            // The labels of a path are not empty, so neither is the pattern.
+            Self {
+                include: vec![pattern::escape(&path.to_string())],
+                exclude: vec![],
+            }
+        }
+    }
+}
+
+impl Pathspec {
+    /// Include the paths matching `pattern`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::EmptyPattern`]
+    pub fn include(mut self, pattern: &str) -> Result<Self, Error> {
+        self.include.push(checked(pattern)?);
+        Ok(self)
+    }
+
+    /// Exclude the paths matching `pattern`, even if they are included.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::EmptyPattern`]
+    pub fn exclude(mut self, pattern: &str) -> Result<Self, Error> {
+        self.exclude.push(checked(pattern)?);
+        Ok(self)
+    }
+
+    /// The patterns of the paths that are included.
+    pub fn includes(&self) -> &[String] {
+        &self.include
+    }
+
+    /// The patterns of the paths that are excluded.
+    pub fn excludes(&self) -> &[String] {
+        &self.exclude
+    }
+
+    /// Check whether the `Pathspec` matches every path, i.e. it has no
+    /// patterns.
+    pub fn is_everything(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Check whether `path` matches the `Pathspec`.
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matcher().is_match(&path.to_string())
+    }
+
+    /// Compile the patterns, so that many paths can be matched against them.
+    pub(crate) fn matcher(&self) -> Matcher {
+        let compile = |patterns: &[String]| patterns.iter().filter_map(|p| to_regex(p)).collect();
+        Matcher {
+            include: compile(&self.include),
+            exclude: compile(&self.exclude),
+        }
+    }
+
+    /// The included patterns that can be handed to libgit2 to narrow down a
+    /// diff before it is matched against the `Pathspec`, which is only
+    /// possible when none of them are globs.
+    ///
+    /// libgit2 matches a pathspec such as `src` against everything under it,
+    /// so it selects a superset of the paths the `Pathspec` does.
+    pub(crate) fn literal_includes(&self) -> Option<&[String]> {
+        let is_glob = |pattern: &String| pattern.contains(['*', '?', '[', '\\']);
+        if self.include.is_empty() || self.include.iter().any(is_glob) {
+            None
+        } else {
+            Some(&self.include)
+        }
+    }
+}
+
+/// Compile `pattern`, relative to the root of the repository.
+fn to_regex(pattern: &str) -> Option<Regex> {
+    // A leading `/` anchors the pattern to the root, even if it has no other
+    // `/`.
+    pattern::to_regex(&format!("/{}", pattern), true)
+}
+
+/// Check that `pattern` can match a path, so that it is not left out of the
+/// [`Matcher`] when it is compiled.
+fn checked(pattern: &str) -> Result<String, Error> {
+    match to_regex(pattern) {
+        Some(_) => Ok(pattern.to_string()),
+        None => Err(Error::EmptyPattern(pattern.to_string())),
+    }
+}
+
+/// The compiled patterns of a [`Pathspec`].
+pub(crate) struct Matcher {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl Matcher {
+    /// Check whether `path`, relative to the root of the repository, matches.
+    pub(crate) fn is_match(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|re| re.is_match(path)))
+            && !self.exclude.iter().any(|re| re.is_match(path))
+    }
+}
@@ -0,0 +1,89 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Matching paths against the patterns of `.gitignore` style files, such as
+//! `CODEOWNERS` and `.gitattributes`.
+
+use regex::Regex;
+
+/// Translate a `.gitignore` style `pattern` into a regular expression that
+/// matches a path, relative to the file the pattern is in, without a leading
+/// `/`.
+///
+/// If `recursive` is `true`, a pattern also matches everything under the
+/// directories it matches, as in `CODEOWNERS`. Otherwise it only matches the
+/// paths themselves, as in `.gitattributes`, where a pattern with a trailing
+/// `/` cannot match anything.
+pub(crate) fn to_regex(pattern: &str, recursive: bool) -> Option<Regex> {
+    let directory = pattern.ends_with('/');
+    if directory && !recursive {
+        return None;
+    }
+    let pattern = pattern.trim_end_matches('/');
+    // A pattern with a `/` before its end is relative to the root, otherwise
+    // it matches at any depth.
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('\\') {
+            // A `\` makes the character after it literal, and a trailing one
+            // is literal itself.
+            let literal = after.chars().next().unwrap_or('\\');
+            regex.push_str(&regex::escape(literal.encode_utf8(&mut [0; 4])));
+            rest = after.get(literal.len_utf8()..).unwrap_or_default();
+        } else {
+            match c {
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    regex.push_str(match (directory, recursive) {
+        (true, _) => "/.*$",
+        (false, true) => "(?:/.*)?$",
+        (false, false) => "$",
+    });
+
+    Regex::new(&regex).ok()
+}
+
+/// Escape the wildcards of `literal`, so that it only matches itself as a
+/// pattern.
+pub(crate) fn escape(literal: &str) -> String {
+    let mut pattern = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if matches!(c, '*' | '?' | '[' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
+}
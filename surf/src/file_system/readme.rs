@@ -0,0 +1,156 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Finding the readme of a [`Directory`].
+//!
+//! See [`Directory::readme`].
+
+use crate::file_system::{Directory, File, Label, Path, SystemType};
+
+/// The markup that a [`Readme`] is written in, as found from the extension
+/// of its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Format {
+    /// Markdown, such as `README.md`.
+    Markdown,
+    /// reStructuredText, such as `README.rst`.
+    ReStructuredText,
+    /// AsciiDoc, such as `README.adoc`.
+    AsciiDoc,
+    /// Org mode, such as `README.org`.
+    Org,
+    /// Plain text, such as `README` or `README.txt`.
+    Plain,
+}
+
+impl Format {
+    /// The `Format` of a readme with the `extension`, if it is one.
+    fn from_extension(extension: Option<&str>) -> Option<Self> {
+        let extension = match extension {
+            None => return Some(Format::Plain),
+            Some(extension) => extension.to_lowercase(),
+        };
+        match extension.as_str() {
+            "md" | "markdown" | "mdown" | "mkdn" => Some(Format::Markdown),
+            "rst" => Some(Format::ReStructuredText),
+            "adoc" | "asciidoc" => Some(Format::AsciiDoc),
+            "org" => Some(Format::Org),
+            "txt" => Some(Format::Plain),
+            _ => None,
+        }
+    }
+}
+
+/// The readme of a [`Directory`], see [`Directory::readme`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Readme {
+    /// The name of the readme, as it is written in the `Directory`.
+    pub name: Label,
+    /// The contents of the readme.
+    pub file: File,
+    /// The markup of the readme.
+    pub format: Format,
+}
+
+/// Parse the name of a file into the [`Format`] of the readme it is, if it is
+/// one.
+fn readme_format(name: &str) -> Option<Format> {
+    let (stem, extension) = match name.split_once('.') {
+        Some((stem, extension)) => (stem, Some(extension)),
+        None => (name, None),
+    };
+    if stem.eq_ignore_ascii_case("readme") {
+        Format::from_extension(extension)
+    } else {
+        None
+    }
+}
+
+impl Directory {
+    /// Find the readme of this `Directory`, among the files directly in it.
+    ///
+    /// The name of a readme is `README`, in any case, optionally followed by
+    /// the extension of its [`Format`]. If there are several, the one whose
+    /// `Format` comes first is picked, so that `README.md` is preferred over
+    /// `README.txt`, and then the one whose name comes first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{readme::Format, unsound, DirectoryBuilder};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut builder = DirectoryBuilder::new();
+    /// builder
+    ///     .insert_file(unsound::path::new("README"), b"Hello")
+    ///     .insert_file(unsound::path::new("readme.rst"), b"Hello\n=====")
+    ///     .insert_file(unsound::path::new("src/README.markdown"), b"# Source");
+    /// let directory = builder.build()?;
+    ///
+    /// let readme = directory.readme().expect("missing readme");
+    /// assert_eq!(readme.name, unsound::label::new("readme.rst"));
+    /// assert_eq!(readme.format, Format::ReStructuredText);
+    ///
+    /// let src = directory.find_directory(unsound::path::new("src")).unwrap();
+    /// assert_eq!(src.readme().map(|readme| readme.format), Some(Format::Markdown));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn readme(&self) -> Option<Readme> {
+        let (format, name) = self
+            .list_directory()
+            .into_iter()
+            .filter_map(|entry| match entry {
+                (name, SystemType::File) => Some((readme_format(&name)?, name)),
+                (_, SystemType::Directory) => None,
+            })
+            .min_by(|(a, a_name), (b, b_name)| a.cmp(b).then_with(|| a_name.cmp(b_name)))?;
+
+        Some(Readme {
+            file: self.find_file(Path::new(name.clone()))?,
+            name,
+            format,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system::{unsound, DirectoryBuilder};
+
+    #[test]
+    fn names() {
+        assert_eq!(readme_format("ReadMe.MD"), Some(Format::Markdown));
+        assert_eq!(readme_format("README.txt"), Some(Format::Plain));
+        assert_eq!(readme_format("readme"), Some(Format::Plain));
+        assert_eq!(readme_format("README.html"), None);
+        assert_eq!(readme_format("READ_ME.md"), None);
+        assert_eq!(readme_format("readme.md.bak"), None);
+    }
+
+    #[test]
+    fn no_readme() {
+        let mut builder = DirectoryBuilder::new();
+        builder
+            .insert_file(unsound::path::new("LICENSE"), b"")
+            .insert_file(unsound::path::new("docs/README.md"), b"");
+        assert_eq!(builder.build().unwrap().readme(), None);
+    }
+}
@@ -15,14 +15,60 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+//! A generic prefix-tree, where values are stored at the end of a non-empty
+//! path of keys.
+//!
+//! This is the structure underlying a
+//! [`Directory`](crate::file_system::Directory), where the keys are the
+//! [`Label`](crate::file_system::Label)s of a path, and it is useful for
+//! building any other path-indexed data, e.g. a cache of file histories.
+//!
+//! A [`Forest`] is a possibly empty collection of [`Tree`]s, which in turn
+//! are a non-empty collection of [`SubTree`]s. A [`SubTree`] is either a
+//! `Node`, holding a value, or a `Branch`, holding a further [`Tree`].
+//!
+//! # Examples
+//!
+//! ```
+//! use nonempty::NonEmpty;
+//! use radicle_surf::file_system::{unsound, Label};
+//! use radicle_surf::tree::Forest;
+//!
+//! let path = |p: &str| -> NonEmpty<Label> { unsound::path::new(p).0 };
+//!
+//! let mut forest = Forest::root();
+//! forest.insert(path("src/lib.rs"), 1);
+//! forest.insert(path("src/tree.rs"), 2);
+//! forest.insert(path("README.md"), 3);
+//!
+//! assert_eq!(forest.find_node(path("src/tree.rs")), Some(&2));
+//! assert_eq!(forest.iter().sum::<i32>(), 6);
+//!
+//! forest.prune(path("src"));
+//! assert_eq!(forest.iter().collect::<Vec<_>>(), vec![&3]);
+//! ```
+
 use crate::nonempty::split_last;
 use nonempty::NonEmpty;
 use std::cmp::Ordering;
 
+/// An entry of a [`Tree`], which is either a value or a further [`Tree`].
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SubTree<K, A> {
-    Node { key: K, value: A },
-    Branch { key: K, forest: Box<Tree<K, A>> },
+    /// A leaf of the tree, holding a value.
+    Node {
+        /// The last key of the path to the value.
+        key: K,
+        /// The value at the end of the path.
+        value: A,
+    },
+    /// An inner part of the tree, holding a non-empty [`Tree`].
+    Branch {
+        /// The key of the path to the tree.
+        key: K,
+        /// The entries beneath the key.
+        forest: Box<Tree<K, A>>,
+    },
 }
 
 impl<K, A> SubTree<K, A> {
@@ -37,13 +83,16 @@ impl<K, A> SubTree<K, A> {
         }
     }
 
-    fn key(&self) -> &K {
+    /// The key of this `SubTree`, whether it is a `Node` or a `Branch`.
+    pub fn key(&self) -> &K {
         match self {
             SubTree::Node { key, .. } => key,
             SubTree::Branch { key, .. } => key,
         }
     }
 
+    /// Find a `SubTree` given a search path, where the first key is the key of
+    /// this `SubTree`. If the path does not match it will return `None`.
     pub fn find(&self, keys: NonEmpty<K>) -> Option<&Self>
     where
         K: Ord,
@@ -63,6 +112,7 @@ impl<K, A> SubTree<K, A> {
         }
     }
 
+    /// Collect all the values in this `SubTree`, ordered by their paths.
     pub fn to_nonempty(&self) -> NonEmpty<A>
     where
         A: Clone,
@@ -74,14 +124,45 @@ impl<K, A> SubTree<K, A> {
         }
     }
 
-    pub(crate) fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &A> + 'a> {
+    /// Iterate over all the values in this `SubTree`, ordered by their paths.
+    pub fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a A> + 'a> {
         match self {
             SubTree::Node { value, .. } => Box::new(std::iter::once(value)),
             SubTree::Branch { ref forest, .. } => Box::new(forest.iter()),
         }
     }
 
-    fn iter_keys<'a>(&'a self) -> Box<dyn Iterator<Item = &K> + 'a> {
+    /// Iterate over all the values in this `SubTree` along with their full
+    /// paths, ordered by their paths.
+    pub fn iter_entries<'a>(&'a self) -> Box<dyn Iterator<Item = (NonEmpty<&'a K>, &'a A)> + 'a> {
+        match self {
+            SubTree::Node { key, value } => Box::new(std::iter::once((NonEmpty::new(key), value))),
+            SubTree::Branch { key, forest } => {
+                Box::new(forest.iter_entries().map(move |(mut keys, value)| {
+                    keys.insert(0, key);
+                    (keys, value)
+                }))
+            },
+        }
+    }
+
+    /// Consume this `SubTree`, collecting all the values along with their full
+    /// paths, ordered by their paths.
+    fn into_entries(self) -> Vec<(NonEmpty<K>, A)>
+    where
+        K: Clone,
+    {
+        match self {
+            SubTree::Node { key, value } => vec![(NonEmpty::new(key), value)],
+            SubTree::Branch { key, forest } => forest
+                .into_entries()
+                .into_iter()
+                .map(|(keys, value)| (NonEmpty::from((key.clone(), keys.into())), value))
+                .collect(),
+        }
+    }
+
+    fn iter_keys<'a>(&'a self) -> Box<dyn Iterator<Item = &'a K> + 'a> {
         match self {
             SubTree::Node { key, .. } => Box::new(std::iter::once(key)),
             SubTree::Branch {
@@ -124,6 +205,8 @@ impl<K, A> SubTree<K, A> {
         }
     }
 
+    /// Find the maximum value in this `SubTree` with respect to the
+    /// comparison function `f`.
     pub fn maximum_by<F>(&self, f: &F) -> &A
     where
         F: Fn(&A, &A) -> Ordering,
@@ -134,6 +217,7 @@ impl<K, A> SubTree<K, A> {
         }
     }
 
+    /// Apply `f` to all the values in this `SubTree`, keeping its shape.
     pub fn map<F, B>(self, f: &mut F) -> SubTree<K, B>
     where
         F: FnMut(A) -> B,
@@ -151,6 +235,7 @@ impl<K, A> SubTree<K, A> {
     }
 }
 
+/// A non-empty collection of [`SubTree`]s, ordered by their keys.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Tree<K, A>(pub(crate) NonEmpty<SubTree<K, A>>);
 
@@ -160,9 +245,17 @@ impl<K, A> From<Tree<K, A>> for Forest<K, A> {
     }
 }
 
+/// A possibly empty [`Tree`], which is the entry point for building a
+/// prefix-tree.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Forest<K, A>(pub(crate) Option<Tree<K, A>>);
 
+impl<K, A> Default for Forest<K, A> {
+    fn default() -> Self {
+        Self::root()
+    }
+}
+
 impl<K, A> Tree<K, A> {
     /// Create a new `Tree` containing a single `Branch` given
     /// the key and sub-tree.
@@ -208,6 +301,7 @@ impl<K, A> Tree<K, A> {
         self.0.binary_search_by(|tree| tree.key().cmp(key))
     }
 
+    /// Apply `f` to all the values in this `Tree`, keeping its shape.
     pub fn map<F, B>(self, mut f: F) -> Tree<K, B>
     where
         F: FnMut(A) -> B,
@@ -302,6 +396,8 @@ impl<K, A> Tree<K, A> {
         }
     }
 
+    /// Insert the `value` in the position given by `keys`, replacing any
+    /// existing value. See [`Forest::insert`] for the details.
     pub fn insert(&mut self, keys: NonEmpty<K>, value: A)
     where
         A: Clone,
@@ -310,6 +406,7 @@ impl<K, A> Tree<K, A> {
         self.insert_with(keys, value.clone(), |old| *old = value)
     }
 
+    /// Collect all the values in this `Tree`, ordered by their paths.
     pub fn to_nonempty(&self) -> NonEmpty<A>
     where
         A: Clone,
@@ -318,18 +415,110 @@ impl<K, A> Tree<K, A> {
         self.0.clone().flat_map(|sub_tree| sub_tree.to_nonempty())
     }
 
-    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &A> + 'a {
+    /// Iterate over all the values in this `Tree`, ordered by their paths.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a A> + 'a {
         self.0.iter().flat_map(|tree| tree.iter())
     }
 
-    pub fn iter_keys<'a>(&'a self) -> impl Iterator<Item = &K> + 'a {
+    /// Iterate over all the values in this `Tree` along with their full paths,
+    /// ordered by their paths.
+    pub fn iter_entries<'a>(&'a self) -> impl Iterator<Item = (NonEmpty<&'a K>, &'a A)> + 'a {
+        self.0.iter().flat_map(|tree| tree.iter_entries())
+    }
+
+    /// Iterate over all the keys in this `Tree`, depth-first.
+    pub fn iter_keys<'a>(&'a self) -> impl Iterator<Item = &'a K> + 'a {
         self.0.iter().flat_map(|tree| tree.iter_keys())
     }
 
-    pub fn iter_subtrees<'a>(&'a self) -> impl Iterator<Item = &SubTree<K, A>> + 'a {
+    /// Iterate over the top-level [`SubTree`]s of this `Tree`.
+    pub fn iter_subtrees<'a>(&'a self) -> impl Iterator<Item = &'a SubTree<K, A>> + 'a {
         self.0.iter()
     }
 
+    fn into_entries(self) -> Vec<(NonEmpty<K>, A)>
+    where
+        K: Clone,
+    {
+        self.0
+            .into_iter()
+            .flat_map(|tree| tree.into_entries())
+            .collect()
+    }
+
+    /// Remove the [`SubTree`] found at `keys`, returning it, along with what
+    /// remains of this `Tree`. Any branches left empty by the removal are
+    /// removed as well.
+    fn prune(self, keys: NonEmpty<K>) -> (Option<Self>, Option<SubTree<K, A>>)
+    where
+        K: Ord,
+    {
+        let (head, tail) = keys.into();
+        let index = match self.search(&head) {
+            Ok(index) => index,
+            Err(_) => return (Some(self), None),
+        };
+
+        let mut sub_trees: Vec<SubTree<K, A>> = self.0.into();
+        let sub_tree = sub_trees.remove(index);
+        let removed = match (sub_tree, NonEmpty::from_vec(tail)) {
+            (sub_tree, None) => Some(sub_tree),
+            (SubTree::Branch { key, forest }, Some(keys)) => {
+                let (forest, removed) = forest.prune(keys);
+                if let Some(forest) = forest {
+                    sub_trees.insert(index, SubTree::branch(key, forest));
+                }
+                removed
+            },
+            (node, Some(_)) => {
+                sub_trees.insert(index, node);
+                None
+            },
+        };
+
+        (NonEmpty::from_vec(sub_trees).map(Tree), removed)
+    }
+
+    /// Keep only the values for which `f` returns `true`, returning what
+    /// remains of this `Tree`. Any branches left empty are removed as well.
+    fn retain<F>(self, f: &mut F) -> Option<Self>
+    where
+        F: FnMut(&A) -> bool,
+    {
+        let sub_trees = self
+            .0
+            .into_iter()
+            .filter_map(|sub_tree| match sub_tree {
+                SubTree::Node { value, key } => {
+                    if f(&value) {
+                        Some(SubTree::Node { key, value })
+                    } else {
+                        None
+                    }
+                },
+                SubTree::Branch { key, forest } => {
+                    forest.retain(f).map(|forest| SubTree::branch(key, forest))
+                },
+            })
+            .collect();
+        NonEmpty::from_vec(sub_trees).map(Tree)
+    }
+
+    fn find_node_mut(&mut self, keys: NonEmpty<K>) -> Option<&mut A>
+    where
+        K: Ord,
+    {
+        let (head, tail) = keys.into();
+        let index = self.search(&head).ok()?;
+        match (self.0.get_mut(index)?, NonEmpty::from_vec(tail)) {
+            (SubTree::Node { value, .. }, None) => Some(value),
+            (SubTree::Branch { forest, .. }, Some(keys)) => forest.find_node_mut(keys),
+            _ => None,
+        }
+    }
+
+    /// Find the value at the end of the path given by `keys`. If the path does
+    /// not match, or ends at a branch, it will return `None`.
     pub fn find_node(&self, keys: NonEmpty<K>) -> Option<&A>
     where
         K: Ord,
@@ -340,6 +529,8 @@ impl<K, A> Tree<K, A> {
         })
     }
 
+    /// Find the `Tree` at the end of the path given by `keys`. If the path
+    /// does not match, or ends at a node, it will return `None`.
     pub fn find_branch(&self, keys: NonEmpty<K>) -> Option<&Self>
     where
         K: Ord,
@@ -376,6 +567,38 @@ impl<K, A> Tree<K, A> {
         }
     }
 
+    /// Find a `SubTree` given a search path of borrowed keys, which are
+    /// compared with the view of each key of the tree given by `view`, so
+    /// that no owned keys have to be built for the search. If the path does
+    /// not match, or is empty, it will return `None`.
+    pub fn find_by<'q, Q, I, F>(&self, keys: I, view: F) -> Option<&SubTree<K, A>>
+    where
+        Q: Ord + ?Sized + 'q,
+        I: IntoIterator<Item = &'q Q>,
+        F: Fn(&K) -> &Q,
+    {
+        let mut keys = keys.into_iter();
+        let mut tree = self;
+        let mut head = keys.next()?;
+        loop {
+            let index = tree
+                .0
+                .binary_search_by(|sub_tree| view(sub_tree.key()).cmp(head))
+                .ok()?;
+            let sub_tree = tree.0.get(index)?;
+            match (keys.next(), sub_tree) {
+                (None, _) => return Some(sub_tree),
+                (Some(next), SubTree::Branch { forest, .. }) => {
+                    tree = forest;
+                    head = next;
+                },
+                (Some(_), SubTree::Node { .. }) => return None,
+            }
+        }
+    }
+
+    /// Find the maximum value in this `Tree` with respect to the comparison
+    /// function `f`.
     pub fn maximum_by<F>(&self, f: &F) -> &A
     where
         F: Fn(&A, &A) -> Ordering,
@@ -383,7 +606,7 @@ impl<K, A> Tree<K, A> {
         self.0.maximum_by(|s, t| s.compare_by(t, f)).maximum_by(f)
     }
 
-    #[allow(dead_code)]
+    /// Find the maximum value in this `Tree`.
     pub fn maximum(&self) -> &A
     where
         A: Ord,
@@ -393,11 +616,12 @@ impl<K, A> Tree<K, A> {
 }
 
 impl<K, A> Forest<K, A> {
+    /// Create an empty `Forest`.
     pub fn root() -> Self {
         Forest(None)
     }
 
-    #[allow(dead_code)]
+    /// Check whether the `Forest` contains no values.
     pub fn is_empty(&self) -> bool {
         self.0.is_none()
     }
@@ -422,7 +646,6 @@ impl<K, A> Forest<K, A> {
     ///
     /// If the path does not exist it will be inserted into the set of
     /// sub-trees.
-    #[allow(dead_code)]
     pub fn insert(&mut self, keys: NonEmpty<K>, node: A)
     where
         A: Clone,
@@ -431,6 +654,9 @@ impl<K, A> Forest<K, A> {
         self.insert_with(keys, node.clone(), |old| *old = node)
     }
 
+    /// Insert the `node` in the position given by `keys`, in the same way as
+    /// [`Forest::insert`], except that if a value already exists at the path
+    /// it is updated by `f` instead of being replaced.
     pub fn insert_with<F>(&mut self, keys: NonEmpty<K>, node: A, f: F)
     where
         F: FnOnce(&mut A),
@@ -458,6 +684,8 @@ impl<K, A> Forest<K, A> {
         }
     }
 
+    /// Find the value at the end of the path given by `keys`. If the path does
+    /// not match, or ends at a branch, it will return `None`.
     pub fn find_node(&self, keys: NonEmpty<K>) -> Option<&A>
     where
         K: Ord,
@@ -465,6 +693,8 @@ impl<K, A> Forest<K, A> {
         self.0.as_ref().and_then(|trees| trees.find_node(keys))
     }
 
+    /// Find the [`Tree`] at the end of the path given by `keys`. If the path
+    /// does not match, or ends at a node, it will return `None`.
     pub fn find_branch(&self, keys: NonEmpty<K>) -> Option<&Tree<K, A>>
     where
         K: Ord,
@@ -472,7 +702,6 @@ impl<K, A> Forest<K, A> {
         self.0.as_ref().and_then(|trees| trees.find_branch(keys))
     }
 
-    #[allow(dead_code)]
     /// Find a `SubTree` given a search path. If the path does not match
     /// it will return `None`.
     pub fn find(&self, keys: NonEmpty<K>) -> Option<&SubTree<K, A>>
@@ -482,7 +711,118 @@ impl<K, A> Forest<K, A> {
         self.0.as_ref().and_then(|trees| trees.find(keys))
     }
 
-    #[allow(dead_code)]
+    /// Find a `SubTree` given a search path of borrowed keys, see
+    /// [`Tree::find_by`].
+    pub fn find_by<'q, Q, I, F>(&self, keys: I, view: F) -> Option<&SubTree<K, A>>
+    where
+        Q: Ord + ?Sized + 'q,
+        I: IntoIterator<Item = &'q Q>,
+        F: Fn(&K) -> &Q,
+    {
+        self.0.as_ref().and_then(|trees| trees.find_by(keys, view))
+    }
+
+    /// Remove the [`SubTree`] at the end of the path given by `keys`, which
+    /// may be a single value or a whole branch, and return it. Any branches
+    /// that are left empty by the removal are removed as well.
+    ///
+    /// If the path does not match, the `Forest` is left untouched and `None`
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nonempty::NonEmpty;
+    /// use radicle_surf::tree::{Forest, SubTree};
+    ///
+    /// let mut forest = Forest::root();
+    /// forest.insert(NonEmpty::from(("a", vec!["b", "c"])), 1);
+    /// forest.insert(NonEmpty::from(("d", vec![])), 2);
+    ///
+    /// assert_eq!(
+    ///     forest.prune(NonEmpty::from(("a", vec!["b", "c"]))),
+    ///     Some(SubTree::Node { key: "c", value: 1 })
+    /// );
+    ///
+    /// // The now empty branches `a` and `a/b` are gone as well.
+    /// assert_eq!(forest.find(NonEmpty::new("a")), None);
+    /// assert_eq!(forest.iter().collect::<Vec<_>>(), vec![&2]);
+    /// ```
+    pub fn prune(&mut self, keys: NonEmpty<K>) -> Option<SubTree<K, A>>
+    where
+        K: Ord,
+    {
+        let (forest, removed) = match self.0.take() {
+            None => (None, None),
+            Some(trees) => trees.prune(keys),
+        };
+        self.0 = forest;
+        removed
+    }
+
+    /// Keep only the values for which `f` returns `true`. Any branches that
+    /// are left empty are removed as well.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&A) -> bool,
+    {
+        self.0 = self.0.take().and_then(|trees| trees.retain(&mut f))
+    }
+
+    /// Merge the values of `other` into this `Forest`. Where both have a value
+    /// at the same path, the two are combined with `f`, which is given this
+    /// `Forest`'s value to update and `other`'s value. Otherwise, the values
+    /// of `other` are inserted as with [`Forest::insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nonempty::NonEmpty;
+    /// use radicle_surf::tree::Forest;
+    ///
+    /// let mut left = Forest::root();
+    /// left.insert(NonEmpty::from(("src", vec!["lib.rs"])), 1);
+    /// left.insert(NonEmpty::from(("src", vec!["tree.rs"])), 2);
+    ///
+    /// let mut right = Forest::root();
+    /// right.insert(NonEmpty::from(("src", vec!["tree.rs"])), 3);
+    /// right.insert(NonEmpty::new("README.md"), 4);
+    ///
+    /// left.merge_with(right, |old, new| *old += new);
+    ///
+    /// assert_eq!(
+    ///     left.iter_entries().map(|(_, value)| *value).collect::<Vec<_>>(),
+    ///     vec![4, 1, 5]
+    /// );
+    /// ```
+    pub fn merge_with<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&mut A, A),
+        K: Ord + Clone,
+    {
+        for (keys, value) in other.0.map(Tree::into_entries).unwrap_or_default() {
+            match self
+                .0
+                .as_mut()
+                .and_then(|trees| trees.find_node_mut(keys.clone()))
+            {
+                Some(old) => f(old, value),
+                None => self.insert_with(keys, value, |_| {}),
+            }
+        }
+    }
+
+    /// Merge the values of `other` into this `Forest`, where the values of
+    /// `other` replace any existing values at the same path.
+    pub fn merge(&mut self, other: Self)
+    where
+        K: Ord + Clone,
+    {
+        self.merge_with(other, |old, new| *old = new)
+    }
+
+    /// Find the maximum value in this `Forest` with respect to the comparison
+    /// function `f`. If the `Forest` is empty it will return `None`.
     pub fn maximum_by<F>(&self, f: F) -> Option<&A>
     where
         F: Fn(&A, &A) -> Ordering,
@@ -490,12 +830,38 @@ impl<K, A> Forest<K, A> {
         self.0.as_ref().map(|trees| trees.maximum_by(&f))
     }
 
-    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &A> + 'a {
+    /// Iterate over all the values in this `Forest`, ordered by their paths.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a A> + 'a {
         self.0.iter().flat_map(|trees| trees.iter())
     }
 
-    #[allow(dead_code)]
-    pub fn iter_keys<'a>(&'a self) -> impl Iterator<Item = &K> + 'a {
+    /// Iterate over all the values in this `Forest` along with their full
+    /// paths, ordered by their paths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nonempty::NonEmpty;
+    /// use radicle_surf::tree::Forest;
+    ///
+    /// let mut forest = Forest::root();
+    /// forest.insert(NonEmpty::from(("b", vec!["c"])), 1);
+    /// forest.insert(NonEmpty::new("a"), 2);
+    ///
+    /// assert_eq!(
+    ///     forest.iter_entries().collect::<Vec<_>>(),
+    ///     vec![
+    ///         (NonEmpty::new(&"a"), &2),
+    ///         (NonEmpty::from((&"b", vec![&"c"])), &1),
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter_entries<'a>(&'a self) -> impl Iterator<Item = (NonEmpty<&'a K>, &'a A)> + 'a {
+        self.0.iter().flat_map(|trees| trees.iter_entries())
+    }
+
+    /// Iterate over all the keys in this `Forest`, depth-first.
+    pub fn iter_keys<'a>(&'a self) -> impl Iterator<Item = &'a K> + 'a {
         self.0.iter().flat_map(|trees| trees.iter_keys())
     }
 }
@@ -1188,4 +1554,83 @@ mod tests {
 
         assert_eq!(tree.iter().fold(0, |b, a| a.id + b), 4);
     }
+
+    #[test]
+    fn test_prune_missing_path() {
+        let mut tree = Forest::root();
+
+        let a_node = TestNode { id: 1 };
+
+        tree.insert(
+            NonEmpty::from((String::from("a"), vec![String::from("b")])),
+            a_node,
+        );
+        let expected = tree.clone();
+
+        assert_eq!(
+            tree.prune(NonEmpty::from((
+                String::from("a"),
+                vec![String::from("b"), String::from("c")]
+            ))),
+            None
+        );
+        assert_eq!(tree.prune(NonEmpty::new(String::from("b"))), None);
+        assert_eq!(tree, expected);
+    }
+
+    #[test]
+    fn test_prune_last_node() {
+        let mut tree = Forest::root();
+
+        let a_node = TestNode { id: 1 };
+
+        tree.insert(
+            NonEmpty::from((String::from("a"), vec![String::from("b")])),
+            a_node,
+        );
+        tree.prune(NonEmpty::from((String::from("a"), vec![String::from("b")])));
+
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_retain_removes_empty_branches() {
+        let mut tree = Forest::root();
+
+        let a_node = TestNode { id: 1 };
+        let b_node = TestNode { id: 2 };
+
+        tree.insert(
+            NonEmpty::from((String::from("a"), vec![String::from("b")])),
+            a_node,
+        );
+        tree.insert(NonEmpty::new(String::from("c")), b_node.clone());
+        tree.retain(|node| node.id > 1);
+
+        assert_eq!(tree, Forest(Some(Tree::node(String::from("c"), b_node))));
+    }
+
+    #[test]
+    fn test_merge_replaces_node_with_branch() {
+        let mut tree = Forest::root();
+        let mut other = Forest::root();
+
+        let a_node = TestNode { id: 1 };
+        let b_node = TestNode { id: 2 };
+
+        tree.insert(NonEmpty::new(String::from("a")), a_node);
+        other.insert(
+            NonEmpty::from((String::from("a"), vec![String::from("b")])),
+            b_node.clone(),
+        );
+        tree.merge(other);
+
+        assert_eq!(
+            tree,
+            Forest(Some(Tree::branch(
+                String::from("a"),
+                Tree::node(String::from("b"), b_node)
+            )))
+        );
+    }
 }
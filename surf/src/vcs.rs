@@ -20,6 +20,7 @@
 
 use crate::file_system::directory::Directory;
 use nonempty::NonEmpty;
+use std::{cmp, collections::BTreeMap, iter, marker::PhantomData, mem, slice};
 
 pub mod git;
 
@@ -27,7 +28,7 @@ pub mod git;
 /// derive a [`crate::file_system::Directory`] view. Examples of artifacts
 /// would be commits in Git or patches in Pijul.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct History<A>(pub NonEmpty<A>);
+pub struct History<A>(NonEmpty<A>);
 
 impl<A> History<A> {
     /// Create a new `History` consisting of one artifact.
@@ -35,6 +36,33 @@ impl<A> History<A> {
         History(NonEmpty::new(a))
     }
 
+    /// Create a `History` from a [`NonEmpty`] collection of artifacts, in
+    /// order.
+    pub fn from_nonempty(artifacts: NonEmpty<A>) -> Self {
+        History(artifacts)
+    }
+
+    /// Create a `History` from the artifacts of `iter`, in order. Returns
+    /// `None` if there are no artifacts.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    ///
+    /// let history = History::try_from_iter(1..4).unwrap();
+    /// assert_eq!(history.first(), &1);
+    /// assert_eq!(history.last(), &3);
+    /// assert_eq!(history.len(), 3);
+    /// assert_eq!(history.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    ///
+    /// assert_eq!(History::try_from_iter(Vec::<u8>::new()), None);
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = A>,
+    {
+        NonEmpty::from_vec(iter.into_iter().collect()).map(History)
+    }
+
     /// Push an artifact to the end of the `History`.
     pub fn push(&mut self, a: A) {
         self.0.push(a)
@@ -50,6 +78,26 @@ impl<A> History<A> {
         self.0.first()
     }
 
+    /// Get the last artifact in the `History`.
+    pub fn last(&self) -> &A {
+        self.0.last()
+    }
+
+    /// Get the artifact at position `index`, where the first artifact is at
+    /// position 0, or `None` if the `History` is not that long.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((3, vec![2, 1])));
+    /// assert_eq!(history.get(1), Some(&2));
+    /// assert_eq!(history.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&A> {
+        self.0.get(index)
+    }
+
     /// Get the length of `History` (aka the artefacts count)
     pub fn len(&self) -> usize {
         self.0.len()
@@ -81,6 +129,68 @@ impl<A> History<A> {
         new_history.map(History)
     }
 
+    /// Given that the `History` is topological order from most
+    /// recent artifact to least recent, `find_prefix` returns the
+    /// history from the most recent artifact up to and including the
+    /// given artifact, i.e. the artifacts that are newer than it along
+    /// with the artifact itself. It is the counterpart of `find_suffix`.
+    ///
+    /// This operation may fail if the artifact does not exist in
+    /// the given `History`.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((4, vec![3, 2, 1])));
+    /// assert_eq!(history.find_prefix(&3), Some(History::from(NonEmpty::from((4, vec![3])))));
+    /// assert_eq!(history.find_suffix(&3), Some(History::from(NonEmpty::from((3, vec![2, 1])))));
+    /// assert_eq!(history.find_prefix(&5), None);
+    /// ```
+    pub fn find_prefix(&self, artifact: &A) -> Option<Self>
+    where
+        A: Clone + PartialEq,
+    {
+        let end = self.iter().position(|current| *current == *artifact)?;
+        NonEmpty::from_slice(&self.iter().take(end + 1).cloned().collect::<Vec<_>>()).map(History)
+    }
+
+    /// Get the artifacts from `from` up to and including `to`, where `from`
+    /// is the more recent of the two, i.e. it comes first in the `History`.
+    ///
+    /// This operation may fail if either artifact does not exist in the
+    /// given `History`, or if `to` comes before `from`.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((4, vec![3, 2, 1])));
+    /// assert_eq!(history.slice(&3, &2), Some(History::from(NonEmpty::from((3, vec![2])))));
+    /// assert_eq!(history.slice(&2, &2), Some(History::new(2)));
+    /// assert_eq!(history.slice(&2, &3), None);
+    /// ```
+    pub fn slice(&self, from: &A, to: &A) -> Option<Self>
+    where
+        A: Clone + PartialEq,
+    {
+        let start = self.iter().position(|current| *current == *from)?;
+        let len = self
+            .iter()
+            .skip(start)
+            .position(|current| *current == *to)?
+            + 1;
+        NonEmpty::from_slice(
+            &self
+                .iter()
+                .skip(start)
+                .take(len)
+                .cloned()
+                .collect::<Vec<_>>(),
+        )
+        .map(History)
+    }
+
     /// Apply a function from `A` to `B` over the `History`
     pub fn map<F, B>(self, f: F) -> History<B>
     where
@@ -89,6 +199,158 @@ impl<A> History<A> {
         History(self.0.map(f))
     }
 
+    /// Fold over the artifacts of the `History`, from the first to the last.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((1, vec![2, 3])));
+    /// assert_eq!(history.fold(0, |total, a| total + a), 6);
+    /// ```
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &A) -> B,
+    {
+        self.iter().fold(init, f)
+    }
+
+    /// Iterate over all contiguous windows of `size` artifacts, overlapping
+    /// by all but one artifact. If the `History` is shorter than `size` there
+    /// are no windows.
+    ///
+    /// This is useful for looking at each artifact alongside its neighbours,
+    /// e.g. a commit and its predecessor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((1, vec![2, 3])));
+    /// let windows = history
+    ///     .windows(2)
+    ///     .map(|window| window.iter().map(|a| **a).collect::<Vec<_>>())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(windows, vec![vec![1, 2], vec![2, 3]]);
+    /// ```
+    pub fn windows(&self, size: usize) -> impl Iterator<Item = History<&A>> + '_ {
+        assert!(size != 0, "window size must be non-zero");
+        let artifacts = self.iter().collect::<Vec<_>>();
+        (0..(artifacts.len() + 1).saturating_sub(size)).map(move |start| {
+            History(
+                NonEmpty::from_slice(&artifacts[start..start + size])
+                    .expect("windows are non-empty"),
+            )
+        })
+    }
+
+    /// Iterate over the artifacts in chunks of `size` artifacts, which do not
+    /// overlap. The last chunk may be shorter than `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((1, vec![2, 3])));
+    /// let chunks = history
+    ///     .chunks(2)
+    ///     .map(|chunk| chunk.iter().map(|a| **a).collect::<Vec<_>>())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(chunks, vec![vec![1, 2], vec![3]]);
+    /// ```
+    pub fn chunks(&self, size: usize) -> impl Iterator<Item = History<&A>> + '_ {
+        assert!(size != 0, "chunk size must be non-zero");
+        let artifacts = self.iter().collect::<Vec<_>>();
+        (0..artifacts.len()).step_by(size).map(move |start| {
+            let end = artifacts.len().min(start + size);
+            History(NonEmpty::from_slice(&artifacts[start..end]).expect("chunks are non-empty"))
+        })
+    }
+
+    /// Keep only the artifacts for which `f` returns `true`, preserving their
+    /// order. Returns `None` if no artifacts are kept.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((1, vec![2, 3, 4])));
+    /// assert_eq!(
+    ///     history.clone().filter(|a| a % 2 == 0),
+    ///     Some(History::from(NonEmpty::from((2, vec![4]))))
+    /// );
+    /// assert_eq!(history.filter(|a| *a > 4), None);
+    /// ```
+    pub fn filter<F>(self, mut f: F) -> Option<Self>
+    where
+        F: FnMut(&A) -> bool,
+    {
+        NonEmpty::from_vec(self.into_iter().filter(|a| f(a)).collect()).map(History)
+    }
+
+    /// Split the artifacts into those for which `f` returns `true` and those
+    /// for which it returns `false`, preserving their order. Either side is
+    /// `None` if it has no artifacts.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let history = History::from(NonEmpty::from((1, vec![2, 3, 4])));
+    /// assert_eq!(
+    ///     history.partition(|a| a % 2 == 0),
+    ///     (
+    ///         Some(History::from(NonEmpty::from((2, vec![4])))),
+    ///         Some(History::from(NonEmpty::from((1, vec![3])))),
+    ///     )
+    /// );
+    /// ```
+    pub fn partition<F>(self, mut f: F) -> (Option<Self>, Option<Self>)
+    where
+        F: FnMut(&A) -> bool,
+    {
+        let (matching, rest): (Vec<A>, Vec<A>) = self.into_iter().partition(|a| f(a));
+        (
+            NonEmpty::from_vec(matching).map(History),
+            NonEmpty::from_vec(rest).map(History),
+        )
+    }
+
+    /// Remove consecutive artifacts that resolve to the same key, keeping the
+    /// first of each run. Since the first artifact is always kept, the
+    /// `History` stays non-empty.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::History;
+    /// use nonempty::NonEmpty;
+    ///
+    /// let mut history = History::from(NonEmpty::from((10, vec![11, 20, 12, 13])));
+    /// history.dedup_by_key(|a| *a / 10);
+    ///
+    /// assert_eq!(history, History::from(NonEmpty::from((10, vec![20, 12]))));
+    /// ```
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut A) -> K,
+        K: PartialEq,
+    {
+        self.0.tail.dedup_by_key(&mut key);
+        let head = key(&mut self.0.head);
+        if matches!(self.0.tail.first_mut().map(&mut key), Some(next) if next == head) {
+            self.0.tail.remove(0);
+        }
+    }
+
     /// Find an artifact in the `History`.
     ///
     /// The function provided should return `Some` if the item is the desired
@@ -135,20 +397,187 @@ impl<A> History<A> {
     }
 }
 
-/// A Snapshot is a function that renders a `Directory` given
-/// the `Repo` object and a `History` of artifacts.
-type Snapshot<A, Repo, Error> = Box<dyn Fn(&Repo, &History<A>) -> Result<Directory, Error>>;
+impl<A> History<A>
+where
+    A: HasCommitMetadata,
+{
+    /// Sort the artifacts of the `History` by their
+    /// [`HasCommitMetadata::time`], newest first. Artifacts with the same time
+    /// keep their order.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// use radicle_surf::vcs::HasCommitMetadata;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let mut history = browser.get();
+    /// history.sort_by_time();
+    /// let times = history.iter().map(|commit| commit.time()).collect::<Vec<_>>();
+    /// assert!(times.windows(2).all(|pair| pair[0] >= pair[1]));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sort_by_time(&mut self) {
+        self.0
+            .tail
+            .sort_by_key(|artifact| cmp::Reverse(artifact.time()));
+        // Put the head before the first artifact in the tail that is not newer.
+        let time = self.0.head.time();
+        let position = self
+            .0
+            .tail
+            .iter()
+            .position(|artifact| artifact.time() <= time)
+            .unwrap_or(self.0.tail.len());
+        if position > 0 {
+            mem::swap(&mut self.0.head, &mut self.0.tail[0]);
+            self.0.tail[..position].rotate_left(1);
+        }
+    }
+
+    /// Group the artifacts of the `History` by their
+    /// [`HasCommitMetadata::author_email`], keeping the order of the `History`
+    /// within each group.
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let history = browser.get();
+    /// let by_author = history.group_by_author();
+    /// assert_eq!(
+    ///     by_author.values().map(|commits| commits.len()).sum::<usize>(),
+    ///     history.len()
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn group_by_author(&self) -> BTreeMap<&str, Vec<&A>> {
+        let mut groups = BTreeMap::<&str, Vec<&A>>::new();
+        for artifact in self.iter() {
+            groups
+                .entry(artifact.author_email())
+                .or_default()
+                .push(artifact);
+        }
+        groups
+    }
+}
+
+impl<A> From<NonEmpty<A>> for History<A> {
+    fn from(artifacts: NonEmpty<A>) -> Self {
+        History(artifacts)
+    }
+}
+
+impl<A> IntoIterator for History<A> {
+    type Item = A;
+    type IntoIter = <NonEmpty<A> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, A> IntoIterator for &'a History<A> {
+    type Item = &'a A;
+    type IntoIter = iter::Chain<iter::Once<&'a A>, slice::Iter<'a, A>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        iter::once(&self.0.head).chain(self.0.tail.iter())
+    }
+}
+
+/// The metadata that the artifacts of a `History` have in most VCSs, so that
+/// code over a `History`, such as [`History::sort_by_time`] and
+/// [`History::group_by_author`], can be written once for all of them.
+pub trait HasCommitMetadata {
+    /// The way to identify an artifact.
+    type Id;
+
+    /// The identifier of the artifact.
+    fn id(&self) -> &Self::Id;
+
+    /// The name of the author of the artifact.
+    fn author_name(&self) -> &str;
+
+    /// The email of the author of the artifact.
+    fn author_email(&self) -> &str;
+
+    /// The time the artifact was authored, in seconds since the Unix epoch.
+    fn time(&self) -> i64;
+
+    /// The message of the artifact.
+    fn message(&self) -> &str;
+}
+
+/// A `Snapshot` renders a `Directory` given the `Repo` object and a `History`
+/// of artifacts.
+///
+/// Any function of the right shape is a `Snapshot`, while a named type can
+/// carry its own configuration and be cloned along with its `Browser`.
+pub trait Snapshot<Repo, A, Error> {
+    /// Render the `Directory` of `history` in `repository`.
+    fn snapshot(&self, repository: &Repo, history: &History<A>) -> Result<Directory, Error>;
+}
+
+impl<F, Repo, A, Error> Snapshot<Repo, A, Error> for F
+where
+    F: Fn(&Repo, &History<A>) -> Result<Directory, Error>,
+{
+    fn snapshot(&self, repository: &Repo, history: &History<A>) -> Result<Directory, Error> {
+        self(repository, history)
+    }
+}
 
 /// A `Browser` is a way of rendering a `History` into a
 /// `Directory` snapshot, and the current `History` it is
 /// viewing.
-pub struct Browser<Repo, A, Error> {
-    snapshot: Snapshot<A, Repo, Error>,
+pub struct Browser<Repo, A, Error, S> {
+    snapshot: S,
     history: History<A>,
     repository: Repo,
+    _error: PhantomData<fn() -> Error>,
+}
+
+impl<Repo, A, Error, S> Clone for Browser<Repo, A, Error, S>
+where
+    Repo: Clone,
+    A: Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Browser {
+            snapshot: self.snapshot.clone(),
+            history: self.history.clone(),
+            repository: self.repository.clone(),
+            _error: PhantomData,
+        }
+    }
 }
 
-impl<Repo, A, Error> Browser<Repo, A, Error> {
+impl<Repo, A, Error, S> Browser<Repo, A, Error, S> {
+    /// Create a `Browser` viewing `history` in `repository`, whose
+    /// `Directory` is rendered by `snapshot`.
+    pub fn from_parts(repository: Repo, history: History<A>, snapshot: S) -> Self {
+        Browser {
+            snapshot,
+            history,
+            repository,
+            _error: PhantomData,
+        }
+    }
+
     /// Get the current `History` the `Browser` is viewing.
     pub fn get(&self) -> History<A>
     where
@@ -163,8 +592,11 @@ impl<Repo, A, Error> Browser<Repo, A, Error> {
     }
 
     /// Render the `Directory` for this `Browser`.
-    pub fn get_directory(&self) -> Result<Directory, Error> {
-        (self.snapshot)(&self.repository, &self.history)
+    pub fn get_directory(&self) -> Result<Directory, Error>
+    where
+        S: Snapshot<Repo, A, Error>,
+    {
+        self.snapshot.snapshot(&self.repository, &self.history)
     }
 
     /// Modify the `History` in this `Browser`.
@@ -186,7 +618,7 @@ impl<Repo, A, Error> Browser<Repo, A, Error> {
     }
 }
 
-impl<Repo, A, Error> Vcs<A, Error> for Browser<Repo, A, Error>
+impl<Repo, A, Error, S> Vcs<A, Error> for Browser<Repo, A, Error, S>
 where
     Repo: Vcs<A, Error>,
 {
@@ -231,8 +663,72 @@ pub trait Vcs<A, Error> {
     fn get_history(&self, identifier: Self::HistoryId) -> Result<History<A>, Error>;
 
     /// Find all histories in a Repo
+    ///
+    /// For git, [`git::Browser::get_named_histories`] also tells which
+    /// reference each history was built from.
     fn get_histories(&self) -> Result<Vec<History<A>>, Error>;
 
     /// Identify artefacts of a Repository
     fn get_identifier(artefact: &A) -> Self::ArtefactId;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Patch {
+        id: u8,
+        author: &'static str,
+        time: i64,
+    }
+
+    impl HasCommitMetadata for Patch {
+        type Id = u8;
+
+        fn id(&self) -> &u8 {
+            &self.id
+        }
+
+        fn author_name(&self) -> &str {
+            self.author
+        }
+
+        fn author_email(&self) -> &str {
+            self.author
+        }
+
+        fn time(&self) -> i64 {
+            self.time
+        }
+
+        fn message(&self) -> &str {
+            ""
+        }
+    }
+
+    fn patch(id: u8, time: i64) -> Patch {
+        Patch {
+            id,
+            author: "alice",
+            time,
+        }
+    }
+
+    #[test]
+    fn sort_by_time_is_stable() {
+        let mut history = History::from(NonEmpty::from((
+            patch(0, 2),
+            vec![patch(1, 3), patch(2, 2), patch(3, 1), patch(4, 3)],
+        )));
+        history.sort_by_time();
+
+        let ids = history.iter().map(|patch| patch.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![1, 4, 0, 2, 3]);
+
+        history.sort_by_time();
+        let again = history.iter().map(|patch| patch.id).collect::<Vec<_>>();
+        assert_eq!(again, ids);
+    }
+}
@@ -67,15 +67,20 @@ pub use git2::{self, Error as Git2Error, Oid, Time};
 
 /// Provides ways of selecting a particular reference/revision.
 mod reference;
-pub use reference::{Ref, Rev};
+pub use reference::{glob::RefGlob, Listing, ParseError, Ref, RefWarning, Rev};
 
 mod repo;
-pub use repo::{History, Repository, RepositoryRef};
+pub use repo::{CommitHistory, History, Repository, RepositoryRef};
 
 pub mod error;
 
 mod ext;
 
+mod commit_graph;
+
+mod shallow;
+pub use shallow::Grafts;
+
 /// Provides the data for talking about branches.
 pub mod branch;
 pub use branch::{Branch, BranchName, BranchType};
@@ -90,28 +95,116 @@ pub use commit::{Author, Commit};
 
 /// Provides the data for talking about namespaces.
 pub mod namespace;
-pub use namespace::Namespace;
+pub use namespace::{Namespace, NamespaceTree};
 
 /// Provides the data for talking about repository statistics.
 pub mod stats;
-pub use stats::Stats;
+pub use stats::{Stats, StorageStats};
+
+/// Provides the data for describing a commit relative to its nearest tag.
+pub mod describe;
+pub use describe::Describe;
+
+/// Provides the data for talking about git objects resolved from revspecs.
+pub mod object;
+pub use object::RevObject;
+
+/// Provides the data for talking about the remotes of a repository.
+pub mod remote;
+pub use remote::Remote;
+
+mod navigation;
+use navigation::{Navigation, View};
+
+mod mailmap;
+
+/// Provides a way of opening repositories from local paths or URLs.
+pub mod source;
+pub use source::RepositorySource;
+
+/// Provides a way of browsing a set of repositories by their identifiers.
+pub mod workspace;
+
+/// Provides a cache of commits, so that they are only converted once.
+pub mod cache;
+
+/// Provides a way of recording the latency of a `Browser`'s operations.
+pub mod instrumentation;
+
+/// Provides limits on the size of the snapshots a `Browser` renders.
+pub mod limits;
+
+/// Provides the options for filtering the history of a `Browser`.
+pub mod history;
+pub use history::{
+    AuthorMatcher,
+    HistoryBuilder,
+    HistoryHandle,
+    HistoryIter,
+    HistoryOptions,
+    HistoryOrder,
+    NamedHistory,
+    RenameChain,
+    RenameLink,
+};
+
+/// Provides a way of searching the contents of the files of a snapshot.
+pub mod grep;
+
+/// Provides a way of rendering a history as an Atom or RSS feed.
+pub mod feed;
+
+/// Provides code-health analyses over a window of history.
+pub mod analytics;
+
+/// Provides a way of noticing when the references of a repository change.
+#[cfg(feature = "watch")]
+pub mod watch;
+
+/// Provides snapshots of a commit's tree that borrow from the repository.
+pub mod snapshot;
+pub use snapshot::{FileRef, TreeRef};
+
+/// Provides a way of finding the commit that introduced a change.
+pub mod bisect;
+pub use bisect::{Bisection, Judgement};
+
+/// Provides a persistent index of the commits of a repository.
+#[cfg(feature = "index")]
+pub mod index;
+
+/// Provides the data for talking about commits applied in memory.
+pub mod merge;
+pub use merge::{MergeInfo, MergeResult};
+
+/// Provides a way of building repositories programmatically, for tests and
+/// examples that should not depend on the `git-platinum` fixture.
+pub mod fixture;
 
 pub use crate::diff::Diff;
 
 use crate::{
+    diff::{FileDiff, FileStatus, FileSummary},
     file_system,
-    file_system::directory,
+    file_system::{directory, Pathspec},
     vcs,
-    vcs::{git::error::*, Vcs},
+    vcs::{
+        git::{error::*, instrumentation::Operation, limits::Limits},
+        Vcs,
+    },
 };
-use nonempty::NonEmpty;
 use std::{
-    collections::{BTreeSet, HashMap},
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet},
     convert::TryFrom,
+    path,
     str,
+    time::{Duration, Instant},
 };
 
-/// The signature of a commit
+/// The cryptographic signature of a commit, e.g. a GPG signature, as raw
+/// bytes. The identity of the author and committer of a commit is an
+/// [`Author`].
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Signature(Vec<u8>);
 
@@ -121,6 +214,12 @@ impl From<git2::Buf> for Signature {
     }
 }
 
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 /// Determines whether to look for local or remote references or both.
 pub enum RefScope {
     /// List all branches by default.
@@ -150,10 +249,55 @@ where
     }
 }
 
+/// Who owns a path, according to the `CODEOWNERS` file of a snapshot, and the
+/// last commit that touched it. See [`Browser::owners`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ownership {
+    /// The owners of the path, which are empty if it has none.
+    pub owners: Vec<String>,
+    /// The last commit that touched the path, if any.
+    pub last_commit: Option<Commit>,
+}
+
+/// A snapshot of the repository along with the last commit that touched each
+/// of its files and directories, for listing them in the way code hosting
+/// sites do. See [`Browser::get_directory_with_last_commits`].
+#[derive(Debug, Clone)]
+pub struct AnnotatedDirectory {
+    /// The snapshot of the repository.
+    pub directory: directory::Directory,
+    last_commits: HashMap<file_system::Path, Commit>,
+}
+
+impl AnnotatedDirectory {
+    /// Get the last commit that touched the file or directory at `path`,
+    /// which is a path from the root, e.g. `~/src/memory.rs`.
+    pub fn last_commit(&self, path: &file_system::Path) -> Option<&Commit> {
+        self.last_commits.get(path)
+    }
+}
+
 /// A [`crate::vcs::Browser`] that uses [`Repository`] as the underlying
 /// repository backend, [`git2::Commit`] as the artifact, and [`Error`] for
 /// error reporting.
-pub type Browser<'a> = vcs::Browser<RepositoryRef<'a>, Commit, Error>;
+pub type Browser<'a> = vcs::Browser<RepositoryRef<'a>, Commit, Error, TreeSnapshot>;
+
+/// The [`vcs::Snapshot`] of a git [`Browser`], which renders the tree of the
+/// most recent commit of its [`History`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TreeSnapshot;
+
+impl<'a> vcs::Snapshot<RepositoryRef<'a>, Commit, Error> for TreeSnapshot {
+    fn snapshot(
+        &self,
+        repository: &RepositoryRef<'a>,
+        history: &History,
+    ) -> Result<directory::Directory, Error> {
+        repository.measure(Operation::Snapshot, || {
+            Browser::get_tree(repository, history.first())
+        })
+    }
+}
 
 impl<'a> Browser<'a> {
     /// Create a new browser to interact with.
@@ -164,6 +308,7 @@ impl<'a> Browser<'a> {
     /// # Errors
     ///
     /// * [`error::Error::Git`]
+    /// * [`error::Error::UnbornBranch`] if `rev` is a branch without commits
     ///
     /// # Examples
     ///
@@ -182,8 +327,10 @@ impl<'a> Browser<'a> {
         repository: impl Into<RepositoryRef<'a>>,
         rev: impl Into<Rev>,
     ) -> Result<Self, Error> {
-        let repository = repository.into();
-        let history = repository.get_history(rev.into())?;
+        let mut repository = repository.into();
+        let rev = rev.into();
+        let history = repository.get_history(rev.clone())?;
+        repository.viewing = Some((rev, history.head_id()));
         Ok(Self::init(repository, history))
     }
 
@@ -227,27 +374,178 @@ impl<'a> Browser<'a> {
         namespace: &Namespace,
         rev: impl Into<Rev>,
     ) -> Result<Self, Error> {
-        let repository = repository.into();
+        let mut repository = repository.into();
         // This is a bit weird, the references don't seem to all be present unless we
         // make a call to `references` o_O.
         let _ = repository.repo_ref.references()?;
         repository.switch_namespace(&namespace.to_string())?;
-        let history = repository.get_history(rev.into())?;
+        let rev = rev.into();
+        let history = repository.get_history(rev.clone())?;
+        repository.viewing = Some((rev, history.head_id()));
         Ok(Self::init(repository, history))
     }
 
     fn init(repository: RepositoryRef<'a>, history: History) -> Self {
-        let snapshot = Box::new(|repository: &RepositoryRef<'a>, history: &History| {
-            let tree = Self::get_tree(repository.repo_ref, history.0.first())?;
-            Ok(directory::Directory::from_hash_map(tree))
-        });
+        vcs::Browser::from_parts(repository, history, TreeSnapshot)
+    }
+
+    /// Fork the `Browser` into a second one that views the same [`History`],
+    /// but can then move independently, e.g. to explore another branch for a
+    /// compare view without disturbing the main view.
+    ///
+    /// The fork shares the underlying `git2::Repository` and copies the
+    /// settings of the `Browser`, but starts with an empty commit cache and no
+    /// views to go back or forward to. Note that the namespace is set on the
+    /// `git2::Repository` itself, so [`Browser::switch_namespace`] affects
+    /// both.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    /// let master = browser.head_id();
+    ///
+    /// let mut compare = browser.fork();
+    /// compare.branch(Branch::local("dev"))?;
+    ///
+    /// assert_eq!(browser.head_id(), master);
+    /// assert_ne!(compare.head_id(), master);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fork(&self) -> Self {
+        vcs::Browser::from_parts(self.repository.fork(), self.get(), self.snapshot)
+    }
+
+    /// Report how long each [`Operation`] of the `Browser`
+    /// takes to `callback`, so that integrators can record per-operation
+    /// latencies, e.g. as metrics in production.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{instrumentation::Operation, Branch, Browser, Repository};
+    /// use std::sync::{Arc, Mutex};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let operations = Arc::new(Mutex::new(vec![]));
+    /// let recorded = operations.clone();
+    ///
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?
+    ///     .with_instrumentation(move |operation, _elapsed| {
+    ///         recorded.lock().unwrap().push(operation)
+    ///     });
+    ///
+    /// browser.rev(Branch::local("dev"))?;
+    /// browser.get_directory()?;
+    ///
+    /// assert_eq!(
+    ///     *operations.lock().unwrap(),
+    ///     vec![Operation::History, Operation::Snapshot]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_instrumentation<F>(self, callback: F) -> Self
+    where
+        F: Fn(Operation, Duration) + Send + Sync + 'static,
+    {
         vcs::Browser {
-            snapshot,
-            history,
-            repository,
+            repository: self.repository.with_instrumentation(callback),
+            ..self
         }
     }
 
+    /// Set a `deadline` for the operations of the `Browser`, after which
+    /// history walks, file histories, and snapshots stop early with
+    /// [`Error::Cancelled`]. This bounds the time a request can take on a
+    /// large repository. A `deadline` of `None` removes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{error::Error, Branch, Browser, Repository};
+    /// use std::time::{Duration, Instant};
+    /// # use std::error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// browser.set_deadline(Some(Instant::now()));
+    /// assert_eq!(browser.get_directory(), Err(Error::Cancelled));
+    /// assert_eq!(
+    ///     browser.file_history(unsound::path::new("~/README.md")),
+    ///     Err(Error::Cancelled)
+    /// );
+    ///
+    /// browser.set_deadline(Instant::now().checked_add(Duration::from_secs(60)));
+    /// assert!(browser.get_directory().is_ok());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.repository.deadline = deadline;
+    }
+
+    /// Set the [`Limits`] on the snapshots rendered by the `Browser`, such as
+    /// by [`Browser::get_directory`]. A snapshot that goes over them fails with
+    /// [`Error::LimitExceeded`], rather than reading a pathological
+    /// repository into memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{
+    ///     error::Error,
+    ///     limits::{LimitExceeded, Limits},
+    ///     Branch,
+    ///     Browser,
+    ///     Repository,
+    /// };
+    /// # use std::error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn error::Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// browser.set_limits(Limits {
+    ///     max_files: Some(3),
+    ///     ..Limits::default()
+    /// });
+    /// assert_eq!(
+    ///     browser.get_directory(),
+    ///     Err(Error::LimitExceeded(LimitExceeded::Files { limit: 3 }))
+    /// );
+    ///
+    /// browser.set_limits(Limits::default());
+    /// assert!(browser.get_directory().is_ok());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.repository.limits = limits;
+    }
+
+    /// Set how the names of files and directories that are not valid UTF-8
+    /// are handled by [`Browser::get_directory`] and friends. By default they
+    /// are an error, see [`file_system::NonUtf8Policy`] for the alternatives.
+    pub fn set_non_utf8_policy(&mut self, policy: file_system::NonUtf8Policy) {
+        self.repository.non_utf8_policy = policy;
+    }
+
     /// Switch the namespace you are browsing in. This will consume the previous
     /// `Browser` and give you back a new `Browser` for that particular
     /// namespace. The `revision` provided will kick-off the history for
@@ -259,11 +557,7 @@ impl<'a> Browser<'a> {
     ) -> Result<Self, Error> {
         self.repository.switch_namespace(&namespace.to_string())?;
         let history = self.get_history(Rev::from(rev))?;
-        Ok(Browser {
-            snapshot: self.snapshot,
-            repository: self.repository,
-            history,
-        })
+        Ok(Browser { history, ..self })
     }
 
     /// What is the current namespace we're browsing in.
@@ -281,6 +575,8 @@ impl<'a> Browser<'a> {
     /// # Errors
     ///
     /// * [`error::Error::Git`]
+    /// * [`error::Error::UnbornBranch`] if `HEAD` points to a branch without
+    ///   commits
     ///
     /// # Examples
     ///
@@ -305,12 +601,46 @@ impl<'a> Browser<'a> {
     /// ```
     pub fn head(&mut self) -> Result<(), Error> {
         let history = self.repository.head()?;
-        self.set(history);
+        self.view(history, Rev::Spec("HEAD".to_string()));
         Ok(())
     }
 
-    /// Set the current `Browser`'s [`History`] to the given [`BranchName`]
-    /// provided.
+    /// Get the [`Branch`] that `HEAD` points to, i.e. the default branch of
+    /// the repository, or `None` if `HEAD` is detached.
+    ///
+    /// Note that the branch may not exist yet, e.g. in a repository without
+    /// commits.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Utf8Error`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let fixture = Fixture::new()?;
+    /// fixture.commit("dev", "Initial commit").file("README.md", "Hi\n").write()?;
+    ///
+    /// let browser = Browser::new(fixture.repository(), Branch::local("dev"))?;
+    /// // A new repository starts with `HEAD` pointing to an unborn `master`.
+    /// assert_eq!(browser.head_branch()?, Some(Branch::local("master")));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn head_branch(&self) -> Result<Option<Branch>, Error> {
+        self.repository.head_branch()
+    }
+
+    /// Set the current `Browser`'s [`History`] to the given branch.
+    ///
+    /// Any [`Rev`] that resolves through a branch reference is accepted, e.g. a
+    /// [`Branch`], or a [`Rev::Spec`] naming a branch such as `"dev"`.
     ///
     /// # Errors
     ///
@@ -361,20 +691,28 @@ impl<'a> Browser<'a> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn branch(&mut self, branch: Branch) -> Result<(), Error> {
-        let name = BranchName(branch.name());
-        self.set(self.repository.reference(branch, |reference| {
-            let is_branch = ext::is_branch(reference) || reference.is_remote();
+    pub fn branch(&mut self, branch: impl Into<Rev>) -> Result<(), Error> {
+        let branch = branch.into();
+        let name = BranchName::try_from(branch.to_string().as_bytes())?;
+        let history = self.repository.rev(branch.clone(), |reference| {
+            let is_branch = matches!(
+                reference,
+                Some(reference) if ext::is_branch(reference) || reference.is_remote()
+            );
             if !is_branch {
                 Some(Error::NotBranch(name))
             } else {
                 None
             }
-        })?);
+        })?;
+        self.view(history, branch);
         Ok(())
     }
 
-    /// Set the current `Browser`'s [`History`] to the [`TagName`] provided.
+    /// Set the current `Browser`'s [`History`] to the given tag.
+    ///
+    /// Any [`Rev`] that resolves through a tag reference is accepted, e.g. a
+    /// [`TagName`], or a [`Rev::Spec`] naming a tag such as `"v0.3.0"`.
     ///
     /// # Errors
     ///
@@ -396,7 +734,7 @@ impl<'a> Browser<'a> {
     /// // Switch to "v0.3.0"
     /// browser.tag(TagName::new("v0.3.0"))?;
     ///
-    /// let expected_history = History(NonEmpty::from((
+    /// let expected_history = History::from(NonEmpty::from((
     ///     Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")?,
     ///     vec![
     ///         Oid::from_str("f3a089488f4cfd1a240a9c01b3fcc4c34a4e97b2")?,
@@ -413,20 +751,25 @@ impl<'a> Browser<'a> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn tag(&mut self, tag_name: TagName) -> Result<(), Error> {
-        let name = tag_name.clone();
-        self.set(self.repository.reference(tag_name, |reference| {
-            if !ext::is_tag(reference) {
+    pub fn tag(&mut self, tag: impl Into<Rev>) -> Result<(), Error> {
+        let tag = tag.into();
+        let name = TagName::try_from(tag.to_string().as_bytes())?;
+        let history = self.repository.rev(tag.clone(), |reference| {
+            if !matches!(reference, Some(reference) if ext::is_tag(reference)) {
                 Some(Error::NotTag(name))
             } else {
                 None
             }
-        })?);
+        })?;
+        self.view(history, tag);
         Ok(())
     }
 
-    /// Set the current `Browser`'s [`History`] to the [`Oid`] (SHA digest)
-    /// provided.
+    /// Set the current `Browser`'s [`History`] to the commit provided, e.g. an
+    /// [`Oid`] (SHA digest), or a [`Rev::Spec`] such as a short commit
+    /// identifier.
+    ///
+    /// This is equivalent to [`Browser::rev`].
     ///
     /// # Errors
     ///
@@ -466,24 +809,26 @@ impl<'a> Browser<'a> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn commit(&mut self, oid: Oid) -> Result<(), Error> {
-        self.set(self.get_history(Rev::Oid(oid))?);
-        Ok(())
+    pub fn commit(&mut self, commit: impl Into<Rev>) -> Result<(), Error> {
+        self.rev(commit)
     }
 
-    /// Set a `Browser`'s [`History`] based on a [revspec](https://git-scm.com/docs/git-rev-parse.html#_specifying_revisions).
+    /// Set a `Browser`'s [`History`] based on a [`Rev`], which can be any
+    /// [revspec](https://git-scm.com/docs/git-rev-parse.html#_specifying_revisions)
+    /// by way of its `FromStr` instance.
     ///
     /// # Errors
     ///
     /// * [`error::Error::Git`]
     /// * [`error::Error::RevParseFailure`]
+    /// * [`error::Error::NamespaceRevParseFailure`]
     ///
     /// # Examples
     ///
     /// ```
     /// use radicle_surf::file_system::{Label, SystemType};
     /// use radicle_surf::file_system::unsound;
-    /// use radicle_surf::vcs::git::{Browser, Branch, Oid, Repository};
+    /// use radicle_surf::vcs::git::{Browser, Branch, Oid, Repository, Rev};
     /// use std::str::FromStr;
     /// # use std::error::Error;
     ///
@@ -500,23 +845,43 @@ impl<'a> Browser<'a> {
     /// assert!(directory_contents.contains(
     ///     &SystemType::file(unsound::label::new("here-we-are-on-a-dev-branch.lol"))
     /// ));
+    ///
+    /// // Revspecs are parsed into a `Rev`
+    /// browser.rev("master~3".parse::<Rev>()?)?;
+    /// assert_eq!(
+    ///     browser.get().first().id,
+    ///     Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?
+    /// );
     /// #
     /// # Ok(())
     /// # }
     /// ```
     pub fn rev(&mut self, rev: impl Into<Rev>) -> Result<(), Error> {
-        let history = self.get_history(rev.into())?;
-        self.set(history);
+        let rev = rev.into();
+        let history = self.get_history(rev.clone())?;
+        self.view(history, rev);
         Ok(())
     }
 
-    /// Parse an [`Oid`] from the given string. This is useful if we have a
-    /// shorthand version of the `Oid`, as opposed to the full one.
+    /// Pin the `Browser`'s [`History`] to the latest commit of its current
+    /// history that was committed at or before `time`, to view the repository
+    /// as it was then.
+    ///
+    /// The commit is found by its committer time, rather than its author time,
+    /// since that is when it became part of the history. If several commits
+    /// share that time, the first of them in the history is taken. The
+    /// `Browser` is then viewing the [`Rev::Oid`] of that commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::EmptyHistory`] if every commit is newer than `time`.
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`] if the deadline of the `Browser` passes.
     ///
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository, Rev, Time};
     /// use std::str::FromStr;
     /// # use std::error::Error;
     ///
@@ -524,404 +889,399 @@ impl<'a> Browser<'a> {
     /// let repo = Repository::new("./data/git-platinum")?;
     /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
     ///
-    /// // Set to the initial commit
-    /// let commit = Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
+    /// // 2020-01-20, between "Extend the docs (#2)" and "Remove src/Folder.svelte (#3)".
+    /// browser.at_time(Time::new(1_579_478_400, 0))?;
+    /// let docs = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
+    /// assert_eq!(browser.get().first().id, docs);
+    /// assert_eq!(browser.current_rev(), Rev::Oid(docs));
     ///
-    /// assert_eq!(
-    ///     commit,
-    ///     browser.oid("e24124b")?,
-    /// );
+    /// // Nothing was committed before the initial commit.
+    /// assert!(browser.at_time(Time::new(0, 0)).is_err());
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn oid(&self, oid: &str) -> Result<Oid, Error> {
-        self.repository.oid(oid)
-    }
-
-    /// Get the [`Diff`] between two commits.
-    pub fn diff(&self, from: Oid, to: Oid) -> Result<Diff, Error> {
-        self.repository.diff(from, to)
-    }
-
-    /// Get the [`Diff`] of a commit with no parents.
-    pub fn initial_diff(&self, oid: Oid) -> Result<Diff, Error> {
-        self.repository.initial_diff(oid)
+    pub fn at_time(&mut self, time: Time) -> Result<(), Error> {
+        let commit = self
+            .history
+            .iter()
+            .filter(|commit| commit.committer.time.seconds() <= time.seconds())
+            // The first of the latest commits is taken, so of the commits that
+            // share a time, the one that comes first in the history wins.
+            .min_by_key(|commit| Reverse(commit.committer.time.seconds()))
+            .map(|commit| commit.id)
+            .ok_or(Error::EmptyHistory)?;
+        self.rev(commit)
     }
 
-    /// List the names of the _branches_ that are contained in the underlying
-    /// [`Repository`].
+    /// Find the first commit between `good` and `bad` that is bad according
+    /// to the `judge`, in the spirit of `git bisect`.
+    ///
+    /// The commits searched are those reachable from `bad` but not from
+    /// `good`. The judge is given each commit it is asked about along with
+    /// its [`directory::Directory`], and about half of the remaining commits
+    /// are ruled out with each step, so only a logarithmic number of
+    /// snapshots is rendered. A commit the judge skips is not counted as
+    /// good or bad, and may leave the search with several candidates, see
+    /// [`Bisection::candidates`].
     ///
     /// # Errors
     ///
+    /// * [`error::Error::EmptyHistory`] if `bad` is reachable from `good`.
     /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
     ///
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Branch, RefScope, BranchName, Browser, Namespace, Repository};
-    /// use std::convert::TryFrom;
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Judgement, Oid, Repository};
+    /// use std::str::FromStr;
     /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let repo = Repository::new("./data/git-platinum")?;
-    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
     ///
-    /// let branches = browser.list_branches(RefScope::All)?;
+    /// // Which commit removed src/Folder.svelte?
+    /// let good = Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
+    /// let bad = browser.head_id();
+    /// let bisection = browser.bisect(good, bad, |_commit, directory| {
+    ///     match directory.find_file(unsound::path::new("src/Folder.svelte")) {
+    ///         Some(_) => Judgement::Good,
+    ///         None => Judgement::Bad,
+    ///     }
+    /// })?;
     ///
-    /// // 'master' exists in the list of branches
-    /// assert!(branches.contains(&Branch::local("master")));
+    /// assert_eq!(
+    ///     bisection.first_bad(),
+    ///     Some(Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?)
+    /// );
+    /// assert!(bisection.steps.len() < 5);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bisect<J>(&self, good: Oid, bad: Oid, mut judge: J) -> Result<Bisection, Error>
+    where
+        J: FnMut(&Commit, &directory::Directory) -> Judgement,
+    {
+        let range = self
+            .repository
+            .history_iter(
+                &HistoryBuilder::new()
+                    .push(bad)
+                    .hide(good)
+                    .order(HistoryOrder::Topological),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        let commits = range
+            .iter()
+            .map(|commit| (commit.id, commit.parents.clone()))
+            .collect::<Vec<_>>();
+        let by_id = range
+            .iter()
+            .map(|commit| (commit.id, commit))
+            .collect::<HashMap<_, _>>();
+
+        bisect::search(&commits, |oid| {
+            self.repository.check_deadline()?;
+            let commit = by_id[&oid];
+            let directory = Self::get_tree(&self.repository, commit)?;
+            Ok(judge(commit, &directory))
+        })
+    }
+
+    /// Resolve a short `name` to the one branch, tag, or commit it names, so
+    /// that it can be given to [`Browser::rev`]. Where [`Browser::rev`] would
+    /// silently pick one of several matches, this reports all of them, so
+    /// that the caller can disambiguate.
     ///
-    /// // Filter the branches by `Remote` 'origin'.
-    /// let mut branches = browser.list_branches(RefScope::Remote {
-    ///     name: Some("origin".to_string())
-    /// })?;
-    /// branches.sort();
+    /// See [`RepositoryRef::resolve`] for what a name can match.
     ///
-    /// assert_eq!(branches, vec![
-    ///     Branch::remote("HEAD", "origin"),
-    ///     Branch::remote("dev", "origin"),
-    ///     Branch::remote("master", "origin"),
-    /// ]);
+    /// # Errors
     ///
-    /// // Filter the branches by all `Remote`s.
-    /// let mut branches = browser.list_branches(RefScope::Remote {
-    ///     name: None
-    /// })?;
-    /// branches.sort();
+    /// * [`error::Error::AmbiguousName`]
+    /// * [`error::Error::RevParseFailure`]
+    /// * [`error::Error::NamespaceRevParseFailure`]
     ///
-    /// assert_eq!(branches, vec![
-    ///     Branch::remote("HEAD", "origin"),
-    ///     Branch::remote("dev", "origin"),
-    ///     Branch::remote("master", "origin"),
-    ///     Branch::remote("orange/pineapple", "banana"),
-    ///     Branch::remote("pineapple", "banana"),
-    /// ]);
+    /// # Examples
     ///
-    /// // We can also switch namespaces and list the branches in that namespace.
-    /// let golden = browser.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository, Rev, TagName};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
     ///
-    /// let mut branches = golden.list_branches(RefScope::Local)?;
-    /// branches.sort();
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
     ///
-    /// assert_eq!(branches, vec![
-    ///     Branch::local("banana"),
-    ///     Branch::local("master"),
-    /// ]);
+    /// assert_eq!(browser.resolve("dev")?, Rev::from(Branch::local("dev")));
+    /// assert_eq!(browser.resolve("origin/dev")?, Rev::from(Branch::remote("dev", "origin")));
+    /// assert_eq!(browser.resolve("v0.1.0")?, Rev::from(TagName::new("v0.1.0")));
+    /// assert_eq!(
+    ///     browser.resolve("3873745c")?,
+    ///     Rev::from(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)
+    /// );
+    ///
+    /// let rev = browser.resolve("v0.1.0")?;
+    /// browser.rev(rev)?;
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn list_branches(&self, filter: RefScope) -> Result<Vec<Branch>, Error> {
-        self.repository.list_branches(filter)
+    pub fn resolve(&self, name: &str) -> Result<Rev, Error> {
+        self.repository.resolve(name)
     }
 
-    /// List the names of the _tags_ that are contained in the underlying
-    /// [`Repository`].
+    /// Build the [`History`] of a [`Rev`] without letting a malformed commit,
+    /// e.g. one with an author name that is not valid UTF-8, make the whole
+    /// history fail. Such commits are left out of the `History`, and returned
+    /// along with the error they caused, so that they can be reported.
+    ///
+    /// The `History` is not set on the `Browser`; use [`Browser::set`] for
+    /// that.
     ///
     /// # Errors
     ///
-    /// * [`error::Error::Git`]
+    /// * [`error::Error::Git`] if the `rev` cannot be resolved, or the revwalk
+    ///   fails.
+    /// * [`error::Error::Cancelled`]
+    /// * the error of the first commit, if none of the commits can be loaded.
     ///
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Branch, RefScope, Browser, Namespace, Oid, Repository, Tag, TagName, Author, Time};
-    /// use std::convert::TryFrom;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
     /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let repo = Repository::new("./data/git-platinum")?;
     /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
     ///
-    /// let tags = browser.list_tags(RefScope::Local)?;
-    ///
-    /// assert_eq!(
-    ///     tags,
-    ///     vec![
-    ///         Tag::Light {
-    ///             id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
-    ///             name: TagName::new("v0.1.0"),
-    ///             remote: None,
-    ///         },
-    ///         Tag::Light {
-    ///             id: Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
-    ///             name: TagName::new("v0.2.0"),
-    ///             remote: None,
-    ///         },
-    ///         Tag::Light {
-    ///             id: Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")?,
-    ///             name: TagName::new("v0.3.0"),
-    ///             remote: None,
-    ///         },
-    ///         Tag::Light {
-    ///             id: Oid::from_str("91b69e00cd8e5a07e20942e9e4457d83ce7a3ff1")?,
-    ///             name: TagName::new("v0.4.0"),
-    ///             remote: None,
-    ///         },
-    ///         Tag::Light {
-    ///             id: Oid::from_str("80ded66281a4de2889cc07293a8f10947c6d57fe")?,
-    ///             name: TagName::new("v0.5.0"),
-    ///             remote: None,
-    ///         },
-    ///         Tag::Annotated {
-    ///             id: Oid::from_str("4d1f4af2703074d37cb877f4fdbe36322c8e541d")?,
-    ///             target_id: Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?,
-    ///             name: TagName::new("v0.6.0"),
-    ///             remote: None,
-    ///             tagger: Some(Author {
-    ///               name: "Thomas Scholtes".to_string(),
-    ///               email: "thomas@monadic.xyz".to_string(),
-    ///               time: Time::new(1620740737, 120),
-    ///             }),
-    ///             message: Some("An annotated tag message for v0.6.0\n".to_string())
-    ///         },
-    ///     ]
-    /// );
-    ///
-    /// // We can also switch namespaces and list the branches in that namespace.
-    /// let golden = browser.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
-    ///
-    /// let branches = golden.list_tags(RefScope::Local)?;
-    /// assert_eq!(branches, vec![
-    ///     Tag::Light {
-    ///         id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
-    ///         name: TagName::new("v0.1.0"),
-    ///         remote: None,
-    ///     },
-    ///     Tag::Light {
-    ///         id: Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
-    ///         name: TagName::new("v0.2.0"),
-    ///         remote: None,
-    ///     },
-    /// ]);
-    /// let golden = golden.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
-    ///
-    /// let branches = golden.list_tags(RefScope::Remote { name: Some("kickflip".to_string()) })?;
-    /// assert_eq!(branches, vec![
-    ///     Tag::Light {
-    ///         id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
-    ///         name: TagName::new("v0.1.0"),
-    ///         remote: Some("kickflip".to_string()),
-    ///     },
-    /// ]);
+    /// let (history, errors) = browser.lenient_history(Branch::local("dev"))?;
+    /// assert!(errors.is_empty());
+    /// browser.set(history);
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn list_tags(&self, scope: RefScope) -> Result<Vec<Tag>, Error> {
-        self.repository.list_tags(scope)
+    pub fn lenient_history(
+        &self,
+        rev: impl Into<Rev>,
+    ) -> Result<(History, Vec<(Oid, Error)>), Error> {
+        self.repository.lenient_history(&rev.into())
     }
 
-    /// List the namespaces within a `Browser`, filtering out ones that do not
-    /// parse correctly.
+    /// Build the [`History`] described by the [`HistoryBuilder`], i.e. the
+    /// commits reachable from the revisions it pushes, but not from the ones
+    /// it hides, that pass its filters.
+    ///
+    /// The `History` is not set on the `Browser`; use [`Browser::set`] for
+    /// that.
     ///
     /// # Errors
     ///
-    /// * [`Error::Git`]
+    /// * [`error::Error::EmptyHistory`] if every commit is hidden or filtered
+    ///   out.
+    /// * [`error::Error::Git`] if a revision cannot be resolved, or the revwalk
+    ///   fails.
+    /// * [`error::Error::Cancelled`]
     ///
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Branch, BranchType, BranchName, Browser, Namespace, Repository};
-    /// use std::convert::TryFrom;
+    /// use radicle_surf::vcs::git::{Branch, Browser, HistoryBuilder, Oid, Repository};
+    /// use std::str::FromStr;
     /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let repo = Repository::new("./data/git-platinum")?;
     /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
     ///
-    /// let mut namespaces = browser.list_namespaces()?;
-    /// namespaces.sort();
-    ///
-    /// assert_eq!(namespaces, vec![
-    ///     Namespace::try_from("golden")?,
-    ///     Namespace::try_from("golden/silver")?,
-    ///     Namespace::try_from("me")?,
-    /// ]);
-    ///
+    /// // The commits on dev that are not on master.
+    /// let history = browser.build_history(
+    ///     &HistoryBuilder::new()
+    ///         .push(Branch::local("dev"))
+    ///         .hide(Branch::local("master")),
+    /// )?;
+    /// assert_eq!(
+    ///     history.iter().map(|commit| commit.id).collect::<Vec<_>>(),
+    ///     vec![Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?]
+    /// );
     ///
+    /// browser.set(history);
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn list_namespaces(&self) -> Result<Vec<Namespace>, Error> {
-        self.repository.list_namespaces()
+    pub fn build_history(&self, builder: &HistoryBuilder) -> Result<History, Error> {
+        self.repository.build_history(builder)
     }
 
-    /// Given a [`crate::file_system::Path`] to a file, return the last
-    /// [`Commit`] that touched that file or directory.
+    /// Walk the commits described by the [`HistoryBuilder`] lazily, in the
+    /// same way as [`Browser::build_history`], so that a caller can stop
+    /// early without loading the rest of the history.
     ///
     /// # Errors
     ///
-    /// * [`error::Error::Git`]
-    /// * [`error::Error::LastCommitException`]
+    /// * [`error::Error::Git`] if a revision cannot be resolved. Errors while
+    ///   walking, including [`error::Error::Cancelled`], are yielded by the
+    ///   iterator.
     ///
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
-    /// use radicle_surf::file_system::{Label, Path, SystemType};
-    /// use radicle_surf::file_system::unsound;
-    /// use std::str::FromStr;
+    /// use radicle_surf::vcs::git::{Branch, Browser, HistoryBuilder, Repository, TagName};
     /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let repo = Repository::new("./data/git-platinum")?;
-    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
-    ///
-    /// // Clamp the Browser to a particular commit
-    /// let commit = Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?;
-    /// browser.commit(commit)?;
-    ///
-    /// let head_commit = browser.get().first().clone();
-    /// let expected_commit = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?;
-    ///
-    /// let readme_last_commit = browser
-    ///     .last_commit(Path::with_root(&[unsound::label::new("README.md")]))?
-    ///     .map(|commit| commit.id);
-    ///
-    /// assert_eq!(readme_last_commit, Some(expected_commit));
-    ///
-    /// let expected_commit = Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
-    ///
-    /// let memory_last_commit = browser
-    ///     .last_commit(Path::with_root(&[unsound::label::new("src"), unsound::label::new("memory.rs")]))?
-    ///     .map(|commit| commit.id);
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
     ///
-    /// assert_eq!(memory_last_commit, Some(expected_commit));
+    /// let mut commits = browser.history_iter(&HistoryBuilder::new().push(TagName::new("v0.3.0")))?;
+    /// let head = commits.next().transpose()?.expect("v0.3.0 has commits");
+    /// assert_eq!(head.summary, "Add some binary files");
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn last_commit(&self, path: file_system::Path) -> Result<Option<Commit>, Error> {
-        let file_history = self.repository.file_history(
-            &path,
-            repo::CommitHistory::Last,
-            self.get().first().clone(),
-        )?;
-        Ok(file_history.first().cloned())
+    pub fn history_iter(&self, builder: &HistoryBuilder) -> Result<HistoryIter<'_, 'a>, Error> {
+        self.repository.history_iter(builder)
     }
 
-    /// Get the commit history for a file _or_ directory.
+    /// The revision the `Browser` is viewing, i.e. the one it was last pointed
+    /// at with [`Browser::new`], [`Browser::head`], [`Browser::branch`],
+    /// [`Browser::tag`], or [`Browser::rev`].
+    ///
+    /// When that is a branch or tag, including by name, e.g. `"dev"`, or
+    /// `HEAD` while it points to a branch, this is a [`Rev::Ref`] to that
+    /// branch or tag. Otherwise, e.g. for a commit or a revspec such as
+    /// `"master~3"`, or a [`History`] given to
+    /// [`Browser::set`](vcs::Browser::set), it is the [`Rev::Oid`] of the
+    /// head of the [`History`].
     ///
     /// # Examples
     ///
     /// ```
-    /// use nonempty::NonEmpty;
-    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
-    /// use radicle_surf::file_system::{Label, Path, SystemType};
-    /// use radicle_surf::file_system::unsound;
-    /// use std::str::FromStr;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository, Rev, TagName};
     /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let repo = Repository::new("./data/git-platinum")?;
     /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    /// assert_eq!(browser.current_rev(), Rev::from(Branch::local("master")));
     ///
-    /// // Clamp the Browser to a particular commit
-    /// let commit = Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?;
-    /// browser.commit(commit)?;
-    ///
-    /// let root_commits: Vec<Oid> = browser
-    ///     .file_history(unsound::path::new("~"))?
-    ///     .into_iter()
-    ///     .map(|commit| commit.id)
-    ///     .collect();
-    ///
-    /// assert_eq!(root_commits,
-    ///     vec![
-    ///         Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?,
-    ///         Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?,
-    ///         Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?,
-    ///         Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?,
-    ///         Oid::from_str("80ded66281a4de2889cc07293a8f10947c6d57fe")?,
-    ///         Oid::from_str("91b69e00cd8e5a07e20942e9e4457d83ce7a3ff1")?,
-    ///         Oid::from_str("1820cb07c1a890016ca5578aa652fd4d4c38967e")?,
-    ///         Oid::from_str("1e0206da8571ca71c51c91154e2fee376e09b4e7")?,
-    ///         Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
-    ///         Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")?,
-    ///         Oid::from_str("f3a089488f4cfd1a240a9c01b3fcc4c34a4e97b2")?,
-    ///         Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
-    ///         Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
-    ///     ]
-    /// );
+    /// browser.rev("dev".parse::<Rev>()?)?;
+    /// assert_eq!(browser.current_rev(), Rev::from(Branch::local("dev")));
     ///
-    /// let eval_commits: Vec<Oid> = browser
-    ///     .file_history(unsound::path::new("~/src/Eval.hs"))?
-    ///     .into_iter()
-    ///     .map(|commit| commit.id)
-    ///     .collect();
+    /// browser.rev("v0.3.0".parse::<Rev>()?)?;
+    /// assert_eq!(browser.current_rev(), Rev::from(TagName::new("v0.3.0")));
     ///
-    /// assert_eq!(eval_commits,
-    ///     vec![
-    ///         Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?,
-    ///         Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
-    ///     ]
+    /// browser.rev("master~3".parse::<Rev>()?)?;
+    /// assert_eq!(
+    ///     browser.current_rev(),
+    ///     Rev::Oid(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)
     /// );
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn file_history(&self, path: file_system::Path) -> Result<Vec<Commit>, Error> {
-        self.repository
-            .file_history(&path, repo::CommitHistory::Full, self.get().first().clone())
+    pub fn current_rev(&self) -> Rev {
+        let at_head = Rev::Oid(self.head_id());
+        let spec = match self.viewing() {
+            Some(Rev::Spec(spec)) => spec,
+            Some(rev) => return rev.clone(),
+            None => return at_head,
+        };
+
+        let reference = match self.repository.repo_ref.revparse_ext(spec) {
+            Ok((_, Some(reference))) => reference,
+            _ => return at_head,
+        };
+        // `HEAD` is a symbolic reference to the branch that is checked out.
+        let reference = match reference.resolve() {
+            Ok(reference) => reference,
+            Err(_) => return at_head,
+        };
+        if !(ext::is_branch(&reference) || reference.is_remote() || ext::is_tag(&reference)) {
+            return at_head;
+        }
+        reference
+            .name()
+            .and_then(|name| name.parse::<Ref>().ok())
+            .map_or(at_head, Rev::Ref)
     }
 
-    /// Extract the signature for a commit
-    ///
-    /// # Arguments
+    /// View `history`, loaded from `rev`, remembering the current view for
+    /// [`Browser::back`] if navigation is enabled.
+    fn view(&mut self, history: History, rev: Rev) {
+        if self.repository.navigation.is_some() {
+            self.push_view();
+        }
+        self.repository.viewing = Some((rev, history.head_id()));
+        self.set(history);
+    }
+
+    /// The revision the `Browser` was last pointed at, unless it has since
+    /// been given a [`History`] with another head, e.g. with
+    /// [`Browser::set`](vcs::Browser::set), which it cannot have been loaded
+    /// from.
+    fn viewing(&self) -> Option<&Rev> {
+        match &self.repository.viewing {
+            Some((rev, head)) if *head == self.head_id() => Some(rev),
+            _ => None,
+        }
+    }
+
+    /// Enable the navigation history of the `Browser`, so that
+    /// [`Browser::back`] and [`Browser::forward`] can return to the views it
+    /// had before [`Browser::head`], [`Browser::branch`], [`Browser::tag`],
+    /// [`Browser::commit`], or [`Browser::rev`] changed them, like the back
+    /// and forward buttons of a web browser.
     ///
-    /// * `commit` - The commit to extract the signature for
-    /// * `field` - the name of the header field containing the signature block;
-    ///   pass `None` to extract the default 'gpgsig'
+    /// Only the revision and the head of each view are remembered, and the
+    /// [`History`] is walked again from that head when the view is returned
+    /// to. At most 100 views are kept to go back to, forgetting the oldest.
     ///
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Branch, Browser, Repository, Oid, error};
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository, TagName};
     /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let repo = Repository::new("./data/git-platinum")?;
-    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
-    ///
-    /// let commit_with_signature_oid = Oid::from_str(
-    ///     "e24124b7538658220b5aaf3b6ef53758f0a106dc"
-    /// )?;
-    ///
-    /// browser.commit(commit_with_signature_oid)?;
-    /// let history = browser.get();
-    /// let commit_with_signature = history.first();
-    /// let signature = browser.extract_signature(commit_with_signature, None)?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?.with_navigation();
+    /// let master = browser.head_id();
     ///
-    /// // We have a signature
-    /// assert!(signature.is_some());
-    ///
-    /// let commit_without_signature_oid = Oid::from_str(
-    ///     "80bacafba303bf0cdf6142921f430ff265f25095"
-    /// )?;
+    /// browser.branch(Branch::local("dev"))?;
+    /// let dev = browser.head_id();
+    /// browser.tag(TagName::new("v0.3.0"))?;
     ///
-    /// browser.commit(commit_without_signature_oid)?;
-    /// let history = browser.get();
-    /// let commit_without_signature = history.first();
-    /// let signature = browser.extract_signature(commit_without_signature, None)?;
+    /// assert!(browser.back()?);
+    /// assert_eq!(browser.head_id(), dev);
+    /// assert!(browser.back()?);
+    /// assert_eq!(browser.head_id(), master);
+    /// assert!(!browser.back()?);
     ///
-    /// // There is no signature
-    /// assert!(signature.is_none());
+    /// assert!(browser.forward()?);
+    /// assert_eq!(browser.head_id(), dev);
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn extract_signature(
-        &self,
-        commit: &Commit,
-        field: Option<&str>,
-    ) -> Result<Option<Signature>, Error> {
-        self.repository.extract_signature(&commit.id, field)
+    pub fn with_navigation(mut self) -> Self {
+        self.repository.navigation = Some(Default::default());
+        self
     }
 
-    /// List the [`Branch`]es, which contain the provided [`Commit`].
+    /// Turn the mapping of the authors and committers of commits with the
+    /// mailmap of the repository on or off, reloading the [`History`] of the
+    /// `Browser` so that it is mapped in the same way. With the mailmap, the
+    /// contributors counted by [`Browser::get_stats`] and the analyses of
+    /// [`analytics`] know a person who committed under several names or
+    /// emails as one.
     ///
     /// # Errors
     ///
@@ -930,62 +1290,207 @@ impl<'a> Browser<'a> {
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Browser, Repository, Branch, BranchName, Namespace, Oid};
-    /// use std::convert::TryFrom;
+    /// use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser};
     /// # use std::error::Error;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// let repo = Repository::new("./data/git-platinum")?;
-    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    /// let fixture = Fixture::new()?;
+    /// fixture
+    ///     .commit("master", "Add a mailmap")
+    ///     .file(".mailmap", "Radicle Fixture <fixture@radicle.xyz>\n")
+    ///     .write()?;
     ///
+    /// let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+    /// assert_eq!(browser.get().first().author.name, "Fixture");
     ///
-    /// let branches = browser.revision_branches(Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?)?;
-    /// assert_eq!(
-    ///     branches,
-    ///     vec![
-    ///         Branch::local("dev"),
-    ///         Branch::remote("dev", "origin"),
-    ///     ]
-    /// );
+    /// browser.set_mailmap(true)?;
+    /// assert_eq!(browser.get().first().author.name, "Radicle Fixture");
     ///
-    /// // TODO(finto): I worry that this test will fail as other branches get added
-    /// let mut branches = browser.revision_branches(Oid::from_str("1820cb07c1a890016ca5578aa652fd4d4c38967e")?)?;
-    /// branches.sort();
-    /// assert_eq!(
-    ///     branches,
-    ///     vec![
-    ///         Branch::remote("HEAD", "origin"),
-    ///         Branch::local("dev"),
-    ///         Branch::remote("dev", "origin"),
-    ///         Branch::local("master"),
-    ///         Branch::remote("master", "origin"),
-    ///         Branch::remote("orange/pineapple", "banana"),
-    ///         Branch::remote("pineapple", "banana"),
-    ///     ]
-    /// );
+    /// browser.set_mailmap(false)?;
+    /// assert_eq!(browser.get().first().author.name, "Fixture");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_mailmap(&mut self, enabled: bool) -> Result<(), Error> {
+        self.repository.set_mailmap(enabled)?;
+
+        let mut commits = Vec::with_capacity(self.history.len());
+        for commit in self.history.iter() {
+            commits.push(self.repository.find_commit(commit.id)?);
+        }
+        self.history = History::try_from_iter(commits).expect("the History was not empty");
+        Ok(())
+    }
+
+    /// Remember the current view, so that [`Browser::back`] returns to it,
+    /// e.g. before changing the [`History`] with [`Browser::set`]. The views
+    /// that [`Browser::forward`] could go to are forgotten.
     ///
-    /// let golden_browser = browser.switch_namespace(&Namespace::try_from("golden")?,
-    /// Branch::local("master"))?;
+    /// This does nothing unless navigation is enabled with
+    /// [`Browser::with_navigation`].
+    pub fn push_view(&mut self) {
+        let view = self.current_view();
+        if let Some(navigation) = self.repository.navigation.as_mut() {
+            navigation.push(view);
+        }
+    }
+
+    /// Go back to the previous view, returning whether there was one.
     ///
-    /// let branches = golden_browser.revision_branches(Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?)?;
-    /// assert_eq!(
-    ///     branches,
-    ///     vec![
-    ///         Branch::local("banana"),
-    ///         Branch::remote("fakie/bigspin", "kickflip"),
-    ///         Branch::remote("heelflip", "kickflip"),
-    ///     ]
-    /// );
+    /// See [`Browser::with_navigation`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    pub fn back(&mut self) -> Result<bool, Error> {
+        let previous = match self.repository.navigation.as_ref() {
+            Some(navigation) => navigation.previous().cloned(),
+            None => None,
+        };
+        let current = self.current_view();
+        self.restore(previous, |navigation| navigation.back(current))
+    }
+
+    /// Go forward to the view that was left with [`Browser::back`], returning
+    /// whether there was one.
+    ///
+    /// See [`Browser::with_navigation`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    pub fn forward(&mut self) -> Result<bool, Error> {
+        let next = match self.repository.navigation.as_ref() {
+            Some(navigation) => navigation.next().cloned(),
+            None => None,
+        };
+        let current = self.current_view();
+        self.restore(next, |navigation| navigation.forward(current))
+    }
+
+    fn current_view(&self) -> View {
+        View {
+            head: self.head_id(),
+            rev: self.viewing().cloned(),
+        }
+    }
+
+    /// Walk the [`History`] of `view` again and view it, only moving the
+    /// navigation on with `step` once that succeeded.
+    fn restore<F>(&mut self, view: Option<View>, step: F) -> Result<bool, Error>
+    where
+        F: FnOnce(&mut Navigation) -> Option<View>,
+    {
+        let View { head, rev } = match view {
+            Some(view) => view,
+            None => return Ok(false),
+        };
+        let history = self.repository.history_from(head)?;
+        if let Some(navigation) = self.repository.navigation.as_mut() {
+            step(navigation);
+        }
+        self.repository.viewing = rev.map(|rev| (rev, head));
+        self.set(history);
+        Ok(true)
+    }
+
+    /// Reload the `Browser`'s [`History`] from the revision it was last
+    /// pointed at, i.e. the one given to [`Browser::new`], [`Browser::head`],
+    /// [`Browser::branch`], [`Browser::tag`], or [`Browser::rev`]. This picks
+    /// up any changes to the references since, e.g. after a
+    /// [`Repository::fetch`], without re-opening the repository.
+    ///
+    /// Returns whether the head of the [`History`] changed. If the `Browser`
+    /// was given a [`History`] with another head since, e.g. with
+    /// [`Browser::set`](vcs::Browser::set), there is no revision to reload it
+    /// from, and nothing changes.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Io`] if the grafts of a shallow clone cannot be read.
+    /// * [`error::Error::UnbornBranch`]
+    /// * [`error::Error::RevParseFailure`]
+    /// * [`error::Error::NamespaceRevParseFailure`]
+    /// * [`error::Error::EmptyNamespace`]
+    /// * [`error::Error::Utf8Error`]
+    /// * [`error::Error::MissingSummary`]
+    /// * [`error::Error::Cancelled`] if the deadline of the `Browser` passes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let fixture = Fixture::new()?;
+    /// fixture.commit("master", "Initial commit").file("README.md", "Hi\n").write()?;
+    ///
+    /// let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+    /// assert!(!browser.refresh()?);
+    ///
+    /// let latest = fixture.commit("master", "Say more").file("README.md", "Hello\n").write()?;
+    /// assert!(browser.refresh()?);
+    /// assert_eq!(browser.get().first().id, latest);
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn revision_branches(&self, rev: impl Into<Rev>) -> Result<Vec<Branch>, Error> {
-        let commit = self.repository.rev_to_commit(&rev.into())?;
-        self.repository.revision_branches(&commit.id())
+    pub fn refresh(&mut self) -> Result<bool, Error> {
+        let rev = match self.viewing() {
+            Some(rev) => rev.clone(),
+            None => return Ok(false),
+        };
+        let history = self.get_history(rev.clone())?;
+        let changed = !history.same_head(&self.history);
+        self.repository.viewing = Some((rev, history.head_id()));
+        self.set(history);
+        Ok(changed)
     }
 
-    /// Get the [`Stats`] of the underlying [`Repository`].
+    /// The [`Oid`] of the head of the `Browser`'s [`History`], without
+    /// cloning the [`History`] as [`Browser::get`] does.
+    ///
+    /// This is a cheap way of checking whether the view of the `Browser`
+    /// changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let before = browser.head_id();
+    /// assert_eq!(before, Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?);
+    ///
+    /// browser.rev(Branch::local("dev"))?;
+    /// assert_ne!(browser.head_id(), before);
+    /// assert_eq!(browser.head_id(), browser.get().head_id());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn head_id(&self) -> Oid {
+        self.history.head_id()
+    }
+
+    /// An [entity tag](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/ETag)
+    /// for what the `Browser` is viewing, so that web layers can answer
+    /// conditional requests without rendering the view again.
+    ///
+    /// The tag is a digest of the namespace, the revision the `Browser` was
+    /// pointed at, and the head of its [`History`], so it changes when any of
+    /// them do, even for a new commit that leaves the files as they were, and
+    /// is the same across processes otherwise. It is returned quoted, as it
+    /// appears in an `ETag` header.
     ///
     /// # Errors
     ///
@@ -1001,692 +1506,4713 @@ impl<'a> Browser<'a> {
     /// let repo = Repository::new("./data/git-platinum")?;
     /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
     ///
-    /// let stats = browser.get_stats()?;
+    /// let master = browser.etag()?;
+    /// assert!(master.starts_with('"') && master.ends_with('"'));
+    /// assert_eq!(browser.etag()?, master);
     ///
-    /// assert_eq!(stats.branches, 2);
+    /// browser.rev(Branch::local("dev"))?;
+    /// assert_ne!(browser.etag()?, master);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn etag(&self) -> Result<String, Error> {
+        let namespace = self
+            .which_namespace()?
+            .map(|namespace| namespace.to_string())
+            .unwrap_or_default();
+        let rev = self
+            .viewing()
+            .map_or_else(|| self.head_id().to_string(), ToString::to_string);
+        let digest = Oid::hash_object(
+            git2::ObjectType::Blob,
+            format!("{}\n{}\n{}\n", namespace, rev, self.head_id()).as_bytes(),
+        )?;
+        Ok(format!("\"{}\"", digest))
+    }
+
+    /// Look up a file or directory using the `<rev>:<path>` form, in the same
+    /// way as `git show`. The `<rev>` is any
+    /// [revspec](https://git-scm.com/docs/git-rev-parse.html#_specifying_revisions)
+    /// and the `<path>` is relative to the root of the repository. If the
+    /// `<path>` is omitted then the root directory of `<rev>` is returned.
     ///
-    /// assert_eq!(stats.commits, 15);
+    /// Note that this does not change the `Browser`'s [`History`].
     ///
-    /// assert_eq!(stats.contributors, 4);
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::FileSystem`]
+    /// * [`error::Error::RevParseFailure`]
+    ///
+    /// # Examples
     ///
+    /// ```
+    /// use radicle_surf::file_system::{DirectoryContents, SystemType};
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// match browser.show("v0.3.0:README.md")? {
+    ///     DirectoryContents::File { name, file } => {
+    ///         assert_eq!(name, unsound::label::new("README.md"));
+    ///         assert!(file.contents.starts_with(b"This repository is a data source"));
+    ///     },
+    ///     DirectoryContents::Directory(_) => panic!("README.md is a file"),
+    /// }
+    ///
+    /// match browser.show("master:src")? {
+    ///     DirectoryContents::Directory(src) => {
+    ///         assert_eq!(src.current(), unsound::label::new("src"));
+    ///         assert_eq!(src.list_directory(), vec![
+    ///             SystemType::file(unsound::label::new("Eval.hs")),
+    ///             SystemType::file(unsound::label::new("memory.rs")),
+    ///         ]);
+    ///     },
+    ///     DirectoryContents::File { .. } => panic!("src is a directory"),
+    /// }
+    /// #
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get_stats(&self) -> Result<Stats, Error> {
-        let branches = self.list_branches(RefScope::Local)?.len();
-        let commits = self.history.len();
+    pub fn show(&self, spec: &str) -> Result<directory::DirectoryContents, Error> {
+        let (rev, path) = match spec.split_once(':') {
+            Some((rev, path)) => (rev, path.trim_matches('/')),
+            None => (spec, ""),
+        };
 
-        let contributors = self
-            .history
-            .iter()
-            .cloned()
-            .map(|commit| (commit.author.name, commit.author.email))
-            .collect::<BTreeSet<_>>();
+        // The name of what we're showing is the last label of the path, or the
+        // root label if there is no path.
+        let name = if path.is_empty() {
+            file_system::Label::root()
+        } else {
+            file_system::Path::try_from(path)?.split_last().1
+        };
 
-        Ok(Stats {
-            branches,
-            commits,
-            contributors: contributors.len(),
-        })
+        let object = self.repository.revparse(&format!("{}:{}", rev, path))?;
+        match object {
+            RevObject::Blob { file, .. } => Ok(directory::DirectoryContents::File { name, file }),
+            object => {
+                let tree = self
+                    .repository
+                    .repo_ref
+                    .find_object(object.id(), None)?
+                    .peel_to_tree()?;
+                let directory = Self::walk_tree(&self.repository, &tree)?;
+                Ok(directory::DirectoryContents::Directory(
+                    directory.relabel(name),
+                ))
+            },
+        }
     }
 
-    /// Do a pre-order TreeWalk of the given commit. This turns a Tree
-    /// into a HashMap of Paths and a list of Files. We can then turn that
-    /// into a Directory.
-    fn get_tree(
-        repo: &git2::Repository,
-        commit: &Commit,
-    ) -> Result<HashMap<file_system::Path, NonEmpty<(file_system::Label, directory::File)>>, Error>
-    {
-        let mut file_paths_or_error: Result<
-            HashMap<file_system::Path, NonEmpty<(file_system::Label, directory::File)>>,
-            Error,
-        > = Ok(HashMap::new());
-
-        let commit = repo.find_commit(commit.id)?;
-        let tree = commit.as_object().peel_to_tree()?;
+    /// Search the files of the current snapshot for the lines that match
+    /// `pattern`, in the order of their paths.
+    ///
+    /// Binary files are skipped, as are submodules. See [`grep::Options`]
+    /// for whether the `pattern` is a regular expression or a literal, and
+    /// how much context is kept around each [`grep::Match`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Regex`] if the `pattern` is not a valid regular expression.
+    /// * [`Error::Cancelled`] if the deadline of the `Browser` passes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{grep, Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let options = grep::Options {
+    ///     mode: grep::Mode::Literal,
+    ///     context: 1,
+    ///     ..grep::Options::default()
+    /// };
+    /// let matches = browser.grep("( eval", &options)?;
+    ///
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].path, unsound::path::new("~/src/Eval.hs"));
+    /// assert_eq!(matches[0].line_number, 2);
+    /// assert_eq!(matches[0].line, "    ( eval");
+    /// assert_eq!(matches[0].before, vec!["module Radicle.Lang.Eval"]);
+    /// assert_eq!(matches[0].after, vec!["    , baseEval"]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn grep(&self, pattern: &str, options: &grep::Options) -> Result<Vec<grep::Match>, Error> {
+        let matcher = options.matcher(pattern)?;
+        let limit = options.max_matches.unwrap_or(usize::MAX);
+        let repo = self.repository.repo_ref;
+        let tree = repo.find_commit(self.get().first().id)?.tree()?;
+
+        let mut matches = vec![];
+        let mut search = |root: &str, entry: &git2::TreeEntry| -> Result<(), Error> {
+            self.repository.check_deadline()?;
+            if matches.len() >= limit || entry.kind() != Some(git2::ObjectType::Blob) {
+                return Ok(());
+            }
 
-        tree.walk(
-            git2::TreeWalkMode::PreOrder,
-            |s, entry| match Self::tree_entry_to_file_and_path(repo, s, entry) {
-                Ok((path, name, file)) => {
-                    match file_paths_or_error.as_mut() {
-                        Ok(files) => Self::update_file_map(path, name, file, files),
+            let blob = repo.find_blob(entry.id())?;
+            if blob.is_binary() {
+                return Ok(());
+            }
+            let name = str::from_utf8(entry.name_bytes())?;
+            let path =
+                file_system::Path::try_from(path::PathBuf::from(format!("{}{}", root, name)))?;
+            grep::search(
+                &path,
+                blob.content(),
+                &matcher,
+                options,
+                limit,
+                &mut matches,
+            );
+            Ok(())
+        };
 
-                        // We don't need to update, we want to keep the error.
-                        Err(_err) => {},
-                    }
-                    git2::TreeWalkResult::Ok
-                },
-                Err(err) => match err {
-                    // We want to continue if the entry was not a Blob.
-                    TreeWalkError::NotBlob => git2::TreeWalkResult::Ok,
-
-                    // We found a ObjectType::Commit (likely a submodule) and
-                    // so we can skip it.
-                    TreeWalkError::Commit => git2::TreeWalkResult::Ok,
-
-                    // But we want to keep the error and abort otherwise.
-                    TreeWalkError::Git(err) => {
-                        file_paths_or_error = Err(err);
-                        git2::TreeWalkResult::Abort
-                    },
+        let mut result = Ok(());
+        let walked = tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            match search(root, entry) {
+                Ok(()) => git2::TreeWalkResult::Ok,
+                Err(err) => {
+                    result = Err(err);
+                    git2::TreeWalkResult::Abort
                 },
-            },
-        )?;
+            }
+        });
 
-        file_paths_or_error
+        // The error that aborted the walk takes precedence.
+        result?;
+        walked?;
+        Ok(matches)
     }
 
-    /// Find the best common ancestor between two commits if it exists.
+    /// Parse an [`Oid`] from the given string. This is useful if we have a
+    /// shorthand version of the `Oid`, as opposed to the full one.
     ///
-    /// See [`git2::Repository::merge_base`] for details.
-    pub fn merge_base(&self, one: Oid, two: Oid) -> Result<Option<Oid>, Error> {
-        match self.repository.repo_ref.merge_base(one, two) {
-            Ok(merge_base) => Ok(Some(merge_base)),
-            Err(err) => {
-                if err.code() == git2::ErrorCode::NotFound {
-                    Ok(None)
-                } else {
-                    Err(Error::Git(err))
-                }
-            },
-        }
-    }
-
-    fn update_file_map(
-        path: file_system::Path,
-        name: file_system::Label,
-        file: directory::File,
-        files: &mut HashMap<file_system::Path, NonEmpty<(file_system::Label, directory::File)>>,
-    ) {
-        files
-            .entry(path)
-            .and_modify(|entries| entries.push((name.clone(), file.clone())))
-            .or_insert_with(|| NonEmpty::new((name, file)));
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // Set to the initial commit
+    /// let commit = Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
+    ///
+    /// assert_eq!(
+    ///     commit,
+    ///     browser.oid("e24124b")?,
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn oid(&self, oid: &str) -> Result<Oid, Error> {
+        self.repository.oid(oid)
     }
 
-    fn tree_entry_to_file_and_path(
-        repo: &git2::Repository,
-        tree_path: &str,
-        entry: &git2::TreeEntry,
-    ) -> Result<(file_system::Path, file_system::Label, directory::File), TreeWalkError> {
-        // Account for the "root" of git being the empty string
-        let path = if tree_path.is_empty() {
-            Ok(file_system::Path::root())
-        } else {
-            file_system::Path::try_from(tree_path)
-        }?;
-
-        // We found a Commit object in the Tree, likely a submodule.
-        // We will skip this entry.
-        if let Some(git2::ObjectType::Commit) = entry.kind() {
-            return Err(TreeWalkError::Commit);
-        }
-
-        let object = entry.to_object(repo)?;
-        let blob = object.as_blob().ok_or(TreeWalkError::NotBlob)?;
-        let name = str::from_utf8(entry.name_bytes())?;
-
-        let name = file_system::Label::try_from(name).map_err(Error::FileSystem)?;
-
-        Ok((
-            path,
-            name,
-            directory::File {
-                contents: blob.content().to_owned(),
-                size: blob.size(),
-            },
-        ))
+    /// Get the [`Diff`] between two commits.
+    pub fn diff(&self, from: Oid, to: Oid) -> Result<Diff, Error> {
+        self.repository.diff(from, to)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[cfg(not(feature = "gh-actions"))]
-    #[test]
-    // An issue with submodules, see: https://github.com/radicle-dev/radicle-surf/issues/54
-    fn test_submodule_failure() {
-        let repo = Repository::new("..").unwrap();
-        let browser = Browser::new(&repo, Branch::local("master")).unwrap();
 
-        browser.get_directory().unwrap();
+    /// Get the [`Diff`] between two commits, limited to the files matching
+    /// the [`Pathspec`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Diff`]
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{unsound, Pathspec};
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let from = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?;
+    /// let to = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    ///
+    /// let pathspec = Pathspec::default().include("src")?.exclude("src/*.hs")?;
+    /// let diff = browser.diff_pathspec(from, to, &pathspec)?;
+    /// let created = diff.created.into_iter().map(|created| created.path).collect::<Vec<_>>();
+    /// assert_eq!(created, vec![unsound::path::new("~/src/memory.rs")]);
+    /// assert!(diff.modified.is_empty());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff_pathspec(&self, from: Oid, to: Oid, pathspec: &Pathspec) -> Result<Diff, Error> {
+        self.repository.diff_pathspec(from, to, pathspec)
     }
 
-    #[cfg(test)]
-    mod namespace {
-        use super::*;
-        use pretty_assertions::{assert_eq, assert_ne};
-
-        #[test]
-        fn switch_to_banana() -> Result<(), Error> {
-            let repo = Repository::new("./data/git-platinum")?;
+    /// Get a summary of the changes between two commits, giving the status
+    /// and the number of lines added and deleted for each file.
+    ///
+    /// This is cheaper than [`Browser::diff`] since the hunks of the changes
+    /// are not collected, which suits showing the size of many commits.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Diff`]
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::diff::{FileStatus, FileSummary};
+    /// use radicle_surf::file_system::{unsound, Path};
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let summary = browser.diff_summary(
+    ///     Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?,
+    ///     Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?,
+    /// )?;
+    ///
+    /// assert_eq!(
+    ///     summary,
+    ///     vec![FileSummary {
+    ///         path: Path::with_root(&[unsound::label::new("README.md")]),
+    ///         status: FileStatus::Modified,
+    ///         additions: 2,
+    ///         deletions: 1,
+    ///         binary: false,
+    ///     }]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff_summary(&self, from: Oid, to: Oid) -> Result<Vec<FileSummary>, Error> {
+        self.repository.diff_summary(from, to)
+    }
+
+    /// Get the paths of the files changed by the commit `oid`, compared to its
+    /// first parent, along with how they changed.
+    ///
+    /// This is cheaper than [`Browser::diff`] or [`Browser::diff_summary`] when
+    /// only the list of changed files is needed, since the contents of the
+    /// files are not looked at.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Diff`]
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::diff::FileStatus;
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let paths = browser.paths_touched(Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?)?;
+    /// assert_eq!(paths, vec![(unsound::path::new("~/README.md"), FileStatus::Modified)]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn paths_touched(&self, oid: Oid) -> Result<Vec<(file_system::Path, FileStatus)>, Error> {
+        self.repository.paths_touched(oid)
+    }
+
+    /// Walk the [`History`] of the `Browser`, from the newest commit to the
+    /// oldest, yielding each commit along with its [`Diff`] against its first
+    /// parent. A root commit is diffed against the empty tree.
+    ///
+    /// The diffs are computed lazily as the walk goes on, so analyses such as
+    /// churn metrics can be done in a single pass and stopped early.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Cancelled`]
+    /// * [`error::Error::Diff`]
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let walked = browser.walk_with_snapshots().collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(walked.len(), browser.get().len());
+    ///
+    /// // The merge of the README update only changed the README.
+    /// let (merge, diff) = &walked[1];
+    /// assert!(merge.is_merge());
+    /// assert_eq!(diff.modified[0].path, unsound::path::new("~/README.md"));
+    ///
+    /// // Everything is created by the root commit.
+    /// let (root, diff) = walked.last().unwrap();
+    /// assert!(root.parents.is_empty());
+    /// assert!(diff.deleted.is_empty() && diff.modified.is_empty());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn walk_with_snapshots(&self) -> impl Iterator<Item = Result<(Commit, Diff), Error>> + '_ {
+        self.history.iter().map(move |commit| {
+            self.repository.check_deadline()?;
+            let diff = self.repository.diff_from_parent(commit.id)?;
+            Ok((commit.clone(), diff))
+        })
+    }
+
+    /// Compute the [`analytics::Churn`] of each file changed over the
+    /// [`History`] of the `Browser`. See [`analytics::churn`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Cancelled`]
+    /// * [`error::Error::Diff`]
+    /// * [`error::Error::Git`]
+    pub fn churn(&self) -> Result<analytics::ChurnReport, Error> {
+        analytics::churn(&self.repository, &self.history)
+    }
+
+    /// Get the [`FileDiff`] of a single file between two commits, or `None` if
+    /// the file did not change.
+    ///
+    /// This is cheaper than [`Browser::diff`] when only one file is of
+    /// interest, since the changes to the other files are not computed.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Diff`]
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::diff::{FileDiff, LineDiff};
+    /// use radicle_surf::file_system::{unsound, Path};
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let from = Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?;
+    /// let to = Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?;
+    ///
+    /// let readme = browser.diff_file(from, to, &Path::with_root(&[unsound::label::new("README.md")]))?;
+    /// match readme {
+    ///     Some(FileDiff::Plain { hunks }) => {
+    ///         let hunk = hunks.iter().next().expect("missing hunk");
+    ///         assert_eq!(
+    ///             hunk.lines[0],
+    ///             LineDiff::deletion(
+    ///                 b"This repository is a data source for the Upstream front-end tests.\n".to_vec(),
+    ///                 1,
+    ///             )
+    ///         );
+    ///     },
+    ///     _ => panic!("README.md should have changed"),
+    /// }
+    ///
+    /// // Files that did not change have no diff
+    /// let eval = Path::with_root(&[unsound::label::new("src"), unsound::label::new("Eval.hs")]);
+    /// assert_eq!(browser.diff_file(from, to, &eval)?, None);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff_file(
+        &self,
+        from: Oid,
+        to: Oid,
+        path: &file_system::Path,
+    ) -> Result<Option<FileDiff>, Error> {
+        self.repository.diff_file(from, to, path)
+    }
+
+    /// Get the [`Diff`] of a commit with no parents.
+    pub fn initial_diff(&self, oid: Oid) -> Result<Diff, Error> {
+        self.repository.initial_diff(oid)
+    }
+
+    /// List the names of the _branches_ that are contained in the underlying
+    /// [`Repository`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, RefScope, BranchName, Browser, Namespace, Repository};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let branches = browser.list_branches(RefScope::All)?;
+    ///
+    /// // 'master' exists in the list of branches
+    /// assert!(branches.contains(&Branch::local("master")));
+    ///
+    /// // Filter the branches by `Remote` 'origin'.
+    /// let mut branches = browser.list_branches(RefScope::Remote {
+    ///     name: Some("origin".to_string())
+    /// })?;
+    /// branches.sort();
+    ///
+    /// assert_eq!(branches, vec![
+    ///     Branch::remote("HEAD", "origin"),
+    ///     Branch::remote("dev", "origin"),
+    ///     Branch::remote("master", "origin"),
+    /// ]);
+    ///
+    /// // Filter the branches by all `Remote`s.
+    /// let mut branches = browser.list_branches(RefScope::Remote {
+    ///     name: None
+    /// })?;
+    /// branches.sort();
+    ///
+    /// assert_eq!(branches, vec![
+    ///     Branch::remote("HEAD", "origin"),
+    ///     Branch::remote("dev", "origin"),
+    ///     Branch::remote("master", "origin"),
+    ///     Branch::remote("orange/pineapple", "banana"),
+    ///     Branch::remote("pineapple", "banana"),
+    /// ]);
+    ///
+    /// // We can also switch namespaces and list the branches in that namespace.
+    /// let golden = browser.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+    ///
+    /// let mut branches = golden.list_branches(RefScope::Local)?;
+    /// branches.sort();
+    ///
+    /// assert_eq!(branches, vec![
+    ///     Branch::local("banana"),
+    ///     Branch::local("master"),
+    /// ]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_branches(&self, filter: RefScope) -> Result<Vec<Branch>, Error> {
+        self.repository.list_branches(filter)
+    }
+
+    /// List the _branches_ as in [`Browser::list_branches`], along with the
+    /// references that were left out, e.g. symbolic references to missing
+    /// targets.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, RefScope, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let listing = browser.list_branches_with_warnings(RefScope::Local)?;
+    /// assert_eq!(listing.items.len(), 2);
+    /// assert!(listing.warnings.is_empty());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_branches_with_warnings(&self, filter: RefScope) -> Result<Listing<Branch>, Error> {
+        self.repository.list_branches_with_warnings(filter)
+    }
+
+    /// List the names of the _tags_ that are contained in the underlying
+    /// [`Repository`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, RefScope, Browser, Namespace, Oid, Repository, Tag, TagName, Author, Time};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let tags = browser.list_tags(RefScope::Local)?;
+    ///
+    /// assert_eq!(
+    ///     tags,
+    ///     vec![
+    ///         Tag::Light {
+    ///             id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    ///             name: TagName::new("v0.1.0"),
+    ///             remote: None,
+    ///         },
+    ///         Tag::Light {
+    ///             id: Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
+    ///             name: TagName::new("v0.2.0"),
+    ///             remote: None,
+    ///         },
+    ///         Tag::Light {
+    ///             id: Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")?,
+    ///             name: TagName::new("v0.3.0"),
+    ///             remote: None,
+    ///         },
+    ///         Tag::Light {
+    ///             id: Oid::from_str("91b69e00cd8e5a07e20942e9e4457d83ce7a3ff1")?,
+    ///             name: TagName::new("v0.4.0"),
+    ///             remote: None,
+    ///         },
+    ///         Tag::Light {
+    ///             id: Oid::from_str("80ded66281a4de2889cc07293a8f10947c6d57fe")?,
+    ///             name: TagName::new("v0.5.0"),
+    ///             remote: None,
+    ///         },
+    ///         Tag::Annotated {
+    ///             id: Oid::from_str("4d1f4af2703074d37cb877f4fdbe36322c8e541d")?,
+    ///             target_id: Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?,
+    ///             name: TagName::new("v0.6.0"),
+    ///             remote: None,
+    ///             tagger: Some(Author {
+    ///               name: "Thomas Scholtes".to_string(),
+    ///               email: "thomas@monadic.xyz".to_string(),
+    ///               time: Time::new(1620740737, 120),
+    ///             }),
+    ///             message: Some("An annotated tag message for v0.6.0\n".to_string())
+    ///         },
+    ///     ]
+    /// );
+    ///
+    /// // We can also switch namespaces and list the branches in that namespace.
+    /// let golden = browser.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+    ///
+    /// let branches = golden.list_tags(RefScope::Local)?;
+    /// assert_eq!(branches, vec![
+    ///     Tag::Light {
+    ///         id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    ///         name: TagName::new("v0.1.0"),
+    ///         remote: None,
+    ///     },
+    ///     Tag::Light {
+    ///         id: Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
+    ///         name: TagName::new("v0.2.0"),
+    ///         remote: None,
+    ///     },
+    /// ]);
+    /// let golden = golden.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+    ///
+    /// let branches = golden.list_tags(RefScope::Remote { name: Some("kickflip".to_string()) })?;
+    /// assert_eq!(branches, vec![
+    ///     Tag::Light {
+    ///         id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    ///         name: TagName::new("v0.1.0"),
+    ///         remote: Some("kickflip".to_string()),
+    ///     },
+    /// ]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_tags(&self, scope: RefScope) -> Result<Vec<Tag>, Error> {
+        self.repository.list_tags(scope)
+    }
+
+    /// Build the [`History`] of every reference, as in
+    /// [`vcs::Vcs::get_histories`], named by the reference it was built
+    /// from. The references that cannot be peeled to a commit are left out.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Ref, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let histories = browser.get_named_histories()?;
+    /// let dev = histories
+    ///     .iter()
+    ///     .find(|named| named.name == Ref::from(Branch::local("dev")))
+    ///     .expect("dev is a local branch");
+    /// assert_eq!(
+    ///     dev.history.first().id,
+    ///     Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_named_histories(&self) -> Result<Vec<NamedHistory>, Error> {
+        self.repository.get_named_histories()
+    }
+
+    /// Build the named [`History`] of every reference, as in
+    /// [`Browser::get_named_histories`], along with the references that were
+    /// left out because they could not be peeled to a commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    pub fn get_histories_with_warnings(&self) -> Result<Listing<NamedHistory>, Error> {
+        self.repository.get_histories_with_warnings()
+    }
+
+    /// List a [`HistoryHandle`] for each reference matching the `glob`,
+    /// without building any of their histories. The references that cannot
+    /// be peeled to a commit are left out and reported as warnings.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, RefGlob, RefScope, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let handles = browser.history_handles(&RefGlob::branch(RefScope::Local))?.items;
+    /// let master = handles
+    ///     .iter()
+    ///     .find(|handle| handle.name == Branch::local("master").into())
+    ///     .expect("master is a local branch");
+    /// assert_eq!(master.tip, Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?);
+    ///
+    /// // The history is only walked when it is loaded.
+    /// let history = browser.load_history(master)?;
+    /// assert_eq!(history.first().id, master.tip);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn history_handles(&self, glob: &RefGlob) -> Result<Listing<HistoryHandle>, Error> {
+        self.repository.history_handles(glob)
+    }
+
+    /// Build the [`History`] of a [`HistoryHandle`], starting from the commit
+    /// the reference pointed to when it was listed.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    pub fn load_history(&self, handle: &HistoryHandle) -> Result<History, Error> {
+        self.repository.load_history(handle)
+    }
+
+    /// Build the [`History`] of each reference matching the `glob`, as in
+    /// [`Browser::get_histories_with_warnings`] but only for those
+    /// references.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    pub fn get_histories_matching(&self, glob: &RefGlob) -> Result<Listing<NamedHistory>, Error> {
+        self.repository.get_histories_matching(glob)
+    }
+
+    /// List the _tags_ as in [`Browser::list_tags`], along with the
+    /// references that were left out because they could not be read or
+    /// peeled.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    pub fn list_tags_with_warnings(&self, scope: RefScope) -> Result<Listing<Tag>, Error> {
+        self.repository.list_tags_with_warnings(scope)
+    }
+
+    /// List the namespaces within a `Browser`, filtering out ones that do not
+    /// parse correctly.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, BranchType, BranchName, Browser, Namespace, Repository};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let mut namespaces = browser.list_namespaces()?;
+    /// namespaces.sort();
+    ///
+    /// assert_eq!(namespaces, vec![
+    ///     Namespace::try_from("golden")?,
+    ///     Namespace::try_from("golden/silver")?,
+    ///     Namespace::try_from("me")?,
+    /// ]);
+    ///
+    ///
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_namespaces(&self) -> Result<Vec<Namespace>, Error> {
+        self.repository.list_namespaces()
+    }
+
+    /// List the namespaces within a repository arranged by how they are
+    /// nested, returning the top-level namespaces.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Namespace, Repository};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let namespaces = browser.namespace_hierarchy()?;
+    /// assert_eq!(
+    ///     namespaces.iter().map(|tree| tree.namespace.clone()).collect::<Vec<_>>(),
+    ///     vec![Namespace::try_from("golden")?, Namespace::try_from("me")?]
+    /// );
+    ///
+    /// let golden = &namespaces[0];
+    /// assert_eq!(golden.children.len(), 1);
+    /// assert_eq!(golden.children[0].namespace, Namespace::try_from("golden/silver")?);
+    /// assert_eq!(golden.children[0].namespace.parent(), Some(golden.namespace.clone()));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn namespace_hierarchy(&self) -> Result<Vec<NamespaceTree>, Error> {
+        self.repository.namespace_hierarchy()
+    }
+
+    /// List the names of the references matching the given [`RefGlob`],
+    /// relative to the current namespace. This allows enumerating reference
+    /// hierarchies other than branches and tags, e.g. `refs/rad/ids/*`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, RefGlob, RefScope, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let tags = browser.list_references(&RefGlob::custom("refs/tags/v0.*"))?;
+    /// assert!(tags.contains(&"refs/tags/v0.1.0".to_string()));
+    ///
+    /// let branches = browser.list_references(&RefGlob::branch(RefScope::Local))?;
+    /// assert!(branches.contains(&"refs/heads/master".to_string()));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_references(&self, glob: &RefGlob) -> Result<Vec<String>, Error> {
+        self.repository.list_references(glob)
+    }
+
+    /// Given a [`crate::file_system::Path`] to a file, return the last
+    /// [`Commit`] that touched that file or directory.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::LastCommitException`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use radicle_surf::file_system::{Label, Path, SystemType};
+    /// use radicle_surf::file_system::unsound;
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // Clamp the Browser to a particular commit
+    /// let commit = Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?;
+    /// browser.commit(commit)?;
+    ///
+    /// let head_commit = browser.get().first().clone();
+    /// let expected_commit = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?;
+    ///
+    /// let readme_last_commit = browser
+    ///     .last_commit(Path::with_root(&[unsound::label::new("README.md")]))?
+    ///     .map(|commit| commit.id);
+    ///
+    /// assert_eq!(readme_last_commit, Some(expected_commit));
+    ///
+    /// let expected_commit = Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
+    ///
+    /// let memory_last_commit = browser
+    ///     .last_commit(Path::with_root(&[unsound::label::new("src"), unsound::label::new("memory.rs")]))?
+    ///     .map(|commit| commit.id);
+    ///
+    /// assert_eq!(memory_last_commit, Some(expected_commit));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn last_commit(&self, path: file_system::Path) -> Result<Option<Commit>, Error> {
+        let file_history = self.repository.file_history(
+            &Pathspec::from(path),
+            CommitHistory::Last,
+            self.get().first().clone(),
+        )?;
+        Ok(file_history.first().cloned())
+    }
+
+    /// Get the last commit for each of the `paths`, in the same order as
+    /// `paths`.
+    ///
+    /// This gives the same results as calling [`Browser::last_commit`] for
+    /// each path, but walks the history only once, which makes it suitable
+    /// for listing the last commits of a whole directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use radicle_surf::file_system::Path;
+    /// use radicle_surf::file_system::unsound;
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let paths = vec![
+    ///     Path::with_root(&[unsound::label::new("src"), unsound::label::new("memory.rs")]),
+    ///     Path::with_root(&[unsound::label::new("nothing-here")]),
+    /// ];
+    /// let last_commits = browser
+    ///     .last_commits(&paths)?
+    ///     .into_iter()
+    ///     .map(|commit| commit.map(|commit| commit.id))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     last_commits,
+    ///     vec![Some(Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?), None]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn last_commits(&self, paths: &[file_system::Path]) -> Result<Vec<Option<Commit>>, Error> {
+        self.repository
+            .last_commits(paths, self.get().first().clone())
+    }
+
+    /// Get a [`TreeRef`] of the most recent commit of this `Browser`'s
+    /// history, whose files borrow their contents from the repository rather
+    /// than being copied, as they are for [`Browser::get_directory`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let tree = browser.tree_ref()?;
+    /// assert_eq!(tree.commit(), browser.head_id());
+    ///
+    /// let path = unsound::path::new("src/memory.rs");
+    /// let memory = tree.file(&path)?.expect("src/memory.rs exists");
+    /// assert_eq!(
+    ///     Some(memory.contents()),
+    ///     browser
+    ///         .get_directory()?
+    ///         .find_file(path)
+    ///         .as_ref()
+    ///         .map(|file| file.contents.as_slice())
+    /// );
+    ///
+    /// // Directories are not files.
+    /// assert!(tree.file(&unsound::path::new("src"))?.is_none());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tree_ref(&self) -> Result<TreeRef<'a>, Error> {
+        TreeRef::new(self.repository.repo_ref, self.get().first().id)
+    }
+
+    /// Render the [`directory::Directory`] for this `Browser`, in the same way
+    /// as [`Browser::get_directory`], along with the last commit of every file
+    /// and directory in it, which are found in a single walk of the history.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use radicle_surf::file_system::{unsound, Path};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let annotated = browser.get_directory_with_last_commits()?;
+    /// assert_eq!(annotated.directory, browser.get_directory()?);
+    ///
+    /// let memory = annotated
+    ///     .last_commit(&unsound::path::new("~/src/memory.rs"))
+    ///     .map(|commit| commit.id);
+    /// assert_eq!(memory, Some(Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?));
+    ///
+    /// let root = annotated.last_commit(&Path::root()).map(|commit| commit.id);
+    /// assert_eq!(root, Some(browser.head_id()));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_directory_with_last_commits(&self) -> Result<AnnotatedDirectory, Error> {
+        let directory = self.get_directory()?;
+
+        let mut paths = vec![file_system::Path::root()];
+        let mut directories = HashSet::new();
+        for (path, _) in directory.iter_files() {
+            let mut rooted = file_system::Path::root();
+            rooted.append(path);
+
+            // Every directory holds a file, so they are all found as the
+            // parents of files.
+            let mut parent = rooted.clone();
+            while parent.pop().is_some() && !parent.is_root() {
+                if !directories.insert(parent.clone()) {
+                    break;
+                }
+                paths.push(parent.clone());
+            }
+            paths.push(rooted);
+        }
+
+        let last_commits = self
+            .repository
+            .last_commits(&paths, self.get().first().clone())?;
+        let last_commits = paths
+            .into_iter()
+            .zip(last_commits)
+            .filter_map(|(path, commit)| Some((path, commit?)))
+            .collect();
+
+        Ok(AnnotatedDirectory {
+            directory,
+            last_commits,
+        })
+    }
+
+    /// Get the [`Ownership`] of the file or directory at `path`, combining the
+    /// `CODEOWNERS` file of the current snapshot with the last commit that
+    /// touched `path`, for routing reviews of changes to it.
+    ///
+    /// The `CODEOWNERS` file is looked up at each of the
+    /// [`owners::LOCATIONS`](file_system::owners::LOCATIONS) in turn. If
+    /// there is none, the `owners` are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // git-platinum has no CODEOWNERS.
+    /// let ownership = browser.owners(unsound::path::new("~/src/memory.rs"))?;
+    /// assert!(ownership.owners.is_empty());
+    /// assert_eq!(
+    ///     ownership.last_commit.map(|commit| commit.id),
+    ///     Some(Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?)
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn owners(&self, path: file_system::Path) -> Result<Ownership, Error> {
+        let repo = self.repository.repo_ref;
+        let tree = repo.find_commit(self.get().first().id)?.tree()?;
+
+        let mut code_owners = None;
+        for location in file_system::owners::LOCATIONS.iter() {
+            let entry = match tree.get_path(path::Path::new(location)) {
+                Ok(entry) => entry,
+                Err(err) if err.code() == git2::ErrorCode::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            if let Ok(blob) = entry.to_object(repo)?.into_blob() {
+                let contents = String::from_utf8_lossy(blob.content());
+                code_owners = Some(file_system::owners::CodeOwners::parse(&contents));
+                break;
+            }
+        }
+
+        let owners = code_owners.map_or_else(Vec::new, |code_owners| {
+            code_owners.owners_for(&path).to_vec()
+        });
+        Ok(Ownership {
+            owners,
+            last_commit: self.last_commit(path)?,
+        })
+    }
+
+    /// Get the commit history for a file _or_ directory, or for any of the
+    /// paths matching a [`Pathspec`], e.g. everything under `src` excluding
+    /// its tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nonempty::NonEmpty;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use radicle_surf::file_system::{Label, Path, SystemType};
+    /// use radicle_surf::file_system::unsound;
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // Clamp the Browser to a particular commit
+    /// let commit = Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?;
+    /// browser.commit(commit)?;
+    ///
+    /// let root_commits: Vec<Oid> = browser
+    ///     .file_history(unsound::path::new("~"))?
+    ///     .into_iter()
+    ///     .map(|commit| commit.id)
+    ///     .collect();
+    ///
+    /// assert_eq!(root_commits,
+    ///     vec![
+    ///         Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?,
+    ///         Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?,
+    ///         Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?,
+    ///         Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?,
+    ///         Oid::from_str("80ded66281a4de2889cc07293a8f10947c6d57fe")?,
+    ///         Oid::from_str("91b69e00cd8e5a07e20942e9e4457d83ce7a3ff1")?,
+    ///         Oid::from_str("1820cb07c1a890016ca5578aa652fd4d4c38967e")?,
+    ///         Oid::from_str("1e0206da8571ca71c51c91154e2fee376e09b4e7")?,
+    ///         Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
+    ///         Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")?,
+    ///         Oid::from_str("f3a089488f4cfd1a240a9c01b3fcc4c34a4e97b2")?,
+    ///         Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
+    ///         Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    ///     ]
+    /// );
+    ///
+    /// let eval_commits: Vec<Oid> = browser
+    ///     .file_history(unsound::path::new("~/src/Eval.hs"))?
+    ///     .into_iter()
+    ///     .map(|commit| commit.id)
+    ///     .collect();
+    ///
+    /// assert_eq!(eval_commits,
+    ///     vec![
+    ///         Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?,
+    ///         Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
+    ///     ]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn file_history(&self, pathspec: impl Into<Pathspec>) -> Result<Vec<Commit>, Error> {
+        self.repository.file_history(
+            &pathspec.into(),
+            CommitHistory::Full,
+            self.get().first().clone(),
+        )
+    }
+
+    /// Get the commit history for everything under the directory at `path`,
+    /// i.e. the commits touching any file beneath it. Unlike
+    /// [`Browser::file_history`], a file at `path` itself does not count,
+    /// e.g. from before the path became a directory.
+    ///
+    /// With [`CommitHistory::Last`] only the latest such commit is returned.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, CommitHistory, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let src = unsound::path::new("~/src");
+    /// let commits: Vec<Oid> = browser
+    ///     .directory_history(&src, CommitHistory::Full)?
+    ///     .into_iter()
+    ///     .map(|commit| commit.id)
+    ///     .collect();
+    /// assert_eq!(commits, vec![
+    ///     Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?,
+    ///     Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?,
+    ///     Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
+    /// ]);
+    ///
+    /// let last = browser.directory_history(&src, CommitHistory::Last)?;
+    /// assert_eq!(last.len(), 1);
+    /// assert_eq!(last[0].id, commits[0]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn directory_history(
+        &self,
+        path: &file_system::Path,
+        mode: CommitHistory,
+    ) -> Result<Vec<Commit>, Error> {
+        // A trailing `/` only matches what is beneath the directory.
+        let pathspec = if path.is_root() {
+            Pathspec::default()
+        } else {
+            let directory = file_system::pattern::escape(&path.to_string());
+            Pathspec::default().include(&format!("{}/", directory))?
+        };
+        self.repository
+            .file_history(&pathspec, mode, self.get().first().clone())
+    }
+
+    /// Get the commits of the `Browser`'s current [`History`], newest first,
+    /// leaving out the ones that do not match the [`HistoryOptions`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::Pathspec;
+    /// use radicle_surf::vcs::git::{Branch, Browser, HistoryOptions, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let no_merges = browser.filtered_history(&HistoryOptions {
+    ///     skip_merges: true,
+    ///     ..HistoryOptions::default()
+    /// })?;
+    /// assert_eq!(no_merges.len(), 14);
+    /// assert!(no_merges.iter().all(|commit| !commit.is_merge()));
+    ///
+    /// let src: Vec<Oid> = browser
+    ///     .filtered_history(&HistoryOptions {
+    ///         pathspec: Pathspec::default().include("src")?,
+    ///         ..HistoryOptions::default()
+    ///     })?
+    ///     .into_iter()
+    ///     .map(|commit| commit.id)
+    ///     .collect();
+    /// assert_eq!(src, vec![
+    ///     Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?,
+    ///     Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?,
+    ///     Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
+    /// ]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn filtered_history(&self, options: &HistoryOptions) -> Result<Vec<Commit>, Error> {
+        self.collect_history(HistoryBuilder::from(options))
+    }
+
+    /// Walk the history from the head of the `Browser`'s [`History`], in the
+    /// same way as [`Browser::filtered_history`], keeping the commits whose
+    /// [`Author`] matches the [`AuthorMatcher`].
+    ///
+    /// Only the matching commits are loaded, so this is cheaper than
+    /// filtering the whole history when an author made few of the commits.
+    /// The authors are matched after the mailmap is applied, if enabled with
+    /// [`Browser::set_mailmap`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{AuthorMatcher, Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let by_email = browser.history_by_author(&AuthorMatcher::Email("rudolfs@osins.org".into()))?;
+    /// let by_name = browser.history_by_author(&AuthorMatcher::Name("Rūdolfs".into()))?;
+    /// assert!(!by_email.is_empty());
+    /// assert_eq!(by_email, by_name);
+    ///
+    /// let others = browser.history_by_author(&AuthorMatcher::predicate(|author| {
+    ///     author.email != "rudolfs@osins.org"
+    /// }))?;
+    /// assert_eq!(by_email.len() + others.len(), browser.get().len());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn history_by_author(&self, matcher: &AuthorMatcher) -> Result<Vec<Commit>, Error> {
+        self.collect_history(HistoryBuilder::new().author(matcher.clone()))
+    }
+
+    /// Collect the commits of the `builder`, walking from the head of the
+    /// `Browser`'s [`History`].
+    fn collect_history(&self, builder: HistoryBuilder) -> Result<Vec<Commit>, Error> {
+        let builder = builder.push(self.history.first().id);
+        self.repository.measure(Operation::History, || {
+            self.repository.history_iter(&builder)?.collect()
+        })
+    }
+
+    /// Get the commit history of the file at `path`, following it across
+    /// renames, in the spirit of `git log --follow`, along with the
+    /// [`RenameChain`] of the paths it was known by.
+    ///
+    /// Only the first parent of each commit is followed, so that the file has
+    /// one path at any point of the history. A file is taken to be renamed
+    /// when it is new at its path and git's rename detection finds its
+    /// previous path.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::PathNotFound`] if no commit has a file at `path`.
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Cancelled`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let (commits, renames) = browser.follow_file_history(&unsound::path::new("~/src/memory.rs"))?;
+    ///
+    /// let moved = Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
+    /// let added = Oid::from_str("f3a089488f4cfd1a240a9c01b3fcc4c34a4e97b2")?;
+    /// assert_eq!(commits.iter().map(|commit| commit.id).collect::<Vec<_>>(), vec![moved, added]);
+    ///
+    /// assert_eq!(renames.current().path, unsound::path::new("~/src/memory.rs"));
+    /// assert_eq!(renames.current().since, moved);
+    ///
+    /// // Previously known as examples/memory.rs, until it was moved.
+    /// let previous = &renames.previous()[0];
+    /// assert_eq!(previous.path, unsound::path::new("~/examples/memory.rs"));
+    /// assert_eq!(previous.since, added);
+    /// assert_eq!(previous.until, Some(moved));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn follow_file_history(
+        &self,
+        path: &file_system::Path,
+    ) -> Result<(Vec<Commit>, RenameChain), Error> {
+        self.repository
+            .follow_file_history(path, self.history.first().id)
+    }
+
+    /// Get the commit history for a file _or_ directory, in the same way as
+    /// [`Browser::file_history`], except that the commits are diffed in
+    /// parallel across a [`rayon`] thread pool.
+    ///
+    /// This pays off for long histories, where diffing each commit dominates
+    /// the cost. The commits are returned in the same order as
+    /// [`Browser::file_history`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::unsound;
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let path = unsound::path::new("~/src/Eval.hs");
+    /// assert_eq!(
+    ///     browser.par_file_history(path.clone())?,
+    ///     browser.file_history(path)?,
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_file_history(&self, pathspec: impl Into<Pathspec>) -> Result<Vec<Commit>, Error> {
+        let pathspec = pathspec.into();
+        self.repository.measure(Operation::FileHistory, || {
+            self.repository
+                .par_file_history(&pathspec, self.get().first().clone())
+        })
+    }
+
+    /// Extract the signature for a commit
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The commit to extract the signature for
+    /// * `field` - the name of the header field containing the signature block;
+    ///   pass `None` to extract the default 'gpgsig'
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository, Oid, error};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let commit_with_signature_oid = Oid::from_str(
+    ///     "e24124b7538658220b5aaf3b6ef53758f0a106dc"
+    /// )?;
+    ///
+    /// browser.commit(commit_with_signature_oid)?;
+    /// let history = browser.get();
+    /// let commit_with_signature = history.first();
+    /// let signature = browser.extract_signature(commit_with_signature, None)?;
+    ///
+    /// // We have a signature
+    /// assert!(signature.is_some());
+    ///
+    /// let commit_without_signature_oid = Oid::from_str(
+    ///     "80bacafba303bf0cdf6142921f430ff265f25095"
+    /// )?;
+    ///
+    /// browser.commit(commit_without_signature_oid)?;
+    /// let history = browser.get();
+    /// let commit_without_signature = history.first();
+    /// let signature = browser.extract_signature(commit_without_signature, None)?;
+    ///
+    /// // There is no signature
+    /// assert!(signature.is_none());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract_signature(
+        &self,
+        commit: &Commit,
+        field: Option<&str>,
+    ) -> Result<Option<Signature>, Error> {
+        self.repository.extract_signature(&commit.id, field)
+    }
+
+    /// Extract the signature for a commit, along with the data that was
+    /// signed, i.e. the commit object without its signature. Together they
+    /// can be handed to a tool such as `gpg --verify`.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The commit to extract the signature for
+    /// * `field` - the name of the header field containing the signature block;
+    ///   pass `None` to extract the default 'gpgsig'
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository, Oid};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// browser.commit(Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?)?;
+    /// let history = browser.get();
+    /// let (signature, data) = browser
+    ///     .extract_signed_data(history.first(), None)?
+    ///     .expect("commit is signed");
+    ///
+    /// assert!(signature.as_ref().starts_with(b"-----BEGIN PGP SIGNATURE-----"));
+    /// assert!(data.starts_with(b"tree "));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract_signed_data(
+        &self,
+        commit: &Commit,
+        field: Option<&str>,
+    ) -> Result<Option<(Signature, Vec<u8>)>, Error> {
+        self.repository.extract_signed_data(&commit.id, field)
+    }
+
+    /// Get the value of the header `field` of the `commit`, or `None` if the
+    /// commit does not have it. See [`RepositoryRef::commit_header_field`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::Utf8Error`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let history = browser.get();
+    /// let commit = history.first();
+    ///
+    /// let tree = browser.commit_header_field(commit, "tree")?;
+    /// assert_eq!(tree.as_deref(), Some("bca66427a09c02a66aa1b648938c6740bddfed55"));
+    ///
+    /// let gpgsig = browser.commit_header_field(commit, "gpgsig")?.unwrap();
+    /// assert!(gpgsig.starts_with("-----BEGIN PGP SIGNATURE-----\n"));
+    ///
+    /// assert_eq!(browser.commit_header_field(commit, "rad-patch")?, None);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commit_header_field(
+        &self,
+        commit: &Commit,
+        field: &str,
+    ) -> Result<Option<String>, Error> {
+        self.repository.commit_header_field(commit.id, field)
+    }
+
+    /// Set the header fields that [`Browser::signatures`] looks for
+    /// signatures in. Only `gpgsig` is looked for by default.
+    pub fn set_signature_fields<I>(&mut self, fields: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.repository.signature_fields = fields.into_iter().map(Into::into).collect();
+    }
+
+    /// Get the signatures of the commit `oid` from each of the header fields
+    /// set with [`Browser::set_signature_fields`], along with the name of the
+    /// field they were found in.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    /// browser.set_signature_fields(vec!["gpgsig", "x-radicle-signature"]);
+    ///
+    /// let signed = Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
+    /// let signatures = browser.signatures(signed)?;
+    /// assert_eq!(signatures.len(), 1);
+    /// assert_eq!(signatures[0].0, "gpgsig");
+    ///
+    /// let unsigned = Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?;
+    /// assert!(browser.signatures(unsigned)?.is_empty());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn signatures(&self, oid: Oid) -> Result<Vec<(String, Signature)>, Error> {
+        self.repository.signatures(oid)
+    }
+
+    /// List the [`Branch`]es, which contain the provided [`Commit`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Browser, Repository, Branch, BranchName, Namespace, Oid};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    ///
+    /// let branches = browser.revision_branches(Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?)?;
+    /// assert_eq!(
+    ///     branches,
+    ///     vec![
+    ///         Branch::local("dev"),
+    ///         Branch::remote("dev", "origin"),
+    ///     ]
+    /// );
+    ///
+    /// // TODO(finto): I worry that this test will fail as other branches get added
+    /// let mut branches = browser.revision_branches(Oid::from_str("1820cb07c1a890016ca5578aa652fd4d4c38967e")?)?;
+    /// branches.sort();
+    /// assert_eq!(
+    ///     branches,
+    ///     vec![
+    ///         Branch::remote("HEAD", "origin"),
+    ///         Branch::local("dev"),
+    ///         Branch::remote("dev", "origin"),
+    ///         Branch::local("master"),
+    ///         Branch::remote("master", "origin"),
+    ///         Branch::remote("orange/pineapple", "banana"),
+    ///         Branch::remote("pineapple", "banana"),
+    ///     ]
+    /// );
+    ///
+    /// let golden_browser = browser.switch_namespace(&Namespace::try_from("golden")?,
+    /// Branch::local("master"))?;
+    ///
+    /// let branches = golden_browser.revision_branches(Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?)?;
+    /// assert_eq!(
+    ///     branches,
+    ///     vec![
+    ///         Branch::local("banana"),
+    ///         Branch::remote("fakie/bigspin", "kickflip"),
+    ///         Branch::remote("heelflip", "kickflip"),
+    ///     ]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn revision_branches(&self, rev: impl Into<Rev>) -> Result<Vec<Branch>, Error> {
+        let commit = self.repository.rev_to_commit(&rev.into())?;
+        self.repository.revision_branches(&commit.id())
+    }
+
+    /// Describe the commit identified by `oid` in terms of the nearest tag
+    /// reachable from it, in the same way as `git describe --tags`.
+    ///
+    /// If there is no tag reachable from the commit then `None` is returned.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository, TagName};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let describe = browser
+    ///     .describe(Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?)?
+    ///     .expect("missing description");
+    ///
+    /// assert_eq!(describe.tag, TagName::new("v0.6.0"));
+    /// assert_eq!(describe.distance, 5);
+    /// assert_eq!(describe.to_string(), "v0.6.0-5-ga0dd912");
+    ///
+    /// // A tagged commit is described by its tag alone
+    /// let describe = browser
+    ///     .describe(Oid::from_str("80ded66281a4de2889cc07293a8f10947c6d57fe")?)?
+    ///     .expect("missing description");
+    ///
+    /// assert_eq!(describe.distance, 0);
+    /// assert_eq!(describe.to_string(), "v0.5.0");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn describe(&self, oid: Oid) -> Result<Option<Describe>, Error> {
+        self.repository.describe(oid)
+    }
+
+    /// Check whether the commit identified by `oid` is part of the `Browser`'s
+    /// current [`History`], i.e. whether it is the head of the history or one
+    /// of its ancestors.
+    ///
+    /// This is answered by the commit graph, so the `History` does not need
+    /// to be searched.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// assert!(browser.contains(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)?);
+    ///
+    /// // The tip of `dev` is not on `master`
+    /// assert!(!browser.contains(Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?)?);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains(&self, oid: Oid) -> Result<bool, Error> {
+        let head = self.history.first().id;
+        self.repository.is_ancestor(oid, head)
+    }
+
+    /// Get the parents of the commit identified by `oid`, in the order they
+    /// were recorded. The commit does not need to be part of the `Browser`'s
+    /// current [`History`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // The merge of pull request #4
+    /// let merge = Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?;
+    /// let parents = browser
+    ///     .parents(merge)?
+    ///     .into_iter()
+    ///     .map(|commit| commit.id)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(parents, vec![
+    ///     Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?,
+    ///     Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?,
+    /// ]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parents(&self, oid: Oid) -> Result<Vec<Commit>, Error> {
+        self.repository
+            .find_commit(oid)?
+            .parents
+            .into_iter()
+            .map(|parent| self.repository.find_commit(parent))
+            .collect()
+    }
+
+    /// Get the commits of the `Browser`'s current [`History`] that have the
+    /// commit identified by `oid` as one of their parents, newest first.
+    ///
+    /// Children are only known through the history being browsed, so commits
+    /// on other branches are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // Both sides of the merge of pull request #4 start from this commit
+    /// let fork = Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?;
+    /// let children = browser
+    ///     .children(fork)
+    ///     .into_iter()
+    ///     .map(|commit| commit.id)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(children, vec![
+    ///     Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?,
+    ///     Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?,
+    /// ]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn children(&self, oid: Oid) -> Vec<Commit> {
+        self.history
+            .iter()
+            .filter(|commit| commit.parent_ids().contains(&oid))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the [`MergeInfo`] of the commit identified by `oid`, or `None` if
+    /// it is not a merge commit.
+    ///
+    /// The merged-in parent is the second parent of the commit. The name of
+    /// the merged branch is taken from the commit message if it follows the
+    /// format of `git merge` or of a merged pull request, falling back to a
+    /// branch that points at the merged-in parent.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let merge = Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?;
+    /// let info = browser.merge_info(merge)?.expect("a merge commit");
+    /// assert_eq!(info.merged.id, Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?);
+    /// assert_eq!(info.branch.as_deref(), Some("FintanH/fintan/update-readme-no-sig"));
+    ///
+    /// let head = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    /// assert_eq!(browser.merge_info(head)?, None);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge_info(&self, oid: Oid) -> Result<Option<MergeInfo>, Error> {
+        let commit = self.repository.find_commit(oid)?;
+        if !commit.is_merge() {
+            return Ok(None);
+        }
+
+        let merged = self.repository.find_commit(commit.parents[1])?;
+        let branch = match merge::merged_branch(&commit.message) {
+            Some(branch) => Some(branch),
+            None => self
+                .repository
+                .branches_at(&merged.id)?
+                .into_iter()
+                .next()
+                .map(|branch| branch.name()),
+        };
+        Ok(Some(MergeInfo { merged, branch }))
+    }
+
+    /// Get the [`Stats`] of the underlying [`Repository`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let stats = browser.get_stats()?;
+    ///
+    /// assert_eq!(stats.branches, 2);
+    ///
+    /// assert_eq!(stats.commits, 15);
+    ///
+    /// assert_eq!(stats.contributors, 4);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_stats(&self) -> Result<Stats, Error> {
+        let branches = self.list_branches(RefScope::Local)?.len();
+        let commits = self.history.len();
+
+        let contributors = self
+            .history
+            .iter()
+            .cloned()
+            .map(|commit| (commit.author.name, commit.author.email))
+            .collect::<BTreeSet<_>>();
+
+        Ok(Stats {
+            branches,
+            commits,
+            contributors: contributors.len(),
+        })
+    }
+
+    /// Do a pre-order TreeWalk of the given commit, turning its Tree into a
+    /// Directory.
+    fn get_tree(
+        repository: &RepositoryRef,
+        commit: &Commit,
+    ) -> Result<directory::Directory, Error> {
+        let commit = repository.repo_ref.find_commit(commit.id)?;
+        let tree = commit.as_object().peel_to_tree()?;
+        Self::walk_tree(repository, &tree)
+    }
+
+    /// Do a pre-order TreeWalk of the given tree, where the paths are relative
+    /// to the tree. Each file is inserted into the Directory as it is found.
+    ///
+    /// The walk is aborted with [`Error::Cancelled`] if the deadline of the
+    /// `repository` passes, and with [`Error::LimitExceeded`] if the files go
+    /// over its [`Limits`]. Names that are not valid UTF-8 are handled
+    /// according to its [`file_system::NonUtf8Policy`].
+    fn walk_tree(
+        repository: &RepositoryRef,
+        tree: &git2::Tree,
+    ) -> Result<directory::Directory, Error> {
+        let mut walk = TreeWalk {
+            repository,
+            builder: directory::DirectoryBuilder::new(),
+            total_files: 0,
+            total_bytes: 0,
+        };
+        walk.subtree(tree, None)?;
+        // No directories are inserted, so none of them can be empty.
+        Ok(walk
+            .builder
+            .finish()
+            .expect("directories are only created for files"))
+    }
+
+    /// Find the best common ancestor between two commits if it exists.
+    ///
+    /// See [`git2::Repository::merge_base`] for details.
+    pub fn merge_base(&self, one: Oid, two: Oid) -> Result<Option<Oid>, Error> {
+        match self.repository.repo_ref.merge_base(one, two) {
+            Ok(merge_base) => Ok(Some(merge_base)),
+            Err(err) => {
+                if err.code() == git2::ErrorCode::NotFound {
+                    Ok(None)
+                } else {
+                    Err(Error::Git(err))
+                }
+            },
+        }
+    }
+}
+
+/// The state of [`Browser::walk_tree`].
+struct TreeWalk<'a, 'repo> {
+    repository: &'a RepositoryRef<'repo>,
+    builder: directory::DirectoryBuilder,
+    total_files: usize,
+    total_bytes: usize,
+}
+
+impl<'a, 'repo> TreeWalk<'a, 'repo> {
+    /// Walk the entries of `tree`, whose path is `path`, or `None` for the
+    /// root, descending into the sub-trees as they are found.
+    fn subtree(
+        &mut self,
+        tree: &git2::Tree,
+        path: Option<&file_system::Path>,
+    ) -> Result<(), Error> {
+        let repo = self.repository.repo_ref;
+        for entry in tree.iter() {
+            self.repository.check_deadline()?;
+            let name = match file_system::Label::from_bytes(
+                entry.name_bytes(),
+                self.repository.non_utf8_policy,
+            )? {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let entry_path = match path {
+                None => file_system::Path::new(name),
+                Some(path) => {
+                    let mut entry_path = path.clone();
+                    entry_path.push(name);
+                    entry_path
+                },
+            };
+
+            match entry.kind() {
+                Some(git2::ObjectType::Tree) => {
+                    self.subtree(&repo.find_tree(entry.id())?, Some(&entry_path))?;
+                },
+                Some(git2::ObjectType::Blob) => {
+                    let blob = repo.find_blob(entry.id())?;
+
+                    // Check the size before the contents are copied out of the blob.
+                    let mut file_path = file_system::Path::root();
+                    file_path.append(entry_path.clone());
+                    let limits = &self.repository.limits;
+                    limits.check_blob(&file_path, blob.size())?;
+
+                    self.total_files += 1;
+                    self.total_bytes += blob.size();
+                    limits.check_snapshot(self.total_files, self.total_bytes)?;
+
+                    let file = directory::File {
+                        contents: blob.content().to_owned(),
+                        size: blob.size(),
+                    };
+                    self.builder.insert(entry_path, file);
+                },
+                // Commits are likely submodules, which we skip, along with
+                // anything else that is not a file or directory.
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "gh-actions"))]
+    #[test]
+    // An issue with submodules, see: https://github.com/radicle-dev/radicle-surf/issues/54
+    fn test_submodule_failure() {
+        let repo = Repository::new("..").unwrap();
+        let browser = Browser::new(&repo, Branch::local("master")).unwrap();
+
+        browser.get_directory().unwrap();
+    }
+
+    #[cfg(test)]
+    mod namespace {
+        use super::*;
+        use pretty_assertions::{assert_eq, assert_ne};
+
+        #[test]
+        fn switch_to_banana() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
             let mut browser = Browser::new_with_namespace(
                 &repo,
                 &Namespace::try_from("golden")?,
                 Branch::local("master"),
             )?;
-            let history = browser.history.clone();
+            let history = browser.history.clone();
+
+            browser.branch(Branch::local("banana"))?;
+
+            assert_ne!(history, browser.history);
+
+            Ok(())
+        }
+
+        #[test]
+        fn me_namespace() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let history = browser.history.clone();
+
+            assert_eq!(browser.which_namespace(), Ok(None));
+
+            let browser = browser
+                .switch_namespace(&Namespace::try_from("me")?, Branch::local("feature/#1194"))?;
+
+            assert_eq!(
+                browser.which_namespace(),
+                Ok(Some(Namespace::try_from("me")?))
+            );
+            assert_eq!(history, browser.history);
+
+            let expected_branches: Vec<Branch> = vec![Branch::local("feature/#1194")];
+            let mut branches = browser.list_branches(RefScope::Local)?;
+            branches.sort();
+
+            assert_eq!(expected_branches, branches);
+
+            let expected_branches: Vec<Branch> = vec![Branch::remote("feature/#1194", "fein")];
+            let mut branches = browser.list_branches(RefScope::Remote {
+                name: Some("fein".to_string()),
+            })?;
+            branches.sort();
+
+            assert_eq!(expected_branches, branches);
+
+            Ok(())
+        }
+
+        #[test]
+        fn golden_namespace() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let history = browser.history.clone();
+
+            assert_eq!(browser.which_namespace(), Ok(None));
+
+            let golden_browser = browser
+                .switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+
+            assert_eq!(
+                golden_browser.which_namespace(),
+                Ok(Some(Namespace::try_from("golden")?))
+            );
+            assert_eq!(history, golden_browser.history);
+
+            let expected_branches: Vec<Branch> =
+                vec![Branch::local("banana"), Branch::local("master")];
+            let mut branches = golden_browser.list_branches(RefScope::Local)?;
+            branches.sort();
+
+            assert_eq!(expected_branches, branches);
+
+            let expected_branches: Vec<Branch> = vec![
+                Branch::remote("fakie/bigspin", "kickflip"),
+                Branch::remote("heelflip", "kickflip"),
+                Branch::remote("v0.1.0", "kickflip"),
+            ];
+            let mut branches = golden_browser.list_branches(RefScope::Remote {
+                name: Some("kickflip".to_string()),
+            })?;
+            branches.sort();
+
+            assert_eq!(expected_branches, branches);
+
+            Ok(())
+        }
+
+        #[test]
+        fn silver_namespace() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let history = browser.history.clone();
+
+            assert_eq!(browser.which_namespace(), Ok(None));
+
+            let silver_browser = browser.switch_namespace(
+                &Namespace::try_from("golden/silver")?,
+                Branch::local("master"),
+            )?;
+
+            assert_eq!(
+                silver_browser.which_namespace(),
+                Ok(Some(Namespace::try_from("golden/silver")?))
+            );
+            assert_ne!(history, silver_browser.history);
+
+            let expected_branches: Vec<Branch> = vec![Branch::local("master")];
+            let mut branches = silver_browser.list_branches(RefScope::All)?;
+            branches.sort();
+
+            assert_eq!(expected_branches, branches);
+
+            Ok(())
+        }
+
+        #[test]
+        fn hierarchy_fills_in_intermediates() -> Result<(), Error> {
+            let fixture = fixture::Fixture::new()?;
+            let oid = fixture
+                .commit("master", "initial")
+                .file("README", "hi")
+                .write()?;
+            for name in &[
+                "refs/namespaces/alice/refs/heads/master",
+                "refs/namespaces/bob/refs/namespaces/peers/refs/namespaces/carol/refs/heads/master",
+                "refs/namespaces/bob/refs/namespaces/peers/refs/namespaces/dave/refs/heads/master",
+            ] {
+                fixture
+                    .repository()
+                    .0
+                    .reference(name, oid, true, "fixture")?;
+            }
+
+            let hierarchy = fixture.repository().as_ref().namespace_hierarchy()?;
+            let namespaces = hierarchy
+                .iter()
+                .flat_map(|tree| tree.iter().map(|namespace| namespace.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                namespaces,
+                vec![
+                    "alice",
+                    "bob",
+                    "bob/peers",
+                    "bob/peers/carol",
+                    "bob/peers/dave"
+                ]
+            );
+
+            let bob = Namespace::try_from("bob")?;
+            let peers = Namespace::try_from("bob/peers")?;
+            assert_eq!(bob.children(fixture.repository())?, vec![peers.clone()]);
+            assert_eq!(peers.parent(), Some(bob.clone()));
+            assert_eq!(bob.parent(), None);
+            assert_eq!(
+                peers.children(fixture.repository())?,
+                vec![
+                    Namespace::try_from("bob/peers/carol")?,
+                    Namespace::try_from("bob/peers/dave")?,
+                ]
+            );
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod rev {
+        use super::{Branch, BranchName, Browser, Error, Oid, Repository, Rev, TagName};
+
+        #[test]
+        fn at_time_prefers_the_first_of_equal_times() -> Result<(), Error> {
+            use crate::vcs::git::{fixture::Fixture, Time};
+
+            let fixture = Fixture::new()?;
+            let root = fixture.commit("master", "root").file("a", "a").write()?;
+
+            // Two commits in a row with the same time, e.g. from a rebase.
+            let repo = &fixture.repository().0;
+            let time = Time::new(1_700_000_000, 0);
+            let signature = git2::Signature::new("Fixture", "fixture@radicle.xyz", &time)?;
+            let root = repo.find_commit(root)?;
+            let tree = root.tree()?;
+            let first = repo.commit(None, &signature, &signature, "first", &tree, &[&root])?;
+            let first = repo.find_commit(first)?;
+            let second = repo.commit(None, &signature, &signature, "second", &tree, &[&first])?;
+            fixture.branch("master", second)?;
+
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            browser.at_time(time)?;
+            assert_eq!(browser.head_id(), second);
+
+            Ok(())
+        }
+
+        #[test]
+        fn resolve_reports_ambiguous_names() -> Result<(), Error> {
+            use crate::vcs::git::fixture::Fixture;
+
+            let fixture = Fixture::new()?;
+            let oid = fixture.commit("master", "root").file("a", "a").write()?;
+            fixture.branch("release", oid)?;
+            fixture
+                .repository()
+                .0
+                .reference("refs/tags/release", oid, false, "tag")?;
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+            assert_eq!(
+                browser.resolve("release"),
+                Err(Error::AmbiguousName {
+                    name: "release".to_string(),
+                    candidates: vec![
+                        Rev::from(TagName::new("release")),
+                        Rev::from(Branch::local("release")),
+                    ],
+                })
+            );
+            assert_eq!(
+                browser.resolve("refs/heads/release")?,
+                Rev::from(Branch::local("release"))
+            );
+            assert!(browser.resolve("missing").unwrap_err().is_not_found());
+
+            Ok(())
+        }
+
+        #[test]
+        fn resolve_reports_ambiguous_commit_prefixes() -> Result<(), Error> {
+            use crate::vcs::git::fixture::Fixture;
+            use std::collections::HashMap;
+
+            let fixture = Fixture::new()?;
+            let root = fixture.commit("master", "root").file("a", "a").write()?;
+            let repo = &fixture.repository().0;
+            let parent = repo.find_commit(root)?;
+            let tree = parent.tree()?;
+            let signature =
+                git2::Signature::new("Test", "test@example.com", &git2::Time::new(0, 0))?;
+
+            // Write commits until two of them share a four digit prefix.
+            let mut prefixes = HashMap::new();
+            let (prefix, expected) = (0..)
+                .find_map(|i| {
+                    let message = format!("commit {}", i);
+                    let oid = repo
+                        .commit(None, &signature, &signature, &message, &tree, &[&parent])
+                        .unwrap();
+                    let prefix = oid.to_string()[..4].to_string();
+                    prefixes
+                        .insert(prefix.clone(), oid)
+                        .map(|other| (prefix, vec![other, oid]))
+                })
+                .unwrap();
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            match browser.resolve(&prefix) {
+                Err(Error::AmbiguousName { name, candidates }) => {
+                    assert_eq!(name, prefix);
+                    // Other commits of the fixture may share the prefix too.
+                    for oid in expected {
+                        assert!(candidates.contains(&Rev::Oid(oid)));
+                    }
+                },
+                other => panic!("expected an ambiguous name, got {:?}", other),
+            }
+
+            Ok(())
+        }
+
+        // **FIXME**: This seems to break occasionally on
+        // buildkite. For some reason the commit
+        // 3873745c8f6ffb45c990eb23b491d4b4b6182f95, which is on master
+        // (currently HEAD), is not found. It seems to load the history
+        // with d6880352fc7fda8f521ae9b7357668b17bb5bad5 as the HEAD.
+        //
+        // To temporarily fix this, we need to select "New Build" from the build kite
+        // build page that's failing.
+        // * Under "Message" put whatever you want.
+        // * Under "Branch" put in the branch you're working on.
+        // * Expand "Options" and select "clean checkout".
+        #[test]
+        fn _master() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+            browser.rev(Branch::remote("master", "origin"))?;
+
+            let commit1 = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
+            assert!(
+                browser
+                    .history
+                    .find(|commit| if commit.id == commit1 {
+                        Some(commit.clone())
+                    } else {
+                        None
+                    })
+                    .is_some(),
+                "commit_id={}, history =\n{:#?}",
+                commit1,
+                browser.history
+            );
+
+            let commit2 = Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?;
+            assert!(
+                browser
+                    .history
+                    .find(|commit| if commit.id == commit2 {
+                        Some(commit.clone())
+                    } else {
+                        None
+                    })
+                    .is_some(),
+                "commit_id={}, history =\n{:#?}",
+                commit2,
+                browser.history
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn commit() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+            browser.rev(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)?;
+
+            let commit1 = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
+            assert!(browser
+                .history
+                .find(|commit| if commit.id == commit1 {
+                    Some(commit.clone())
+                } else {
+                    None
+                })
+                .is_some());
+
+            Ok(())
+        }
+
+        #[test]
+        fn commit_parents() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+            browser.rev(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)?;
+            let commit = browser.history.first();
+
+            assert_eq!(
+                commit.parents,
+                vec![Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn commit_short() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+            browser.rev(browser.oid("3873745c8")?)?;
+
+            let commit1 = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
+            assert!(browser
+                .history
+                .find(|commit| if commit.id == commit1 {
+                    Some(commit.clone())
+                } else {
+                    None
+                })
+                .is_some());
+
+            Ok(())
+        }
+
+        #[test]
+        fn tag() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+            browser.rev(TagName::new("v0.2.0"))?;
+
+            let commit1 = Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?;
+            assert_eq!(browser.history.first().id, commit1);
+
+            Ok(())
+        }
+
+        #[test]
+        fn spec() -> Result<(), Box<dyn std::error::Error>> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+
+            browser.commit("3873745c8".parse::<Rev>()?)?;
+            let commit1 = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
+            assert_eq!(browser.history.first().id, commit1);
+
+            browser.branch("dev".parse::<Rev>()?)?;
+            let commit2 = Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?;
+            assert_eq!(browser.history.first().id, commit2);
+
+            browser.tag("v0.2.0".parse::<Rev>()?)?;
+            let commit3 = Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?;
+            assert_eq!(browser.history.first().id, commit3);
+
+            Ok(())
+        }
+
+        #[test]
+        fn spec_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+
+            assert_eq!(
+                browser.branch("v0.2.0".parse::<Rev>()?),
+                Err(Error::NotBranch(BranchName::new("v0.2.0")))
+            );
+            assert_eq!(
+                browser.tag(Branch::local("dev")),
+                Err(Error::NotTag(TagName::new("dev")))
+            );
+            assert_eq!(
+                browser.rev("not-a-rev".parse::<Rev>()?),
+                Err(Error::RevParseFailure {
+                    rev: "not-a-rev".to_string()
+                })
+            );
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod last_commit {
+        use crate::{
+            file_system::{unsound, Path},
+            vcs::git::{Branch, Browser, Oid, Repository},
+        };
+
+        #[test]
+        fn readme_missing_and_memory() {
+            let repo = Repository::new("./data/git-platinum")
+                .expect("Could not retrieve ./data/git-platinum as git repository");
+            let mut browser =
+                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+
+            // Set the browser history to the initial commit
+            let commit = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")
+                .expect("Failed to parse SHA");
+            browser.commit(commit).unwrap();
+
+            let head_commit = browser.get().first().clone();
+
+            // memory.rs is commited later so it should not exist here.
+            let memory_last_commit = browser
+                .last_commit(Path::with_root(&[
+                    unsound::label::new("src"),
+                    unsound::label::new("memory.rs"),
+                ]))
+                .expect("Failed to get last commit")
+                .map(|commit| commit.id);
+
+            assert_eq!(memory_last_commit, None);
+
+            // README.md exists in this commit.
+            let readme_last_commit = browser
+                .last_commit(Path::with_root(&[unsound::label::new("README.md")]))
+                .expect("Failed to get last commit")
+                .map(|commit| commit.id);
+
+            assert_eq!(readme_last_commit, Some(head_commit.id));
+        }
+
+        #[test]
+        fn folder_svelte() {
+            let repo = Repository::new("./data/git-platinum")
+                .expect("Could not retrieve ./data/git-platinum as git repository");
+            let mut browser =
+                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+
+            // Check that last commit is the actual last commit even if head commit differs.
+            let commit = Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")
+                .expect("Could not parse SHA");
+            browser.commit(commit).unwrap();
+
+            let expected_commit_id =
+                Oid::from_str("f3a089488f4cfd1a240a9c01b3fcc4c34a4e97b2").unwrap();
+
+            let folder_svelte = browser
+                .last_commit(unsound::path::new("~/examples/Folder.svelte"))
+                .expect("Failed to get last commit")
+                .map(|commit| commit.id);
+
+            assert_eq!(folder_svelte, Some(expected_commit_id));
+        }
+
+        #[test]
+        fn nest_directory() {
+            let repo = Repository::new("./data/git-platinum")
+                .expect("Could not retrieve ./data/git-platinum as git repository");
+            let mut browser =
+                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+
+            // Check that last commit is the actual last commit even if head commit differs.
+            let commit = Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")
+                .expect("Failed to parse SHA");
+            browser.commit(commit).unwrap();
+
+            let expected_commit_id =
+                Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977").unwrap();
+
+            let nested_directory_tree_commit_id = browser
+                .last_commit(unsound::path::new(
+                    "~/this/is/a/really/deeply/nested/directory/tree",
+                ))
+                .expect("Failed to get last commit")
+                .map(|commit| commit.id);
+
+            assert_eq!(nested_directory_tree_commit_id, Some(expected_commit_id));
+        }
+
+        #[test]
+        #[cfg(not(windows))]
+        fn can_get_last_commit_for_special_filenames() {
+            let repo = Repository::new("./data/git-platinum")
+                .expect("Could not retrieve ./data/git-platinum as git repository");
+            let mut browser =
+                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+
+            // Check that last commit is the actual last commit even if head commit differs.
+            let commit = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")
+                .expect("Failed to parse SHA");
+            browser.commit(commit).unwrap();
+
+            let expected_commit_id =
+                Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02").unwrap();
+
+            let backslash_commit_id = browser
+                .last_commit(unsound::path::new("~/special/faux\\path"))
+                .expect("Failed to get last commit")
+                .map(|commit| commit.id);
+            assert_eq!(backslash_commit_id, Some(expected_commit_id));
+
+            let ogre_commit_id = browser
+                .last_commit(unsound::path::new("~/special/👹👹👹"))
+                .expect("Failed to get last commit")
+                .map(|commit| commit.id);
+            assert_eq!(ogre_commit_id, Some(expected_commit_id));
+        }
+
+        #[test]
+        fn root() {
+            let repo = Repository::new("./data/git-platinum")
+                .expect("Could not retrieve ./data/git-platinum as git repository");
+            let browser =
+                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+
+            let root_last_commit_id = browser
+                .last_commit(Path::root())
+                .expect("Failed to get last commit")
+                .map(|commit| commit.id);
+
+            assert_eq!(root_last_commit_id, Some(browser.get().first().id));
+        }
+
+        #[test]
+        fn batched_matches_last_commit() {
+            let repo = Repository::new("./data/git-platinum")
+                .expect("Could not retrieve ./data/git-platinum as git repository");
+            let browser =
+                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+
+            let paths = vec![
+                Path::root(),
+                unsound::path::new("~/README.md"),
+                unsound::path::new("~/src"),
+                unsound::path::new("~/src/memory.rs"),
+                unsound::path::new("~/this/is/a/really/deeply/nested/directory/tree"),
+                unsound::path::new("~/special/faux\\path"),
+                unsound::path::new("~/missing"),
+            ];
+
+            let batched = browser
+                .last_commits(&paths)
+                .expect("Failed to get last commits")
+                .into_iter()
+                .map(|commit| commit.map(|commit| commit.id))
+                .collect::<Vec<_>>();
+            let single = paths
+                .into_iter()
+                .map(|path| {
+                    browser
+                        .last_commit(path)
+                        .expect("Failed to get last commit")
+                        .map(|commit| commit.id)
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(batched, single);
+        }
+
+        #[test]
+        fn annotated_directory_matches_last_commit() {
+            let repo = Repository::new("./data/git-platinum")
+                .expect("Could not retrieve ./data/git-platinum as git repository");
+            let browser =
+                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+
+            let annotated = browser
+                .get_directory_with_last_commits()
+                .expect("Failed to get annotated directory");
+            for path in &[
+                "~/src",
+                "~/src/memory.rs",
+                "~/this/is/a/really/deeply/nested/directory/tree",
+                "~/text/emoji.txt",
+            ] {
+                let path = unsound::path::new(path);
+                assert_eq!(
+                    annotated.last_commit(&path).map(|commit| commit.id),
+                    browser
+                        .last_commit(path)
+                        .expect("Failed to get last commit")
+                        .map(|commit| commit.id)
+                );
+            }
+            assert!(annotated
+                .last_commit(&unsound::path::new("~/missing"))
+                .is_none());
+        }
+    }
+
+    #[cfg(test)]
+    mod diff {
+        use crate::{diff::*, vcs::git::*};
+
+        #[test]
+        fn paths_touched() -> Result<(), Error> {
+            use crate::file_system::unsound;
+
+            let fixture = fixture::Fixture::new()?;
+            let initial = fixture
+                .commit("master", "initial")
+                .file("README", "hi")
+                .file("src/lib.rs", "")
+                .write()?;
+            let second = fixture
+                .commit("master", "second")
+                .remove("README")
+                .file("src/lib.rs", "pub mod git;")
+                .write()?;
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+            // The root commit is compared to an empty tree.
+            assert_eq!(
+                browser.paths_touched(initial)?,
+                vec![
+                    (unsound::path::new("~/README"), FileStatus::Created),
+                    (unsound::path::new("~/src/lib.rs"), FileStatus::Created),
+                ]
+            );
+            assert_eq!(
+                browser.paths_touched(second)?,
+                vec![
+                    (unsound::path::new("~/README"), FileStatus::Deleted),
+                    (unsound::path::new("~/src/lib.rs"), FileStatus::Modified),
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_initial_diff() -> Result<(), Error> {
+            use file_system::*;
+            use pretty_assertions::assert_eq;
+
+            let oid = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?;
+            let repo = Repository::new("./data/git-platinum")?;
+            let commit = repo.0.find_commit(oid).unwrap();
+
+            assert!(commit.parents().count() == 0);
+            assert!(commit.parent(0).is_err());
+
+            let bro = Browser::new(&repo, Branch::local("master"))?;
+            let diff = bro.initial_diff(oid)?;
+
+            let expected_diff = Diff {
+                created: vec![CreateFile {
+                    path: Path::with_root(&[unsound::label::new("README.md")]),
+                    mode: git2::FileMode::Blob,
+                    diff: FileDiff::Plain {
+                        hunks: vec![Hunk {
+                            header: Line(b"@@ -0,0 +1 @@\n".to_vec()),
+                            lines: vec![
+                                LineDiff::addition(b"This repository is a data source for the Upstream front-end tests.\n".to_vec(), 1),
+                            ]
+                        }].into()
+                    },
+                }],
+                deleted: vec![],
+                moved: vec![],
+                copied: vec![],
+                modified: vec![],
+            };
+            assert_eq!(expected_diff, diff);
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_diff() -> Result<(), Error> {
+            use file_system::*;
+            use pretty_assertions::assert_eq;
+
+            let repo = Repository::new("./data/git-platinum")?;
+            let commit = repo
+                .0
+                .find_commit(Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?)
+                .unwrap();
+            let parent = commit.parent(0)?;
+
+            let bro = Browser::new(&repo, Branch::local("master"))?;
+
+            let diff = bro.diff(parent.id(), commit.id())?;
+
+            let expected_diff = Diff {
+                created: vec![],
+                deleted: vec![],
+                moved: vec![],
+                copied: vec![],
+                modified: vec![ModifiedFile {
+                    path: Path::with_root(&[unsound::label::new("README.md")]),
+                    diff: FileDiff::Plain {
+                        hunks: vec![Hunk {
+                            header: Line(b"@@ -1 +1,2 @@\n".to_vec()),
+                            lines: vec![
+                                LineDiff::deletion(b"This repository is a data source for the Upstream front-end tests.\n".to_vec(), 1),
+                                LineDiff::addition(b"This repository is a data source for the Upstream front-end tests and the\n".to_vec(), 1),
+                                LineDiff::addition(b"[`radicle-surf`](https://github.com/radicle-dev/git-platinum) unit tests.\n".to_vec(), 2),
+                            ]
+                        }].into()
+                    },
+                    eof: None,
+                }]
+            };
+            assert_eq!(expected_diff, diff);
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_apply() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let commit = Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?;
+            let parent = repo.0.find_commit(commit)?.parent_id(0)?;
+
+            let mut bro = Browser::new(&repo, Branch::local("master"))?;
+            let diff = bro.diff(parent, commit)?;
+
+            bro.commit(parent)?;
+            let before = bro.get_directory()?;
+            bro.commit(commit)?;
+            let after = bro.get_directory()?;
+
+            assert_eq!(before.apply(&diff), Ok(after));
+
+            Ok(())
+        }
+
+        #[test]
+        fn apply_skips_modified_submodules() {
+            use crate::file_system::{unsound, Directory, File};
+
+            let mut directory = Directory::root();
+            directory.insert_file(unsound::path::new("README.md"), File::new(b"hello\n"));
+
+            let mut diff = Diff::new();
+            diff.modified.push(ModifiedFile {
+                path: unsound::path::new("~/vendor/lib"),
+                diff: FileDiff::Submodule {
+                    old: Oid::zero(),
+                    new: Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02").unwrap(),
+                },
+                eof: None,
+            });
+
+            assert_eq!(directory.apply(&diff), Ok(directory.clone()));
+        }
+
+        #[test]
+        fn unified_string_keeps_file_modes() -> Result<(), Error> {
+            let fixture = fixture::Fixture::new()?;
+            let initial = fixture
+                .commit("master", "initial")
+                .file("README", "hi\n")
+                .write()?;
+            let repo = &fixture.repository().0;
+            let parent = repo.find_commit(initial)?;
+            let blob = repo.blob(b"#!/bin/sh\n")?;
+            let mut tree = repo.treebuilder(Some(&parent.tree()?))?;
+            tree.insert("run.sh", blob, 0o100755)?;
+            let tree = repo.find_tree(tree.write()?)?;
+            let signature = git2::Signature::now("Test", "test@example.com")?;
+            let executable = repo.commit(
+                Some("refs/heads/master"),
+                &signature,
+                &signature,
+                "add a script",
+                &tree,
+                &[&parent],
+            )?;
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let created = browser.diff(initial, executable)?;
+            assert_eq!(created.created[0].mode, git2::FileMode::BlobExecutable);
+            assert!(created
+                .to_unified_string()
+                .contains("new file mode 100755\n"));
+
+            let deleted = browser.diff(executable, initial)?;
+            assert!(deleted
+                .to_unified_string()
+                .contains("deleted file mode 100755\n"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_binary_diff() -> Result<(), Error> {
+            use file_system::{unsound, Path};
+
+            let repo = Repository::new("./data/git-platinum")?;
+            let bro = Browser::new(&repo, Branch::local("master"))?;
+            let diff = bro.diff(
+                Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+                Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?,
+            )?;
+
+            let cat = diff
+                .created
+                .iter()
+                .find(|created| {
+                    created.path
+                        == Path::with_root(&[
+                            unsound::label::new("bin"),
+                            unsound::label::new("cat"),
+                        ])
+                })
+                .expect("bin/cat should be created");
+            match &cat.diff {
+                FileDiff::Binary {
+                    old: None,
+                    new: Some(new),
+                } => {
+                    assert_eq!(new.size, 36768);
+                    assert_eq!(new.id, repo.0.revparse_single("a0dd912:bin/cat")?.id());
+                },
+                diff => panic!("unexpected diff for bin/cat: {:?}", diff),
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_directory_diff() -> Result<(), Error> {
+            use file_system::{unsound, Path};
+
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut bro = Browser::new(&repo, Branch::local("master"))?;
+            let head = bro.get_directory()?;
+            bro.commit(Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?)?;
+            let initial = bro.get_directory()?;
+
+            let diff = initial.diff(&head)?;
+            let git_diff = bro.diff(
+                Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+                Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?,
+            )?;
+
+            assert_eq!(diff.modified, git_diff.modified);
+            assert!(diff.created.iter().any(|created| {
+                created.path
+                    == Path::with_root(&[unsound::label::new("bin"), unsound::label::new("cat")])
+                    && matches!(
+                        created.diff,
+                        FileDiff::Binary {
+                            old: None,
+                            new: Some(_)
+                        }
+                    )
+            }));
+            assert!(head.diff(&head)?.created.is_empty());
+
+            Ok(())
+        }
+
+        #[cfg(feature = "serialize")]
+        #[test]
+        fn test_diff_serde() -> Result<(), Error> {
+            use file_system::*;
+
+            let diff = Diff {
+                created: vec![CreateFile{path: unsound::path::new("LICENSE"), mode: git2::FileMode::Blob, diff: FileDiff::Plain { hunks: Hunks::default() }}],
+                deleted: vec![],
+                moved: vec![
+                    MoveFile {
+                        old_path: unsound::path::new("CONTRIBUTING"),
+                        new_path: unsound::path::new("CONTRIBUTING.md")
+                    }
+                ],
+                copied: vec![],
+                modified: vec![ModifiedFile {
+                    path: Path::with_root(&[unsound::label::new("README.md")]),
+                    diff: FileDiff::Plain {
+                        hunks: vec![Hunk {
+                            header: Line(b"@@ -1 +1,2 @@\n".to_vec()),
+                            lines: vec![
+                                LineDiff::deletion(b"This repository is a data source for the Upstream front-end tests.\n".to_vec(), 1),
+                                LineDiff::addition(b"This repository is a data source for the Upstream front-end tests and the\n".to_vec(), 1),
+                                LineDiff::addition(b"[`radicle-surf`](https://github.com/radicle-dev/git-platinum) unit tests.\n".to_vec(), 2),
+                                LineDiff::context(b"\n".to_vec(), 3, 4),
+                            ]
+                        }].into()
+                    },
+                    eof: None,
+                }]
+            };
+
+            let eof: Option<u8> = None;
+            let json = serde_json::json!({
+                "created": [{"path": "LICENSE", "mode": "100644", "diff": {
+                        "type": "plain",
+                        "hunks": []
+                    },
+                }],
+                "deleted": [],
+                "moved": [{ "oldPath": "CONTRIBUTING", "newPath": "CONTRIBUTING.md" }],
+                "copied": [],
+                "modified": [{
+                    "path": "README.md",
+                    "diff": {
+                        "type": "plain",
+                        "hunks": [{
+                            "header": "@@ -1 +1,2 @@\n",
+                            "lines": [
+                                { "lineNum": 1,
+                                  "line": "This repository is a data source for the Upstream front-end tests.\n",
+                                  "type": "deletion"
+                                },
+                                { "lineNum": 1,
+                                  "line": "This repository is a data source for the Upstream front-end tests and the\n",
+                                  "type": "addition"
+                                },
+                                { "lineNum": 2,
+                                  "line": "[`radicle-surf`](https://github.com/radicle-dev/git-platinum) unit tests.\n",
+                                  "type": "addition"
+                                },
+                                { "lineNumOld": 3, "lineNumNew": 4,
+                                  "line": "\n",
+                                  "type": "context"
+                                }
+                            ]
+                        }]
+                    },
+                    "eof" : eof,
+                }]
+            });
+            assert_eq!(serde_json::to_value(&diff).unwrap(), json);
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod history {
+        use crate::{
+            file_system::unsound,
+            vcs::{self, git::*},
+        };
+        use nonempty::NonEmpty;
+        use std::collections::HashMap;
+
+        #[test]
+        fn lenient_history_skips_malformed_commits() -> Result<(), Error> {
+            let fixture = fixture::Fixture::new()?;
+            let root = fixture.commit("master", "root").file("a", "a").write()?;
+            fixture.branch("root", root)?;
+
+            // A commit whose author name is not valid UTF-8.
+            let repo = &fixture.repository().0;
+            let tree = repo.find_commit(root)?.tree_id();
+            let mut raw = format!("tree {}\nparent {}\nauthor ", tree, root).into_bytes();
+            raw.extend_from_slice(b"Bad \xff <bad@radicle.xyz> 0 +0000\n");
+            raw.extend_from_slice(b"committer Bad <bad@radicle.xyz> 0 +0000\n\nbad\n");
+            let bad = repo.odb()?.write(git2::ObjectType::Commit, &raw)?;
+            fixture.branch("master", bad)?;
+            let tip = fixture.commit("master", "tip").file("b", "b").write()?;
+
+            let mut browser = Browser::new(fixture.repository(), Branch::local("root"))?;
+            assert!(browser.branch(Branch::local("master")).is_err());
+
+            let (history, errors) = browser.lenient_history(Branch::local("master"))?;
+            let ids = history.iter().map(|commit| commit.id).collect::<Vec<_>>();
+            assert_eq!(ids, vec![tip, root]);
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].0, bad);
+            assert!(matches!(errors[0].1, Error::Utf8Error(_)));
+
+            Ok(())
+        }
+
+        #[test]
+        fn directory_history_skips_files() -> Result<(), Error> {
+            use crate::file_system::unsound;
+
+            let fixture = fixture::Fixture::new()?;
+            let file = fixture
+                .commit("master", "file")
+                .file("docs", "todo")
+                .write()?;
+            let directory = fixture
+                .commit("master", "directory")
+                .remove("docs")
+                .file("docs/intro.md", "# Intro")
+                .write()?;
+            fixture
+                .commit("master", "readme")
+                .file("README", "hi")
+                .write()?;
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+            let docs = unsound::path::new("~/docs");
+            let ids = |commits: Vec<Commit>| {
+                commits
+                    .into_iter()
+                    .map(|commit| commit.id)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                ids(browser.directory_history(&docs, CommitHistory::Full)?),
+                vec![directory]
+            );
+            assert_eq!(
+                ids(browser.directory_history(&docs, CommitHistory::Last)?),
+                vec![directory]
+            );
+            assert_eq!(ids(browser.file_history(docs)?), vec![directory, file]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn file_history_excludes() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+
+            let pathspec = Pathspec::default().include("src")?.exclude("src/Eval.hs")?;
+            let commits = browser
+                .file_history(pathspec)?
+                .into_iter()
+                .map(|commit| commit.id)
+                .collect::<Vec<_>>();
+
+            // The commit that only changed `src/Eval.hs` is left out.
+            assert_eq!(
+                commits,
+                vec![
+                    Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?,
+                    Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
+                ]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn file_history_of_a_literal_path() -> Result<(), Error> {
+            let fixture = fixture::Fixture::new()?;
+            let star = fixture
+                .commit("master", "Add the star")
+                .file("docs/*.md", "*\n")
+                .write()?;
+            fixture
+                .commit("master", "Add the readme")
+                .file("docs/README.md", "Hi\n")
+                .write()?;
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+            // The `*` of the path is not a wildcard.
+            let commits = browser
+                .file_history(unsound::path::new("~/docs/*.md"))?
+                .into_iter()
+                .map(|commit| commit.id)
+                .collect::<Vec<_>>();
+            assert_eq!(commits, vec![star]);
+
+            assert_eq!(
+                Pathspec::default().exclude("/").err(),
+                Some(file_system::Error::EmptyPattern("/".to_string()))
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn complete() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let history = browser.get();
+
+            let grafts = repo.as_ref().grafts()?;
+            assert!(!repo.is_shallow());
+            assert!(grafts.is_empty());
+            assert!(history.is_complete(&grafts));
+            assert!(history.boundary(&grafts).is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn same_head() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let history = browser.get();
+            let commits = history.iter().take(2).cloned().collect::<Vec<_>>();
+            let truncated = vcs::History::from_nonempty(NonEmpty::from_slice(&commits).unwrap());
+
+            // Histories that start at the same commit share a head even when
+            // one was cut short, though they are not equal.
+            assert!(history.same_head(&truncated));
+            assert_eq!(history.id(), truncated.id());
+            assert_ne!(history, truncated);
+
+            let dev = repo.as_ref().get_history(Branch::local("dev").into())?;
+            assert!(!history.same_head(&dev));
+            assert_eq!(dev.head_id(), dev.first().id);
+
+            Ok(())
+        }
+
+        #[cfg(feature = "rayon")]
+        #[test]
+        fn par_file_history() -> Result<(), Error> {
+            use crate::file_system::unsound;
+
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+
+            for path in &["~", "~/README.md", "~/src/memory.rs", "~/not/a/file"] {
+                let path = unsound::path::new(path);
+                assert_eq!(
+                    browser.par_file_history(path.clone())?,
+                    browser.file_history(path)?
+                );
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn truncated() -> Result<(), Error> {
+            let fixture = fixture::Fixture::new()?;
+            fixture
+                .commit("master", "Initial commit")
+                .file("README.md", "Hello, world!\n")
+                .write()?;
+            let second = fixture
+                .commit("master", "Add sources")
+                .file("src/lib.rs", "pub mod banana;\n")
+                .write()?;
+            let third = fixture
+                .commit("master", "Add bananas")
+                .file("src/banana.rs", "pub struct Banana;\n")
+                .write()?;
+
+            let clone = fixture.shallow_clone(2)?;
+            let repo = clone.repository();
+            let browser = Browser::new(repo, Branch::local("master"))?;
+            let history = browser.get();
+            let grafts = repo.as_ref().grafts()?;
+
+            assert!(repo.is_shallow());
+            assert_eq!(grafts.iter().copied().collect::<Vec<_>>(), vec![second]);
+            assert_eq!(
+                history.iter().map(|commit| commit.id).collect::<Vec<_>>(),
+                vec![third, second]
+            );
+            assert!(!history.is_complete(&grafts));
+            assert_eq!(
+                history
+                    .boundary(&grafts)
+                    .into_iter()
+                    .map(|commit| commit.id)
+                    .collect::<Vec<_>>(),
+                vec![second]
+            );
+
+            // The first commit of a shallow clone touches every file.
+            let pathspec = Pathspec::default().include("README.md")?;
+            let commits = browser
+                .file_history(pathspec)?
+                .into_iter()
+                .map(|commit| commit.id)
+                .collect::<Vec<_>>();
+            assert_eq!(commits, vec![second]);
+
+            // Following a file stops at the boundary, which it has since.
+            let (commits, renames) =
+                browser.follow_file_history(&unsound::path::new("~/README.md"))?;
+            assert_eq!(
+                commits.iter().map(|commit| commit.id).collect::<Vec<_>>(),
+                vec![second]
+            );
+            assert_eq!(renames.current().since, second);
+            assert!(!renames.is_renamed());
+
+            // A `History` that is only cut short by a limit has no boundary.
+            let limited = browser.build_history(&HistoryBuilder::new().limit(1))?;
+            assert!(limited.boundary(&grafts).is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn builder_hides_revisions() -> Result<(), Error> {
+            let fixture = fixture::Fixture::new()?;
+            let base = fixture.commit("master", "base").file("a", "a").write()?;
+            let main = fixture.commit("master", "main").file("b", "b").write()?;
+            fixture.branch("feature", base)?;
+            let feature = fixture
+                .commit("feature", "feature")
+                .file("c", "c")
+                .write()?;
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let ids = |builder: &HistoryBuilder| -> Result<Vec<Oid>, Error> {
+                Ok(browser
+                    .build_history(builder)?
+                    .iter()
+                    .map(|commit| commit.id)
+                    .collect())
+            };
+
+            let only_feature = HistoryBuilder::new()
+                .push(Branch::local("feature"))
+                .hide(Branch::local("master"));
+            assert_eq!(ids(&only_feature)?, vec![feature]);
+
+            let mut both = ids(&HistoryBuilder::new()
+                .push(Branch::local("feature"))
+                .push(Branch::local("master")))?;
+            both.sort();
+            let mut expected = vec![base, main, feature];
+            expected.sort();
+            assert_eq!(both, expected);
+
+            // Nothing pushed is HEAD, which is master.
+            assert_eq!(ids(&HistoryBuilder::new().hide(base))?, vec![main]);
+
+            let everything_hidden = HistoryBuilder::new()
+                .push(Branch::local("master"))
+                .hide(Branch::local("master"));
+            assert_eq!(
+                browser.build_history(&everything_hidden),
+                Err(Error::EmptyHistory)
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn builder_orders_commits() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let build = |order| {
+                browser.build_history(
+                    &HistoryBuilder::new()
+                        .push(Branch::local("master"))
+                        .order(order),
+                )
+            };
+
+            let topological = build(HistoryOrder::Topological)?;
+            let position = topological
+                .iter()
+                .enumerate()
+                .map(|(i, commit)| (commit.id, i))
+                .collect::<HashMap<_, _>>();
+            for (i, commit) in topological.iter().enumerate() {
+                for parent in &commit.parents {
+                    assert!(position[parent] > i, "{} comes before its child", parent);
+                }
+            }
+
+            let time = build(HistoryOrder::Time)?;
+            assert!(time
+                .iter()
+                .zip(time.iter().skip(1))
+                .all(|(a, b)| a.committer.time.seconds() >= b.committer.time.seconds()));
+
+            // Every order walks the same commits.
+            assert_eq!(topological.len(), browser.get().len());
+            assert_eq!(time.len(), browser.get().len());
+
+            Ok(())
+        }
+
+        #[test]
+        fn builder_limits_commits() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let ids =
+                |history: &History| history.iter().map(|commit| commit.id).collect::<Vec<_>>();
+
+            let master = HistoryBuilder::new().push(Branch::local("master"));
+
+            let limited = browser.build_history(&master.clone().limit(3))?;
+            assert_eq!(ids(&limited), ids(&browser.get())[..3].to_vec());
+
+            let unlimited = browser.build_history(&master.clone().limit(1000))?;
+            assert_eq!(unlimited, browser.get());
+
+            assert_eq!(
+                browser.build_history(&master.limit(0)),
+                Err(Error::EmptyHistory)
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn builder_matches_authors() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let email = "rudolfs@osins.org";
+
+            let by_email = browser.build_history(
+                &HistoryBuilder::new()
+                    .push(Branch::local("master"))
+                    .author(AuthorMatcher::Email(email.into())),
+            )?;
+            let expected = browser
+                .get()
+                .iter()
+                .filter(|commit| commit.author.email == email)
+                .cloned()
+                .collect::<Vec<_>>();
+            assert_eq!(by_email.iter().cloned().collect::<Vec<_>>(), expected);
+            assert!(by_email.len() < browser.get().len());
+
+            let nobody = HistoryBuilder::new()
+                .push(Branch::local("master"))
+                .author(AuthorMatcher::predicate(|_| false));
+            assert_eq!(browser.build_history(&nobody), Err(Error::EmptyHistory));
 
-            browser.branch(Branch::local("banana"))?;
+            Ok(())
+        }
+
+        #[test]
+        fn builder_equality() {
+            let builder = HistoryBuilder::new()
+                .push(Branch::local("master"))
+                .author(AuthorMatcher::Name("Rūdolfs".into()));
+            assert_eq!(builder.clone(), builder);
+            assert_ne!(builder.clone().limit(1), builder);
+
+            // Predicates are only equal to their clones.
+            let predicate = AuthorMatcher::predicate(|_| true);
+            assert_eq!(predicate.clone(), predicate);
+            assert_ne!(AuthorMatcher::predicate(|_| true), predicate);
+        }
+
+        #[test]
+        fn follow_renames() -> Result<(), Error> {
+            let contents = "fn main() {\n    println!(\"Hello, world!\");\n}\n";
+            let fixture = fixture::Fixture::new()?;
+            let added = fixture
+                .commit("master", "add")
+                .file("main.rs", contents)
+                .write()?;
+            let first = fixture
+                .commit("master", "first rename")
+                .remove("main.rs")
+                .file("src/main.rs", contents)
+                .write()?;
+            let changed = fixture
+                .commit("master", "change")
+                .file("src/main.rs", format!("{}// changed\n", contents))
+                .write()?;
+            fixture
+                .commit("master", "unrelated")
+                .file("README", "hi")
+                .write()?;
+            let second = fixture
+                .commit("master", "second rename")
+                .remove("src/main.rs")
+                .file("bin/main.rs", format!("{}// changed\n", contents))
+                .write()?;
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let (commits, renames) =
+                browser.follow_file_history(&unsound::path::new("bin/main.rs"))?;
+            assert_eq!(
+                commits.iter().map(|commit| commit.id).collect::<Vec<_>>(),
+                vec![second, changed, first, added]
+            );
+            assert_eq!(
+                renames.iter().cloned().collect::<Vec<_>>(),
+                vec![
+                    RenameLink {
+                        path: unsound::path::new("~/bin/main.rs"),
+                        since: second,
+                        until: None,
+                    },
+                    RenameLink {
+                        path: unsound::path::new("~/src/main.rs"),
+                        since: first,
+                        until: Some(second),
+                    },
+                    RenameLink {
+                        path: unsound::path::new("~/main.rs"),
+                        since: added,
+                        until: Some(first),
+                    },
+                ]
+            );
+
+            assert!(browser
+                .follow_file_history(&unsound::path::new("missing.rs"))
+                .unwrap_err()
+                .is_not_found());
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod cache {
+        use crate::{
+            file_system::unsound,
+            vcs::git::{cache::CommitCache, *},
+        };
+
+        #[test]
+        fn history_is_cached() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let cached = browser.repository.commits.borrow().len();
+            assert_eq!(cached, browser.get().len());
+
+            // The file history only visits commits that are already cached.
+            let history = browser.file_history(unsound::path::new("~/README.md"))?;
+            assert!(!history.is_empty());
+            assert_eq!(browser.repository.commits.borrow().len(), cached);
+
+            Ok(())
+        }
+
+        #[test]
+        fn disabled_cache() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let repo = repo.as_ref().with_commit_cache(CommitCache::new(0));
+            let browser = Browser::new(repo, Branch::local("master"))?;
+            assert!(browser.repository.commits.borrow().is_empty());
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod fetch {
+        use crate::vcs::git::{fixture::Fixture, *};
+        use std::cell::Cell;
+
+        #[test]
+        fn fetch_and_refresh() -> Result<(), Error> {
+            let upstream = Fixture::new()?;
+            upstream
+                .commit("master", "Initial commit")
+                .file("README.md", "Hello\n")
+                .write()?;
+            let url = upstream.path().to_str().unwrap();
+            let refspecs = ["+refs/heads/*:refs/remotes/origin/*"];
+
+            let local = Fixture::new()?;
+            local
+                .repository()
+                .fetch(url, &refspecs, git2::RemoteCallbacks::new())?;
+            let mut browser = Browser::new(local.repository(), Branch::remote("master", "origin"))?;
+            assert_eq!(browser.get().len(), 1);
+            assert!(!browser.refresh()?);
+
+            let latest = upstream
+                .commit("master", "Say more")
+                .file("README.md", "Hello, world\n")
+                .write()?;
+            let progressed = Cell::new(false);
+            let mut callbacks = git2::RemoteCallbacks::new();
+            callbacks.transfer_progress(|_| {
+                progressed.set(true);
+                true
+            });
+            local.repository().fetch(url, &refspecs, callbacks)?;
+
+            assert!(progressed.get());
+            assert!(browser.refresh()?);
+            assert_eq!(browser.get().first().id, latest);
+            assert_eq!(browser.get().len(), 2);
+
+            Ok(())
+        }
+    }
+
+    mod source {
+        use crate::vcs::git::{fixture::Fixture, *};
+        use std::path::PathBuf;
+
+        #[test]
+        fn local() -> Result<(), Error> {
+            let source = RepositorySource::Local(PathBuf::from("./data/git-platinum"));
+            let repo = source.open()?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            assert_eq!(
+                browser.get().first().id,
+                Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn remote_is_cloned_once() -> Result<(), Error> {
+            let upstream = Fixture::new()?;
+            upstream
+                .commit("master", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+            upstream.branch(
+                "dev",
+                upstream.repository().0.refname_to_id("refs/heads/master")?,
+            )?;
+
+            let cache = Fixture::new()?;
+            let source = RepositorySource::Remote {
+                url: upstream.path().to_str().unwrap().to_string(),
+                cache_dir: cache.path().join("clones"),
+            };
+            let repo = source.open()?;
+            assert!(Browser::new(&repo, Branch::local("dev")).is_ok());
+
+            // Commits made upstream after the clone are not seen, since the
+            // clone is reused rather than made again.
+            upstream
+                .commit("master", "Say more")
+                .file("README.md", "Hello\n")
+                .write()?;
+            let repo = source.open()?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            assert_eq!(browser.get().len(), 1);
+
+            Ok(())
+        }
+
+        #[test]
+        fn remote_is_cloned_concurrently() -> Result<(), Error> {
+            let upstream = Fixture::new()?;
+            upstream
+                .commit("master", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+
+            let cache = Fixture::new()?;
+            let source = RepositorySource::Remote {
+                url: upstream.path().to_str().unwrap().to_string(),
+                cache_dir: cache.path().join("clones"),
+            };
+            let opened = std::thread::scope(|scope| {
+                let threads = (0..4)
+                    .map(|_| scope.spawn(|| source.open().map(|repo| repo.as_ref().is_shallow())))
+                    .collect::<Vec<_>>();
+                threads
+                    .into_iter()
+                    .map(|thread| thread.join().expect("the clone panicked"))
+                    .collect::<Result<Vec<_>, _>>()
+            })?;
+            assert_eq!(opened, vec![false; 4]);
+
+            // Only the clone is left in the cache.
+            let entries = std::fs::read_dir(cache.path().join("clones"))
+                .unwrap()
+                .count();
+            assert_eq!(entries, 1);
+
+            Ok(())
+        }
+
+        #[test]
+        fn local_shallow_clone() -> Result<(), Error> {
+            let upstream = Fixture::new()?;
+            upstream.commit("master", "first").file("a", "a").write()?;
+            upstream.commit("master", "second").file("b", "b").write()?;
+            let shallow = upstream.shallow_clone(1)?;
+
+            let repo = RepositorySource::Local(shallow.path().to_path_buf()).open()?;
+            assert!(repo.as_ref().is_shallow());
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            assert_eq!(browser.get().len(), 1);
+
+            Ok(())
+        }
+    }
+
+    mod workspace {
+        use crate::vcs::git::{fixture::Fixture, workspace::Workspace, *};
+        use std::path::PathBuf;
+
+        #[test]
+        fn least_recently_used_is_closed() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let initial = fixture
+                .commit("master", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+
+            let mut workspace = Workspace::new(1);
+            workspace.insert(
+                "platinum".to_string(),
+                RepositorySource::Local(PathBuf::from("./data/git-platinum")),
+            );
+            workspace.insert(
+                "fixture".to_string(),
+                RepositorySource::Local(fixture.path().to_path_buf()),
+            );
+            let platinum = "platinum".to_string();
+            let fixture = "fixture".to_string();
+
+            workspace.browser(&platinum, Branch::local("master"))?;
+            assert!(workspace.is_open(&platinum));
+
+            let head = workspace
+                .browser(&fixture, Branch::local("master"))?
+                .get()
+                .first()
+                .id;
+            assert_eq!(head, initial);
+            assert!(workspace.is_open(&fixture));
+            assert!(!workspace.is_open(&platinum));
+
+            workspace.remove(&fixture);
+            assert!(!workspace.is_open(&fixture));
+            assert_eq!(
+                workspace.repository(&fixture).err(),
+                Some(Error::UnknownRepository(fixture))
+            );
+
+            Ok(())
+        }
+    }
+
+    mod remote_branch {
+        use crate::vcs::git::{fixture::Fixture, *};
+
+        #[test]
+        fn matches_configured_remotes() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let repo = fixture.repository();
+            repo.0
+                .remote("peers/alice", "https://example.com/alice.git")?;
+            repo.0.remote("peers", "https://example.com/peers.git")?;
+
+            assert_eq!(
+                repo.as_ref().remote_branch("peers/alice/feature/dev")?,
+                Branch::remote("feature/dev", "peers/alice")
+            );
+            assert_eq!(
+                repo.as_ref().remote_branch("refs/remotes/peers/bob")?,
+                Branch::remote("bob", "peers")
+            );
+            // Unknown remotes fall back to splitting on the first `/`.
+            assert_eq!(
+                repo.as_ref().remote_branch("origin/feature/dev")?,
+                Branch::remote("feature/dev", "origin")
+            );
+            assert!(matches!(
+                repo.as_ref().remote_branch("peers"),
+                Err(Error::ParseRemoteBranch(_))
+            ));
+
+            Ok(())
+        }
+    }
+
+    mod merge_info {
+        use crate::vcs::git::{fixture::Fixture, *};
+
+        #[test]
+        fn branch_from_refs() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let base = fixture
+                .commit("master", "initial")
+                .file("README", "hi")
+                .write()?;
+            fixture.branch("feature", base)?;
+            let feature = fixture
+                .commit("feature", "feature")
+                .file("feature", "x")
+                .write()?;
+            let main = fixture.commit("master", "main").file("main", "y").write()?;
+
+            let repo = &fixture.repository().0;
+            let (feature, main) = (repo.find_commit(feature)?, repo.find_commit(main)?);
+            let tree = repo.find_commit(base)?.tree()?;
+            let merge = repo.commit(
+                Some("refs/heads/master"),
+                &main.author(),
+                &main.committer(),
+                "Combine the work",
+                &tree,
+                &[&main, &feature],
+            )?;
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let info = browser.merge_info(merge)?.unwrap();
+            assert_eq!(info.merged.id, feature.id());
+            assert_eq!(info.branch.as_deref(), Some("feature"));
+            assert!(browser.merge_info(main.id())?.is_none());
+
+            Ok(())
+        }
+    }
+
+    mod current_rev {
+        use crate::vcs::git::{fixture::Fixture, *};
+
+        #[test]
+        fn head_and_commits() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let initial = fixture
+                .commit("master", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+            fixture.repository().0.set_head("refs/heads/master")?;
+
+            let mut browser = Browser::new(fixture.repository(), initial)?;
+            assert_eq!(browser.current_rev(), Rev::Oid(initial));
+
+            browser.head()?;
+            assert_eq!(browser.current_rev(), Rev::from(Branch::local("master")));
+
+            let latest = fixture
+                .commit("master", "Say more")
+                .file("README.md", "Hello\n")
+                .write()?;
+            browser.refresh()?;
+            assert_eq!(browser.head_id(), latest);
+            assert_eq!(browser.current_rev(), Rev::from(Branch::local("master")));
+
+            browser.rev(Rev::Spec("HEAD~1".to_string()))?;
+            assert_eq!(browser.current_rev(), Rev::Oid(initial));
+
+            Ok(())
+        }
+
+        #[test]
+        fn set_forgets_the_rev() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let initial = fixture
+                .commit("master", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+            let latest = fixture
+                .commit("master", "Say more")
+                .file("README.md", "Hello\n")
+                .write()?;
+
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let master = browser.etag()?;
+            let history = fixture
+                .repository()
+                .as_ref()
+                .get_history(Rev::Oid(initial))?;
+            browser.set(history);
+
+            // The `Browser` no longer follows `master`, so there is nothing to
+            // refresh from.
+            assert_eq!(browser.current_rev(), Rev::Oid(initial));
+            assert_ne!(browser.etag()?, master);
+            assert!(!browser.refresh()?);
+            assert_eq!(browser.head_id(), initial);
+
+            browser.rev(Branch::local("master"))?;
+            assert_eq!(browser.head_id(), latest);
+            assert_eq!(browser.current_rev(), Rev::from(Branch::local("master")));
+
+            Ok(())
+        }
+
+        #[test]
+        fn etag_changes_with_the_head() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let before = browser.etag()?;
+
+            // A commit that changes no files has the same tree as its parent.
+            fixture
+                .commit("master", "Nothing to see")
+                .file("README.md", "Hi\n")
+                .write()?;
+            assert!(browser.refresh()?);
+            assert_ne!(browser.etag()?, before);
+
+            Ok(())
+        }
+    }
+
+    mod head_branch {
+        use crate::vcs::git::{fixture::Fixture, *};
+
+        #[test]
+        fn attached_and_detached() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let initial = fixture
+                .commit("dev", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+            let browser = Browser::new(fixture.repository(), Branch::local("dev"))?;
+
+            fixture.repository().0.set_head("refs/heads/dev")?;
+            assert_eq!(browser.head_branch()?, Some(Branch::local("dev")));
+
+            fixture.repository().0.set_head_detached(initial)?;
+            assert_eq!(browser.head_branch()?, None);
+
+            Ok(())
+        }
+    }
+
+    mod unborn {
+        use crate::vcs::git::{error::Category, fixture::Fixture, *};
+
+        #[test]
+        fn distinguished_from_missing() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let unborn = || Error::UnbornBranch(BranchName::new("master"));
+
+            for rev in [
+                Rev::from(Branch::local("master")),
+                Rev::Spec("master".to_string()),
+                Rev::Spec("HEAD".to_string()),
+            ] {
+                assert_eq!(
+                    Browser::new(fixture.repository(), rev).err(),
+                    Some(unborn())
+                );
+            }
+            assert_eq!(fixture.repository().as_ref().head().err(), Some(unborn()));
+            assert!(matches!(Category::from(unborn()), Category::NotFound(_)));
 
-            assert_ne!(history, browser.history);
+            let missing = Browser::new(fixture.repository(), Branch::local("dev"))
+                .err()
+                .unwrap();
+            assert!(missing.is_not_found());
+            assert!(!matches!(missing, Error::UnbornBranch(_)));
 
             Ok(())
         }
+    }
 
-        #[test]
-        fn me_namespace() -> Result<(), Error> {
-            let repo = Repository::new("./data/git-platinum")?;
-            let browser = Browser::new(&repo, Branch::local("master"))?;
-            let history = browser.history.clone();
+    mod references {
+        use crate::vcs::git::{fixture::Fixture, *};
+        use std::convert::TryFrom;
 
-            assert_eq!(browser.which_namespace(), Ok(None));
+        #[test]
+        fn custom_hierarchies() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let oid = fixture
+                .commit("master", "initial")
+                .file("README", "hi")
+                .write()?;
+            let repo = &fixture.repository().0;
+            for name in &[
+                "refs/rad/ids/alice",
+                "refs/pulls/1/head",
+                "refs/pulls/1/base",
+                "refs/pulls/2/head",
+                "refs/namespaces/me/refs/heads/master",
+                "refs/namespaces/me/refs/rad/ids/bob",
+            ] {
+                repo.reference(name, oid, true, "fixture")?;
+            }
 
-            let browser = browser
-                .switch_namespace(&Namespace::try_from("me")?, Branch::local("feature/#1194"))?;
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let mut pulls = browser.list_references(&RefGlob::custom("refs/pulls/*/head"))?;
+            pulls.sort();
+            assert_eq!(pulls, vec!["refs/pulls/1/head", "refs/pulls/2/head"]);
+            assert_eq!(
+                browser.list_references(&RefGlob::custom("refs/rad/ids/*"))?,
+                vec!["refs/rad/ids/alice"]
+            );
 
+            // Within a namespace the references are listed relative to it.
+            let namespace = Namespace::try_from("me")?;
+            let browser = Browser::new_with_namespace(
+                fixture.repository(),
+                &namespace,
+                Branch::local("master"),
+            )?;
             assert_eq!(
-                browser.which_namespace(),
-                Ok(Some(Namespace::try_from("me")?))
+                browser.list_references(&RefGlob::custom("refs/rad/ids/*"))?,
+                vec!["refs/rad/ids/bob"]
             );
-            assert_eq!(history, browser.history);
 
-            let expected_branches: Vec<Branch> = vec![Branch::local("feature/#1194")];
-            let mut branches = browser.list_branches(RefScope::Local)?;
-            branches.sort();
+            Ok(())
+        }
 
-            assert_eq!(expected_branches, branches);
+        #[test]
+        fn display_globs() {
+            assert_eq!(
+                RefGlob::branch(RefScope::All).to_string(),
+                "refs/remotes/**/* refs/heads/*"
+            );
+            assert_eq!(
+                RefGlob::tag(RefScope::All).to_string(),
+                "refs/remotes/*/tags/* refs/tags/*"
+            );
+            assert_eq!(
+                RefGlob::branch(RefScope::Remote {
+                    name: Some("origin".to_string())
+                })
+                .to_string(),
+                "refs/remotes/origin/*"
+            );
+            assert_eq!(RefGlob::custom("refs/rad/*").to_string(), "refs/rad/*");
+        }
 
-            let expected_branches: Vec<Branch> = vec![Branch::remote("feature/#1194", "fein")];
-            let mut branches = browser.list_branches(RefScope::Remote {
-                name: Some("fein".to_string()),
-            })?;
-            branches.sort();
+        #[test]
+        fn broken_references_are_skipped() -> Result<(), Error> {
+            use crate::vcs::Vcs as _;
+
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "initial")
+                .file("README", "hi")
+                .write()?;
+            let repo = &fixture.repository().0;
+            repo.reference_symbolic("refs/heads/dangling", "refs/heads/gone", true, "")?;
+            repo.reference_symbolic("refs/heads/ping", "refs/heads/pong", true, "")?;
+            repo.reference_symbolic("refs/heads/pong", "refs/heads/ping", true, "")?;
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            assert_eq!(
+                browser.list_branches(RefScope::Local)?,
+                vec![Branch::local("master")]
+            );
 
-            assert_eq!(expected_branches, branches);
+            let listing = browser.list_branches_with_warnings(RefScope::Local)?;
+            let mut skipped = listing
+                .warnings
+                .iter()
+                .filter_map(|warning| warning.name.as_deref())
+                .collect::<Vec<_>>();
+            skipped.sort_unstable();
+            assert_eq!(
+                skipped,
+                vec!["refs/heads/dangling", "refs/heads/ping", "refs/heads/pong"]
+            );
+
+            assert_eq!(browser.get_histories()?.len(), 1);
+            assert_eq!(browser.get_named_histories()?.len(), 1);
+            assert_eq!(browser.get_histories_with_warnings()?.warnings.len(), 3);
 
             Ok(())
         }
 
         #[test]
-        fn golden_namespace() -> Result<(), Error> {
-            let repo = Repository::new("./data/git-platinum")?;
-            let browser = Browser::new(&repo, Branch::local("master"))?;
-            let history = browser.history.clone();
+        fn histories_matching_glob() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let first = fixture.commit("master", "first").file("a", "a").write()?;
+            let second = fixture.commit("master", "second").file("b", "b").write()?;
+            let repo = &fixture.repository().0;
+            repo.reference("refs/pulls/1/head", first, true, "fixture")?;
+            repo.reference("refs/pulls/2/head", second, true, "fixture")?;
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let glob = RefGlob::custom("refs/pulls/*/head");
+            let mut handles = browser.history_handles(&glob)?.items;
+            handles.sort_by_key(|handle| handle.name.to_string());
+            assert_eq!(
+                handles,
+                vec![
+                    HistoryHandle {
+                        name: Ref::Custom {
+                            name: "refs/pulls/1/head".to_string(),
+                        },
+                        tip: first,
+                    },
+                    HistoryHandle {
+                        name: Ref::Custom {
+                            name: "refs/pulls/2/head".to_string(),
+                        },
+                        tip: second,
+                    },
+                ]
+            );
 
-            assert_eq!(browser.which_namespace(), Ok(None));
+            let mut lengths = browser
+                .get_histories_matching(&glob)?
+                .items
+                .into_iter()
+                .map(|named| (named.name.to_string(), named.history.len()))
+                .collect::<Vec<_>>();
+            lengths.sort_unstable();
+            assert_eq!(
+                lengths,
+                vec![
+                    ("refs/pulls/1/head".to_string(), 1),
+                    ("refs/pulls/2/head".to_string(), 2)
+                ]
+            );
 
-            let golden_browser = browser
-                .switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "watch")]
+    mod watch {
+        use crate::vcs::git::{fixture::Fixture, watch::RefEvent, *};
 
+        #[test]
+        fn failed_refresh_keeps_changes() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let first = fixture
+                .commit("master", "Initial commit")
+                .file("README.md", "Hi\n")
+                .write()?;
+            fixture.branch("dev", first)?;
+
+            let mut browser = Browser::new(fixture.repository(), Branch::local("dev"))?;
+            let mut watcher = browser.watch(RefGlob::branch(RefScope::Local))?;
+
+            fixture
+                .repository()
+                .0
+                .find_reference("refs/heads/dev")?
+                .delete()?;
+            assert!(browser.poll_changes(&mut watcher).is_err());
+
+            // The deletion is still reported, since the refresh failed.
             assert_eq!(
-                golden_browser.which_namespace(),
-                Ok(Some(Namespace::try_from("golden")?))
+                watcher.poll(&fixture.repository().as_ref())?,
+                vec![RefEvent::Deleted {
+                    name: "refs/heads/dev".to_string(),
+                    oid: first
+                }]
             );
-            assert_eq!(history, golden_browser.history);
 
-            let expected_branches: Vec<Branch> =
-                vec![Branch::local("banana"), Branch::local("master")];
-            let mut branches = golden_browser.list_branches(RefScope::Local)?;
-            branches.sort();
+            Ok(())
+        }
+    }
 
-            assert_eq!(expected_branches, branches);
+    mod navigation {
+        use crate::vcs::{self, git::*};
 
-            let expected_branches: Vec<Branch> = vec![
-                Branch::remote("fakie/bigspin", "kickflip"),
-                Branch::remote("heelflip", "kickflip"),
-                Branch::remote("v0.1.0", "kickflip"),
-            ];
-            let mut branches = golden_browser.list_branches(RefScope::Remote {
-                name: Some("kickflip".to_string()),
-            })?;
-            branches.sort();
+        #[test]
+        fn disabled_by_default() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?;
+            browser.branch(Branch::local("dev"))?;
+            let dev = browser.head_id();
 
-            assert_eq!(expected_branches, branches);
+            assert!(!browser.back()?);
+            assert_eq!(browser.head_id(), dev);
 
             Ok(())
         }
 
         #[test]
-        fn silver_namespace() -> Result<(), Error> {
+        fn push_view_forgets_forward() -> Result<(), Error> {
             let repo = Repository::new("./data/git-platinum")?;
-            let browser = Browser::new(&repo, Branch::local("master"))?;
-            let history = browser.history.clone();
-
-            assert_eq!(browser.which_namespace(), Ok(None));
+            let mut browser = Browser::new(&repo, Branch::local("master"))?.with_navigation();
+            let master = browser.get();
 
-            let silver_browser = browser.switch_namespace(
-                &Namespace::try_from("golden/silver")?,
-                Branch::local("master"),
-            )?;
+            browser.branch(Branch::local("dev"))?;
+            assert!(browser.back()?);
 
-            assert_eq!(
-                silver_browser.which_namespace(),
-                Ok(Some(Namespace::try_from("golden/silver")?))
-            );
-            assert_ne!(history, silver_browser.history);
+            // Changing the history by hand is remembered with `push_view`.
+            browser.push_view();
+            browser.modify(|history| vcs::History::new(history.first().clone()));
+            assert!(!browser.forward()?);
+            assert_eq!(browser.get().len(), 1);
 
-            let expected_branches: Vec<Branch> = vec![Branch::local("master")];
-            let mut branches = silver_browser.list_branches(RefScope::All)?;
-            branches.sort();
+            assert!(browser.back()?);
+            assert_eq!(browser.get(), master);
 
-            assert_eq!(expected_branches, branches);
+            // The revision is restored along with the history.
+            assert!(!browser.refresh()?);
+            assert_eq!(browser.get(), master);
 
             Ok(())
         }
-    }
-
-    #[cfg(test)]
-    mod rev {
-        use super::{Branch, Browser, Error, Oid, Repository, TagName};
 
-        // **FIXME**: This seems to break occasionally on
-        // buildkite. For some reason the commit
-        // 3873745c8f6ffb45c990eb23b491d4b4b6182f95, which is on master
-        // (currently HEAD), is not found. It seems to load the history
-        // with d6880352fc7fda8f521ae9b7357668b17bb5bad5 as the HEAD.
-        //
-        // To temporarily fix this, we need to select "New Build" from the build kite
-        // build page that's failing.
-        // * Under "Message" put whatever you want.
-        // * Under "Branch" put in the branch you're working on.
-        // * Expand "Options" and select "clean checkout".
         #[test]
-        fn _master() -> Result<(), Error> {
+        fn depth_is_capped() -> Result<(), Error> {
             let repo = Repository::new("./data/git-platinum")?;
-            let mut browser = Browser::new(&repo, Branch::local("master"))?;
-            browser.rev(Branch::remote("master", "origin"))?;
-
-            let commit1 = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
-            assert!(
-                browser
-                    .history
-                    .find(|commit| if commit.id == commit1 {
-                        Some(commit.clone())
-                    } else {
-                        None
-                    })
-                    .is_some(),
-                "commit_id={}, history =\n{:#?}",
-                commit1,
-                browser.history
-            );
+            let mut browser = Browser::new(&repo, Branch::local("master"))?.with_navigation();
+            for _ in 0..110 {
+                browser.branch(Branch::local("dev"))?;
+            }
 
-            let commit2 = Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?;
-            assert!(
-                browser
-                    .history
-                    .find(|commit| if commit.id == commit2 {
-                        Some(commit.clone())
-                    } else {
-                        None
-                    })
-                    .is_some(),
-                "commit_id={}, history =\n{:#?}",
-                commit2,
-                browser.history
-            );
+            let mut steps = 0;
+            while browser.back()? {
+                steps += 1;
+            }
+            assert_eq!(steps, 100);
+            assert_eq!(browser.current_rev(), Rev::from(Branch::local("dev")));
 
             Ok(())
         }
 
         #[test]
-        fn commit() -> Result<(), Error> {
+        fn fork_starts_without_views() -> Result<(), Error> {
             let repo = Repository::new("./data/git-platinum")?;
-            let mut browser = Browser::new(&repo, Branch::local("master"))?;
-            browser.rev(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)?;
+            let mut browser = Browser::new(&repo, Branch::local("master"))?.with_navigation();
+            browser.branch(Branch::local("dev"))?;
 
-            let commit1 = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
-            assert!(browser
-                .history
-                .find(|commit| if commit.id == commit1 {
-                    Some(commit.clone())
-                } else {
-                    None
-                })
-                .is_some());
+            let mut fork = browser.fork();
+            assert!(!fork.back()?);
+            assert_eq!(fork.get(), browser.get());
+
+            // Navigation stays enabled in the fork, separately from the original.
+            fork.tag(TagName::new("v0.3.0"))?;
+            assert!(fork.back()?);
+            assert_eq!(fork.get(), browser.get());
+            assert!(browser.back()?);
+            assert_ne!(fork.get(), browser.get());
 
             Ok(())
         }
+    }
 
-        #[test]
-        fn commit_parents() -> Result<(), Error> {
-            let repo = Repository::new("./data/git-platinum")?;
-            let mut browser = Browser::new(&repo, Branch::local("master"))?;
-            browser.rev(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)?;
-            let commit = browser.history.first();
+    #[cfg(test)]
+    mod analytics {
+        use crate::{
+            file_system::unsound,
+            vcs::git::{fixture::Fixture, *},
+        };
 
+        #[test]
+        fn churn_follows_moves() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "Add the parser")
+                .file("parser.rs", "fn parse() {}\n")
+                .write()?;
+            fixture
+                .commit("master", "Move the parser")
+                .remove("parser.rs")
+                .file("src/parser.rs", "fn parse() {}\n")
+                .write()?;
+
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            let report = browser.churn()?;
+            let before = report
+                .file(&unsound::path::new("parser.rs"))
+                .expect("the parser was added at the root");
             assert_eq!(
-                commit.parents,
-                vec![Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?]
+                (before.commits, before.additions, before.deletions),
+                (1, 1, 0)
             );
 
+            // The move changes no lines, rather than deleting and adding all of
+            // them, but it is a commit to the file.
+            let parser = report
+                .file(&unsound::path::new("src/parser.rs"))
+                .expect("the parser was moved to src");
+            assert_eq!(parser.commits, 1);
+            assert_eq!(parser.additions, 0);
+            assert_eq!(parser.deletions, 0);
+
             Ok(())
         }
+    }
+
+    mod deadline {
+        use crate::vcs::git::*;
+        use std::time::Instant;
 
         #[test]
-        fn commit_short() -> Result<(), Error> {
+        fn history_walk_is_cancelled() -> Result<(), Error> {
             let repo = Repository::new("./data/git-platinum")?;
             let mut browser = Browser::new(&repo, Branch::local("master"))?;
-            browser.rev(browser.oid("3873745c8")?)?;
+            let history = browser.get();
 
-            let commit1 = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
-            assert!(browser
-                .history
-                .find(|commit| if commit.id == commit1 {
-                    Some(commit.clone())
-                } else {
-                    None
-                })
-                .is_some());
+            browser.set_deadline(Some(Instant::now()));
+            assert_eq!(browser.rev(Branch::local("dev")), Err(Error::Cancelled));
+            // The failed switch leaves the browser where it was.
+            assert_eq!(browser.get(), history);
+
+            browser.set_deadline(None);
+            browser.rev(Branch::local("dev"))?;
 
             Ok(())
         }
 
+        #[cfg(feature = "rayon")]
         #[test]
-        fn tag() -> Result<(), Error> {
+        fn par_file_history_is_cancelled() -> Result<(), Error> {
+            use crate::file_system::unsound;
+
             let repo = Repository::new("./data/git-platinum")?;
             let mut browser = Browser::new(&repo, Branch::local("master"))?;
-            browser.rev(TagName::new("v0.2.0"))?;
 
-            let commit1 = Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?;
-            assert_eq!(browser.history.first().id, commit1);
+            browser.set_deadline(Some(Instant::now()));
+            assert_eq!(
+                browser.par_file_history(unsound::path::new("~/README.md")),
+                Err(Error::Cancelled)
+            );
 
             Ok(())
         }
     }
 
     #[cfg(test)]
-    mod last_commit {
+    mod limits {
         use crate::{
-            file_system::{unsound, Path},
-            vcs::git::{Branch, Browser, Oid, Repository},
+            file_system::unsound,
+            vcs::git::{fixture::Fixture, limits::*, *},
         };
 
         #[test]
-        fn readme_missing_and_memory() {
-            let repo = Repository::new("./data/git-platinum")
-                .expect("Could not retrieve ./data/git-platinum as git repository");
-            let mut browser =
-                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+        fn blob_size() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "files")
+                .file("small", "abc")
+                .file("src/large", "abcdefgh")
+                .write()?;
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+            browser.set_limits(Limits {
+                max_blob_size: Some(4),
+                ..Limits::default()
+            });
+            assert_eq!(
+                browser.get_directory(),
+                Err(Error::LimitExceeded(LimitExceeded::BlobSize {
+                    path: unsound::path::new("~/src/large"),
+                    size: 8,
+                    limit: 4,
+                }))
+            );
 
-            // Set the browser history to the initial commit
-            let commit = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")
-                .expect("Failed to parse SHA");
-            browser.commit(commit).unwrap();
+            browser.set_limits(Limits {
+                max_blob_size: Some(8),
+                ..Limits::default()
+            });
+            assert!(browser.get_directory().is_ok());
 
-            let head_commit = browser.get().0.first().clone();
+            Ok(())
+        }
 
-            // memory.rs is commited later so it should not exist here.
-            let memory_last_commit = browser
-                .last_commit(Path::with_root(&[
-                    unsound::label::new("src"),
-                    unsound::label::new("memory.rs"),
-                ]))
-                .expect("Failed to get last commit")
-                .map(|commit| commit.id);
+        #[test]
+        fn total_bytes() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "files")
+                .file("one", "abcd")
+                .file("two", "abcd")
+                .write()?;
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+            browser.set_limits(Limits {
+                max_total_bytes: Some(7),
+                ..Limits::default()
+            });
+            assert_eq!(
+                browser.get_directory(),
+                Err(Error::LimitExceeded(LimitExceeded::TotalBytes { limit: 7 }))
+            );
 
-            assert_eq!(memory_last_commit, None);
+            browser.set_limits(Limits {
+                max_files: Some(2),
+                max_total_bytes: Some(8),
+                max_blob_size: Some(4),
+            });
+            assert!(browser.get_directory().is_ok());
 
-            // README.md exists in this commit.
-            let readme_last_commit = browser
-                .last_commit(Path::with_root(&[unsound::label::new("README.md")]))
-                .expect("Failed to get last commit")
-                .map(|commit| commit.id);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod non_utf8 {
+        use crate::{
+            file_system::{unsound, NonUtf8Policy},
+            vcs::git::{fixture::Fixture, *},
+        };
 
-            assert_eq!(readme_last_commit, Some(head_commit.id));
+        /// A commit with the files `ok`, `caf\xe9` and `d\xe9/ok`.
+        fn commit(fixture: &Fixture) -> Result<Oid, Error> {
+            let repo = &fixture.repository().0;
+            let blob = repo.blob(b"content")?;
+
+            let mut subtree = repo.treebuilder(None)?;
+            subtree.insert("ok", blob, 0o100644)?;
+            let subtree = subtree.write()?;
+
+            let mut tree = repo.treebuilder(None)?;
+            tree.insert("ok", blob, 0o100644)?;
+            tree.insert(b"caf\xe9".to_vec(), blob, 0o100644)?;
+            tree.insert(b"d\xe9".to_vec(), subtree, 0o040000)?;
+            let tree = repo.find_tree(tree.write()?)?;
+
+            let signature = git2::Signature::now("Fixture", "fixture@radicle.xyz")?;
+            let oid = repo.commit(None, &signature, &signature, "non-utf8", &tree, &[])?;
+            fixture.branch("master", oid)?;
+            Ok(oid)
         }
 
         #[test]
-        fn folder_svelte() {
-            let repo = Repository::new("./data/git-platinum")
-                .expect("Could not retrieve ./data/git-platinum as git repository");
-            let mut browser =
-                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+        fn error_by_default() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            commit(&fixture)?;
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
 
-            // Check that last commit is the actual last commit even if head commit differs.
-            let commit = Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")
-                .expect("Could not parse SHA");
-            browser.commit(commit).unwrap();
+            assert!(matches!(browser.get_directory(), Err(Error::FileSystem(_))));
 
-            let expected_commit_id =
-                Oid::from_str("f3a089488f4cfd1a240a9c01b3fcc4c34a4e97b2").unwrap();
+            Ok(())
+        }
 
-            let folder_svelte = browser
-                .last_commit(unsound::path::new("~/examples/Folder.svelte"))
-                .expect("Failed to get last commit")
-                .map(|commit| commit.id);
+        #[test]
+        fn lossy() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            commit(&fixture)?;
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            browser.set_non_utf8_policy(NonUtf8Policy::Lossy);
+
+            let directory = browser.get_directory()?;
+            assert!(directory.find_file(unsound::path::new("ok")).is_some());
+            assert!(directory
+                .find_file(unsound::path::new("caf\u{FFFD}"))
+                .is_some());
+            assert!(directory
+                .find_file(unsound::path::new("d\u{FFFD}/ok"))
+                .is_some());
 
-            assert_eq!(folder_svelte, Some(expected_commit_id));
+            Ok(())
         }
 
         #[test]
-        fn nest_directory() {
-            let repo = Repository::new("./data/git-platinum")
-                .expect("Could not retrieve ./data/git-platinum as git repository");
-            let mut browser =
-                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
-
-            // Check that last commit is the actual last commit even if head commit differs.
-            let commit = Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")
-                .expect("Failed to parse SHA");
-            browser.commit(commit).unwrap();
+        fn skip() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            commit(&fixture)?;
+            let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+            browser.set_non_utf8_policy(NonUtf8Policy::Skip);
+
+            let directory = browser.get_directory()?;
+            let labels = directory
+                .list_directory()
+                .into_iter()
+                .map(|(label, _)| label.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(labels, vec!["ok".to_string()]);
 
-            let expected_commit_id =
-                Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977").unwrap();
+            Ok(())
+        }
+    }
 
-            let nested_directory_tree_commit_id = browser
-                .last_commit(unsound::path::new(
-                    "~/this/is/a/really/deeply/nested/directory/tree",
-                ))
-                .expect("Failed to get last commit")
-                .map(|commit| commit.id);
+    #[cfg(test)]
+    mod grep {
+        use crate::{
+            file_system::unsound,
+            vcs::git::{fixture::Fixture, grep::*, *},
+        };
 
-            assert_eq!(nested_directory_tree_commit_id, Some(expected_commit_id));
+        fn browser(fixture: &Fixture) -> Result<Browser<'_>, Error> {
+            Browser::new(fixture.repository(), Branch::local("master"))
         }
 
         #[test]
-        #[cfg(not(windows))]
-        fn can_get_last_commit_for_special_filenames() {
-            let repo = Repository::new("./data/git-platinum")
-                .expect("Could not retrieve ./data/git-platinum as git repository");
-            let mut browser =
-                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
-
-            // Check that last commit is the actual last commit even if head commit differs.
-            let commit = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")
-                .expect("Failed to parse SHA");
-            browser.commit(commit).unwrap();
-
-            let expected_commit_id =
-                Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02").unwrap();
+        fn matches_and_context() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "files")
+                .file("a.txt", "TODO first\nsecond\r\nthird todo\n")
+                .file("bin/data", b"todo\0binary".as_ref())
+                .file("z/b.txt", "nothing to do")
+                .write()?;
+            let browser = browser(&fixture)?;
+
+            let options = Options {
+                case_insensitive: true,
+                context: 2,
+                ..Options::default()
+            };
+            let matches = browser.grep("^to|todo$", &options)?;
+            assert_eq!(
+                matches,
+                vec![
+                    Match {
+                        path: unsound::path::new("~/a.txt"),
+                        line_number: 1,
+                        line: "TODO first".to_string(),
+                        before: vec![],
+                        after: vec!["second".to_string(), "third todo".to_string()],
+                    },
+                    Match {
+                        path: unsound::path::new("~/a.txt"),
+                        line_number: 3,
+                        line: "third todo".to_string(),
+                        before: vec!["TODO first".to_string(), "second".to_string()],
+                        after: vec![],
+                    },
+                ]
+            );
 
-            let backslash_commit_id = browser
-                .last_commit(unsound::path::new("~/special/faux\\path"))
-                .expect("Failed to get last commit")
-                .map(|commit| commit.id);
-            assert_eq!(backslash_commit_id, Some(expected_commit_id));
+            let options = Options {
+                max_matches: Some(1),
+                ..Options::default()
+            };
+            assert_eq!(browser.grep("o", &options)?.len(), 1);
 
-            let ogre_commit_id = browser
-                .last_commit(unsound::path::new("~/special/👹👹👹"))
-                .expect("Failed to get last commit")
-                .map(|commit| commit.id);
-            assert_eq!(ogre_commit_id, Some(expected_commit_id));
+            Ok(())
         }
 
         #[test]
-        fn root() {
-            let repo = Repository::new("./data/git-platinum")
-                .expect("Could not retrieve ./data/git-platinum as git repository");
-            let browser =
-                Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+        fn literal_and_invalid_patterns() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            fixture
+                .commit("master", "files")
+                .file("a.txt", "f(x)\nfx\n")
+                .write()?;
+            let browser = browser(&fixture)?;
+
+            let literal = Options {
+                mode: Mode::Literal,
+                ..Options::default()
+            };
+            let matches = browser.grep("f(x)", &literal)?;
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].line_number, 1);
 
-            let root_last_commit_id = browser
-                .last_commit(Path::root())
-                .expect("Failed to get last commit")
-                .map(|commit| commit.id);
+            assert!(matches!(
+                browser.grep("f(x", &Options::default()),
+                Err(Error::Regex(_))
+            ));
 
-            assert_eq!(root_last_commit_id, Some(browser.get().first().id));
+            Ok(())
         }
     }
 
     #[cfg(test)]
-    mod diff {
-        use crate::{diff::*, vcs::git::*};
+    mod owners {
+        use crate::{
+            file_system::unsound,
+            vcs::git::{fixture::Fixture, *},
+        };
 
         #[test]
-        fn test_initial_diff() -> Result<(), Error> {
-            use file_system::*;
-            use pretty_assertions::assert_eq;
-
-            let oid = Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?;
-            let repo = Repository::new("./data/git-platinum")?;
-            let commit = repo.0.find_commit(oid).unwrap();
+        fn code_owners_and_last_commit() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let first = fixture
+                .commit("master", "Add the parser")
+                .file("CODEOWNERS", "* @core")
+                .file(".github/CODEOWNERS", "* @core\n/src/ @parsers")
+                .file("src/parser.rs", "fn parse() {}")
+                .write()?;
+            fixture
+                .commit("master", "Add the docs")
+                .file("README.md", "A parser")
+                .write()?;
+            let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+            let ownership = browser.owners(unsound::path::new("~/src/parser.rs"))?;
+            assert_eq!(ownership.owners, vec!["@parsers".to_string()]);
+            assert_eq!(ownership.last_commit.map(|commit| commit.id), Some(first));
+
+            let ownership = browser.owners(unsound::path::new("~/README.md"))?;
+            assert_eq!(ownership.owners, vec!["@core".to_string()]);
 
-            assert!(commit.parents().count() == 0);
-            assert!(commit.parent(0).is_err());
+            Ok(())
+        }
+    }
 
-            let bro = Browser::new(&repo, Branch::local("master"))?;
-            let diff = bro.initial_diff(oid)?;
+    #[cfg(feature = "index")]
+    mod index {
+        use crate::{
+            file_system::unsound,
+            vcs::git::{fixture::Fixture, index::Index, *},
+        };
 
-            let expected_diff = Diff {
-                created: vec![CreateFile {
-                    path: Path::with_root(&[unsound::label::new("README.md")]),
-                    diff: FileDiff::Plain {
-                        hunks: vec![Hunk {
-                            header: Line(b"@@ -0,0 +1 @@\n".to_vec()),
-                            lines: vec![
-                                LineDiff::addition(b"This repository is a data source for the Upstream front-end tests.\n".to_vec(), 1),
-                            ]
-                        }].into()
-                    },
-                }],
-                deleted: vec![],
-                moved: vec![],
-                copied: vec![],
-                modified: vec![],
-            };
-            assert_eq!(expected_diff, diff);
+        #[test]
+        fn incremental_update() -> Result<(), index::Error> {
+            let fixture = Fixture::new()?;
+            let first = fixture
+                .commit("master", "Add the parser")
+                .file("src/parser.rs", "fn parse() {}")
+                .file("README", "A parser")
+                .write()?;
+            let index = Index::temporary()?;
+            assert_eq!(index.update(&fixture.repository().as_ref(), first)?, 1);
+
+            let second = fixture
+                .commit("master", "Fix the PARSER on empty input")
+                .file("src/parser.rs", "fn parse() { todo!() }")
+                .remove("README")
+                .write()?;
+            assert_eq!(index.update(&fixture.repository().as_ref(), second)?, 1);
+            assert_eq!(index.update(&fixture.repository().as_ref(), second)?, 0);
+
+            assert_eq!(index.search_commits("parser")?, vec![second, first]);
+            assert_eq!(index.search_commits("fix parser")?, vec![second]);
+            assert!(index.search_commits("lexer")?.is_empty());
+
+            let mut touched = index.paths_touched_by(second)?.unwrap();
+            touched.sort_by_key(|path| path.to_string());
+            assert_eq!(
+                touched,
+                vec![
+                    unsound::path::new("~/README"),
+                    unsound::path::new("~/src/parser.rs")
+                ]
+            );
+            assert_eq!(index.paths_touched_by(Oid::zero())?, None);
 
             Ok(())
         }
 
         #[test]
-        fn test_diff() -> Result<(), Error> {
-            use file_system::*;
-            use pretty_assertions::assert_eq;
-
-            let repo = Repository::new("./data/git-platinum")?;
-            let commit = repo
-                .0
-                .find_commit(Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?)
-                .unwrap();
-            let parent = commit.parent(0)?;
-
-            let bro = Browser::new(&repo, Branch::local("master"))?;
-
-            let diff = bro.diff(parent.id(), commit.id())?;
+        fn search_skips_partly_indexed_commits() -> Result<(), index::Error> {
+            let fixture = Fixture::new()?;
+            let commit = fixture
+                .commit("master", "Add the parser")
+                .file("src/parser.rs", "fn parse() {}")
+                .write()?;
+            let dir = tempfile::tempdir().unwrap();
+
+            // The words of a commit whose insert was interrupted before its
+            // time was written.
+            {
+                let db = sled::open(dir.path())?;
+                let mut key = b"lexer\0".to_vec();
+                key.extend_from_slice(Oid::zero().as_bytes());
+                db.open_tree("words")?.insert(key, &[])?;
+                db.flush()?;
+            }
 
-            let expected_diff = Diff {
-                created: vec![],
-                deleted: vec![],
-                moved: vec![],
-                copied: vec![],
-                modified: vec![ModifiedFile {
-                    path: Path::with_root(&[unsound::label::new("README.md")]),
-                    diff: FileDiff::Plain {
-                        hunks: vec![Hunk {
-                            header: Line(b"@@ -1 +1,2 @@\n".to_vec()),
-                            lines: vec![
-                                LineDiff::deletion(b"This repository is a data source for the Upstream front-end tests.\n".to_vec(), 1),
-                                LineDiff::addition(b"This repository is a data source for the Upstream front-end tests and the\n".to_vec(), 1),
-                                LineDiff::addition(b"[`radicle-surf`](https://github.com/radicle-dev/git-platinum) unit tests.\n".to_vec(), 2),
-                            ]
-                        }].into()
-                    },
-                    eof: None,
-                }]
-            };
-            assert_eq!(expected_diff, diff);
+            let index = Index::open(dir.path())?;
+            assert!(index.search_commits("lexer")?.is_empty());
+            index.update(&fixture.repository().as_ref(), commit)?;
+            assert_eq!(index.search_commits("parser")?, vec![commit]);
 
             Ok(())
         }
+    }
+
+    #[cfg(test)]
+    mod commit_graph {
+        use crate::vcs::git::{fixture::Fixture, *};
 
-        #[cfg(feature = "serialize")]
         #[test]
-        fn test_diff_serde() -> Result<(), Error> {
-            use file_system::*;
+        fn write_with_octopus_merge() -> Result<(), Error> {
+            let fixture = Fixture::new()?;
+            let base = fixture.commit("master", "base").file("a", "a").write()?;
+            let mut heads = vec![];
+            for branch in &["one", "two", "three"] {
+                fixture.branch(branch, base)?;
+                heads.push(
+                    fixture
+                        .commit(branch, branch)
+                        .file(branch, *branch)
+                        .write()?,
+                );
+            }
 
-            let diff = Diff {
-                created: vec![CreateFile{path: unsound::path::new("LICENSE"), diff: FileDiff::Plain { hunks: Hunks::default() }}],
-                deleted: vec![],
-                moved: vec![
-                    MoveFile {
-                        old_path: unsound::path::new("CONTRIBUTING"),
-                        new_path: unsound::path::new("CONTRIBUTING.md")
-                    }
-                ],
-                copied: vec![],
-                modified: vec![ModifiedFile {
-                    path: Path::with_root(&[unsound::label::new("README.md")]),
-                    diff: FileDiff::Plain {
-                        hunks: vec![Hunk {
-                            header: Line(b"@@ -1 +1,2 @@\n".to_vec()),
-                            lines: vec![
-                                LineDiff::deletion(b"This repository is a data source for the Upstream front-end tests.\n".to_vec(), 1),
-                                LineDiff::addition(b"This repository is a data source for the Upstream front-end tests and the\n".to_vec(), 1),
-                                LineDiff::addition(b"[`radicle-surf`](https://github.com/radicle-dev/git-platinum) unit tests.\n".to_vec(), 2),
-                                LineDiff::context(b"\n".to_vec(), 3, 4),
-                            ]
-                        }].into()
-                    },
-                    eof: None,
-                }]
-            };
+            // Merge all three branches at once into master.
+            let repo = &fixture.repository().0;
+            let parents = heads
+                .iter()
+                .map(|oid| repo.find_commit(*oid))
+                .collect::<Result<Vec<_>, _>>()?;
+            let tree = parents[0].tree()?;
+            let signature = parents[0].author();
+            let merge = repo.commit(
+                None,
+                &signature,
+                &signature,
+                "octopus",
+                &tree,
+                &parents.iter().collect::<Vec<_>>(),
+            )?;
+            fixture.branch("master", merge)?;
 
-            let eof: Option<u8> = None;
-            let json = serde_json::json!({
-                "created": [{"path": "LICENSE", "diff": {
-                        "type": "plain",
-                        "hunks": []
-                    },
-                }],
-                "deleted": [],
-                "moved": [{ "oldPath": "CONTRIBUTING", "newPath": "CONTRIBUTING.md" }],
-                "copied": [],
-                "modified": [{
-                    "path": "README.md",
-                    "diff": {
-                        "type": "plain",
-                        "hunks": [{
-                            "header": "@@ -1 +1,2 @@\n",
-                            "lines": [
-                                { "lineNum": 1,
-                                  "line": "This repository is a data source for the Upstream front-end tests.\n",
-                                  "type": "deletion"
-                                },
-                                { "lineNum": 1,
-                                  "line": "This repository is a data source for the Upstream front-end tests and the\n",
-                                  "type": "addition"
-                                },
-                                { "lineNum": 2,
-                                  "line": "[`radicle-surf`](https://github.com/radicle-dev/git-platinum) unit tests.\n",
-                                  "type": "addition"
-                                },
-                                { "lineNumOld": 3, "lineNumNew": 4,
-                                  "line": "\n",
-                                  "type": "context"
-                                }
-                            ]
-                        }]
-                    },
-                    "eof" : eof,
-                }]
-            });
-            assert_eq!(serde_json::to_value(&diff).unwrap(), json);
+            let before = Browser::new(fixture.repository(), Branch::local("master"))?.get();
+            assert!(!fixture.repository().stats()?.commit_graph_file);
+
+            fixture.repository().write_commit_graph()?;
+            assert!(fixture.repository().stats()?.commit_graph_file);
+
+            // A fresh handle loads the commit-graph, and walks the same history.
+            let repo = Repository::new(fixture.path())?;
+            let after = Browser::new(&repo, Branch::local("master"))?.get();
+            assert_eq!(before, after);
+            assert_eq!(repo.as_ref().ahead_behind(merge, base)?, (4, 0));
+            assert!(repo.as_ref().is_ancestor(heads[2], merge)?);
 
             Ok(())
         }
@@ -1721,5 +6247,21 @@ mod tests {
 
             Ok(())
         }
+
+        #[test]
+        fn cloned_browser_is_independent() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+            let mut cloned = browser.clone();
+            cloned.branch(Branch::local("dev"))?;
+
+            assert_ne!(browser.get(), cloned.get());
+            assert_eq!(
+                browser.get_directory()?,
+                Browser::new(&repo, Branch::local("master"))?.get_directory()?
+            );
+
+            Ok(())
+        }
     }
 }
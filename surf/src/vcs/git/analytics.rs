@@ -0,0 +1,344 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The analyses of a [`History`] include which files change the most often,
+//! and who knows the most about each directory.
+//!
+//! # Examples
+//!
+//! ```
+//! use radicle_surf::file_system::unsound;
+//! use radicle_surf::vcs::git::{analytics, Branch, Browser, Repository};
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let repo = Repository::new("./data/git-platinum")?;
+//! let browser = Browser::new(&repo, Branch::local("master"))?;
+//!
+//! let report = analytics::churn(&repo.as_ref(), &browser.get())?;
+//! let (path, churn) = &report.ranked()[0];
+//! assert_eq!(*path, unsound::path::new("~/src/Folder.svelte"));
+//! assert_eq!((churn.commits, churn.lines()), (2, 99));
+//!
+//! let readme = report.file(&unsound::path::new("README.md")).unwrap();
+//! assert_eq!(readme.authors.len(), 2);
+//!
+//! let src = report.directory(&unsound::path::new("src")).unwrap();
+//! assert_eq!(src.commits, 5);
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    diff::{git::summary, FileSummary},
+    file_system::{Label, Path},
+    tree::Forest,
+    vcs::git::{error::Error, Commit, History, RepositoryRef},
+};
+use nonempty::NonEmpty;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How much a file, or the files of a directory, changed over a window of
+/// [`History`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Churn {
+    /// The number of commits that changed the file.
+    pub commits: usize,
+    /// The number of lines added to the file.
+    pub additions: usize,
+    /// The number of lines deleted from the file.
+    pub deletions: usize,
+    /// The emails of the authors of the commits that changed the file.
+    pub authors: BTreeSet<String>,
+}
+
+impl Churn {
+    /// The number of lines that were added or deleted.
+    pub fn lines(&self) -> usize {
+        self.additions + self.deletions
+    }
+
+    fn merge(&mut self, other: &Churn) {
+        self.commits += other.commits;
+        self.additions += other.additions;
+        self.deletions += other.deletions;
+        self.authors.extend(other.authors.iter().cloned());
+    }
+}
+
+/// The [`Churn`] of each file that changed over a window of [`History`],
+/// computed by [`churn`].
+#[derive(Debug, Clone, Default)]
+pub struct ChurnReport {
+    files: Forest<Label, Churn>,
+}
+
+impl ChurnReport {
+    /// Get the [`Churn`] of the file at `path`, or `None` if it did not
+    /// change.
+    pub fn file(&self, path: &Path) -> Option<&Churn> {
+        self.files.find_node(rooted(path))
+    }
+
+    /// Get the [`Churn`] of all the files under the directory at `path`, or
+    /// `None` if none of them changed. The number of commits is summed over
+    /// the files, so a commit changing two of them is counted twice.
+    pub fn directory(&self, path: &Path) -> Option<Churn> {
+        let directory = self.files.find_branch(rooted(path))?;
+        let mut total = Churn::default();
+        for churn in directory.iter() {
+            total.merge(churn);
+        }
+        Some(total)
+    }
+
+    /// The files that changed, ranked by the number of commits that changed
+    /// them and then by the number of lines that changed, most first. Files
+    /// that rank the same are in the order of their paths.
+    pub fn ranked(&self) -> Vec<(Path, &Churn)> {
+        let mut ranked = self
+            .files
+            .iter_entries()
+            .map(|(labels, churn)| (Path(labels.map(Label::clone)), churn))
+            .collect::<Vec<_>>();
+        ranked.sort_by(|(_, a), (_, b)| {
+            b.commits
+                .cmp(&a.commits)
+                .then_with(|| b.lines().cmp(&a.lines()))
+        });
+        ranked
+    }
+}
+
+/// Compute the [`Churn`] of each file changed by the commits of `history`,
+/// compared to their first parents.
+///
+/// Merge commits are skipped, since their changes were already counted in
+/// the commits they merge. The commit that moves a file is counted under its
+/// new path, as a change of the lines that differ, rather than as the
+/// deletion and addition of all of them. The commits before the move stay
+/// under the old path.
+///
+/// # Errors
+///
+/// * [`Error::Cancelled`]
+/// * [`Error::Diff`]
+/// * [`Error::Git`]
+pub fn churn(repo: &RepositoryRef<'_>, history: &History) -> Result<ChurnReport, Error> {
+    let mut files = Forest::root();
+    for_each_change(repo, history, |commit, summaries| {
+        for file in summaries {
+            let churn = Churn {
+                commits: 1,
+                additions: file.additions,
+                deletions: file.deletions,
+                authors: BTreeSet::from([commit.author.email.clone()]),
+            };
+            files.insert_with(rooted(&file.path), churn.clone(), |old| old.merge(&churn));
+        }
+    })?;
+    Ok(ChurnReport { files })
+}
+
+/// What an author contributed to a directory over a window of [`History`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Contribution {
+    /// The number of commits of the author that changed the directory.
+    pub commits: usize,
+    /// The number of lines the author added or deleted in the directory.
+    pub lines: usize,
+}
+
+/// How the changes to a directory over a window of [`History`] are shared
+/// between its authors, to tell how concentrated the knowledge of it is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ownership {
+    /// The [`Contribution`] of each author, by email.
+    pub authors: BTreeMap<String, Contribution>,
+}
+
+impl Ownership {
+    /// The number of commits that changed the directory.
+    pub fn commits(&self) -> usize {
+        self.authors
+            .values()
+            .map(|contribution| contribution.commits)
+            .sum()
+    }
+
+    /// The number of lines that were added or deleted in the directory.
+    pub fn lines(&self) -> usize {
+        self.authors
+            .values()
+            .map(|contribution| contribution.lines)
+            .sum()
+    }
+
+    /// The share of the commits to the directory made by the author with the
+    /// `email`, between 0 and 1.
+    pub fn commit_share(&self, email: &str) -> f64 {
+        let commits = self.authors.get(email).map_or(0, |c| c.commits);
+        share(commits, self.commits())
+    }
+
+    /// The share of the lines changed in the directory by the author with the
+    /// `email`, between 0 and 1.
+    pub fn line_share(&self, email: &str) -> f64 {
+        let lines = self.authors.get(email).map_or(0, |c| c.lines);
+        share(lines, self.lines())
+    }
+
+    /// The email of the author who changed the most lines in the directory,
+    /// or made the most commits to it if the lines are equal.
+    pub fn main_author(&self) -> Option<&str> {
+        self.authors
+            .iter()
+            .max_by_key(|(_, contribution)| (contribution.lines, contribution.commits))
+            .map(|(email, _)| email.as_str())
+    }
+}
+
+/// The [`Ownership`] of each directory that changed over a window of
+/// [`History`], computed by [`ownership`].
+#[derive(Debug, Clone, Default)]
+pub struct OwnershipReport {
+    directories: BTreeMap<Vec<Label>, Ownership>,
+}
+
+impl OwnershipReport {
+    /// Get the [`Ownership`] of the directory at `path`, including its
+    /// sub-directories, or `None` if it did not change. The root path gives
+    /// the `Ownership` of the whole repository.
+    pub fn directory(&self, path: &Path) -> Option<&Ownership> {
+        let labels: Vec<Label> = rooted(path).into();
+        self.directories.get(&labels)
+    }
+
+    /// Iterate over the directories that changed, in the order of their
+    /// paths, along with their [`Ownership`].
+    pub fn iter(&self) -> impl Iterator<Item = (Path, &Ownership)> + '_ {
+        self.directories.iter().map(|(labels, ownership)| {
+            let (root, labels) = labels
+                .split_first()
+                .expect("the root label is always present");
+            (Path::from_labels(root.clone(), labels), ownership)
+        })
+    }
+}
+
+/// Compute the [`Ownership`] of each directory changed by the commits of
+/// `history`, compared to their first parents. The changes to a file count
+/// towards every directory above it.
+///
+/// Merge commits are skipped, since their changes were already counted in
+/// the commits they merge.
+///
+/// # Errors
+///
+/// * [`Error::Cancelled`]
+/// * [`Error::Diff`]
+/// * [`Error::Git`]
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::file_system::{unsound, Path};
+/// use radicle_surf::vcs::git::{analytics, Branch, Browser, Repository};
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = Repository::new("./data/git-platinum")?;
+/// let browser = Browser::new(&repo, Branch::local("master"))?;
+///
+/// let report = analytics::ownership(&repo.as_ref(), &browser.get())?;
+///
+/// let root = report.directory(&Path::root()).unwrap();
+/// // One of the 14 commits that are not merges is empty.
+/// assert_eq!(root.commits(), 13);
+///
+/// let text = report.directory(&unsound::path::new("text")).unwrap();
+/// let author = text.main_author().unwrap();
+/// assert_eq!(text.line_share(author), 1.0);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub fn ownership(repo: &RepositoryRef<'_>, history: &History) -> Result<OwnershipReport, Error> {
+    let mut directories = BTreeMap::<Vec<Label>, Ownership>::new();
+    for_each_change(repo, history, |commit, summaries| {
+        // The lines changed by the commit under each directory.
+        let mut touched = BTreeMap::<Vec<Label>, usize>::new();
+        for file in summaries {
+            let (parents, _) = Path(rooted(&file.path)).split_last();
+            for depth in 1..=parents.len() {
+                *touched.entry(parents[..depth].to_vec()).or_default() +=
+                    file.additions + file.deletions;
+            }
+        }
+        for (directory, lines) in touched {
+            let contribution = directories
+                .entry(directory)
+                .or_default()
+                .authors
+                .entry(commit.author.email.clone())
+                .or_default();
+            contribution.commits += 1;
+            contribution.lines += lines;
+        }
+    })?;
+    Ok(OwnershipReport { directories })
+}
+
+/// Call `f` with the [`FileSummary`] of each file changed by each commit of
+/// `history` that is not a merge, compared to its first parent.
+fn for_each_change<F>(repo: &RepositoryRef<'_>, history: &History, mut f: F) -> Result<(), Error>
+where
+    F: FnMut(&Commit, Vec<FileSummary>),
+{
+    for commit in history.iter().filter(|commit| !commit.is_merge()) {
+        repo.check_deadline()?;
+        let mut diff = repo.diff_trees(
+            commit.parents.first().copied(),
+            commit.id,
+            &mut git2::DiffOptions::new(),
+        )?;
+        // Otherwise a moved file is deleted at its old path and added at its
+        // new one, counting all of its lines twice.
+        diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)))?;
+        f(commit, summary(&diff)?);
+    }
+    Ok(())
+}
+
+/// The ratio of `part` to `total`, or 0 if the `total` is 0.
+fn share(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// The labels of `path`, starting with the root label.
+fn rooted(path: &Path) -> NonEmpty<Label> {
+    if path.0.first().is_root() {
+        path.0.clone()
+    } else {
+        NonEmpty::from((Label::root(), path.0.clone().into()))
+    }
+}
@@ -0,0 +1,267 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The search works in the spirit of `git bisect`, see
+//! [`crate::vcs::git::Browser::bisect`].
+
+use crate::vcs::git::{error::Error, Oid};
+use std::collections::HashMap;
+
+/// Whether a commit given to the judge of
+/// [`Browser::bisect`](crate::vcs::git::Browser::bisect) has the change being
+/// looked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Judgement {
+    /// The commit does not have the change, e.g. the regression.
+    Good,
+    /// The commit has the change.
+    Bad,
+    /// The commit cannot be judged, e.g. because it does not build, so
+    /// another commit is tried instead.
+    Skip,
+}
+
+/// The outcome of [`Browser::bisect`](crate::vcs::git::Browser::bisect).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bisection {
+    /// The commits that may be the first bad commit, which is only one
+    /// commit unless skipped commits prevented the search from narrowing it
+    /// down. The first candidate is always bad, and the rest were skipped.
+    pub candidates: Vec<Oid>,
+    /// The commits that were judged, in the order they were judged.
+    pub steps: Vec<(Oid, Judgement)>,
+}
+
+impl Bisection {
+    /// The first bad commit, if the search narrowed it down to one commit.
+    pub fn first_bad(&self) -> Option<Oid> {
+        match self.candidates.as_slice() {
+            [first_bad] => Some(*first_bad),
+            _ => None,
+        }
+    }
+}
+
+/// Search the `commits`, given in topological order along with their
+/// parents, for the first bad commit, where the first commit is known to be
+/// bad and the parents outside of `commits` are known to be good.
+///
+/// Each step judges the commit whose ancestors split the remaining commits
+/// most evenly, so that either answer rules out about half of them.
+pub(super) fn search<J>(commits: &[(Oid, Vec<Oid>)], mut judge: J) -> Result<Bisection, Error>
+where
+    J: FnMut(Oid) -> Result<Judgement, Error>,
+{
+    if commits.is_empty() {
+        return Err(Error::EmptyHistory);
+    }
+    let index = commits
+        .iter()
+        .enumerate()
+        .map(|(i, (oid, _))| (*oid, i))
+        .collect::<HashMap<_, _>>();
+    let parents = commits
+        .iter()
+        .map(|(_, parents)| {
+            parents
+                .iter()
+                .filter_map(|parent| index.get(parent).copied())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut bad = 0;
+    let mut remaining = CommitSet::full(commits.len());
+    let mut skipped = CommitSet::empty(commits.len());
+    let mut steps = vec![];
+
+    loop {
+        let ancestors = ancestors(&parents, &remaining);
+        let total = remaining.len();
+        let next = (0..commits.len())
+            .filter(|&i| i != bad && remaining.contains(i) && !skipped.contains(i))
+            .map(|i| {
+                let weight = ancestors[i].len();
+                (i, weight.min(total - weight))
+            })
+            // Prefer the first of equally good commits, i.e. the newest.
+            .fold(
+                None,
+                |best: Option<(usize, usize)>, (i, split)| match best {
+                    Some((_, best_split)) if best_split >= split => best,
+                    _ => Some((i, split)),
+                },
+            );
+        let i = match next {
+            Some((i, _)) => i,
+            None => break,
+        };
+
+        let judgement = judge(commits[i].0)?;
+        steps.push((commits[i].0, judgement));
+        match judgement {
+            Judgement::Good => remaining.remove_all(&ancestors[i]),
+            Judgement::Bad => {
+                bad = i;
+                remaining = ancestors[i].clone();
+            },
+            Judgement::Skip => skipped.insert(i),
+        }
+    }
+
+    let candidates = (0..commits.len())
+        .filter(|&i| i != bad && remaining.contains(i))
+        .map(|i| commits[i].0);
+    Ok(Bisection {
+        candidates: std::iter::once(commits[bad].0).chain(candidates).collect(),
+        steps,
+    })
+}
+
+/// The ancestors among the `remaining` commits of each of the commits,
+/// including itself, where the commits are indices into a topological order
+/// and `parents` are their parents' indices.
+///
+/// Since every parent comes after its children, the ancestors are found in
+/// one pass from the oldest commit, each being its parents' ancestors and
+/// itself.
+fn ancestors(parents: &[Vec<usize>], remaining: &CommitSet) -> Vec<CommitSet> {
+    let mut ancestors = vec![CommitSet::empty(parents.len()); parents.len()];
+    for i in (0..parents.len()).rev() {
+        if !remaining.contains(i) {
+            continue;
+        }
+        let mut set = CommitSet::empty(parents.len());
+        set.insert(i);
+        for &parent in &parents[i] {
+            set.insert_all(&ancestors[parent]);
+        }
+        ancestors[i] = set;
+    }
+    ancestors
+}
+
+/// A set of commits, by their index in the topological order.
+#[derive(Clone)]
+struct CommitSet(Vec<u64>);
+
+impl CommitSet {
+    fn empty(n: usize) -> Self {
+        CommitSet(vec![0; n.div_ceil(64)])
+    }
+
+    fn full(n: usize) -> Self {
+        let mut commits = Self::empty(n);
+        for i in 0..n {
+            commits.insert(i);
+        }
+        commits
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.0[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn insert_all(&mut self, other: &CommitSet) {
+        for (word, other) in self.0.iter_mut().zip(&other.0) {
+            *word |= other;
+        }
+    }
+
+    fn remove_all(&mut self, other: &CommitSet) {
+        for (word, other) in self.0.iter_mut().zip(&other.0) {
+            *word &= !other;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    /// A linear history of `n` commits, newest first, where the parent of the
+    /// last one is outside of the range.
+    fn linear(n: u8) -> Vec<(Oid, Vec<Oid>)> {
+        (1..=n).rev().map(|i| (oid(i), vec![oid(i - 1)])).collect()
+    }
+
+    #[test]
+    fn finds_the_first_bad_commit() {
+        let commits = linear(16);
+        for first_bad in 1..=16 {
+            let bisection = search(&commits, |commit| {
+                Ok(if commit >= oid(first_bad) {
+                    Judgement::Bad
+                } else {
+                    Judgement::Good
+                })
+            })
+            .unwrap();
+            assert_eq!(bisection.first_bad(), Some(oid(first_bad)));
+            assert!(bisection.steps.len() <= 4);
+        }
+    }
+
+    #[test]
+    fn skipped_commits_are_candidates() {
+        let commits = linear(8);
+        let bisection = search(&commits, |commit| {
+            Ok(if commit == oid(4) || commit == oid(5) {
+                Judgement::Skip
+            } else if commit >= oid(5) {
+                Judgement::Bad
+            } else {
+                Judgement::Good
+            })
+        })
+        .unwrap();
+        assert_eq!(bisection.first_bad(), None);
+        assert_eq!(bisection.candidates, vec![oid(6), oid(5), oid(4)]);
+    }
+
+    #[test]
+    fn merges_are_not_blamed_for_their_branches() {
+        // 4 merges 3 and 2, which both have 1 as their parent.
+        let commits = vec![
+            (oid(4), vec![oid(3), oid(2)]),
+            (oid(3), vec![oid(1)]),
+            (oid(2), vec![oid(1)]),
+            (oid(1), vec![oid(0)]),
+        ];
+        let bisection = search(&commits, |commit| {
+            Ok(if commit == oid(4) || commit == oid(3) {
+                Judgement::Bad
+            } else {
+                Judgement::Good
+            })
+        })
+        .unwrap();
+        assert_eq!(bisection.first_bad(), Some(oid(3)));
+    }
+}
@@ -54,6 +54,14 @@ impl From<git2::BranchType> for BranchType {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BranchName(pub(crate) String);
 
+impl str::FromStr for BranchName {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::try_new(name)
+    }
+}
+
 impl fmt::Display for BranchName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
@@ -79,6 +87,31 @@ impl BranchName {
         Self(name.into())
     }
 
+    /// Create a new `BranchName`, checking that it is a valid branch name
+    /// according to git's reference name rules. The name is normalized, e.g.
+    /// repeated slashes are collapsed.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidRefName`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{error::Error, BranchName};
+    ///
+    /// assert_eq!(BranchName::try_new("feature/dev"), Ok(BranchName::new("feature/dev")));
+    /// assert_eq!(
+    ///     BranchName::try_new("feature/dev.lock"),
+    ///     Err(Error::InvalidRefName("feature/dev.lock".to_string()))
+    /// );
+    /// ```
+    pub fn try_new(name: &str) -> Result<Self, Error> {
+        ext::normalize_refname("refs/heads/", name)
+            .map(Self)
+            .ok_or_else(|| Error::InvalidRefName(name.to_string()))
+    }
+
     /// Access the string value of the `BranchName`.
     pub fn name(&self) -> &str {
         &self.0
@@ -140,6 +173,75 @@ impl Branch {
         }
     }
 
+    /// Parse a remote `Branch` from its name, e.g. `origin/dev`, or its full
+    /// reference name, e.g. `refs/remotes/origin/dev`.
+    ///
+    /// The remote name is taken to be everything up to the first `/`, so
+    /// `origin/feature/dev` is the branch `feature/dev` of `origin`. If remote
+    /// names may contain a `/` themselves, use
+    /// [`RepositoryRef::remote_branch`](crate::vcs::git::RepositoryRef::remote_branch)
+    /// instead, which matches against the configured remotes.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::ParseRemoteBranch`] if the remote or branch name is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::Branch;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let branch = Branch::parse_remote("refs/remotes/origin/feature/dev")?;
+    /// assert_eq!(branch, Branch::remote("feature/dev", "origin"));
+    /// assert_eq!(branch.remote_name(), Some("origin"));
+    /// assert_eq!(branch.short_name(), "feature/dev");
+    /// assert_eq!(branch.refname(), "refs/remotes/origin/feature/dev");
+    ///
+    /// assert!(Branch::parse_remote("origin").is_err());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_remote(name: &str) -> Result<Self, Error> {
+        let stripped = name.strip_prefix("refs/remotes/").unwrap_or(name);
+        match stripped.split_once('/') {
+            Some((remote, branch)) if !remote.is_empty() && !branch.is_empty() => {
+                Ok(Self::remote(branch, remote))
+            },
+            _ => Err(Error::ParseRemoteBranch(BranchName::new(name))),
+        }
+    }
+
+    /// Get the name of the remote that the `Branch` belongs to, or `None` if
+    /// it is a `Local` branch or the remote was not specified.
+    ///
+    /// The name can be passed to
+    /// [`RepositoryRef::remote`](crate::vcs::git::RepositoryRef::remote) to
+    /// get the URLs of the remote.
+    pub fn remote_name(&self) -> Option<&str> {
+        match &self.locality {
+            BranchType::Local => None,
+            BranchType::Remote { name } => name.as_deref(),
+        }
+    }
+
+    /// Get the name of the `Branch` without its remote, e.g. `dev` for
+    /// `origin/dev`.
+    pub fn short_name(&self) -> &str {
+        self.name.name()
+    }
+
+    /// Get the fully qualified reference name of the `Branch`, e.g.
+    /// `refs/heads/dev` or `refs/remotes/origin/dev`.
+    ///
+    /// If the remote of a remote `Branch` was not specified, the `**` wildcard
+    /// is used in its place.
+    pub fn refname(&self) -> String {
+        Ref::from(self.clone()).to_string()
+    }
+
     /// Get the name of the `Branch`.
     pub fn name(&self) -> String {
         let branch_name = self.name.0.clone();
@@ -0,0 +1,145 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::vcs::git::Commit;
+use git2::Oid;
+use std::collections::{BTreeMap, HashMap};
+
+/// The number of commits a [`CommitCache`] holds by default.
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+/// A least-recently-used cache of [`Commit`]s keyed by their [`Oid`].
+///
+/// Converting a [`git2::Commit`] into a [`Commit`] means parsing its
+/// signatures and message, which adds up when the same commits are visited by
+/// a history, a file history, and a last commit lookup. A
+/// [`RepositoryRef`](crate::vcs::git::RepositoryRef), and so a
+/// [`Browser`](crate::vcs::git::Browser), keeps one of these so that each
+/// commit is only converted once.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::vcs::git::{cache::CommitCache, Branch, Browser, Repository};
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = Repository::new("./data/git-platinum")?;
+/// let browser = Browser::new(&repo, Branch::local("master"))?;
+///
+/// let mut cache = CommitCache::new(2);
+/// for commit in browser.get().iter().take(3) {
+///     cache.insert(commit.clone());
+/// }
+///
+/// // Only the two most recently used commits are kept.
+/// let history = browser.get();
+/// let mut commits = history.iter();
+/// assert_eq!(cache.get(&commits.next().unwrap().id), None);
+/// assert!(cache.get(&commits.next().unwrap().id).is_some());
+/// assert!(cache.get(&commits.next().unwrap().id).is_some());
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommitCache {
+    capacity: usize,
+    tick: u64,
+    commits: HashMap<Oid, (u64, Commit)>,
+    recency: BTreeMap<u64, Oid>,
+}
+
+impl Default for CommitCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl CommitCache {
+    /// Create an empty cache that holds at most `capacity` commits. A
+    /// `capacity` of 0 disables caching.
+    pub fn new(capacity: usize) -> Self {
+        CommitCache {
+            capacity,
+            tick: 0,
+            commits: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// The maximum number of commits the cache holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of commits the cache currently holds.
+    pub fn len(&self) -> usize {
+        self.commits.len()
+    }
+
+    /// Check whether the cache holds no commits.
+    pub fn is_empty(&self) -> bool {
+        self.commits.is_empty()
+    }
+
+    /// Get the commit for `oid`, marking it as the most recently used.
+    pub fn get(&mut self, oid: &Oid) -> Option<Commit> {
+        let tick = self.next_tick();
+        let (used, commit) = self.commits.get_mut(oid)?;
+        self.recency.remove(used);
+        self.recency.insert(tick, *oid);
+        *used = tick;
+        Some(commit.clone())
+    }
+
+    /// Insert a commit as the most recently used, evicting the least recently
+    /// used commit if the cache is full.
+    pub fn insert(&mut self, commit: Commit) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let tick = self.next_tick();
+        if let Some((used, _)) = self.commits.insert(commit.id, (tick, commit.clone())) {
+            self.recency.remove(&used);
+        }
+        self.recency.insert(tick, commit.id);
+
+        while self.commits.len() > self.capacity {
+            match self.recency.keys().next().copied() {
+                Some(oldest) => {
+                    if let Some(oid) = self.recency.remove(&oldest) {
+                        self.commits.remove(&oid);
+                    }
+                },
+                None => break,
+            }
+        }
+    }
+
+    /// Remove all commits from the cache.
+    pub fn clear(&mut self) {
+        self.commits.clear();
+        self.recency.clear();
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
@@ -15,9 +15,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::vcs::git::error::Error;
+use crate::vcs::{
+    self,
+    git::{error::Error, RepositoryRef},
+};
 use git2::Oid;
-use std::{convert::TryFrom, str};
+use std::{convert::TryFrom, fmt, str};
+
+#[cfg(feature = "serialize")]
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 
 /// `Author` is the static information of a [`git2::Signature`].
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -30,8 +36,8 @@ pub struct Author {
     pub time: git2::Time,
 }
 
-impl std::fmt::Debug for Author {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Debug for Author {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use std::cmp::Ordering;
         let time = match self.time.offset_minutes().cmp(&0) {
             Ordering::Equal => format!("{}", self.time.seconds()),
@@ -46,6 +52,61 @@ impl std::fmt::Debug for Author {
     }
 }
 
+/// Formats the `Author` the way git writes it in commit and tag objects,
+/// e.g. `A U Thor <author@example.com> 1602778504 +0200`.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = Repository::new("./data/git-platinum")?;
+/// let browser = Browser::new(&repo, Branch::local("master"))?;
+///
+/// let history = browser.get();
+/// assert_eq!(
+///     history.first().author.to_string(),
+///     "Rūdolfs Ošiņš <rudolfs@osins.org> 1602778504 +0200"
+/// );
+/// #
+/// # Ok(())
+/// # }
+/// ```
+impl fmt::Display for Author {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offset = self.time.offset_minutes().abs();
+        write!(
+            f,
+            "{} <{}> {} {}{:02}{:02}",
+            self.name,
+            self.email,
+            self.time.seconds(),
+            self.time.sign(),
+            offset / 60,
+            offset % 60
+        )
+    }
+}
+
+/// Serializes the `Author` with its `time` in seconds since the Unix epoch,
+/// and the `offset` of its timezone in minutes.
+#[cfg(feature = "serialize")]
+impl Serialize for Author {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Author", 4)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("email", &self.email)?;
+        state.serialize_field("time", &self.time.seconds())?;
+        state.serialize_field("offset", &self.time.offset_minutes())?;
+        state.end()
+    }
+}
+
 impl<'repo> TryFrom<git2::Signature<'repo>> for Author {
     type Error = str::Utf8Error;
 
@@ -77,6 +138,97 @@ pub struct Commit {
     pub parents: Vec<Oid>,
 }
 
+impl Commit {
+    /// Get the [`Oid`]s of the commit's parents, in the order they were
+    /// recorded. The first parent is the commit that was checked out when the
+    /// commit was made.
+    pub fn parent_ids(&self) -> &[Oid] {
+        &self.parents
+    }
+
+    /// Check whether the commit is a merge, i.e. it has more than one parent.
+    pub fn is_merge(&self) -> bool {
+        self.parents.len() > 1
+    }
+
+    /// Get the shortest unambiguous abbreviation of the commit's [`Oid`] in the
+    /// given repository.
+    ///
+    /// See [`RepositoryRef::short_id`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    /// browser.commit(Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?)?;
+    ///
+    /// let history = browser.get();
+    /// assert_eq!(history.first().short_id(&repo.as_ref())?, "3873745");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn short_id(&self, repo: &RepositoryRef<'_>) -> Result<String, Error> {
+        repo.short_id(self.id)
+    }
+
+    /// Get the raw header of the commit in the given repository, i.e. the
+    /// lines of the commit object before its message.
+    ///
+    /// See [`RepositoryRef::commit_raw_header`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let history = browser.get();
+    /// let header = history.first().raw_header(&repo.as_ref())?;
+    /// assert!(header.starts_with("tree bca66427a09c02a66aa1b648938c6740bddfed55\n"));
+    /// assert!(header.contains("\ngpgsig -----BEGIN PGP SIGNATURE-----"));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raw_header(&self, repo: &RepositoryRef<'_>) -> Result<String, Error> {
+        repo.commit_raw_header(self.id)
+    }
+}
+
+impl vcs::HasCommitMetadata for Commit {
+    type Id = Oid;
+
+    fn id(&self) -> &Oid {
+        &self.id
+    }
+
+    fn author_name(&self) -> &str {
+        &self.author.name
+    }
+
+    fn author_email(&self) -> &str {
+        &self.author.email
+    }
+
+    fn time(&self) -> i64 {
+        self.author.time.seconds()
+    }
+
+    fn message(&self) -> &str {
+        &self.message
+    }
+}
+
 impl<'repo> TryFrom<git2::Commit<'repo>> for Commit {
     type Error = Error;
 
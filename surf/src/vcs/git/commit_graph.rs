@@ -0,0 +1,204 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Writing of [commit-graph] files, which libgit2 reads to speed up revwalks
+//! when a valid one is present in the object database.
+//!
+//! [commit-graph]: https://git-scm.com/docs/commit-graph-format
+
+use crate::vcs::git::error::Error;
+use git2::Oid;
+use sha1::{Digest, Sha1};
+use std::{collections::HashMap, fs, path};
+
+const SIGNATURE: &[u8; 4] = b"CGPH";
+const VERSION: u8 = 1;
+const HASH_VERSION: u8 = 1;
+
+const OID_FANOUT: u32 = 0x4f49_4446;
+const OID_LOOKUP: u32 = 0x4f49_444c;
+const COMMIT_DATA: u32 = 0x4344_4154;
+const EXTRA_EDGES: u32 = 0x4544_4745;
+
+const HEADER_SIZE: usize = 8;
+const CHUNK_LOOKUP_ENTRY_SIZE: usize = 12;
+const OID_SIZE: usize = 20;
+
+const PARENT_NONE: u32 = 0x7000_0000;
+const PARENT_EXTRA_EDGES: u32 = 0x8000_0000;
+const LAST_EDGE: u32 = 0x8000_0000;
+const GENERATION_MAX: u64 = 0x3fff_ffff;
+const TIME_MAX: u64 = 0x3_ffff_ffff;
+
+/// The location of the commit-graph file within a git directory.
+pub(super) fn location(git_dir: &path::Path) -> path::PathBuf {
+    git_dir.join("objects").join("info").join("commit-graph")
+}
+
+/// An entry of the commit-graph, where the parents are positions in the
+/// sorted list of commits.
+struct Entry {
+    oid: Oid,
+    tree: Oid,
+    parents: Vec<u32>,
+    generation: u64,
+    time: u64,
+}
+
+/// Write a commit-graph of all the commits reachable from the references of
+/// `repo`, replacing any existing one.
+pub(super) fn write(repo: &git2::Repository) -> Result<(), Error> {
+    let entries = entries(repo)?;
+    let graph = serialize(&entries);
+
+    let location = location(repo.path());
+    let info = location.parent().unwrap_or(&location);
+    let tmp = location.with_extension("tmp");
+    fs::create_dir_all(info).map_err(|err| super::repo::io_error(info, err))?;
+    fs::write(&tmp, graph).map_err(|err| super::repo::io_error(&tmp, err))?;
+    fs::rename(&tmp, &location).map_err(|err| super::repo::io_error(&location, err))
+}
+
+/// Collect the entries for all commits reachable from the references of
+/// `repo`, sorted by their `Oid`.
+fn entries(repo: &git2::Repository) -> Result<Vec<Entry>, Error> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    revwalk.push_glob("*")?;
+    if let Ok(head) = repo.head() {
+        if let Ok(commit) = head.peel_to_commit() {
+            revwalk.push(commit.id())?;
+        }
+    }
+    // Parents are walked before their children, so their generation is known
+    // by the time it is needed.
+    let topological = revwalk.collect::<Result<Vec<_>, _>>()?;
+
+    let mut oids = topological.clone();
+    oids.sort();
+    let positions = oids
+        .iter()
+        .enumerate()
+        .map(|(position, oid)| (*oid, position as u32))
+        .collect::<HashMap<_, _>>();
+
+    let mut generations = HashMap::with_capacity(oids.len());
+    let mut entries = HashMap::with_capacity(oids.len());
+    for oid in topological {
+        let commit = repo.find_commit(oid)?;
+        let mut parents = Vec::with_capacity(commit.parent_count());
+        let mut generation = 1;
+        for parent in commit.parent_ids() {
+            let position = positions.get(&parent).ok_or_else(|| {
+                git2::Error::from_str(&format!(
+                    "the parent {} of commit {} is missing, e.g. in a shallow clone",
+                    parent, oid
+                ))
+            })?;
+            parents.push(*position);
+            generation = generation.max(generations.get(&parent).copied().unwrap_or(0) + 1);
+        }
+        generations.insert(oid, generation);
+        entries.insert(
+            oid,
+            Entry {
+                oid,
+                tree: commit.tree_id(),
+                parents,
+                generation: generation.min(GENERATION_MAX),
+                time: (commit.time().seconds().max(0) as u64).min(TIME_MAX),
+            },
+        );
+    }
+
+    Ok(oids.iter().filter_map(|oid| entries.remove(oid)).collect())
+}
+
+/// Serialize the entries, which must be sorted by their `Oid`, into the
+/// commit-graph file format, including its trailing checksum.
+fn serialize(entries: &[Entry]) -> Vec<u8> {
+    let mut counts = [0u32; 256];
+    for entry in entries {
+        counts[entry.oid.as_bytes()[0] as usize] += 1;
+    }
+    let mut fanout = Vec::with_capacity(counts.len() * 4);
+    let mut total = 0;
+    for count in counts.iter() {
+        total += count;
+        fanout.extend_from_slice(&total.to_be_bytes());
+    }
+
+    let mut lookup = Vec::with_capacity(entries.len() * OID_SIZE);
+    let mut data = Vec::with_capacity(entries.len() * (OID_SIZE + 16));
+    let mut edges = Vec::new();
+    for entry in entries {
+        lookup.extend_from_slice(entry.oid.as_bytes());
+
+        let (first, second) = match entry.parents.as_slice() {
+            [] => (PARENT_NONE, PARENT_NONE),
+            [first] => (*first, PARENT_NONE),
+            [first, second] => (*first, *second),
+            [first, rest @ ..] => {
+                let second = PARENT_EXTRA_EDGES | (edges.len() / 4) as u32;
+                for (i, parent) in rest.iter().enumerate() {
+                    let edge = if i == rest.len() - 1 {
+                        parent | LAST_EDGE
+                    } else {
+                        *parent
+                    };
+                    edges.extend_from_slice(&edge.to_be_bytes());
+                }
+                (*first, second)
+            },
+        };
+
+        data.extend_from_slice(entry.tree.as_bytes());
+        data.extend_from_slice(&first.to_be_bytes());
+        data.extend_from_slice(&second.to_be_bytes());
+        data.extend_from_slice(&((entry.generation << 34) | entry.time).to_be_bytes());
+    }
+
+    let mut chunks = vec![
+        (OID_FANOUT, fanout),
+        (OID_LOOKUP, lookup),
+        (COMMIT_DATA, data),
+    ];
+    if !edges.is_empty() {
+        chunks.push((EXTRA_EDGES, edges));
+    }
+
+    let mut graph = Vec::new();
+    graph.extend_from_slice(SIGNATURE);
+    graph.extend_from_slice(&[VERSION, HASH_VERSION, chunks.len() as u8, 0]);
+
+    let mut offset = (HEADER_SIZE + (chunks.len() + 1) * CHUNK_LOOKUP_ENTRY_SIZE) as u64;
+    for (id, chunk) in &chunks {
+        graph.extend_from_slice(&id.to_be_bytes());
+        graph.extend_from_slice(&offset.to_be_bytes());
+        offset += chunk.len() as u64;
+    }
+    graph.extend_from_slice(&0u32.to_be_bytes());
+    graph.extend_from_slice(&offset.to_be_bytes());
+
+    for (_, chunk) in chunks {
+        graph.extend_from_slice(&chunk);
+    }
+
+    let checksum = Sha1::digest(&graph);
+    graph.extend_from_slice(&checksum);
+    graph
+}
@@ -0,0 +1,44 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::vcs::git::TagName;
+use std::fmt;
+
+/// The description of a commit in terms of the nearest tag reachable from it,
+/// as given by `git describe --tags`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Describe {
+    /// The name of the nearest tag reachable from the commit.
+    pub tag: TagName,
+    /// The number of commits between the tag and the described commit. This is
+    /// `0` when the commit is the one being tagged.
+    pub distance: usize,
+    /// The abbreviated object ID of the described commit.
+    pub short_id: String,
+}
+
+/// Renders the description in the same form as `git describe --tags`, e.g.
+/// `v0.6.0-5-ga0dd912`, or just the tag name if the commit is tagged.
+impl fmt::Display for Describe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.distance == 0 {
+            write!(f, "{}", self.tag)
+        } else {
+            write!(f, "{}-{}-g{}", self.tag, self.distance, self.short_id)
+        }
+    }
+}
@@ -21,9 +21,9 @@
 use crate::{
     diff,
     file_system,
-    vcs::git::{BranchName, Namespace, TagName},
+    vcs::git::{limits::LimitExceeded, BranchName, Namespace, Rev, TagName},
 };
-use std::str;
+use std::{io, path, str};
 use thiserror::Error;
 
 /// Enumeration of errors that can occur in operations from [`crate::vcs::git`].
@@ -35,6 +35,10 @@ pub enum Error {
     /// or that a tag or commit was provided by accident.
     #[error("provided branch name does not exist: {0}")]
     NotBranch(BranchName),
+    /// A branch or tag name does not follow git's rules for reference names,
+    /// e.g. it contains `..` or ends with `.lock`.
+    #[error("'{0}' is not a valid reference name")]
+    InvalidRefName(String),
     /// We tried to convert a name into its remote and branch name parts.
     #[error("could not parse '{0}' into a remote name and branch name")]
     ParseRemoteBranch(BranchName),
@@ -59,6 +63,19 @@ pub enum Error {
         /// The provided revspec that failed to parse.
         rev: String,
     },
+    /// A name given to
+    /// [`Browser::resolve`](crate::vcs::git::Browser::resolve) matches more
+    /// than one branch, tag, or commit.
+    #[error(
+        "the name '{name}' is ambiguous, it could be any of: {}",
+        .candidates.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    AmbiguousName {
+        /// The name that was resolved.
+        name: String,
+        /// Everything the name matches, from which the caller can pick.
+        candidates: Vec<Rev>,
+    },
     /// When parsing a namespace we may come across one that was an empty
     /// string.
     #[error("tried parsing the namespace but it was empty")]
@@ -85,42 +102,196 @@ pub enum Error {
     /// An error that comes from performing a *diff* operations.
     #[error(transparent)]
     Diff(#[from] diff::git::error::Diff),
+    /// An I/O error that occurred while reading the repository's files on
+    /// disk.
+    #[error("failed to read '{}': {kind}", path.display())]
+    Io {
+        /// The path that was being read.
+        path: path::PathBuf,
+        /// The kind of I/O error that occurred.
+        kind: io::ErrorKind,
+    },
+    /// The deadline set with
+    /// [`Browser::set_deadline`](crate::vcs::git::Browser::set_deadline)
+    /// passed before the operation finished.
+    #[error("the operation was cancelled because its deadline passed")]
+    Cancelled,
+    /// A snapshot went over the limits set with
+    /// [`Browser::set_limits`](crate::vcs::git::Browser::set_limits).
+    #[error(transparent)]
+    LimitExceeded(#[from] LimitExceeded),
+    /// The pattern given to
+    /// [`Browser::grep`](crate::vcs::git::Browser::grep) is not a valid
+    /// regular expression.
+    #[error(transparent)]
+    Regex(#[from] regex::Error),
+    /// The branch exists in name only, since it has no commits yet, e.g. the
+    /// branch `HEAD` points to in a new repository. A branch that does not
+    /// exist at all is reported as not found instead.
+    #[error("the branch '{0}' has no commits yet")]
+    UnbornBranch(BranchName),
+    /// A history would have no commits, e.g. because everything a
+    /// [`HistoryBuilder`](crate::vcs::git::HistoryBuilder) pushed was also
+    /// hidden, or every commit is newer than the time given to
+    /// [`Browser::at_time`](crate::vcs::git::Browser::at_time).
+    #[error("the history has no commits")]
+    EmptyHistory,
+    /// The repository is not part of the
+    /// [`Workspace`](crate::vcs::git::workspace::Workspace).
+    #[error("the repository '{0}' is not part of the workspace")]
+    UnknownRepository(String),
     /// A wrapper around the generic [`git2::Error`].
     #[error(transparent)]
     Git(#[from] git2::Error),
 }
 
-/// A private enum that captures a recoverable and
-/// non-recoverable error when walking the git tree.
+impl Error {
+    /// The underlying [`git2::Error`], if this error was caused by one,
+    /// including those that occurred while computing a diff.
+    pub fn git(&self) -> Option<&git2::Error> {
+        match self {
+            Error::Git(err)
+            | Error::Diff(diff::git::error::Diff::Git(err))
+            | Error::Diff(diff::git::error::Diff::Hunk(diff::git::error::Hunk::Git(err))) => {
+                Some(err)
+            },
+            _ => None,
+        }
+    }
+
+    /// The [`git2::ErrorCode`] of the underlying [`git2::Error`], if this
+    /// error was caused by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Oid, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let oid = Oid::from_str("0000000000000000000000000000000000000001")?;
+    ///
+    /// let master = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    /// let err = repo.as_ref().diff(oid, master).unwrap_err();
+    /// assert_eq!(err.code(), Some(git2::ErrorCode::NotFound));
+    /// assert!(err.is_not_found());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn code(&self) -> Option<git2::ErrorCode> {
+        self.git().map(git2::Error::code)
+    }
+
+    /// The [`git2::ErrorClass`] of the underlying [`git2::Error`], if this
+    /// error was caused by one.
+    pub fn class(&self) -> Option<git2::ErrorClass> {
+        self.git().map(git2::Error::class)
+    }
+
+    /// Check whether the error is due to something not existing, whether
+    /// it is a branch, tag, revision, path, or any other git object.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::NotBranch(_)
+            | Error::NotTag(_)
+            | Error::RevParseFailure { .. }
+            | Error::NamespaceRevParseFailure { .. }
+            | Error::PathNotFound(_)
+            | Error::UnknownRepository(_) => true,
+            _ => self.code() == Some(git2::ErrorCode::NotFound),
+        }
+    }
+}
+
+/// The broad category of an [`Error`], for reporting it to a caller without
+/// having to match on every variant, e.g. when mapping to HTTP status codes.
+///
+/// The original [`Error`] is kept as the [`std::error::Error::source`] of the
+/// category.
+///
+/// # Examples
 ///
-/// In the case of `NotBlob` we abort the the computation but do
-/// a check for it and recover.
+/// ```
+/// use radicle_surf::file_system::Path;
+/// use radicle_surf::vcs::git::{
+///     error::{Category, Error as GitError},
+///     Branch,
+///     Browser,
+///     Repository,
+/// };
+/// use std::convert::TryFrom;
+/// # use std::error::Error;
 ///
-/// In the of `Git` we abort both computations.
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = Repository::new("./data/git-platinum")?;
+/// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+///
+/// let err = browser.rev(Branch::local("not-a-branch")).unwrap_err();
+/// assert!(matches!(Category::from(err), Category::NotFound(_)));
+///
+/// let err = GitError::from(Path::try_from("").unwrap_err());
+/// assert!(matches!(Category::from(err), Category::Parse(_)));
+/// #
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug, Error)]
-pub(crate) enum TreeWalkError {
-    #[error("entry is not a blob")]
-    NotBlob,
-    #[error("git object is a commit")]
-    Commit,
-    #[error(transparent)]
-    Git(#[from] Error),
+pub enum Category {
+    /// The requested branch, tag, revision, or path does not exist.
+    #[error("the requested object was not found")]
+    NotFound(#[source] Error),
+    /// A name or path provided by the caller could not be parsed.
+    #[error("the provided input is invalid")]
+    Parse(#[source] Error),
+    /// Reading or interpreting the repository failed.
+    #[error("an internal error occurred")]
+    Internal(#[source] Error),
 }
 
-impl From<git2::Error> for TreeWalkError {
-    fn from(err: git2::Error) -> Self {
-        TreeWalkError::Git(err.into())
+impl Category {
+    /// Get the [`Error`] that was categorised.
+    pub fn into_inner(self) -> Error {
+        match self {
+            Category::NotFound(err) | Category::Parse(err) | Category::Internal(err) => err,
+        }
     }
 }
 
-impl From<file_system::Error> for TreeWalkError {
-    fn from(err: file_system::Error) -> Self {
-        err.into()
-    }
-}
+impl From<Error> for Category {
+    fn from(err: Error) -> Self {
+        if err.is_not_found() {
+            return Category::NotFound(err);
+        }
 
-impl From<str::Utf8Error> for TreeWalkError {
-    fn from(err: str::Utf8Error) -> Self {
-        err.into()
+        match &err {
+            Error::NotBranch(_)
+            | Error::NotTag(_)
+            | Error::RevParseFailure { .. }
+            | Error::NamespaceRevParseFailure { .. }
+            | Error::PathNotFound(_)
+            | Error::UnknownRepository(_)
+            | Error::UnbornBranch(_)
+            | Error::EmptyHistory => Category::NotFound(err),
+            Error::ParseRemoteBranch(_)
+            | Error::InvalidRefName(_)
+            | Error::AmbiguousName { .. }
+            | Error::EmptyNamespace
+            | Error::Utf8Error(_)
+            | Error::FileSystem(_)
+            | Error::Regex(_) => Category::Parse(err),
+            Error::Git(git) => match git.code() {
+                git2::ErrorCode::InvalidSpec | git2::ErrorCode::Ambiguous => Category::Parse(err),
+                _ => Category::Internal(err),
+            },
+            Error::MissingSummary
+            | Error::LastCommitException
+            | Error::Diff(_)
+            | Error::Io { .. }
+            | Error::Cancelled
+            | Error::LimitExceeded(_) => Category::Internal(err),
+        }
     }
 }
@@ -34,6 +34,18 @@ pub fn try_extract_refname(spec: &str) -> Result<String, String> {
         .ok_or_else(|| spec.to_string())
 }
 
+/// Normalize `name` according to git's reference name rules once it is placed
+/// under `prefix`, e.g. `refs/heads/`, returning `None` if it is not a valid
+/// name.
+pub fn normalize_refname(prefix: &str, name: &str) -> Option<String> {
+    let refname = git2::Reference::normalize_name(
+        &format!("{}{}", prefix, name),
+        git2::ReferenceFormat::NORMAL,
+    )
+    .ok()?;
+    refname.strip_prefix(prefix).map(str::to_string)
+}
+
 /// [`git2::Reference::is_tag`] just does a check for the prefix of `tags/`.
 /// The issue with that is, as soon as we're in 'namespaces' ref that
 /// is a tag it will say that it's not a tag. Instead we do a regex check on
@@ -69,6 +81,27 @@ pub fn is_remote(reference: &git2::Reference) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_normalize_refname() {
+        assert_eq!(
+            normalize_refname("refs/heads/", "feature//dev"),
+            Some("feature/dev".to_string())
+        );
+        assert_eq!(
+            normalize_refname("refs/tags/", "v1.0.0"),
+            Some("v1.0.0".to_string())
+        );
+
+        for invalid in &["", "dev..x", "dev.lock", "a b", "dev~1", "dev/", "@{1}"] {
+            assert_eq!(
+                normalize_refname("refs/heads/", invalid),
+                None,
+                "{}",
+                invalid
+            );
+        }
+    }
+
     #[test]
     fn test_try_extract_refname() {
         assert_eq!(try_extract_refname("refs/heads/dev"), Ok("dev".to_string()));
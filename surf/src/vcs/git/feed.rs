@@ -0,0 +1,342 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The feeds follow [Atom](https://www.rfc-editor.org/rfc/rfc4287) and
+//! [RSS 2.0](https://www.rssboard.org/rss-specification), so that the commits
+//! of a branch can be followed in a feed reader.
+//!
+//! # Examples
+//!
+//! ```
+//! use radicle_surf::vcs::git::{feed::Feed, Branch, Browser, Repository};
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let repo = Repository::new("./data/git-platinum")?;
+//! let browser = Browser::new(&repo, Branch::local("master"))?;
+//!
+//! let feed = Feed::new("git-platinum", "https://example.com/git-platinum");
+//! let atom = feed.atom(&browser.get());
+//!
+//! assert!(atom.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>"));
+//! assert!(atom.contains("<title>git-platinum</title>"));
+//! assert!(atom.contains(
+//!     "<link href=\"https://example.com/git-platinum/commits/a0dd9122d33dff2a35f564d564db127152c88e02\"/>"
+//! ));
+//! assert!(atom.contains("<updated>2020-10-15T18:15:04+02:00</updated>"));
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::vcs::git::{Author, History, Oid};
+
+/// The days of the week, starting from Sunday.
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// The months of the year.
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The description of a repository that a feed of its commits is rendered
+/// with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed<'a> {
+    name: &'a str,
+    base_url: &'a str,
+}
+
+impl<'a> Feed<'a> {
+    /// A feed for the repository called `name`, which is browsed at
+    /// `base_url`.
+    pub fn new(name: &'a str, base_url: &'a str) -> Self {
+        Feed {
+            name,
+            base_url: base_url.trim_end_matches('/'),
+        }
+    }
+
+    /// The URL of the commit `id`, which is `<base_url>/commits/<id>`.
+    pub fn commit_url(&self, id: Oid) -> String {
+        format!("{}/commits/{}", self.base_url, id)
+    }
+
+    /// Render the `history` as an Atom feed, with an entry for each commit.
+    ///
+    /// The feed is updated at the time of the first commit of the `history`.
+    pub fn atom(&self, history: &History) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!("  <title>{}</title>\n", escape(self.name)));
+        xml.push_str(&format!("  <id>{}</id>\n", escape(self.base_url)));
+        xml.push_str(&format!("  <link href=\"{}\"/>\n", escape(self.base_url)));
+        xml.push_str(&format!(
+            "  <updated>{}</updated>\n",
+            rfc3339(&history.first().committer.time)
+        ));
+
+        for commit in history.iter() {
+            let url = escape(&self.commit_url(commit.id));
+            xml.push_str("  <entry>\n");
+            xml.push_str(&format!("    <title>{}</title>\n", escape(&commit.summary)));
+            xml.push_str(&format!("    <id>{}</id>\n", url));
+            xml.push_str(&format!("    <link href=\"{}\"/>\n", url));
+            xml.push_str(&format!(
+                "    <updated>{}</updated>\n",
+                rfc3339(&commit.committer.time)
+            ));
+            xml.push_str(&format!(
+                "    <author><name>{}</name><email>{}</email></author>\n",
+                escape(&commit.author.name),
+                escape(&commit.author.email)
+            ));
+            xml.push_str(&format!(
+                "    <content type=\"text\">{}</content>\n",
+                escape(&commit.message)
+            ));
+            xml.push_str("  </entry>\n");
+        }
+
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    /// Render the `history` as an RSS 2.0 feed, with an item for each commit.
+    pub fn rss(&self, history: &History) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\">\n");
+        xml.push_str("  <channel>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(self.name)));
+        xml.push_str(&format!("    <link>{}</link>\n", escape(self.base_url)));
+        xml.push_str(&format!(
+            "    <description>The commits of {}</description>\n",
+            escape(self.name)
+        ));
+        xml.push_str(&format!(
+            "    <lastBuildDate>{}</lastBuildDate>\n",
+            rfc2822(&history.first().committer.time)
+        ));
+
+        for commit in history.iter() {
+            xml.push_str("    <item>\n");
+            xml.push_str(&format!(
+                "      <title>{}</title>\n",
+                escape(&commit.summary)
+            ));
+            xml.push_str(&format!(
+                "      <link>{}</link>\n",
+                escape(&self.commit_url(commit.id))
+            ));
+            xml.push_str(&format!(
+                "      <guid isPermaLink=\"false\">{}</guid>\n",
+                commit.id
+            ));
+            xml.push_str(&format!(
+                "      <pubDate>{}</pubDate>\n",
+                rfc2822(&commit.committer.time)
+            ));
+            xml.push_str(&format!(
+                "      <author>{}</author>\n",
+                escape(&rss_author(&commit.author))
+            ));
+            xml.push_str(&format!(
+                "      <description>{}</description>\n",
+                escape(&commit.message)
+            ));
+            xml.push_str("    </item>\n");
+        }
+
+        xml.push_str("  </channel>\n");
+        xml.push_str("</rss>\n");
+        xml
+    }
+}
+
+/// An author in the form RSS expects, which is an email address followed by
+/// the name in parentheses.
+fn rss_author(author: &Author) -> String {
+    format!("{} ({})", author.email, author.name)
+}
+
+/// Escape the characters of `text` that are special in XML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace are not allowed in XML.
+            c if c.is_control() && !c.is_whitespace() => {},
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The date and time of `time`, in its own offset from UTC.
+struct DateTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    weekday: usize,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    offset: i32,
+}
+
+impl From<&git2::Time> for DateTime {
+    fn from(time: &git2::Time) -> Self {
+        let offset = time.offset_minutes();
+        let local = time.seconds() + i64::from(offset) * 60;
+        let days = local.div_euclid(86_400);
+        let seconds = local.rem_euclid(86_400);
+        let (year, month, day) = civil_from_days(days);
+
+        DateTime {
+            year,
+            month,
+            day,
+            weekday: (days + 4).rem_euclid(7) as usize,
+            hour: seconds / 3600,
+            minute: seconds % 3600 / 60,
+            second: seconds % 60,
+            offset,
+        }
+    }
+}
+
+/// Format `time` as in [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339),
+/// for Atom, such as `2020-01-13T22:51:09+01:00`.
+fn rfc3339(time: &git2::Time) -> String {
+    let t = DateTime::from(time);
+    let sign = if t.offset < 0 { '-' } else { '+' };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+        t.year,
+        t.month,
+        t.day,
+        t.hour,
+        t.minute,
+        t.second,
+        sign,
+        t.offset.abs() / 60,
+        t.offset.abs() % 60
+    )
+}
+
+/// Format `time` as in [RFC 2822](https://www.rfc-editor.org/rfc/rfc2822),
+/// for RSS, such as `Mon, 13 Jan 2020 22:51:09 +0100`.
+fn rfc2822(time: &git2::Time) -> String {
+    let t = DateTime::from(time);
+    let sign = if t.offset < 0 { '-' } else { '+' };
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} {}{:02}{:02}",
+        WEEKDAYS[t.weekday],
+        t.day,
+        MONTHS[t.month as usize - 1],
+        t.year,
+        t.hour,
+        t.minute,
+        t.second,
+        sign,
+        t.offset.abs() / 60,
+        t.offset.abs() % 60
+    )
+}
+
+/// Convert a number of days since 1970-01-01 into a year, month and day of
+/// the proleptic Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        let time = git2::Time::new(0, 0);
+        assert_eq!(rfc3339(&time), "1970-01-01T00:00:00+00:00");
+        assert_eq!(rfc2822(&time), "Thu, 01 Jan 1970 00:00:00 +0000");
+
+        // 2000-02-29T23:30:00Z, seen from UTC-05:30.
+        let time = git2::Time::new(951_867_000, -330);
+        assert_eq!(rfc3339(&time), "2000-02-29T18:00:00-05:30");
+        assert_eq!(rfc2822(&time), "Tue, 29 Feb 2000 18:00:00 -0530");
+
+        // The day changes with the offset.
+        let time = git2::Time::new(1_609_459_200 - 1, 60);
+        assert_eq!(rfc3339(&time), "2021-01-01T00:59:59+01:00");
+
+        let time = git2::Time::new(-86_400, 0);
+        assert_eq!(rfc2822(&time), "Wed, 31 Dec 1969 00:00:00 +0000");
+    }
+
+    #[test]
+    fn rss() -> Result<(), crate::vcs::git::error::Error> {
+        use crate::vcs::git::{fixture::Fixture, Branch, Browser};
+
+        let fixture = Fixture::new()?;
+        let id = fixture
+            .commit("master", "Compare a < b\n\nand b > a")
+            .file("a", "a")
+            .write()?;
+        let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+
+        let rss = Feed::new("fixture", "https://example.com/fixture/").rss(&browser.get());
+        assert!(rss.contains("<link>https://example.com/fixture</link>"));
+        assert!(rss.contains(&format!(
+            "<link>https://example.com/fixture/commits/{}</link>",
+            id
+        )));
+        assert!(rss.contains("<title>Compare a &lt; b</title>"));
+        assert!(rss.contains("<description>Compare a &lt; b\n\nand b &gt; a"));
+        assert_eq!(rss.matches("<item>").count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            escape("<a href=\"x\">Tom & Jerry's</a>\u{1}\n"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;\n"
+        );
+    }
+}
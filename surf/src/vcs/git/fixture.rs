@@ -0,0 +1,285 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! ```
+//! use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser, TagName};
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let fixture = Fixture::new()?;
+//! let initial = fixture
+//!     .commit("master", "Initial commit")
+//!     .file("README.md", "Hello, world!\n")
+//!     .write()?;
+//! fixture
+//!     .commit("master", "Add sources")
+//!     .file("src/lib.rs", "pub mod banana;\n")
+//!     .write()?;
+//! fixture.tag("v0.1.0", initial, Some("First release"))?;
+//! fixture.branch("dev", initial)?;
+//!
+//! let browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+//! assert_eq!(browser.get().len(), 2);
+//!
+//! let tags = browser.list_tags(radicle_surf::vcs::git::RefScope::Local)?;
+//! assert_eq!(tags[0].name(), TagName::new("v0.1.0"));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::vcs::git::{error::Error, repo::io_error, Oid, Repository};
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Used for giving each `Fixture` of the process its own directory.
+static FIXTURES: AtomicUsize = AtomicUsize::new(0);
+
+/// The time of the first commit of a `Fixture`, where each following commit is
+/// made a minute later. This keeps the `Oid`s of the commits stable.
+const EPOCH: i64 = 1_600_000_000;
+
+/// A bare repository in a temporary directory, which is removed when the
+/// `Fixture` is dropped.
+pub struct Fixture {
+    repo: Repository,
+    path: PathBuf,
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+impl Fixture {
+    /// Create an empty `Fixture` repository.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn new() -> Result<Self, Error> {
+        let path = temp_path();
+        let repo = git2::Repository::init_bare(&path)?;
+        Ok(Fixture {
+            repo: Repository(repo),
+            path,
+        })
+    }
+
+    /// Make a shallow clone of the `Fixture` with `git clone --depth`, which
+    /// only has the last `depth` commits of each branch.
+    ///
+    /// libgit2 cannot make shallow clones, so this needs `git` on the `PATH`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Io`] if `git` cannot be run or fails.
+    /// * [`Error::Git`]
+    pub fn shallow_clone(&self, depth: usize) -> Result<Self, Error> {
+        let path = temp_path();
+        let status = process::Command::new("git")
+            .arg("clone")
+            .arg("--quiet")
+            .arg("--bare")
+            .arg("--no-local")
+            .arg("--depth")
+            .arg(depth.to_string())
+            .arg(&self.path)
+            .arg(&path)
+            .status()
+            .map_err(|err| io_error(&path, err))?;
+        if !status.success() {
+            return Err(io_error(&path, io::Error::other("git clone failed")));
+        }
+
+        let repo = git2::Repository::open_bare(&path)?;
+        Ok(Fixture {
+            repo: Repository(repo),
+            path,
+        })
+    }
+
+    /// Get the [`Repository`] of the `Fixture` for browsing it.
+    pub fn repository(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// Get the path of the `Fixture` repository on disk.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Start building a commit on `branch`, on top of its current tip if it
+    /// exists. The files of the parent commit are kept unless they are
+    /// removed.
+    pub fn commit<'a>(&'a self, branch: &str, message: &str) -> CommitBuilder<'a> {
+        CommitBuilder {
+            fixture: self,
+            branch: branch.to_string(),
+            message: message.to_string(),
+            changes: vec![],
+        }
+    }
+
+    /// Point the local branch `name` at the commit `target`, creating the
+    /// branch if needed.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn branch(&self, name: &str, target: Oid) -> Result<(), Error> {
+        let commit = self.repo.0.find_commit(target)?;
+        self.repo.0.branch(name, &commit, true)?;
+        Ok(())
+    }
+
+    /// Tag the commit `target` with `name`. The tag is annotated with the
+    /// `message` if one is given, otherwise it is a lightweight tag.
+    ///
+    /// Returns the `Oid` of the annotated tag object, or of the `target` for a
+    /// lightweight tag.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn tag(&self, name: &str, target: Oid, message: Option<&str>) -> Result<Oid, Error> {
+        let target = self.repo.0.find_object(target, None)?;
+        let oid = match message {
+            Some(message) => self
+                .repo
+                .0
+                .tag(name, &target, &self.signature()?, message, true)?,
+            None => self.repo.0.tag_lightweight(name, &target, true)?,
+        };
+        Ok(oid)
+    }
+
+    /// The signature used for every commit and tag, where the time is moved
+    /// on by a minute for each commit made so far.
+    fn signature(&self) -> Result<git2::Signature<'static>, Error> {
+        let mut revwalk = self.repo.0.revwalk()?;
+        revwalk.push_glob("refs/heads/*")?;
+        let time = git2::Time::new(EPOCH + revwalk.count() as i64 * 60, 0);
+        Ok(git2::Signature::new(
+            "Fixture",
+            "fixture@radicle.xyz",
+            &time,
+        )?)
+    }
+}
+
+/// A new directory for a `Fixture` repository.
+fn temp_path() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "radicle-surf-fixture-{}-{}",
+        process::id(),
+        FIXTURES.fetch_add(1, Ordering::SeqCst)
+    ))
+}
+
+/// A commit being built by [`Fixture::commit`].
+pub struct CommitBuilder<'a> {
+    fixture: &'a Fixture,
+    branch: String,
+    message: String,
+    changes: Vec<(String, Option<Vec<u8>>)>,
+}
+
+impl<'a> CommitBuilder<'a> {
+    /// Create or overwrite the file at `path`, where `path` is relative to the
+    /// root of the repository and uses `/` as the separator.
+    pub fn file(mut self, path: &str, contents: impl AsRef<[u8]>) -> Self {
+        self.changes
+            .push((path.to_string(), Some(contents.as_ref().to_vec())));
+        self
+    }
+
+    /// Remove the file at `path` from the parent commit.
+    pub fn remove(mut self, path: &str) -> Self {
+        self.changes.push((path.to_string(), None));
+        self
+    }
+
+    /// Write the commit and move the branch to it, returning the `Oid` of the
+    /// commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn write(self) -> Result<Oid, Error> {
+        let repo = &self.fixture.repo.0;
+        let refname = format!("refs/heads/{}", self.branch);
+        let parent = match repo.find_reference(&refname) {
+            Ok(reference) => Some(reference.peel_to_commit()?),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut index = git2::Index::new()?;
+        if let Some(parent) = &parent {
+            index.read_tree(&parent.tree()?)?;
+        }
+        for (path, contents) in self.changes {
+            match contents {
+                Some(contents) => {
+                    let id = repo.blob(&contents)?;
+                    index.add(&index_entry(path, id, contents.len()))?;
+                },
+                None => index.remove_path(Path::new(&path))?,
+            }
+        }
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+
+        let signature = self.fixture.signature()?;
+        let parents = parent.iter().collect::<Vec<_>>();
+        let oid = repo.commit(
+            Some(&refname),
+            &signature,
+            &signature,
+            &self.message,
+            &tree,
+            &parents,
+        )?;
+        Ok(oid)
+    }
+}
+
+/// An index entry for a regular file, with the stat information zeroed out
+/// since there is no working copy.
+fn index_entry(path: String, id: Oid, size: usize) -> git2::IndexEntry {
+    // The lower bits of the flags hold the length of the path, up to 0xfff.
+    let flags = path.len().min(0xfff) as u16;
+    git2::IndexEntry {
+        ctime: git2::IndexTime::new(0, 0),
+        mtime: git2::IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: 0o100_644,
+        uid: 0,
+        gid: 0,
+        file_size: size as u32,
+        id,
+        flags,
+        flags_extended: 0,
+        path: path.into_bytes(),
+    }
+}
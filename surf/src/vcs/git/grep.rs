@@ -0,0 +1,119 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! See [`Browser::grep`](crate::vcs::git::Browser::grep).
+
+use crate::file_system;
+use regex::bytes::{Regex, RegexBuilder};
+
+/// How the pattern given to [`Browser::grep`](crate::vcs::git::Browser::grep)
+/// is interpreted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The pattern is a regular expression, in the syntax of the [`regex`]
+    /// crate.
+    #[default]
+    Regex,
+    /// The pattern is matched as it is written.
+    Literal,
+}
+
+/// The options of [`Browser::grep`](crate::vcs::git::Browser::grep).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// How the pattern is interpreted.
+    pub mode: Mode,
+    /// Whether letters match regardless of their case.
+    pub case_insensitive: bool,
+    /// The number of lines of context kept before and after each matching
+    /// line.
+    pub context: usize,
+    /// The maximum number of matches to return, or `None` for all of them.
+    pub max_matches: Option<usize>,
+}
+
+impl Options {
+    pub(crate) fn matcher(&self, pattern: &str) -> Result<Regex, regex::Error> {
+        let pattern = match self.mode {
+            Mode::Regex => pattern.to_string(),
+            Mode::Literal => regex::escape(pattern),
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(self.case_insensitive)
+            .build()
+    }
+}
+
+/// A line of a file that matched the pattern given to
+/// [`Browser::grep`](crate::vcs::git::Browser::grep).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// The path of the file, from the root of the snapshot.
+    pub path: file_system::Path,
+    /// The number of the line, starting at 1.
+    pub line_number: usize,
+    /// The matching line, without its line ending.
+    pub line: String,
+    /// The lines before the matching line, at most
+    /// [`Options::context`] of them.
+    pub before: Vec<String>,
+    /// The lines after the matching line, at most
+    /// [`Options::context`] of them.
+    pub after: Vec<String>,
+}
+
+/// Push the lines of `contents` that match `matcher` onto `matches`, stopping
+/// once there are `limit` of them.
+pub(crate) fn search(
+    path: &file_system::Path,
+    contents: &[u8],
+    matcher: &Regex,
+    options: &Options,
+    limit: usize,
+    matches: &mut Vec<Match>,
+) {
+    let lines = contents
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .collect::<Vec<_>>();
+    // A trailing newline does not start another line.
+    let count = match lines.last() {
+        Some([]) => lines.len() - 1,
+        _ => lines.len(),
+    };
+    let text = |lines: &[&[u8]]| {
+        lines
+            .iter()
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect()
+    };
+
+    for (index, line) in lines[..count].iter().enumerate() {
+        if matches.len() >= limit {
+            return;
+        }
+        if matcher.is_match(line) {
+            matches.push(Match {
+                path: path.clone(),
+                line_number: index + 1,
+                line: String::from_utf8_lossy(line).into_owned(),
+                before: text(&lines[index.saturating_sub(options.context)..index]),
+                after: text(&lines[index + 1..count.min(index + 1 + options.context)]),
+            });
+        }
+    }
+}
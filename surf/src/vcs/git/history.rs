@@ -0,0 +1,425 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    file_system::{self, pathspec::Matcher, Pathspec},
+    vcs::git::{
+        error::Error,
+        shallow::Revwalk,
+        Author,
+        Commit,
+        History,
+        Oid,
+        Ref,
+        RepositoryRef,
+        Rev,
+    },
+};
+use nonempty::NonEmpty;
+use std::{convert::TryFrom, fmt, sync::Arc};
+
+/// Options for walking the history of a [`Browser`](crate::vcs::git::Browser)
+/// with [`Browser::filtered_history`](crate::vcs::git::Browser::filtered_history),
+/// in the spirit of `git log --no-merges -- <paths>`.
+///
+/// The default options keep every commit of the history.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HistoryOptions {
+    /// Leave out commits that have more than one parent.
+    pub skip_merges: bool,
+    /// Only keep the commits that touch a path matching the [`Pathspec`]. A
+    /// commit touches a path if it differs from its first parent there. The
+    /// default `Pathspec` keeps all commits.
+    pub pathspec: Pathspec,
+}
+
+/// Which commits
+/// [`Browser::history_by_author`](crate::vcs::git::Browser::history_by_author)
+/// keeps, by their [`Author`].
+#[derive(Clone)]
+pub enum AuthorMatcher {
+    /// Keep the commits whose author has exactly this email.
+    Email(String),
+    /// Keep the commits whose author has a name containing this string.
+    Name(String),
+    /// Keep the commits whose author the predicate holds for.
+    Predicate(Arc<dyn Fn(&Author) -> bool + Send + Sync>),
+}
+
+impl AuthorMatcher {
+    /// Keep the commits whose author `f` holds for.
+    pub fn predicate<F>(f: F) -> Self
+    where
+        F: Fn(&Author) -> bool + Send + Sync + 'static,
+    {
+        AuthorMatcher::Predicate(Arc::new(f))
+    }
+
+    /// Check whether the `author` matches.
+    pub fn matches(&self, author: &Author) -> bool {
+        match self {
+            AuthorMatcher::Email(email) => author.email == *email,
+            AuthorMatcher::Name(name) => author.name.contains(name.as_str()),
+            AuthorMatcher::Predicate(f) => f(author),
+        }
+    }
+}
+
+impl fmt::Debug for AuthorMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthorMatcher::Email(email) => f.debug_tuple("Email").field(email).finish(),
+            AuthorMatcher::Name(name) => f.debug_tuple("Name").field(name).finish(),
+            AuthorMatcher::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+/// Matchers are equal if they match on the same email or name. A
+/// [`AuthorMatcher::Predicate`] is only equal to its clones, since functions
+/// cannot be compared.
+impl PartialEq for AuthorMatcher {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (AuthorMatcher::Email(a), AuthorMatcher::Email(b)) => a == b,
+            (AuthorMatcher::Name(a), AuthorMatcher::Name(b)) => a == b,
+            (AuthorMatcher::Predicate(a), AuthorMatcher::Predicate(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for AuthorMatcher {}
+
+/// A reference and the commit it points to, from which its
+/// [`History`](crate::vcs::git::History) can be built on demand with
+/// [`Browser::load_history`](crate::vcs::git::Browser::load_history).
+///
+/// Listing handles with
+/// [`Browser::history_handles`](crate::vcs::git::Browser::history_handles)
+/// only peels each reference, rather than walking the history of each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryHandle {
+    /// The reference, e.g. `refs/heads/master`, named in the same way as the
+    /// reference of a [`NamedHistory`].
+    pub name: Ref,
+    /// The commit the reference pointed to when it was listed.
+    pub tip: Oid,
+}
+
+/// The [`History`](crate::vcs::git::History) of a reference, along with
+/// that reference, so that histories listed with
+/// [`Browser::get_named_histories`](crate::vcs::git::Browser::get_named_histories)
+/// can be told apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedHistory {
+    /// The reference, e.g. `refs/heads/master`.
+    pub name: Ref,
+    /// The history starting at the commit the reference points to.
+    pub history: History,
+}
+
+/// A path a file was known by, and the range of the history it had that path
+/// for, see [`RenameChain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameLink {
+    /// The path of the file.
+    pub path: file_system::Path,
+    /// The oldest commit with the file at this path, i.e. the one that added
+    /// the file, or renamed it to this path. In a shallow clone, this may be
+    /// the oldest commit that was fetched instead.
+    pub since: Oid,
+    /// The commit that renamed the file away from this path, or `None` for
+    /// the path the file has at the head of the history.
+    pub until: Option<Oid>,
+}
+
+/// The paths a file was known by over its history, as found by
+/// [`Browser::follow_file_history`](crate::vcs::git::Browser::follow_file_history),
+/// so that it can be shown as e.g. "previously known as `examples/memory.rs`
+/// (until `e24124b`)".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameChain(NonEmpty<RenameLink>);
+
+impl RenameChain {
+    pub(super) fn new(links: NonEmpty<RenameLink>) -> Self {
+        RenameChain(links)
+    }
+
+    /// The path the file has at the head of the history.
+    pub fn current(&self) -> &RenameLink {
+        self.0.first()
+    }
+
+    /// The paths the file had before it was renamed, newest first.
+    pub fn previous(&self) -> &[RenameLink] {
+        self.0.tail()
+    }
+
+    /// Iterate over every path of the file, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &RenameLink> {
+        self.0.iter()
+    }
+
+    /// Whether the file was ever renamed.
+    pub fn is_renamed(&self) -> bool {
+        !self.0.tail().is_empty()
+    }
+}
+
+/// The order in which a [`HistoryBuilder`] walks the commits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryOrder {
+    /// The order of libgit2's revwalk, which is the order of the `History`
+    /// of a [`Browser`](crate::vcs::git::Browser).
+    #[default]
+    Revwalk,
+    /// No parent comes before any of its children.
+    Topological,
+    /// The newest commits, by commit time, come first.
+    Time,
+}
+
+impl From<HistoryOrder> for git2::Sort {
+    fn from(order: HistoryOrder) -> Self {
+        match order {
+            HistoryOrder::Revwalk => git2::Sort::NONE,
+            HistoryOrder::Topological => git2::Sort::TOPOLOGICAL,
+            HistoryOrder::Time => git2::Sort::TIME,
+        }
+    }
+}
+
+/// A description of a [`History`](crate::vcs::git::History), which is built
+/// with [`Browser::build_history`](crate::vcs::git::Browser::build_history),
+/// or walked lazily with
+/// [`Browser::history_iter`](crate::vcs::git::Browser::history_iter), in the
+/// spirit of `git log`.
+///
+/// The history contains the commits reachable from any of the pushed
+/// revisions, but not from any of the hidden ones, e.g. pushing `dev` and
+/// hiding `master` gives the commits on `dev` that are not on `master`. If
+/// nothing is pushed, `HEAD` is. Of those, only the commits that pass every
+/// filter are kept, up to the limit, if any.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::file_system::Pathspec;
+/// use radicle_surf::vcs::git::{
+///     AuthorMatcher,
+///     Branch,
+///     Browser,
+///     HistoryBuilder,
+///     HistoryOrder,
+///     Oid,
+///     Repository,
+/// };
+/// use std::str::FromStr;
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = Repository::new("./data/git-platinum")?;
+/// let browser = Browser::new(&repo, Branch::local("master"))?;
+///
+/// let builder = HistoryBuilder::new()
+///     .push(Branch::local("master"))
+///     .order(HistoryOrder::Topological)
+///     .pathspec(Pathspec::default().include("src")?)
+///     .author(AuthorMatcher::Email("rudolfs@osins.org".into()))
+///     .skip_merges(true)
+///     .limit(2);
+///
+/// let ids = browser
+///     .history_iter(&builder)?
+///     .map(|commit| commit.map(|commit| commit.id))
+///     .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(ids, vec![
+///     Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e")?,
+///     Oid::from_str("e24124b7538658220b5aaf3b6ef53758f0a106dc")?,
+/// ]);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HistoryBuilder {
+    pub(crate) push: Vec<Rev>,
+    pub(crate) hide: Vec<Rev>,
+    pub(crate) order: HistoryOrder,
+    limit: Option<usize>,
+    skip_merges: bool,
+    pathspec: Pathspec,
+    author: Option<AuthorMatcher>,
+}
+
+impl HistoryBuilder {
+    /// Create a builder that has nothing pushed or hidden, and keeps every
+    /// commit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include the commits reachable from `rev`.
+    pub fn push(mut self, rev: impl Into<Rev>) -> Self {
+        self.push.push(rev.into());
+        self
+    }
+
+    /// Exclude the commits reachable from `rev`, even if they are reachable
+    /// from a pushed revision.
+    pub fn hide(mut self, rev: impl Into<Rev>) -> Self {
+        self.hide.push(rev.into());
+        self
+    }
+
+    /// Walk the commits in the given order.
+    pub fn order(mut self, order: HistoryOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Keep at most `limit` commits, like `git log -n <limit>`.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Leave out commits that have more than one parent.
+    pub fn skip_merges(mut self, skip_merges: bool) -> Self {
+        self.skip_merges = skip_merges;
+        self
+    }
+
+    /// Only keep the commits that touch a path matching the `pathspec`, in the
+    /// same way as [`HistoryOptions::pathspec`].
+    pub fn pathspec(mut self, pathspec: Pathspec) -> Self {
+        self.pathspec = pathspec;
+        self
+    }
+
+    /// Only keep the commits whose author matches, after the mailmap is
+    /// applied, if it is enabled.
+    pub fn author(mut self, author: AuthorMatcher) -> Self {
+        self.author = Some(author);
+        self
+    }
+}
+
+impl From<&HistoryOptions> for HistoryBuilder {
+    fn from(options: &HistoryOptions) -> Self {
+        HistoryBuilder::new()
+            .skip_merges(options.skip_merges)
+            .pathspec(options.pathspec.clone())
+    }
+}
+
+/// The commits of a [`HistoryBuilder`], which are loaded as the iterator is
+/// advanced, see
+/// [`Browser::history_iter`](crate::vcs::git::Browser::history_iter).
+///
+/// The iterator yields an error, rather than stopping, if a commit cannot
+/// be loaded or the deadline of the `Browser` passes.
+pub struct HistoryIter<'r, 'repo> {
+    repository: &'r RepositoryRef<'repo>,
+    revwalk: Revwalk<'repo>,
+    builder: HistoryBuilder,
+    matcher: Matcher,
+    remaining: Option<usize>,
+}
+
+impl<'r, 'repo> fmt::Debug for HistoryIter<'r, 'repo> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HistoryIter")
+            .field("builder", &self.builder)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+impl<'r, 'repo> HistoryIter<'r, 'repo> {
+    pub(super) fn new(
+        repository: &'r RepositoryRef<'repo>,
+        revwalk: Revwalk<'repo>,
+        builder: &HistoryBuilder,
+    ) -> Self {
+        HistoryIter {
+            repository,
+            revwalk,
+            builder: builder.clone(),
+            matcher: builder.pathspec.matcher(),
+            remaining: builder.limit,
+        }
+    }
+
+    /// Load the commit `oid` if it passes the filters of the builder.
+    fn keep(&self, oid: Oid) -> Result<Option<Commit>, Error> {
+        self.repository.check_deadline()?;
+        let commit = self.repository.repo_ref.find_commit(oid)?;
+        if self.builder.skip_merges && commit.parent_count() > 1 {
+            return Ok(None);
+        }
+
+        // The author is matched before the rest of the commit is converted, so
+        // the commits of other authors are skipped cheaply.
+        if let Some(matcher) = &self.builder.author {
+            let author = match &self.repository.mailmap {
+                Some(mailmap) => mailmap.author(&commit)?,
+                None => Author::try_from(commit.author())?,
+            };
+            if !matcher.matches(&author) {
+                return Ok(None);
+            }
+        }
+
+        let pathspec = &self.builder.pathspec;
+        if !pathspec.is_everything()
+            && !self.repository.touches(pathspec, &self.matcher, &commit)?
+        {
+            return Ok(None);
+        }
+
+        self.repository.to_commit(commit).map(Some)
+    }
+}
+
+impl<'r, 'repo> Iterator for HistoryIter<'r, 'repo> {
+    type Item = Result<Commit, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+
+        loop {
+            let kept = match self.revwalk.next()? {
+                Ok(oid) => self.keep(oid),
+                Err(err) => Err(err.into()),
+            };
+            match kept {
+                Ok(None) => continue,
+                Ok(Some(commit)) => {
+                    if let Some(remaining) = self.remaining.as_mut() {
+                        *remaining -= 1;
+                    }
+                    return Some(Ok(commit));
+                },
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! With the index, searching commit messages and authors, and finding the
+//! paths a commit touched, does not need to walk the history each time.
+//!
+//! The [`Index`](crate::vcs::git::index::Index) is stored with [`sled`] and is
+//! updated incrementally: each call to
+//! [`Index::update`](crate::vcs::git::index::Index::update) only visits the
+//! commits that are not reachable from a head that was indexed before.
+
+use crate::{
+    file_system,
+    vcs::git::{self, Oid, RepositoryRef},
+};
+use std::{
+    collections::BTreeSet,
+    convert::{TryFrom, TryInto},
+    path,
+    str,
+};
+use thiserror::Error;
+
+/// The `sled` tree mapping the commits that were indexed to their time.
+const COMMITS: &str = "commits";
+/// The `sled` tree mapping the commits to the paths they touched.
+const PATHS: &str = "paths";
+/// The `sled` tree of the words of the commits, each followed by the commit.
+const WORDS: &str = "words";
+/// The `sled` tree of the heads that were indexed.
+const HEADS: &str = "heads";
+
+/// The length of an [`Oid`] in bytes.
+const OID_LEN: usize = 20;
+
+/// Errors that can occur when building or querying an [`Index`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error occurred when reading the repository.
+    #[error(transparent)]
+    Git(#[from] git::error::Error),
+    /// An error occurred in the storage of the index.
+    #[error(transparent)]
+    Storage(#[from] sled::Error),
+    /// An entry of the index could not be decoded.
+    #[error("the index entry for '{0}' is corrupt")]
+    Corrupt(String),
+}
+
+impl From<git2::Error> for Error {
+    fn from(err: git2::Error) -> Self {
+        Error::Git(err.into())
+    }
+}
+
+impl From<file_system::Error> for Error {
+    fn from(err: file_system::Error) -> Self {
+        Error::Git(err.into())
+    }
+}
+
+/// A persistent index of commit messages, authors, and touched paths.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::file_system::unsound;
+/// use radicle_surf::vcs::git::{index::Index, Oid, Repository};
+/// use std::str::FromStr;
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = Repository::new("./data/git-platinum")?;
+/// let index = Index::temporary()?;
+///
+/// let head = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+/// assert_eq!(index.update(&repo.as_ref(), head)?, 15);
+/// // Everything reachable from `head` is already indexed.
+/// assert_eq!(index.update(&repo.as_ref(), head)?, 0);
+///
+/// let commits = index.search_commits("updated readme")?;
+/// assert!(commits.contains(&Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?));
+///
+/// let paths = index.paths_touched_by(Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5")?)?;
+/// assert_eq!(paths, Some(vec![unsound::path::new("~/README.md")]));
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct Index {
+    db: sled::Db,
+    commits: sled::Tree,
+    paths: sled::Tree,
+    words: sled::Tree,
+    heads: sled::Tree,
+}
+
+impl Index {
+    /// Open the index stored at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<path::Path>) -> Result<Self, Error> {
+        Self::from_db(sled::open(path)?)
+    }
+
+    /// Open an index that is removed when it is dropped.
+    pub fn temporary() -> Result<Self, Error> {
+        Self::from_db(sled::Config::new().temporary(true).open()?)
+    }
+
+    fn from_db(db: sled::Db) -> Result<Self, Error> {
+        Ok(Index {
+            commits: db.open_tree(COMMITS)?,
+            paths: db.open_tree(PATHS)?,
+            words: db.open_tree(WORDS)?,
+            heads: db.open_tree(HEADS)?,
+            db,
+        })
+    }
+
+    /// Index the commits reachable from `head` that have not been indexed
+    /// yet, returning how many were added.
+    ///
+    /// The history behind the heads of earlier updates is not walked again,
+    /// so keeping the index up to date with a branch is proportional to the
+    /// number of new commits.
+    pub fn update(&self, repo: &RepositoryRef, head: Oid) -> Result<usize, Error> {
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(head)?;
+
+        let mut previous = vec![];
+        for entry in self.heads.iter() {
+            let (key, _) = entry?;
+            let oid = decode_oid(&key)?;
+            match revwalk.hide(oid) {
+                Ok(()) => previous.push(oid),
+                // The head may have been garbage collected since.
+                Err(err) if err.code() == git2::ErrorCode::NotFound => {},
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let mut added = 0;
+        for oid in revwalk {
+            let oid = oid?;
+            repo.check_deadline()?;
+            if !self.commits.contains_key(oid.as_bytes())? {
+                self.insert(repo, oid)?;
+                added += 1;
+            }
+        }
+
+        // Heads that are behind the new one are covered by it.
+        for oid in previous {
+            if oid == head || repo.repo_ref.graph_descendant_of(head, oid)? {
+                self.heads.remove(oid.as_bytes())?;
+            }
+        }
+        self.heads.insert(head.as_bytes(), &[])?;
+        self.db.flush()?;
+
+        Ok(added)
+    }
+
+    /// Find the commits whose message or author contains all the words of
+    /// the `query`, the most recent first.
+    ///
+    /// Words are compared case-insensitively, and anything that is not a
+    /// letter or a digit separates them.
+    pub fn search_commits(&self, query: &str) -> Result<Vec<Oid>, Error> {
+        let mut matches: Option<BTreeSet<Oid>> = None;
+        for word in words(query) {
+            let mut prefix = word.into_bytes();
+            prefix.push(0);
+
+            let mut found = BTreeSet::new();
+            for entry in self.words.scan_prefix(&prefix) {
+                let (key, _) = entry?;
+                found.insert(decode_oid(&key[prefix.len()..])?);
+            }
+
+            matches = Some(match matches {
+                None => found,
+                Some(matches) => matches.intersection(&found).copied().collect(),
+            });
+        }
+
+        let mut commits = vec![];
+        for oid in matches.unwrap_or_default() {
+            // A commit whose insert was interrupted, or is still going on, has
+            // words but no time yet. It is left out until it is indexed.
+            if let Some(time) = self.time(oid)? {
+                commits.push((time, oid));
+            }
+        }
+        commits.sort_by(|a, b| b.cmp(a));
+        Ok(commits.into_iter().map(|(_, oid)| oid).collect())
+    }
+
+    /// Get the paths that the commit `oid` touched, compared to its first
+    /// parent, or `None` if it has not been indexed.
+    pub fn paths_touched_by(&self, oid: Oid) -> Result<Option<Vec<file_system::Path>>, Error> {
+        let paths = match self.paths.get(oid.as_bytes())? {
+            None => return Ok(None),
+            Some(paths) => paths,
+        };
+
+        paths
+            .split(|byte| *byte == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| {
+                let path = str::from_utf8(path).map_err(|_| Error::Corrupt(oid.to_string()))?;
+                Ok(file_system::Path::try_from(path::PathBuf::from(path))?)
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+
+    /// Index the commit `oid`.
+    ///
+    /// Its entry in the commits tree is written last, so a commit that was
+    /// interrupted half way is indexed again by the next update.
+    fn insert(&self, repo: &RepositoryRef, oid: Oid) -> Result<(), Error> {
+        let commit = repo.repo_ref.find_commit(oid)?;
+        let author = commit.author();
+
+        let text = [
+            commit.message_bytes(),
+            author.name_bytes(),
+            author.email_bytes(),
+        ];
+        let mut batch = sled::Batch::default();
+        for word in text
+            .iter()
+            .flat_map(|text| words(&String::from_utf8_lossy(text)).collect::<Vec<_>>())
+            .collect::<BTreeSet<_>>()
+        {
+            let mut key = word.into_bytes();
+            key.push(0);
+            key.extend_from_slice(oid.as_bytes());
+            batch.insert(key, &[]);
+        }
+        self.words.apply_batch(batch)?;
+
+        let parent = commit.parent_ids().next();
+        let mut opts = git2::DiffOptions::new();
+        opts.skip_binary_check(true);
+        let diff = repo.diff_trees(parent, oid, &mut opts)?;
+        let mut paths = vec![];
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+                let path = file_system::Path::try_from(path.to_path_buf())?;
+                paths.extend_from_slice(path.to_string().as_bytes());
+                paths.push(0);
+            }
+        }
+        self.paths.insert(oid.as_bytes(), paths)?;
+
+        self.commits
+            .insert(oid.as_bytes(), &commit.time().seconds().to_be_bytes())?;
+        Ok(())
+    }
+
+    /// The time of the commit `oid`, or `None` if it has not been indexed.
+    fn time(&self, oid: Oid) -> Result<Option<i64>, Error> {
+        let time = match self.commits.get(oid.as_bytes())? {
+            None => return Ok(None),
+            Some(time) => time,
+        };
+        let time = time
+            .as_ref()
+            .try_into()
+            .map_err(|_| Error::Corrupt(oid.to_string()))?;
+        Ok(Some(i64::from_be_bytes(time)))
+    }
+}
+
+/// Split `text` into lowercase words of letters and digits.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn decode_oid(bytes: &[u8]) -> Result<Oid, Error> {
+    if bytes.len() != OID_LEN {
+        return Err(Error::Corrupt(String::from_utf8_lossy(bytes).into_owned()));
+    }
+    Ok(Oid::from_bytes(bytes)?)
+}
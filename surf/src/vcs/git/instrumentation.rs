@@ -0,0 +1,77 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The operations of a [`Browser`](crate::vcs::git::Browser) whose latency is
+/// reported to the callback given to
+/// [`Browser::with_instrumentation`](crate::vcs::git::Browser::with_instrumentation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// Building a [`History`](crate::vcs::git::History) by walking the commits
+    /// of a revision.
+    History,
+    /// Rendering a [`Directory`](crate::file_system::Directory) snapshot of a
+    /// commit.
+    Snapshot,
+    /// Finding the last commit that touched a path.
+    LastCommit,
+    /// Finding all the commits that touched a path.
+    FileHistory,
+    /// Computing and converting a [`Diff`](crate::diff::Diff) between two
+    /// commits.
+    Diff,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operation::History => "history",
+            Operation::Snapshot => "snapshot",
+            Operation::LastCommit => "last_commit",
+            Operation::FileHistory => "file_history",
+            Operation::Diff => "diff",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A callback that is told how long each [`Operation`] took.
+#[derive(Clone)]
+pub(crate) struct Instrumentation(Arc<dyn Fn(Operation, Duration) + Send + Sync>);
+
+impl Instrumentation {
+    pub(crate) fn new<F>(callback: F) -> Self
+    where
+        F: Fn(Operation, Duration) + Send + Sync + 'static,
+    {
+        Instrumentation(Arc::new(callback))
+    }
+
+    /// Run `f`, reporting how long it took as the `operation`.
+    pub(crate) fn measure<T>(&self, operation: Operation, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        (self.0)(operation, start.elapsed());
+        result
+    }
+}
@@ -0,0 +1,90 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::file_system;
+use thiserror::Error;
+
+/// Limits on the size of the snapshots a
+/// [`Browser`](crate::vcs::git::Browser) renders, set with
+/// [`Browser::set_limits`](crate::vcs::git::Browser::set_limits).
+///
+/// A limit of `None` is not enforced, which is the default for all of them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of files in a snapshot.
+    pub max_files: Option<usize>,
+    /// The maximum sum of the sizes of the files in a snapshot, in bytes.
+    pub max_total_bytes: Option<usize>,
+    /// The maximum size of a single file in a snapshot, in bytes.
+    pub max_blob_size: Option<usize>,
+}
+
+impl Limits {
+    /// Fail with [`LimitExceeded::BlobSize`] if a blob of `size` bytes at
+    /// `path` is over the `max_blob_size`.
+    pub(crate) fn check_blob(
+        &self,
+        path: &file_system::Path,
+        size: usize,
+    ) -> Result<(), LimitExceeded> {
+        match self.max_blob_size {
+            Some(limit) if size > limit => Err(LimitExceeded::BlobSize {
+                path: path.clone(),
+                size,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail with [`LimitExceeded::Files`] or [`LimitExceeded::TotalBytes`] if
+    /// a snapshot of `files` files, totalling `bytes`, is over the limits.
+    pub(crate) fn check_snapshot(&self, files: usize, bytes: usize) -> Result<(), LimitExceeded> {
+        match (self.max_files, self.max_total_bytes) {
+            (Some(limit), _) if files > limit => Err(LimitExceeded::Files { limit }),
+            (_, Some(limit)) if bytes > limit => Err(LimitExceeded::TotalBytes { limit }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The [`Limits`] that a snapshot went over.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LimitExceeded {
+    /// The snapshot has more than `limit` files.
+    #[error("the snapshot has more than {limit} files")]
+    Files {
+        /// The `max_files` that was exceeded.
+        limit: usize,
+    },
+    /// The files of the snapshot total more than `limit` bytes.
+    #[error("the snapshot is larger than {limit} bytes")]
+    TotalBytes {
+        /// The `max_total_bytes` that was exceeded.
+        limit: usize,
+    },
+    /// The file at `path` is larger than `limit` bytes.
+    #[error("the file '{path}' is {size} bytes, which is larger than {limit} bytes")]
+    BlobSize {
+        /// The path of the file.
+        path: file_system::Path,
+        /// The size of the file, in bytes.
+        size: usize,
+        /// The `max_blob_size` that was exceeded.
+        limit: usize,
+    },
+}
@@ -0,0 +1,42 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::vcs::git::{error::Error, Author};
+use std::{convert::TryFrom, rc::Rc};
+
+/// The mailmap of a repository, which maps the names and emails that people
+/// used in their commits to the ones they want to be known by.
+#[derive(Clone)]
+pub(super) struct Mailmap(Rc<git2::Mailmap>);
+
+impl Mailmap {
+    /// Load the mailmap of `repo`, from its `.mailmap` file and the
+    /// `mailmap.file` and `mailmap.blob` configuration.
+    pub(super) fn load(repo: &git2::Repository) -> Result<Self, Error> {
+        Ok(Mailmap(Rc::new(repo.mailmap()?)))
+    }
+
+    /// The author of `commit`, as mapped by the mailmap.
+    pub(super) fn author(&self, commit: &git2::Commit) -> Result<Author, Error> {
+        Ok(Author::try_from(commit.author_with_mailmap(&self.0)?)?)
+    }
+
+    /// The committer of `commit`, as mapped by the mailmap.
+    pub(super) fn committer(&self, commit: &git2::Commit) -> Result<Author, Error> {
+        Ok(Author::try_from(commit.committer_with_mailmap(&self.0)?)?)
+    }
+}
@@ -0,0 +1,106 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{diff::Diff, file_system::Path, vcs::git::Commit};
+
+/// The outcome of applying a commit onto another commit in memory, e.g. by
+/// [`RepositoryRef::cherry_pick`](super::RepositoryRef::cherry_pick) or
+/// [`RepositoryRef::revert`](super::RepositoryRef::revert).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    /// The changes that applied cleanly, relative to the commit they were
+    /// applied onto.
+    pub diff: Diff,
+    /// The paths of the files that could not be merged. These are left out of
+    /// the `diff`.
+    pub conflicts: Vec<Path>,
+}
+
+impl MergeResult {
+    /// Check whether all the changes applied cleanly.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// How a merge commit came about, see
+/// [`Browser::merge_info`](super::Browser::merge_info).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeInfo {
+    /// The parent that was merged in, i.e. the tip of the branch that was
+    /// merged.
+    pub merged: Commit,
+    /// The name of the branch that was merged in, if it could be derived from
+    /// the merge commit's message or from a branch that still points at
+    /// `merged`.
+    pub branch: Option<String>,
+}
+
+/// Find the name of the merged branch in a merge commit message, as written by
+/// `git merge` or by merging a pull request on a code hosting site.
+pub(crate) fn merged_branch(message: &str) -> Option<String> {
+    let summary = message.lines().next()?;
+    let branch = match summary.strip_prefix("Merge pull request #") {
+        Some(request) => {
+            let (number, from) = request.split_once(" from ")?;
+            if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            from.split(char::is_whitespace).next()?
+        },
+        None => {
+            let merge = summary.strip_prefix("Merge ")?;
+            let merge = merge.strip_prefix("remote-tracking ").unwrap_or(merge);
+            let (branch, _) = merge.strip_prefix("branch '")?.split_once('\'')?;
+            branch
+        },
+    };
+    if branch.is_empty() {
+        None
+    } else {
+        Some(branch.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_branch() {
+        assert_eq!(
+            merged_branch("Merge branch 'feature/dev'"),
+            Some("feature/dev".to_string())
+        );
+        assert_eq!(
+            merged_branch("Merge branch 'dev' into master\n\nConflicts: none"),
+            Some("dev".to_string())
+        );
+        assert_eq!(
+            merged_branch("Merge remote-tracking branch 'origin/dev'"),
+            Some("origin/dev".to_string())
+        );
+        assert_eq!(
+            merged_branch("Merge pull request #4 from FintanH/fintan/update-readme"),
+            Some("FintanH/fintan/update-readme".to_string())
+        );
+        assert_eq!(merged_branch("Merge tag 'v0.1.0'"), None);
+        assert_eq!(merged_branch("Merge branch ''"), None);
+        assert_eq!(merged_branch("Merge pull request #x from a/b"), None);
+        assert_eq!(merged_branch("Fix the merge of branches"), None);
+    }
+}
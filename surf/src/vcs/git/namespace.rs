@@ -15,10 +15,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::vcs::git::error::Error;
+use crate::vcs::git::{error::Error, repo::RepositoryRef};
 pub use git2::Oid;
 use nonempty::NonEmpty;
-use std::{convert::TryFrom, fmt, str};
+use std::{collections::BTreeSet, convert::TryFrom, fmt, str};
 
 /// A `Namespace` value allows us to switch the git namespace of
 /// [`super::Browser`].
@@ -30,6 +30,90 @@ pub struct Namespace {
     pub(super) values: NonEmpty<String>,
 }
 
+impl Namespace {
+    /// The namespace this one is nested in, e.g. the parent of `foo/bar` is
+    /// `foo`. Top-level namespaces have no parent.
+    pub fn parent(&self) -> Option<Self> {
+        let mut values: Vec<String> = self.values.clone().into();
+        values.pop();
+        NonEmpty::from_vec(values).map(|values| Self { values })
+    }
+
+    /// The namespaces of `repo` that are nested directly within this one,
+    /// in sorted order.
+    ///
+    /// A child is included even if it only contains further nested
+    /// namespaces, e.g. `foo/bar` is a child of `foo` when only
+    /// `foo/bar/baz` holds any references.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn children<'a>(&self, repo: impl Into<RepositoryRef<'a>>) -> Result<Vec<Self>, Error> {
+        Ok(with_ancestors(repo.into().list_namespaces()?)
+            .into_iter()
+            .filter(|namespace| namespace.parent().as_ref() == Some(self))
+            .collect())
+    }
+}
+
+/// A [`Namespace`] along with the namespaces nested within it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceTree {
+    /// The namespace at this point of the hierarchy.
+    pub namespace: Namespace,
+    /// The namespaces nested directly within `namespace`, in sorted order.
+    pub children: Vec<NamespaceTree>,
+}
+
+impl NamespaceTree {
+    /// Arrange `namespaces` into their hierarchy, returning the top-level
+    /// namespaces in sorted order. Intermediate namespaces that are missing
+    /// from `namespaces` are filled in.
+    pub fn build(namespaces: impl IntoIterator<Item = Namespace>) -> Vec<Self> {
+        let all = with_ancestors(namespaces);
+        all.iter()
+            .filter(|namespace| namespace.values.len() == 1)
+            .map(|namespace| Self::subtree(namespace, &all))
+            .collect()
+    }
+
+    fn subtree(namespace: &Namespace, all: &BTreeSet<Namespace>) -> Self {
+        let children = all
+            .iter()
+            .filter(|child| child.parent().as_ref() == Some(namespace))
+            .map(|child| Self::subtree(child, all))
+            .collect();
+        Self {
+            namespace: namespace.clone(),
+            children,
+        }
+    }
+
+    /// Iterate over the namespaces of the tree, parents before their
+    /// children.
+    pub fn iter(&self) -> impl Iterator<Item = &Namespace> + '_ {
+        let children: Box<dyn Iterator<Item = &Namespace>> =
+            Box::new(self.children.iter().flat_map(|child| child.iter()));
+        std::iter::once(&self.namespace).chain(children)
+    }
+}
+
+/// Collect `namespaces` along with every namespace they are nested in.
+fn with_ancestors(namespaces: impl IntoIterator<Item = Namespace>) -> BTreeSet<Namespace> {
+    let mut all = BTreeSet::new();
+    for namespace in namespaces {
+        let mut next = Some(namespace);
+        while let Some(namespace) = next {
+            next = namespace.parent();
+            if !all.insert(namespace) {
+                break;
+            }
+        }
+    }
+    all
+}
+
 impl fmt::Display for Namespace {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let values: Vec<_> = self.values.clone().into();
@@ -0,0 +1,80 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::vcs::git::{Oid, Rev};
+use std::collections::VecDeque;
+
+/// How many views a `Browser` remembers for going back to, after which the
+/// oldest are forgotten.
+pub(super) const DEPTH: usize = 100;
+
+/// What a `Browser` is viewing: the head of its `History`, and the revision
+/// that it was loaded from, if any. The `History` is walked again from the
+/// head when the view is returned to, rather than being kept around.
+#[derive(Clone)]
+pub(super) struct View {
+    pub(super) head: Oid,
+    pub(super) rev: Option<Rev>,
+}
+
+/// The views a `Browser` can go back and forward to, like the history of a
+/// web browser.
+#[derive(Clone, Default)]
+pub(super) struct Navigation {
+    back: VecDeque<View>,
+    forward: Vec<View>,
+}
+
+impl Navigation {
+    /// Remember `view` for going back to, forgetting the views that could be
+    /// gone forward to.
+    pub(super) fn push(&mut self, view: View) {
+        self.remember(view);
+        self.forward.clear();
+    }
+
+    /// The view that [`Navigation::back`] leaves for, if there is one.
+    pub(super) fn previous(&self) -> Option<&View> {
+        self.back.back()
+    }
+
+    /// The view that [`Navigation::forward`] leaves for, if there is one.
+    pub(super) fn next(&self) -> Option<&View> {
+        self.forward.last()
+    }
+
+    /// Leave `current` for the previous view, if there is one.
+    pub(super) fn back(&mut self, current: View) -> Option<View> {
+        let previous = self.back.pop_back()?;
+        self.forward.push(current);
+        Some(previous)
+    }
+
+    /// Leave `current` for the next view, if there is one.
+    pub(super) fn forward(&mut self, current: View) -> Option<View> {
+        let next = self.forward.pop()?;
+        self.remember(current);
+        Some(next)
+    }
+
+    fn remember(&mut self, view: View) {
+        if self.back.len() == DEPTH {
+            self.back.pop_front();
+        }
+        self.back.push_back(view);
+    }
+}
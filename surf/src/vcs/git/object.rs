@@ -0,0 +1,112 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    file_system::directory::File,
+    vcs::git::{error::Error, Commit, Tag},
+};
+use git2::{ObjectType, Oid};
+use std::convert::TryFrom;
+
+/// A git object that a revspec resolved to, see
+/// [`crate::vcs::git::RepositoryRef::revparse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevObject {
+    /// The revspec resolved to a commit, e.g. `master` or `HEAD~3`.
+    Commit(Commit),
+    /// The revspec resolved to a tree, e.g. `master:src` or `master^{tree}`.
+    Tree {
+        /// The Object ID of the tree.
+        id: Oid,
+    },
+    /// The revspec resolved to a blob, e.g. `master:src/lib.rs`.
+    Blob {
+        /// The Object ID of the blob.
+        id: Oid,
+        /// The contents of the blob.
+        file: File,
+    },
+    /// The revspec resolved to an annotated tag, e.g. `v1.0.0` when the tag
+    /// is annotated.
+    Tag(Tag),
+}
+
+impl RevObject {
+    /// Get the [`Oid`] of the object, regardless of its type.
+    pub fn id(&self) -> Oid {
+        match self {
+            Self::Commit(commit) => commit.id,
+            Self::Tree { id } => *id,
+            Self::Blob { id, .. } => *id,
+            Self::Tag(tag) => tag.id(),
+        }
+    }
+
+    /// Get the [`ObjectType`] of the object.
+    pub fn kind(&self) -> ObjectType {
+        match self {
+            Self::Commit(_) => ObjectType::Commit,
+            Self::Tree { .. } => ObjectType::Tree,
+            Self::Blob { .. } => ObjectType::Blob,
+            Self::Tag(_) => ObjectType::Tag,
+        }
+    }
+
+    /// Get the [`Commit`] if the object is a commit.
+    pub fn into_commit(self) -> Option<Commit> {
+        match self {
+            Self::Commit(commit) => Some(commit),
+            _ => None,
+        }
+    }
+
+    /// Get the [`File`] contents if the object is a blob.
+    pub fn into_file(self) -> Option<File> {
+        match self {
+            Self::Blob { file, .. } => Some(file),
+            _ => None,
+        }
+    }
+}
+
+impl<'repo> TryFrom<git2::Object<'repo>> for RevObject {
+    type Error = Error;
+
+    fn try_from(object: git2::Object) -> Result<Self, Self::Error> {
+        let id = object.id();
+        match object.kind() {
+            Some(ObjectType::Commit) => {
+                Ok(Self::Commit(Commit::try_from(object.peel_to_commit()?)?))
+            },
+            Some(ObjectType::Tree) => Ok(Self::Tree { id }),
+            Some(ObjectType::Blob) => {
+                let blob = object.peel_to_blob()?;
+                Ok(Self::Blob {
+                    id,
+                    file: File::new(blob.content()),
+                })
+            },
+            Some(ObjectType::Tag) => Ok(Self::Tag(Tag::try_from(object.peel_to_tag()?)?)),
+            // `Any` is only used as an argument to lookups, an object always has a
+            // concrete type.
+            Some(ObjectType::Any) | None => Err(Error::Git(git2::Error::from_str(&format!(
+                "object {} has an unknown type",
+                id
+            )))),
+        }
+    }
+}
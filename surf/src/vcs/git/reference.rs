@@ -19,17 +19,62 @@ use std::{fmt, str};
 
 use thiserror::Error;
 
-use crate::vcs::git::{repo::RepositoryRef, BranchName, Namespace, TagName};
+use crate::vcs::git::{error, repo::RepositoryRef, BranchName, Namespace, TagName};
 
 pub(super) mod glob;
 
-/// A revision within the repository.
+/// A revision that can be used to select a [`crate::vcs::git::History`].
+///
+/// A `Rev` can be parsed from a string using its `FromStr` instance:
+///   * a fully qualified reference, e.g. `refs/heads/master` or
+///     `refs/namespaces/golden/refs/tags/v0.1.0`, becomes a [`Rev::Ref`].
+///   * a full, 40 character, object id becomes a [`Rev::Oid`].
+///   * anything else, e.g. `master`, `v0.1.0`, `HEAD~3`, or `3873745`, becomes
+///     a [`Rev::Spec`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Rev {
     /// A reference to a branch or tag.
     Ref(Ref),
     /// A particular commit identifier.
     Oid(git2::Oid),
+    /// A [revspec](https://git-scm.com/docs/git-rev-parse.html#_specifying_revisions),
+    /// e.g. a branch or tag name, `HEAD~3`, or a short commit identifier. The
+    /// revspec is resolved against the repository when it is used.
+    Spec(String),
+}
+
+impl fmt::Display for Rev {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ref(reference) => write!(f, "{}", reference),
+            Self::Oid(oid) => write!(f, "{}", oid),
+            Self::Spec(spec) => write!(f, "{}", spec),
+        }
+    }
+}
+
+impl str::FromStr for Rev {
+    type Err = ParseError;
+
+    fn from_str(rev: &str) -> Result<Self, Self::Err> {
+        // Any of these characters means that we are dealing with revspec syntax,
+        // which cannot appear in a reference name.
+        let is_spec = rev.contains(&['~', '^', ':', '@', '{'][..]);
+
+        if rev.is_empty() {
+            Err(ParseError::EmptyRev)
+        } else if is_spec {
+            Ok(Self::Spec(rev.to_owned()))
+        } else if rev.starts_with("refs/") {
+            Ok(Self::Ref(rev.parse()?))
+        } else if rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit()) {
+            git2::Oid::from_str(rev)
+                .map(Self::Oid)
+                .map_err(|_| ParseError::MalformedRef(rev.to_owned()))
+        } else {
+            Ok(Self::Spec(rev.to_owned()))
+        }
+    }
 }
 
 impl<R> From<R> for Rev
@@ -47,7 +92,7 @@ impl From<git2::Oid> for Rev {
     }
 }
 
-/// A structured way of referring to a git reference.
+/// A fully qualified git reference, e.g. `refs/heads/master`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Ref {
     /// A git tag, which can be found under `.git/refs/tags/`.
@@ -78,6 +123,12 @@ pub enum Ref {
         /// moi/refs/remotes/origin/master`.
         reference: Box<Ref>,
     },
+    /// Any other reference, outside of the hierarchies above, e.g. the
+    /// `refs/pulls/1/head` of a pull request.
+    Custom {
+        /// The fully qualified name of the reference.
+        name: String,
+    },
 }
 
 impl Ref {
@@ -114,14 +165,41 @@ impl fmt::Display for Ref {
                 namespace,
                 reference,
             } => write!(f, "refs/namespaces/{}/{}", namespace, reference),
+            Self::Custom { name } => write!(f, "{}", name),
         }
     }
 }
 
+/// The items listed from the references of a repository, such as its
+/// branches, where the references that could not be read were left out and
+/// reported as `warnings`, rather than failing the whole listing.
+#[derive(Debug)]
+pub struct Listing<T> {
+    /// The items of the references that could be read.
+    pub items: Vec<T>,
+    /// The references that were left out.
+    pub warnings: Vec<RefWarning>,
+}
+
+/// A reference left out of a [`Listing`], e.g. a symbolic reference to a
+/// missing target, or one of a cycle of symbolic references.
+#[derive(Debug)]
+pub struct RefWarning {
+    /// The name of the reference, if it could be read.
+    pub name: Option<String>,
+    /// Why the reference was left out.
+    pub error: error::Error,
+}
+
+/// Errors that can occur when parsing a [`Ref`] or [`Rev`] from a string.
 #[derive(Debug, PartialEq, Error)]
 pub enum ParseError {
+    /// The string did not match any of the known reference hierarchies.
     #[error("the ref provided '{0}' was malformed")]
     MalformedRef(String),
+    /// The string provided for a [`Rev`] was empty.
+    #[error("the revision provided was empty")]
+    EmptyRev,
 }
 
 pub mod parser {
@@ -131,6 +209,7 @@ pub mod parser {
 
     use super::Ref;
 
+    const REFS: &str = "refs/";
     const HEADS: &str = "refs/heads/";
     const REMOTES: &str = "refs/remotes/";
     const TAGS: &str = "refs/tags/";
@@ -181,11 +260,29 @@ pub mod parser {
         })
     }
 
+    /// Any reference under `refs/` that is not in one of the hierarchies
+    /// above, so that a malformed branch or tag is not taken for a custom
+    /// reference.
+    pub fn custom(s: &str) -> Result<Ref, Error<'_>> {
+        bytes::complete::tag(REFS)(s).and_then(|(name, _)| {
+            let known = [HEADS, REMOTES, TAGS, NAMESPACES];
+            if name.is_empty() || known.iter().any(|prefix| s.starts_with(prefix)) {
+                Err(nom::Err::Error(nom::error::Error::new(
+                    s,
+                    nom::error::ErrorKind::Verify,
+                )))
+            } else {
+                Ok(Ref::Custom { name: s.to_owned() })
+            }
+        })
+    }
+
     pub fn parse(s: &str) -> Result<Ref, nom::Err<nom::error::Error<&str>>> {
         local(s)
             .or_else(|_| remote(s))
             .or_else(|_| tag(s))
             .or_else(|_| namespace(s))
+            .or_else(|_| custom(s))
     }
 }
 
@@ -285,11 +382,33 @@ mod tests {
             })
         );
 
+        assert_eq!(
+            Ref::from_str("refs/pulls/1/head"),
+            Ok(Ref::Custom {
+                name: "refs/pulls/1/head".to_string(),
+            })
+        );
+
+        assert_eq!(
+            Ref::from_str("refs/namespaces/moi/refs/rad/id"),
+            Ok(Ref::Namespace {
+                namespace: "moi".to_string(),
+                reference: Box::new(Ref::Custom {
+                    name: "refs/rad/id".to_string(),
+                }),
+            })
+        );
+
         assert_eq!(
             Ref::from_str("refs/remotes/master"),
             Err(ParseError::MalformedRef("refs/remotes/master".to_owned())),
         );
 
+        assert_eq!(
+            Ref::from_str("refs/"),
+            Err(ParseError::MalformedRef("refs/".to_owned())),
+        );
+
         assert_eq!(
             Ref::from_str("refs/namespaces/refs/remotes/origin/master"),
             Err(ParseError::MalformedRef(
@@ -299,4 +418,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn parse_rev() -> Result<(), ParseError> {
+        assert_eq!(
+            Rev::from_str("refs/heads/master"),
+            Ok(Rev::Ref(Ref::LocalBranch {
+                name: BranchName::new("master"),
+            }))
+        );
+
+        assert_eq!(
+            Rev::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95"),
+            Ok(Rev::Oid(
+                git2::Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95").unwrap()
+            ))
+        );
+
+        assert_eq!(
+            Rev::from_str("3873745"),
+            Ok(Rev::Spec("3873745".to_string()))
+        );
+        assert_eq!(Rev::from_str("master"), Ok(Rev::Spec("master".to_string())));
+        assert_eq!(Rev::from_str("v0.1.0"), Ok(Rev::Spec("v0.1.0".to_string())));
+        assert_eq!(Rev::from_str("HEAD~3"), Ok(Rev::Spec("HEAD~3".to_string())));
+        assert_eq!(
+            Rev::from_str("refs/heads/master~3"),
+            Ok(Rev::Spec("refs/heads/master~3".to_string()))
+        );
+
+        assert_eq!(
+            Rev::from_str("refs/remotes/master"),
+            Err(ParseError::MalformedRef("refs/remotes/master".to_owned())),
+        );
+        assert_eq!(Rev::from_str(""), Err(ParseError::EmptyRev));
+
+        Ok(())
+    }
 }
@@ -22,6 +22,12 @@ use crate::{
 use either::Either;
 use std::fmt::{self, Write as _};
 
+/// A glob for enumerating the references of a repository, scoped to the
+/// repository's current namespace, if any.
+///
+/// Besides the well-known branch and tag hierarchies, arbitrary hierarchies
+/// such as `refs/rad/ids/*` or `refs/pulls/*/head` can be listed via
+/// [`RefGlob::custom`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RefGlob {
     /// When calling [`RefGlob::references`] this will return the references via
@@ -56,6 +62,9 @@ pub enum RefGlob {
     },
     /// refs/namespaces/**
     Namespace,
+    /// When calling [`RefGlob::references`] this will return the references via
+    /// the provided glob, e.g. `refs/rad/ids/*` or `refs/pulls/*/head`.
+    Custom(String),
 }
 
 /// Iterator chaining multiple [`git2::References`]
@@ -71,6 +80,7 @@ impl<'a> References<'a> {
 }
 
 impl RefGlob {
+    /// The glob for the branches within the given [`RefScope`].
     pub fn branch(scope: RefScope) -> Self {
         match scope {
             RefScope::All => Self::Branch,
@@ -79,6 +89,7 @@ impl RefGlob {
         }
     }
 
+    /// The glob for the tags within the given [`RefScope`].
     pub fn tag(scope: RefScope) -> Self {
         match scope {
             RefScope::All => Self::Tag,
@@ -87,6 +98,20 @@ impl RefGlob {
         }
     }
 
+    /// The glob for the namespaces of the repository.
+    pub fn namespace() -> Self {
+        Self::Namespace
+    }
+
+    /// A glob for a custom reference hierarchy, e.g. `refs/rad/ids/*` or
+    /// `refs/pulls/*/head`. The glob is taken as-is, relative to the
+    /// repository's current namespace.
+    pub fn custom(glob: impl Into<String>) -> Self {
+        Self::Custom(glob.into())
+    }
+
+    /// Look up the references matching this glob within the repository's
+    /// current namespace.
     pub fn references<'a>(&self, repo: &RepositoryRef<'a>) -> Result<References<'a>, error::Error> {
         let namespace = repo
             .which_namespace()?
@@ -167,17 +192,18 @@ impl fmt::Display for RefGlob {
                 };
                 write!(f, "refs/remotes/{}/tags/*", remote)
             },
-            // Note: the glob below would be used, but libgit doesn't care for union globs.
-            // write!(f, "refs/{{remotes/**/*,heads/*}}")
-            Self::Branch | Self::Tag => {
-                panic!("{}",
-                "fatal: `Display` should not be called on `RefGlob::Branch` or `RefGlob::Tag` Since
-                this `enum` is private to the repository, it should not be called from the outside.
-                Unfortunately, libgit does not support union of globs otherwise this would display
-                refs/{remotes/**/*,heads/*}"
-            )
-            },
+            // libgit2 does not support a union of globs, such as
+            // `refs/{remotes/**/*,heads/*}`, so these are looked up with two globs,
+            // which are displayed separated by a space.
+            Self::Branch => write!(
+                f,
+                "{} {}",
+                Self::RemoteBranch { remote: None },
+                Self::LocalBranch
+            ),
+            Self::Tag => write!(f, "{} {}", Self::RemoteTag { remote: None }, Self::LocalTag),
             Self::Namespace => write!(f, "refs/namespaces/**"),
+            Self::Custom(glob) => write!(f, "{}", glob),
         }
     }
 }
@@ -0,0 +1,28 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+/// A remote configured for a repository, e.g. `origin`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Remote {
+    /// The name of the remote, e.g. `origin`.
+    pub name: String,
+    /// The URL that the remote fetches from, if it is set and is valid UTF-8.
+    pub url: Option<String>,
+    /// The URL that the remote pushes to, if it is set separately from the
+    /// fetch URL and is valid UTF-8.
+    pub push_url: Option<String>,
+}
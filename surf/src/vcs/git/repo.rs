@@ -17,41 +17,126 @@
 
 use crate::{
     diff::*,
-    file_system,
+    file_system::{self, pathspec::Matcher, NonUtf8Policy, Pathspec},
     vcs,
     vcs::{
         git::{
+            cache::CommitCache,
+            commit_graph,
             error::*,
-            reference::{glob::RefGlob, Ref, Rev},
+            history::{
+                HistoryBuilder,
+                HistoryHandle,
+                HistoryIter,
+                NamedHistory,
+                RenameChain,
+                RenameLink,
+            },
+            instrumentation::{Instrumentation, Operation},
+            limits::Limits,
+            mailmap::Mailmap,
+            navigation::Navigation,
+            reference::{glob::RefGlob, Listing, Ref, RefWarning, Rev},
+            shallow::{Grafts, Revwalk},
             Branch,
             Commit,
+            Describe,
+            MergeResult,
             Namespace,
+            NamespaceTree,
             RefScope,
+            Remote,
+            RevObject,
             Signature,
+            StorageStats,
             Tag,
+            TagName,
         },
         Vcs,
     },
 };
 use git2::Oid;
 use nonempty::NonEmpty;
-use std::{collections::HashSet, convert::TryFrom, str};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    fs,
+    path,
+    str,
+    time::{Duration, Instant},
+};
 
-/// This is for flagging to the `file_history` function that it should
-/// stop at the first (i.e. Last) commit it finds for a file.
-pub(super) enum CommitHistory {
+/// How much of the history of a path to look up, see
+/// [`Browser::directory_history`](super::Browser::directory_history). The
+/// history can stop at the first (i.e. Last) commit it finds for the path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitHistory {
+    /// Find every commit that touched the path.
     Full,
+    /// Stop at the latest commit that touched the path.
     Last,
 }
 
 /// A `History` that uses `git2::Commit` as the underlying artifact.
 pub type History = vcs::History<Commit>;
 
+impl History {
+    /// The [`Oid`] of the head of the `History`, i.e. its latest commit.
+    ///
+    /// Since a `History` is walked back from its head, this identifies it
+    /// without comparing every commit.
+    pub fn head_id(&self) -> Oid {
+        self.first().id
+    }
+
+    /// An identifier for the `History`, namely its [`History::head_id`].
+    ///
+    /// Two histories with the same id start at the same commit, though one may
+    /// have been cut shorter than the other, e.g. by a limit or a filter.
+    pub fn id(&self) -> Oid {
+        self.head_id()
+    }
+
+    /// Check whether `other` starts at the same commit, without comparing
+    /// every commit as `==` does.
+    ///
+    /// This does not mean the two are equal: either may have been cut short,
+    /// e.g. by a limit or a filter, and only `==` tells them apart.
+    pub fn same_head(&self, other: &History) -> bool {
+        self.head_id() == other.head_id()
+    }
+
+    /// Get the commits of the `History` whose parents were not fetched by a
+    /// shallow clone, i.e. those in `grafts`, see [`RepositoryRef::grafts`].
+    ///
+    /// The walks of a shallow clone stop at these commits, so they are the
+    /// oldest the `History` can reach. A `History` that was only cut short,
+    /// e.g. by a limit, has no boundary.
+    pub fn boundary(&self, grafts: &Grafts) -> Vec<&Commit> {
+        self.iter()
+            .filter(|commit| grafts.contains(&commit.id))
+            .collect()
+    }
+
+    /// Check whether the `History` reaches all the way back to the root
+    /// commits, i.e. it has no [`History::boundary`].
+    ///
+    /// This will be `false` for a `History` that was truncated by a shallow
+    /// clone.
+    pub fn is_complete(&self, grafts: &Grafts) -> bool {
+        self.boundary(grafts).is_empty()
+    }
+}
+
 /// Wrapper around the `git2`'s `git2::Repository` type.
 /// This is to to limit the functionality that we can do
 /// on the underlying object.
 pub struct Repository(pub(super) git2::Repository);
 
+/// The header field of a commit that git keeps its signature in.
+const DEFAULT_SIGNATURE_FIELD: &str = "gpgsig";
+
 /// A reference-only `Repository`. This means that we cannot mutate the
 /// underlying `Repository`. Not being able to mutate the `Repository` means
 /// that the functions defined for `RepositoryRef` should be thread-safe.
@@ -60,8 +145,39 @@ pub struct Repository(pub(super) git2::Repository);
 ///
 /// Use the `From<&'a git2::Repository>` implementation to construct a
 /// `RepositoryRef`.
+///
+/// # Caching
+///
+/// Every `RepositoryRef` keeps a [`CommitCache`], so that building histories
+/// only converts each commit once. See [`RepositoryRef::with_commit_cache`]
+/// for setting its capacity. Cloning a `RepositoryRef` shares the underlying
+/// `git2::Repository` but copies the cache, so the clones fill their caches
+/// independently.
+#[derive(Clone)]
 pub struct RepositoryRef<'a> {
     pub(super) repo_ref: &'a git2::Repository,
+    pub(super) commits: RefCell<CommitCache>,
+    pub(super) instrumentation: Option<Instrumentation>,
+    pub(super) deadline: Option<Instant>,
+    pub(super) limits: Limits,
+    /// The revision the `Browser` was last pointed at, for
+    /// [`Browser::refresh`](crate::vcs::git::Browser::refresh), along with the
+    /// head of the `History` it loaded. The revision no longer applies once
+    /// the `Browser` views a `History` with another head, e.g. one given to
+    /// [`Browser::set`](crate::vcs::Browser::set).
+    pub(super) viewing: Option<(Rev, Oid)>,
+    /// The views the `Browser` can go back and forward to, if enabled with
+    /// [`Browser::with_navigation`](crate::vcs::git::Browser::with_navigation).
+    pub(super) navigation: Option<Navigation>,
+    /// The mailmap applied to the authors and committers of commits, if
+    /// enabled with [`RepositoryRef::with_mailmap`].
+    pub(super) mailmap: Option<Mailmap>,
+    /// The header fields that [`RepositoryRef::signatures`] looks for
+    /// signatures in.
+    pub(super) signature_fields: Vec<String>,
+    /// How the names of files and directories that are not valid UTF-8 are
+    /// handled when walking trees.
+    pub(super) non_utf8_policy: NonUtf8Policy,
 }
 
 // RepositoryRef should be safe to transfer across thread boundaries since it
@@ -71,11 +187,132 @@ unsafe impl<'a> Send for RepositoryRef<'a> {}
 
 impl<'a> From<&'a git2::Repository> for RepositoryRef<'a> {
     fn from(repo_ref: &'a git2::Repository) -> Self {
-        RepositoryRef { repo_ref }
+        RepositoryRef {
+            repo_ref,
+            commits: RefCell::new(CommitCache::default()),
+            instrumentation: None,
+            deadline: None,
+            limits: Limits::default(),
+            viewing: None,
+            navigation: None,
+            mailmap: None,
+            signature_fields: vec![DEFAULT_SIGNATURE_FIELD.to_owned()],
+            non_utf8_policy: NonUtf8Policy::default(),
+        }
     }
 }
 
 impl<'a> RepositoryRef<'a> {
+    /// Replace the [`CommitCache`] of this `RepositoryRef`, e.g. with one of a
+    /// different capacity, or with a capacity of 0 to disable caching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{cache::CommitCache, Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref().with_commit_cache(CommitCache::new(16));
+    /// let browser = Browser::new(repo, Branch::local("master"))?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_commit_cache(self, cache: CommitCache) -> Self {
+        RepositoryRef {
+            commits: RefCell::new(cache),
+            ..self
+        }
+    }
+
+    /// Map the authors and committers of the commits loaded from now on with
+    /// the mailmap of the repository, so that a person who committed under
+    /// several names or emails is known by one. See
+    /// [`RepositoryRef::mailmap`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`] if the mailmap cannot be read.
+    pub fn with_mailmap(mut self) -> Result<Self, Error> {
+        self.set_mailmap(true)?;
+        Ok(self)
+    }
+
+    /// Turn the mapping of authors and committers with the mailmap on or off.
+    /// The [`CommitCache`] is cleared, since its commits were mapped the old
+    /// way.
+    pub(super) fn set_mailmap(&mut self, enabled: bool) -> Result<(), Error> {
+        self.mailmap = if enabled {
+            Some(Mailmap::load(self.repo_ref)?)
+        } else {
+            None
+        };
+        self.commits.borrow_mut().clear();
+        Ok(())
+    }
+
+    /// Get the mailmap of the repository, which is read from the `.mailmap`
+    /// file at the root of the working directory, or of `HEAD` in a bare
+    /// repository, and from the `mailmap.file` and `mailmap.blob`
+    /// configuration.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn mailmap(&self) -> Result<git2::Mailmap, Error> {
+        Ok(self.repo_ref.mailmap()?)
+    }
+
+    /// Report how long each [`Operation`] takes to `callback`, e.g. for
+    /// recording latencies in production. See
+    /// [`Browser::with_instrumentation`](crate::vcs::git::Browser::with_instrumentation).
+    pub fn with_instrumentation<F>(self, callback: F) -> Self
+    where
+        F: Fn(Operation, Duration) + Send + Sync + 'static,
+    {
+        RepositoryRef {
+            instrumentation: Some(Instrumentation::new(callback)),
+            ..self
+        }
+    }
+
+    /// A copy of this `RepositoryRef` for
+    /// [`Browser::fork`](crate::vcs::git::Browser::fork). It shares the
+    /// `git2::Repository` and settings, but starts with an empty cache of the
+    /// same capacity and, if navigation is enabled, no views to go back to.
+    pub(super) fn fork(&self) -> Self {
+        RepositoryRef {
+            repo_ref: self.repo_ref,
+            commits: RefCell::new(CommitCache::new(self.commits.borrow().capacity())),
+            instrumentation: self.instrumentation.clone(),
+            deadline: self.deadline,
+            limits: self.limits,
+            viewing: self.viewing.clone(),
+            navigation: self.navigation.as_ref().map(|_| Navigation::default()),
+            mailmap: self.mailmap.clone(),
+            signature_fields: self.signature_fields.clone(),
+            non_utf8_policy: self.non_utf8_policy,
+        }
+    }
+
+    /// Fail with [`Error::Cancelled`] if the deadline set with
+    /// [`Browser::set_deadline`](crate::vcs::git::Browser::set_deadline) has
+    /// passed.
+    pub(super) fn check_deadline(&self) -> Result<(), Error> {
+        check_deadline(self.deadline)
+    }
+
+    /// Run `f`, reporting how long it took as the `operation` if the
+    /// `RepositoryRef` is instrumented.
+    pub(super) fn measure<T>(&self, operation: Operation, f: impl FnOnce() -> T) -> T {
+        match &self.instrumentation {
+            Some(instrumentation) => instrumentation.measure(operation, f),
+            None => f(),
+        }
+    }
+
     /// What is the current namespace we're browsing in.
     pub fn which_namespace(&self) -> Result<Option<Namespace>, Error> {
         self.repo_ref
@@ -84,86 +321,1129 @@ impl<'a> RepositoryRef<'a> {
             .transpose()
     }
 
-    /// List the branches within a repository, filtering out ones that do not
-    /// parse correctly.
+    /// Check whether the repository is a shallow clone, in which case the
+    /// histories loaded from it may not be complete.
+    ///
+    /// See [`RepositoryRef::grafts`], [`History::is_complete`] and
+    /// [`History::boundary`].
+    pub fn is_shallow(&self) -> bool {
+        self.repo_ref.is_shallow()
+    }
+
+    /// Get the commits at which a shallow clone was cut, i.e. whose parents
+    /// were not fetched. These are empty if the repository is not shallow.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Io`]
+    /// * [`Error::Git`]
+    pub fn grafts(&self) -> Result<Grafts, Error> {
+        Grafts::read(self.repo_ref)
+    }
+
+    /// Start a revwalk, which stops at the [`Grafts`] of a shallow clone.
+    pub(super) fn revwalk(&self) -> Result<Revwalk<'a>, Error> {
+        Revwalk::new(self.repo_ref, self.grafts()?)
+    }
+
+    /// Check whether the object `oid` exists in the repository, without
+    /// loading it.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn contains_object(&self, oid: Oid) -> Result<bool, Error> {
+        Ok(self.repo_ref.odb()?.exists(oid))
+    }
+
+    /// Get the [`git2::ObjectType`] of the object `oid` by reading only its
+    /// header, or `None` if there is no such object. This allows telling a
+    /// missing object apart from one of the wrong type before loading it.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{git2::ObjectType, Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref();
+    ///
+    /// let commit = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    /// let tree = Oid::from_str("bca66427a09c02a66aa1b648938c6740bddfed55")?;
+    /// let missing = Oid::from_str("0000000000000000000000000000000000000001")?;
+    ///
+    /// assert_eq!(repo.object_type(commit)?, Some(ObjectType::Commit));
+    /// assert_eq!(repo.object_type(tree)?, Some(ObjectType::Tree));
+    /// assert_eq!(repo.object_type(missing)?, None);
+    /// assert!(repo.contains_object(tree)?);
+    /// assert!(!repo.contains_object(missing)?);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn object_type(&self, oid: Oid) -> Result<Option<git2::ObjectType>, Error> {
+        match self.repo_ref.odb()?.read_header(oid) {
+            Ok((_, kind)) => Ok(Some(kind)),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Look up the [`Commit`] for each of the `oids`, in the same order. An
+    /// entry is `None` if there is no such object or it is not a commit,
+    /// rather than failing the whole lookup.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref();
+    ///
+    /// let commit = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    /// let tree = Oid::from_str("bca66427a09c02a66aa1b648938c6740bddfed55")?;
+    /// let missing = Oid::from_str("0000000000000000000000000000000000000001")?;
+    ///
+    /// let commits = repo.get_commits(&[commit, tree, missing])?;
+    /// assert_eq!(commits.len(), 3);
+    /// assert_eq!(commits[0].as_ref().map(|commit| commit.id), Some(commit));
+    /// assert!(commits[1].is_none());
+    /// assert!(commits[2].is_none());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_commits(&self, oids: &[Oid]) -> Result<Vec<Option<Commit>>, Error> {
+        let odb = self.repo_ref.odb()?;
+        oids.iter()
+            .map(|oid| {
+                if let Some(commit) = self.commits.borrow_mut().get(oid) {
+                    return Ok(Some(commit));
+                }
+                match odb.read_header(*oid) {
+                    Ok((_, git2::ObjectType::Commit)) => self.find_commit(*oid).map(Some),
+                    Ok(_) => Ok(None),
+                    Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+                    Err(err) => Err(err.into()),
+                }
+            })
+            .collect()
+    }
+
+    /// Gather the [`StorageStats`] of the repository, i.e. how many objects of
+    /// each kind it holds, how they are packed, and how much space the git
+    /// directory takes up on disk.
+    ///
+    /// **Note**: this iterates over the whole object database and git
+    /// directory, so it can be expensive for large repositories.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Io`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::Repository;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let stats = repo.stats()?;
+    ///
+    /// assert_eq!(stats.packs, 1);
+    /// assert_eq!(stats.loose, 0);
+    /// assert_eq!(stats.tags, 1);
+    /// assert!(stats.commits > 0);
+    /// assert!(stats.size >= stats.pack_size);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stats(&self) -> Result<StorageStats, Error> {
+        let git_dir = self.repo_ref.path();
+        let objects_dir = git_dir.join("objects");
+        let odb = self.repo_ref.odb()?;
+        let mut stats = StorageStats::default();
+
+        let mut result = Ok(());
+        odb.foreach(|oid| match odb.read_header(*oid) {
+            Ok((_, kind)) => {
+                match kind {
+                    git2::ObjectType::Commit => stats.commits += 1,
+                    git2::ObjectType::Tree => stats.trees += 1,
+                    git2::ObjectType::Blob => stats.blobs += 1,
+                    git2::ObjectType::Tag => stats.tags += 1,
+                    _ => {},
+                }
+                let hex = oid.to_string();
+                if objects_dir.join(&hex[..2]).join(&hex[2..]).is_file() {
+                    stats.loose += 1;
+                }
+                true
+            },
+            Err(err) => {
+                result = Err(err);
+                false
+            },
+        })?;
+        result?;
+
+        let pack_dir = objects_dir.join("pack");
+        if pack_dir.is_dir() {
+            for entry in read_dir(&pack_dir)? {
+                if matches!(entry.extension(), Some(ext) if ext == "pack") {
+                    stats.packs += 1;
+                    stats.pack_size += metadata(&entry)?.len();
+                }
+            }
+        }
+
+        stats.size = disk_size(git_dir)?;
+        stats.commit_graph_file = commit_graph::location(git_dir).is_file();
+
+        Ok(stats)
+    }
+
+    /// List the branches within a repository, filtering out ones that do not
+    /// parse correctly, or whose symbolic references cannot be resolved.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_branches(&self, scope: RefScope) -> Result<Vec<Branch>, Error> {
+        Ok(self.list_branches_with_warnings(scope)?.items)
+    }
+
+    /// List the branches within a repository, as in
+    /// [`RepositoryRef::list_branches`], along with the references that were
+    /// left out.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_branches_with_warnings(&self, scope: RefScope) -> Result<Listing<Branch>, Error> {
+        let references = RefGlob::branch(scope).references(self)?;
+        self.collect_references(references.iter(), |reference| {
+            reference.resolve()?;
+            Branch::try_from(reference)
+        })
+    }
+
+    /// List the tags within a repository, filtering out ones that do not parse
+    /// correctly, or cannot be peeled.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_tags(&self, scope: RefScope) -> Result<Vec<Tag>, Error> {
+        Ok(self.list_tags_with_warnings(scope)?.items)
+    }
+
+    /// List the tags within a repository, as in [`RepositoryRef::list_tags`],
+    /// along with the references that were left out.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_tags_with_warnings(&self, scope: RefScope) -> Result<Listing<Tag>, Error> {
+        let references = RefGlob::tag(scope).references(self)?;
+        self.collect_references(references.iter(), Tag::try_from)
+    }
+
+    /// Build the [`History`] of every reference, as in
+    /// [`Vcs::get_histories`], named by its reference, along with the
+    /// references that were left out because they could not be peeled to a
+    /// commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Cancelled`]
+    pub fn get_histories_with_warnings(&self) -> Result<Listing<NamedHistory>, Error> {
+        let references = self.repo_ref.references()?;
+        self.collect_references(references, |reference| self.named_history(&reference))
+    }
+
+    /// Build the [`History`] of every reference, as in
+    /// [`Vcs::get_histories`], named by its reference. The references that
+    /// cannot be peeled to a commit are left out.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Cancelled`]
+    pub fn get_named_histories(&self) -> Result<Vec<NamedHistory>, Error> {
+        Ok(self.get_histories_with_warnings()?.items)
+    }
+
+    /// List a [`HistoryHandle`] for each reference matching the `glob`,
+    /// leaving out the ones that cannot be peeled to a commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn history_handles(&self, glob: &RefGlob) -> Result<Listing<HistoryHandle>, Error> {
+        let references = glob.references(self)?;
+        self.collect_references(references.iter(), |reference| {
+            Ok(HistoryHandle {
+                name: parse_ref_name(&reference)?,
+                tip: reference.peel_to_commit()?.id(),
+            })
+        })
+    }
+
+    /// Build the [`History`] of a [`HistoryHandle`], from its tip.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Cancelled`]
+    pub fn load_history(&self, handle: &HistoryHandle) -> Result<History, Error> {
+        self.history_from(handle.tip)
+    }
+
+    /// Build the [`History`] of each reference matching the `glob`, named by
+    /// its reference, leaving out the ones that cannot be peeled to a commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Cancelled`]
+    pub fn get_histories_matching(&self, glob: &RefGlob) -> Result<Listing<NamedHistory>, Error> {
+        let references = glob.references(self)?;
+        self.collect_references(references.iter(), |reference| {
+            self.named_history(&reference)
+        })
+    }
+
+    /// Build the [`History`] of the `reference`, along with its name.
+    fn named_history(&self, reference: &git2::Reference<'a>) -> Result<NamedHistory, Error> {
+        Ok(NamedHistory {
+            name: parse_ref_name(reference)?,
+            history: self.history_from(reference.peel_to_commit()?.id())?,
+        })
+    }
+
+    /// Convert each of the `references` with `f`, leaving out the ones that
+    /// cannot be read or converted, and reporting them as warnings. Only a
+    /// passed deadline fails the whole listing.
+    fn collect_references<T, I, F>(&self, references: I, mut f: F) -> Result<Listing<T>, Error>
+    where
+        I: Iterator<Item = Result<git2::Reference<'a>, git2::Error>>,
+        F: FnMut(git2::Reference<'a>) -> Result<T, Error>,
+    {
+        let mut listing = Listing {
+            items: vec![],
+            warnings: vec![],
+        };
+        for reference in references {
+            self.check_deadline()?;
+            let (name, item) = match reference {
+                Ok(reference) => {
+                    let name = String::from_utf8_lossy(reference.name_bytes()).into_owned();
+                    (Some(name), f(reference))
+                },
+                Err(err) => (None, Err(err.into())),
+            };
+            match item {
+                Ok(item) => listing.items.push(item),
+                Err(Error::Cancelled) => return Err(Error::Cancelled),
+                Err(error) => listing.warnings.push(RefWarning { name, error }),
+            }
+        }
+        Ok(listing)
+    }
+
+    /// List the namespaces within a repository, filtering out ones that do not
+    /// parse correctly.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_namespaces(&self) -> Result<Vec<Namespace>, Error> {
+        let namespaces: Result<HashSet<Namespace>, Error> = RefGlob::Namespace
+            .references(self)?
+            .iter()
+            .try_fold(HashSet::new(), |mut acc, reference| {
+                let namespace = Namespace::try_from(reference?)?;
+                acc.insert(namespace);
+                Ok(acc)
+            });
+        Ok(namespaces?.into_iter().collect())
+    }
+
+    /// List the namespaces within a repository arranged by how they are
+    /// nested, returning the top-level namespaces.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn namespace_hierarchy(&self) -> Result<Vec<NamespaceTree>, Error> {
+        Ok(NamespaceTree::build(self.list_namespaces()?))
+    }
+
+    /// List the names of the references matching the given [`RefGlob`],
+    /// relative to the current namespace. References whose names are not
+    /// valid UTF-8 are filtered out.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_references(&self, glob: &RefGlob) -> Result<Vec<String>, Error> {
+        let prefix = self
+            .which_namespace()?
+            .map_or_else(String::new, |namespace| {
+                namespace
+                    .values
+                    .iter()
+                    .map(|n| format!("refs/namespaces/{}/", n))
+                    .collect()
+            });
+        glob.references(self)?
+            .iter()
+            .try_fold(vec![], |mut acc, reference| {
+                if let Some(name) = reference?.name() {
+                    acc.push(name.strip_prefix(&prefix).unwrap_or(name).to_string());
+                }
+                Ok(acc)
+            })
+    }
+
+    /// List the remotes configured for the repository, along with their URLs.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Remote, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref();
+    ///
+    /// let origin = Remote {
+    ///     name: "origin".to_string(),
+    ///     url: Some("https://github.com/radicle-dev/git-platinum.git".to_string()),
+    ///     push_url: None,
+    /// };
+    /// assert_eq!(repo.list_remotes()?, vec![origin.clone()]);
+    ///
+    /// // A remote branch can be linked back to its remote
+    /// let dev = Branch::remote("dev", "origin");
+    /// assert_eq!(dev.remote_name(), Some("origin"));
+    /// assert_eq!(repo.remote("origin")?, Some(origin));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_remotes(&self) -> Result<Vec<Remote>, Error> {
+        let names = self.repo_ref.remotes()?;
+        names.iter().flatten().try_fold(vec![], |mut acc, name| {
+            if let Some(remote) = self.remote(name)? {
+                acc.push(remote);
+            }
+            Ok(acc)
+        })
+    }
+
+    /// Get the remote configured under `name`, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn remote(&self, name: &str) -> Result<Option<Remote>, Error> {
+        match self.repo_ref.find_remote(name) {
+            Ok(remote) => Ok(Some(Remote {
+                name: name.to_string(),
+                url: remote.url().map(str::to_string),
+                push_url: remote.pushurl().map(str::to_string),
+            })),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Parse the remote branch `name`, e.g. `origin/dev` or
+    /// `refs/remotes/origin/dev`, into its remote and branch names.
+    ///
+    /// Unlike [`Branch::parse_remote`], the name is matched against the
+    /// configured remotes, so that remotes whose names contain a `/` are split
+    /// correctly. The longest matching remote is used, and if none match the
+    /// name is split on its first `/`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::ParseRemoteBranch`]
+    pub fn remote_branch(&self, name: &str) -> Result<Branch, Error> {
+        let stripped = name.strip_prefix("refs/remotes/").unwrap_or(name);
+        let remotes = self.repo_ref.remotes()?;
+        let remote = remotes
+            .iter()
+            .flatten()
+            .filter(|remote| {
+                stripped
+                    .strip_prefix(remote)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .is_some_and(|branch| !branch.is_empty())
+            })
+            .max_by_key(|remote| remote.len());
+        match remote {
+            Some(remote) => Ok(Branch::remote(&stripped[remote.len() + 1..], remote)),
+            None => Branch::parse_remote(name),
+        }
+    }
+
+    pub(super) fn reference<R, P>(&self, reference: R, check: P) -> Result<History, Error>
+    where
+        R: Into<Ref>,
+        P: FnOnce(&git2::Reference) -> Option<Error>,
+    {
+        let reference = match self.which_namespace()? {
+            None => reference.into(),
+            Some(namespace) => reference.into().namespaced(namespace),
+        };
+        let reference = match reference.find_ref(self) {
+            Ok(reference) => reference,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => {
+                return Err(self.unborn(&reference.to_string())?.unwrap_or(err.into()))
+            },
+            Err(err) => return Err(err.into()),
+        };
+
+        if let Some(err) = check(&reference) {
+            return Err(err);
+        }
+
+        self.history_from(reference.peel_to_commit()?.id())
+    }
+
+    /// Get the [`Error::UnbornBranch`] for `name`, if it names the branch that
+    /// `HEAD` points to, i.e. `HEAD` itself, the branch name or its full
+    /// reference name, and that branch has no commits yet.
+    fn unborn(&self, name: &str) -> Result<Option<Error>, Error> {
+        if self.which_namespace()?.is_some() {
+            return Ok(None);
+        }
+        let branch = match self.head_branch()? {
+            Some(branch) => branch,
+            None => return Ok(None),
+        };
+        let refname = format!("refs/heads/{}", branch.name);
+        if name != "HEAD" && name != branch.name.name() && name != refname {
+            return Ok(None);
+        }
+
+        match self.repo_ref.find_reference(&refname) {
+            Ok(_) => Ok(None),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => {
+                Ok(Some(Error::UnbornBranch(branch.name)))
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Build the [`History`] for `rev`, in the same way as the `Browser`
+    /// does, except that a branch without any commits yet gives `None`
+    /// rather than an [`Error::UnbornBranch`].
+    ///
+    /// This is useful for showing repositories that were just created, where
+    /// `HEAD` points to a branch that has not been committed to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{fixture::Fixture, Branch, Rev};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let fixture = Fixture::new()?;
+    /// let repo = fixture.repository().as_ref();
+    ///
+    /// assert!(repo.maybe_history(Branch::local("master").into())?.is_none());
+    /// assert!(repo.maybe_history(Rev::Spec("HEAD".to_string()))?.is_none());
+    ///
+    /// fixture.commit("master", "Initial commit").file("README.md", "Hi\n").write()?;
+    /// assert!(repo.maybe_history(Branch::local("master").into())?.is_some());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn maybe_history(&self, rev: Rev) -> Result<Option<History>, Error> {
+        match self.rev(rev, |_| None) {
+            Ok(history) => Ok(Some(history)),
+            Err(Error::UnbornBranch(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Build a [`History`] from any [`Rev`], where `check` is given the
+    /// [`git2::Reference`] the `Rev` resolved through, if there was one.
+    pub(super) fn rev<P>(&self, rev: Rev, check: P) -> Result<History, Error>
+    where
+        P: FnOnce(Option<&git2::Reference>) -> Option<Error>,
+    {
+        match rev {
+            Rev::Ref(reference) => self.reference(reference, |reference| check(Some(reference))),
+            Rev::Oid(oid) => match check(None) {
+                Some(err) => Err(err),
+                None => self.history_from(oid),
+            },
+            Rev::Spec(spec) => {
+                let (object, reference) = self.revparse_ext(&spec)?;
+                match check(reference.as_ref()) {
+                    Some(err) => Err(err),
+                    None => self.history_from(object.peel_to_commit()?.id()),
+                }
+            },
+        }
+    }
+
+    /// Resolve a revspec, reporting a [`Error::RevParseFailure`] (or
+    /// [`Error::NamespaceRevParseFailure`]) if it could not be found.
+    fn revparse_ext(
+        &self,
+        spec: &str,
+    ) -> Result<(git2::Object<'a>, Option<git2::Reference<'a>>), Error> {
+        match self.repo_ref.revparse_ext(spec) {
+            Ok(resolved) => Ok(resolved),
+            Err(err) => {
+                if err.code() == git2::ErrorCode::NotFound
+                    || err.code() == git2::ErrorCode::UnbornBranch
+                {
+                    if let Some(unborn) = self.unborn(spec)? {
+                        return Err(unborn);
+                    }
+                    Err(self.rev_parse_failure(spec)?)
+                } else {
+                    Err(err.into())
+                }
+            },
+        }
+    }
+
+    /// The error for a `rev` that could not be found, which depends on
+    /// whether we are in a namespace.
+    fn rev_parse_failure(&self, rev: &str) -> Result<Error, Error> {
+        let rev = rev.to_string();
+        Ok(match self.which_namespace()? {
+            None => Error::RevParseFailure { rev },
+            Some(namespace) => Error::NamespaceRevParseFailure { namespace, rev },
+        })
+    }
+
+    /// Get the [`Diff`] between two commits.
+    pub fn diff(&self, from: Oid, to: Oid) -> Result<Diff, Error> {
+        self.measure(Operation::Diff, || {
+            self.diff_commits(None, Some(from), to)
+                .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
+        })
+    }
+
+    /// Get the [`Diff`] of the commit `oid` against its first parent, or
+    /// against the empty tree if it is a root commit.
+    pub fn diff_from_parent(&self, oid: Oid) -> Result<Diff, Error> {
+        self.measure(Operation::Diff, || {
+            let parent = self.repo_ref.find_commit(oid)?.parent_ids().next();
+            self.diff_commits(None, parent, oid)
+                .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
+        })
+    }
+
+    /// Get the [`Diff`] between two commits, limited to the files matching
+    /// the `pathspec`. A moved or copied file is kept if either of its paths
+    /// matches.
+    pub fn diff_pathspec(&self, from: Oid, to: Oid, pathspec: &Pathspec) -> Result<Diff, Error> {
+        self.measure(Operation::Diff, || {
+            let mut opts = git2::DiffOptions::new();
+            for include in pathspec.literal_includes().into_iter().flatten() {
+                opts.pathspec(include);
+            }
+            let mut diff = Diff::try_from(self.diff_trees(Some(from), to, &mut opts)?)?;
+
+            let matcher = pathspec.matcher();
+            diff.retain(|path| matcher.is_match(&path.to_string()));
+            Ok(diff)
+        })
+    }
+
+    /// Compute the result of cherry-picking `commit` onto the commit `onto`,
+    /// without writing anything to the repository.
+    ///
+    /// If `commit` is a merge commit then its changes are taken relative to
+    /// its first parent.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Diff`]
+    /// * [`Error::FileSystem`]
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{unsound, Path};
+    /// use radicle_surf::vcs::git::{Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref();
+    ///
+    /// // Pick the README update onto the initial commit
+    /// let result = repo.cherry_pick(
+    ///     Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?,
+    ///     Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    /// )?;
+    ///
+    /// assert!(result.is_clean());
+    /// assert_eq!(
+    ///     result.diff.modified[0].path,
+    ///     Path::with_root(&[unsound::label::new("README.md")])
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cherry_pick(&self, commit: Oid, onto: Oid) -> Result<MergeResult, Error> {
+        let commit = self.repo_ref.find_commit(commit)?;
+        let onto = self.repo_ref.find_commit(onto)?;
+        let mainline = if commit.parent_count() > 1 { 1 } else { 0 };
+        let index = self
+            .repo_ref
+            .cherrypick_commit(&commit, &onto, mainline, None)?;
+        self.merge_result(&onto, index)
+    }
+
+    /// Compute the result of reverting `commit` on top of the commit `onto`,
+    /// without writing anything to the repository.
+    ///
+    /// If `commit` is a merge commit then its changes are taken relative to
+    /// its first parent.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Diff`]
+    /// * [`Error::FileSystem`]
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{unsound, Path};
+    /// use radicle_surf::vcs::git::{Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref();
+    /// let master = Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    ///
+    /// // Reverting the README update on master applies cleanly
+    /// let result = repo.revert(Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?, master)?;
+    /// assert!(result.is_clean());
+    /// assert_eq!(result.diff.modified.len(), 1);
+    ///
+    /// // Reverting the initial commit would delete the README, but it has
+    /// // been modified since
+    /// let result = repo.revert(Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?, master)?;
+    /// assert_eq!(
+    ///     result.conflicts,
+    ///     vec![Path::with_root(&[unsound::label::new("README.md")])]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn revert(&self, commit: Oid, onto: Oid) -> Result<MergeResult, Error> {
+        let commit = self.repo_ref.find_commit(commit)?;
+        let onto = self.repo_ref.find_commit(onto)?;
+        let mainline = if commit.parent_count() > 1 { 1 } else { 0 };
+        let index = self
+            .repo_ref
+            .revert_commit(&commit, &onto, mainline, None)?;
+        self.merge_result(&onto, index)
+    }
+
+    /// Turn the in-memory `index` of a merge onto `onto` into a
+    /// [`MergeResult`].
+    ///
+    /// The conflicts are resolved to "our" side, i.e. the side of `onto`, so
+    /// that they do not show up in the diff.
+    fn merge_result(
+        &self,
+        onto: &git2::Commit,
+        mut index: git2::Index,
+    ) -> Result<MergeResult, Error> {
+        // The bits of `git2::IndexEntry::flags` that hold the stage of a
+        // conflicting entry.
+        const STAGE_MASK: u16 = 0x3000;
+
+        let mut conflicts = vec![];
+        let mut resolved = vec![];
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entry = conflict
+                .our
+                .as_ref()
+                .or(conflict.their.as_ref())
+                .or(conflict.ancestor.as_ref());
+            if let Some(entry) = entry {
+                conflicts.push(path::PathBuf::from(str::from_utf8(&entry.path)?));
+            }
+            if let Some(mut our) = conflict.our {
+                our.flags &= !STAGE_MASK;
+                resolved.push(our);
+            }
+        }
+        // Removing the path also removes its conflicting entries.
+        for path in &conflicts {
+            index.remove_path(path)?;
+        }
+        for entry in &resolved {
+            index.add(entry)?;
+        }
+
+        let diff = self
+            .repo_ref
+            .diff_tree_to_index(Some(&onto.tree()?), Some(&index), None)?;
+
+        Ok(MergeResult {
+            diff: Diff::try_from(diff)?,
+            conflicts: conflicts
+                .into_iter()
+                .map(file_system::Path::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Get a [`FileSummary`] of each file that changed between two commits.
+    ///
+    /// Only the number of lines added and deleted are kept for each file, so
+    /// this is cheaper than [`RepositoryRef::diff`] when the hunks are not
+    /// needed.
+    pub fn diff_summary(&self, from: Oid, to: Oid) -> Result<Vec<FileSummary>, Error> {
+        let diff = self.diff_trees(Some(from), to, &mut git2::DiffOptions::new())?;
+        Ok(git::summary(&diff)?)
+    }
+
+    /// Get the paths of the files changed by the commit `oid`, compared to its
+    /// first parent, along with how they changed.
+    ///
+    /// Only the deltas of the diff are computed, so this is cheaper than
+    /// [`RepositoryRef::diff`] or [`RepositoryRef::diff_summary`].
+    pub fn paths_touched(&self, oid: Oid) -> Result<Vec<(file_system::Path, FileStatus)>, Error> {
+        self.measure(Operation::Diff, || {
+            let commit = self.repo_ref.find_commit(oid)?;
+            let parent = commit.parents().next().map(|parent| parent.id());
+
+            let mut opts = git2::DiffOptions::new();
+            // We're skipping the binary pass because we won't be inspecting deltas.
+            opts.skip_binary_check(true);
+            let diff = self.diff_trees(parent, oid, &mut opts)?;
+            Ok(git::paths(&diff)?)
+        })
+    }
+
+    /// Get the [`FileDiff`] of the file at `path` between two commits, or
+    /// `None` if the file did not change.
+    ///
+    /// The diff is limited to `path`, so the changes to other files are not
+    /// computed.
+    pub fn diff_file(
+        &self,
+        from: Oid,
+        to: Oid,
+        path: &file_system::Path,
+    ) -> Result<Option<FileDiff>, Error> {
+        let mut opts = git2::DiffOptions::new();
+        opts.pathspec(path.clone());
+        let diff = Diff::try_from(self.diff_trees(Some(from), to, &mut opts)?)?;
+
+        // The pathspec may match more than the file itself, e.g. a directory
+        // of the same name, so we look for the exact path.
+        let created = diff
+            .created
+            .into_iter()
+            .map(|created| (created.path, created.diff));
+        let deleted = diff
+            .deleted
+            .into_iter()
+            .map(|deleted| (deleted.path, deleted.diff));
+        let modified = diff
+            .modified
+            .into_iter()
+            .map(|modified| (modified.path, modified.diff));
+        Ok(created
+            .chain(deleted)
+            .chain(modified)
+            .find(|(changed, _)| changed == path)
+            .map(|(_, diff)| diff))
+    }
+
+    /// Get the [`Diff`] of a commit with no parents.
+    pub fn initial_diff(&self, oid: Oid) -> Result<Diff, Error> {
+        self.diff_commits(None, None, oid)
+            .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
+    }
+
+    /// Describe a commit in terms of the nearest tag reachable from it, in the
+    /// same way as `git describe --tags`.
+    ///
+    /// If there is no tag reachable from the commit then `None` is returned.
+    pub fn describe(&self, oid: Oid) -> Result<Option<Describe>, Error> {
+        let commit = self.repo_ref.find_commit(oid)?;
+        let description = match commit
+            .as_object()
+            .describe(git2::DescribeOptions::new().describe_tags())
+        {
+            Ok(description) => description,
+            Err(err) => {
+                if err.code() == git2::ErrorCode::NotFound {
+                    return Ok(None);
+                } else {
+                    return Err(err.into());
+                }
+            },
+        };
+
+        // An abbreviated size of 0 leaves us with only the tag name.
+        let name =
+            description.format(Some(git2::DescribeFormatOptions::new().abbreviated_size(0)))?;
+        let tag = TagName::new(&name);
+        let tagged = Ref::from(tag.clone())
+            .find_ref(self)?
+            .peel_to_commit()?
+            .id();
+        let (distance, _) = self.repo_ref.graph_ahead_behind(commit.id(), tagged)?;
+
+        Ok(Some(Describe {
+            tag,
+            distance,
+            short_id: self.short_id(oid)?,
+        }))
+    }
+
+    /// Get the shortest abbreviation of the given [`Oid`] that still uniquely
+    /// identifies the object within the repository, see
+    /// [`git2::Object::short_id`].
+    ///
+    /// This is at least as long as the `core.abbrev` setting, which defaults
+    /// to 7 characters.
+    pub fn short_id(&self, oid: Oid) -> Result<String, Error> {
+        let short_id = self.repo_ref.find_object(oid, None)?.short_id()?;
+        Ok(str::from_utf8(&short_id)?.to_string())
+    }
+
+    /// Count the commits that `local` is ahead and behind of `upstream`, in the
+    /// same way as `git rev-list --left-right --count local...upstream`.
+    ///
+    /// The first element of the pair is the number of commits that are only
+    /// reachable from `local`, and the second element is the number of commits
+    /// that are only reachable from `upstream`.
     ///
     /// # Errors
     ///
     /// * [`Error::Git`]
-    pub fn list_branches(&self, scope: RefScope) -> Result<Vec<Branch>, Error> {
-        RefGlob::branch(scope)
-            .references(self)?
-            .iter()
-            .try_fold(vec![], |mut acc, reference| {
-                let branch = Branch::try_from(reference?)?;
-                acc.push(branch);
-                Ok(acc)
-            })
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Oid, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref();
+    ///
+    /// let master = repo.oid("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    /// let dev = repo.oid("27acd68c7504755aa11023300890bb85bbd69d45")?;
+    ///
+    /// assert_eq!(repo.ahead_behind(master, dev)?, (8, 1));
+    /// assert_eq!(repo.ahead_behind(dev, master)?, (1, 8));
+    /// assert_eq!(repo.ahead_behind(master, master)?, (0, 0));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ahead_behind(&self, local: Oid, upstream: Oid) -> Result<(usize, usize), Error> {
+        Ok(self.repo_ref.graph_ahead_behind(local, upstream)?)
     }
 
-    /// List the tags within a repository, filtering out ones that do not parse
-    /// correctly.
+    /// Check whether the commit `ancestor` is reachable by following the
+    /// parents of the commit `descendant`, in the same way as
+    /// `git merge-base --is-ancestor`.
+    ///
+    /// A commit is considered to be an ancestor of itself.
+    pub fn is_ancestor(&self, ancestor: Oid, descendant: Oid) -> Result<bool, Error> {
+        Ok(ancestor == descendant || self.repo_ref.graph_descendant_of(descendant, ancestor)?)
+    }
+
+    /// Resolve a [revspec](https://git-scm.com/docs/git-rev-parse.html#_specifying_revisions)
+    /// to the object it names. Unlike [`crate::vcs::git::Browser::rev`], the
+    /// object is not peeled to a commit, so specs such as `master:src/lib.rs`
+    /// or `master^{tree}` resolve to blobs and trees respectively.
     ///
     /// # Errors
     ///
     /// * [`Error::Git`]
-    pub fn list_tags(&self, scope: RefScope) -> Result<Vec<Tag>, Error> {
-        RefGlob::tag(scope)
-            .references(self)?
-            .iter()
-            .try_fold(vec![], |mut acc, reference| {
-                let tag = Tag::try_from(reference?)?;
-                acc.push(tag);
-                Ok(acc)
-            })
+    /// * [`Error::RevParseFailure`]
+    /// * [`Error::NamespaceRevParseFailure`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{git2::ObjectType, Oid, Repository, RevObject};
+    /// use radicle_surf::file_system::File;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let repo = repo.as_ref();
+    ///
+    /// let readme = repo.revparse("master:README.md")?;
+    /// assert_eq!(readme.kind(), ObjectType::Blob);
+    /// assert!(readme
+    ///     .into_file()
+    ///     .expect("README.md is a blob")
+    ///     .contents
+    ///     .starts_with(b"This repository is a data source"));
+    ///
+    /// let src = repo.revparse("master:src")?;
+    /// assert_eq!(src.kind(), ObjectType::Tree);
+    ///
+    /// // Objects can be peeled to a different type
+    /// let tag = repo.revparse("v0.6.0")?;
+    /// assert_eq!(tag.kind(), ObjectType::Tag);
+    /// assert_eq!(
+    ///     repo.peel(&tag, ObjectType::Commit)?.id(),
+    ///     Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?,
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn revparse(&self, spec: &str) -> Result<RevObject, Error> {
+        let (object, _) = self.revparse_ext(spec)?;
+        RevObject::try_from(object)
     }
 
-    /// List the namespaces within a repository, filtering out ones that do not
-    /// parse correctly.
+    /// Resolve a short `name` to the one tag, local branch, remote branch
+    /// (e.g. `origin/dev`), or commit (by a prefix of its id) that it names.
+    /// Fully qualified references and full commit ids are returned as they
+    /// are.
+    ///
+    /// Unlike [`RepositoryRef::revparse`], which picks one in the order git
+    /// does, a name that matches more than one of these is an error.
     ///
     /// # Errors
     ///
-    /// * [`Error::Git`]
-    pub fn list_namespaces(&self) -> Result<Vec<Namespace>, Error> {
-        let namespaces: Result<HashSet<Namespace>, Error> = RefGlob::Namespace
-            .references(self)?
-            .iter()
-            .try_fold(HashSet::new(), |mut acc, reference| {
-                let namespace = Namespace::try_from(reference?)?;
-                acc.insert(namespace);
-                Ok(acc)
-            });
-        Ok(namespaces?.into_iter().collect())
-    }
+    /// * [`Error::AmbiguousName`]
+    /// * [`Error::RevParseFailure`]
+    /// * [`Error::NamespaceRevParseFailure`]
+    pub fn resolve(&self, name: &str) -> Result<Rev, Error> {
+        match name.parse::<Rev>() {
+            Ok(rev @ Rev::Ref(_)) | Ok(rev @ Rev::Oid(_)) => return Ok(rev),
+            _ => {},
+        }
 
-    pub(super) fn reference<R, P>(&self, reference: R, check: P) -> Result<History, Error>
-    where
-        R: Into<Ref>,
-        P: FnOnce(&git2::Reference) -> Option<Error>,
-    {
-        let reference = match self.which_namespace()? {
-            None => reference.into(),
-            Some(namespace) => reference.into().namespaced(namespace),
+        let mut candidates = vec![];
+        let references = [
+            format!("refs/tags/{}", name),
+            format!("refs/heads/{}", name),
+            format!("refs/remotes/{}", name),
+        ];
+        for reference in &references {
+            let exists = match self.repo_ref.find_reference(reference) {
+                Ok(_) => true,
+                Err(err) if err.code() == git2::ErrorCode::NotFound => false,
+                // Names that are not valid reference names cannot match.
+                Err(err) if err.code() == git2::ErrorCode::InvalidSpec => false,
+                Err(err) => return Err(err.into()),
+            };
+            if exists {
+                if let Ok(reference) = reference.parse::<Ref>() {
+                    candidates.push(Rev::Ref(reference));
+                }
+            }
         }
-        .find_ref(self)?;
 
-        if let Some(err) = check(&reference) {
-            return Err(err);
+        if name.len() >= 4 && name.len() < 40 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+            let prefix = Oid::from_str(name)?;
+            match self.repo_ref.odb()?.exists_prefix(prefix, name.len()) {
+                Ok(oid) => {
+                    if self.repo_ref.find_commit(oid).is_ok() {
+                        candidates.push(Rev::Oid(oid));
+                    }
+                },
+                Err(err) if err.code() == git2::ErrorCode::NotFound => {},
+                Err(err) if err.code() == git2::ErrorCode::Ambiguous => {
+                    candidates.extend(self.commits_with_prefix(name)?.into_iter().map(Rev::Oid));
+                },
+                Err(err) => return Err(err.into()),
+            }
         }
 
-        self.to_history(&reference)
+        match candidates.len() {
+            0 => Err(self.rev_parse_failure(name)?),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(Error::AmbiguousName {
+                name: name.to_string(),
+                candidates,
+            }),
+        }
     }
 
-    /// Get the [`Diff`] between two commits.
-    pub fn diff(&self, from: Oid, to: Oid) -> Result<Diff, Error> {
-        self.diff_commits(None, Some(from), to)
-            .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
+    /// The ids of all the commits whose hex form starts with `prefix`, in
+    /// order.
+    fn commits_with_prefix(&self, prefix: &str) -> Result<Vec<Oid>, Error> {
+        let prefix = prefix.to_ascii_lowercase();
+        let odb = self.repo_ref.odb()?;
+        let mut oids = vec![];
+        odb.foreach(|oid| {
+            if oid.to_string().starts_with(&prefix) {
+                oids.push(*oid);
+            }
+            true
+        })?;
+
+        let mut commits = vec![];
+        for oid in oids {
+            if odb.read_header(oid)?.1 == git2::ObjectType::Commit {
+                commits.push(oid);
+            }
+        }
+        commits.sort();
+        commits.dedup();
+        Ok(commits)
     }
 
-    /// Get the [`Diff`] of a commit with no parents.
-    pub fn initial_diff(&self, oid: Oid) -> Result<Diff, Error> {
-        self.diff_commits(None, None, oid)
-            .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
+    /// Peel a [`RevObject`] until an object of the given `kind` is found, e.g.
+    /// peeling a tag to the commit it points to, or a commit to its tree.
+    ///
+    /// See [`git2::Object::peel`] for details.
+    pub fn peel(&self, object: &RevObject, kind: git2::ObjectType) -> Result<RevObject, Error> {
+        let peeled = self.repo_ref.find_object(object.id(), None)?.peel(kind)?;
+        RevObject::try_from(peeled)
     }
 
     /// Parse an [`Oid`] from the given string.
@@ -175,6 +1455,7 @@ impl<'a> RepositoryRef<'a> {
         match rev {
             Rev::Oid(oid) => Ok(self.repo_ref.find_commit(*oid)?),
             Rev::Ref(reference) => Ok(reference.find_ref(self)?.peel_to_commit()?),
+            Rev::Spec(spec) => Ok(self.revparse_ext(spec)?.0.peel_to_commit()?),
         }
     }
 
@@ -182,50 +1463,129 @@ impl<'a> RepositoryRef<'a> {
         Ok(self.repo_ref.set_namespace(namespace)?)
     }
 
-    /// Get a particular `Commit`.
-    pub(super) fn get_commit(&self, oid: Oid) -> Result<git2::Commit<'a>, Error> {
-        let commit = self.repo_ref.find_commit(oid)?;
+    /// Find a [`Commit`] given its `oid`, using the [`CommitCache`] if the
+    /// commit has been seen before.
+    pub(super) fn find_commit(&self, oid: Oid) -> Result<Commit, Error> {
+        if let Some(commit) = self.commits.borrow_mut().get(&oid) {
+            return Ok(commit);
+        }
+        self.to_commit(self.repo_ref.find_commit(oid)?)
+    }
+
+    /// Convert a [`git2::Commit`] into a [`Commit`], using the [`CommitCache`]
+    /// if the commit has been seen before.
+    pub(super) fn to_commit(&self, commit: git2::Commit) -> Result<Commit, Error> {
+        if let Some(commit) = self.commits.borrow_mut().get(&commit.id()) {
+            return Ok(commit);
+        }
+        let commit = match &self.mailmap {
+            None => Commit::try_from(commit)?,
+            Some(mailmap) => {
+                let author = mailmap.author(&commit)?;
+                let committer = mailmap.committer(&commit)?;
+                Commit {
+                    author,
+                    committer,
+                    ..Commit::try_from(commit)?
+                }
+            },
+        };
+        self.commits.borrow_mut().insert(commit.clone());
         Ok(commit)
     }
 
     /// Build a [`History`] using the `head` reference.
     pub(super) fn head(&self) -> Result<History, Error> {
-        let head = self.repo_ref.head()?;
-        self.to_history(&head)
+        let head = match self.repo_ref.head() {
+            Ok(head) => head,
+            Err(err) if err.code() == git2::ErrorCode::UnbornBranch => {
+                return Err(self.unborn("HEAD")?.unwrap_or(err.into()))
+            },
+            Err(err) => return Err(err.into()),
+        };
+        self.history_from(head.peel_to_commit()?.id())
     }
 
-    /// Turn a [`git2::Reference`] into a [`History`] by completing
-    /// a revwalk over the first commit in the reference.
-    pub(super) fn to_history(&self, history: &git2::Reference<'a>) -> Result<History, Error> {
-        let head = history.peel_to_commit()?;
-        self.commit_to_history(head)
+    /// Get the local [`Branch`] that `HEAD` points to, or `None` if `HEAD` is
+    /// detached.
+    pub(super) fn head_branch(&self) -> Result<Option<Branch>, Error> {
+        let head = self.repo_ref.find_reference("HEAD")?;
+        let target = match head.symbolic_target_bytes() {
+            Some(target) => str::from_utf8(target)?,
+            None => return Ok(None),
+        };
+        Ok(target.strip_prefix("refs/heads/").map(Branch::local))
     }
 
-    /// Turn a [`git2::Reference`] into a [`History`] by completing
-    /// a revwalk over the first commit in the reference.
-    pub(super) fn commit_to_history(&self, head: git2::Commit) -> Result<History, Error> {
-        let head_id = head.id();
-        let mut commits = NonEmpty::new(Commit::try_from(head)?);
-        let mut revwalk = self.repo_ref.revwalk()?;
+    /// Build the [`History`] of `head`, i.e. `head` and all of its ancestors,
+    /// with a [`HistoryBuilder`].
+    pub(super) fn history_from(&self, head: Oid) -> Result<History, Error> {
+        self.build_history(&HistoryBuilder::new().push(head))
+    }
 
-        // Set the revwalk to the head commit
-        revwalk.push(head_id)?;
+    /// Build a [`History`] from `rev` in the same way as
+    /// [`RepositoryRef::history_from`], except that the commits that
+    /// cannot be loaded, e.g. because of an author name that is not valid
+    /// UTF-8, are left out and returned with their errors.
+    ///
+    /// If none of the commits can be loaded, the error of the first one is
+    /// returned.
+    pub(super) fn lenient_history(&self, rev: &Rev) -> Result<(History, Vec<(Oid, Error)>), Error> {
+        let head = self.rev_to_commit(rev)?.id();
+        self.measure(Operation::History, || {
+            let mut revwalk = self.revwalk()?;
+            revwalk.push(head)?;
 
-        for commit_result_id in revwalk {
-            // The revwalk iter returns results so
-            // we unpack these and push them to the history
-            let commit_id: Oid = commit_result_id?;
+            let mut commits = vec![];
+            let mut errors = vec![];
+            for oid in revwalk {
+                self.check_deadline()?;
+                let oid = oid?;
+                match self.find_commit(oid) {
+                    Ok(commit) => commits.push(commit),
+                    Err(err) => errors.push((oid, err)),
+                }
+            }
 
-            // Skip the head commit since we have processed it
-            if commit_id == head_id {
-                continue;
+            match vcs::History::try_from_iter(commits) {
+                Some(history) => Ok((history, errors)),
+                None => Err(errors
+                    .into_iter()
+                    .map(|(_, err)| err)
+                    .next()
+                    .expect("the head failed to load")),
             }
+        })
+    }
 
-            let commit = Commit::try_from(self.repo_ref.find_commit(commit_id)?)?;
-            commits.push(commit);
+    /// Walk the commits described by the `builder`, starting from the
+    /// revisions it pushes, or `HEAD` if there are none, while hiding the
+    /// commits reachable from the revisions it hides.
+    pub(super) fn history_iter(
+        &self,
+        builder: &HistoryBuilder,
+    ) -> Result<HistoryIter<'_, 'a>, Error> {
+        let mut revwalk = self.revwalk()?;
+        revwalk.set_sorting(builder.order.into())?;
+        if builder.push.is_empty() {
+            revwalk.push_head()?;
+        }
+        for rev in &builder.push {
+            revwalk.push(self.rev_to_commit(rev)?.id())?;
         }
+        for rev in &builder.hide {
+            revwalk.hide(self.rev_to_commit(rev)?.id())?;
+        }
+        Ok(HistoryIter::new(self, revwalk, builder))
+    }
 
-        Ok(vcs::History(commits))
+    /// Build the [`History`] described by the `builder`, see
+    /// [`RepositoryRef::history_iter`].
+    pub(super) fn build_history(&self, builder: &HistoryBuilder) -> Result<History, Error> {
+        self.measure(Operation::History, || {
+            let commits = self.history_iter(builder)?.collect::<Result<Vec<_>, _>>()?;
+            vcs::History::try_from_iter(commits).ok_or(Error::EmptyHistory)
+        })
     }
 
     /// Extract the signature from a commit
@@ -240,6 +1600,16 @@ impl<'a> RepositoryRef<'a> {
         commit_oid: &Oid,
         field: Option<&str>,
     ) -> Result<Option<Signature>, Error> {
+        Ok(self
+            .extract_signed_data(commit_oid, field)?
+            .map(|(signature, _)| signature))
+    }
+
+    pub(super) fn extract_signed_data(
+        &self,
+        commit_oid: &Oid,
+        field: Option<&str>,
+    ) -> Result<Option<(Signature, Vec<u8>)>, Error> {
         // Match is necessary here because according to the documentation for
         // git_commit_extract_signature at
         // https://libgit2.org/libgit2/#HEAD/group/commit/git_commit_extract_signature
@@ -252,7 +1622,73 @@ impl<'a> RepositoryRef<'a> {
                     Err(error.into())
                 }
             },
-            Ok(sig) => Ok(Some(Signature::from(sig.0))),
+            Ok((signature, data)) => Ok(Some((Signature::from(signature), (*data).into()))),
+        }
+    }
+
+    /// Get the signatures of the commit `oid` from each of the header fields
+    /// that are looked for, along with the name of the field they were found
+    /// in, in the order of the fields. Only the `gpgsig` field is looked for,
+    /// unless other fields are set with
+    /// [`RepositoryRef::with_signature_fields`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn signatures(&self, oid: Oid) -> Result<Vec<(String, Signature)>, Error> {
+        let mut signatures = vec![];
+        for field in &self.signature_fields {
+            if let Some(signature) = self.extract_signature(&oid, Some(field))? {
+                signatures.push((field.clone(), signature));
+            }
+        }
+        Ok(signatures)
+    }
+
+    /// Set the header fields that [`RepositoryRef::signatures`] looks for
+    /// signatures in, e.g. `gpgsig` and `x-radicle-signature`.
+    pub fn with_signature_fields<I>(self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        RepositoryRef {
+            signature_fields: fields.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Get the raw header of the commit `oid`, i.e. the lines of the commit
+    /// object before its message, such as `tree`, `parent`, `author`, and any
+    /// extra headers.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Utf8Error`]
+    pub fn commit_raw_header(&self, oid: Oid) -> Result<String, Error> {
+        let commit = self.repo_ref.find_commit(oid)?;
+        Ok(str::from_utf8(commit.raw_header_bytes())?.to_owned())
+    }
+
+    /// Get the value of the header `field` of the commit `oid`, or `None` if
+    /// the commit does not have it. This can read any header, e.g. ones added
+    /// by radicle, and not only the signature read by
+    /// [`Browser::extract_signature`](crate::vcs::git::Browser::extract_signature).
+    ///
+    /// A value that spans several lines is returned with the leading space of
+    /// its continuation lines removed.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Utf8Error`]
+    pub fn commit_header_field(&self, oid: Oid, field: &str) -> Result<Option<String>, Error> {
+        let commit = self.repo_ref.find_commit(oid)?;
+        match commit.header_field_bytes(field) {
+            Ok(value) => Ok(Some(str::from_utf8(&value)?.to_owned())),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
         }
     }
 
@@ -277,57 +1713,319 @@ impl<'a> RepositoryRef<'a> {
         Ok(contained_branches)
     }
 
+    /// Get the branches whose tip is the commit `oid`.
+    pub(crate) fn branches_at(&self, oid: &Oid) -> Result<Vec<Branch>, Error> {
+        RefGlob::Branch
+            .references(self)?
+            .iter()
+            .try_fold(vec![], |mut acc, reference| {
+                let reference = reference?;
+                if reference.peel_to_commit()?.id() == *oid {
+                    acc.push(Branch::try_from(reference)?);
+                }
+                Ok(acc)
+            })
+    }
+
     fn reachable_from(&self, reference: &git2::Reference, oid: &Oid) -> Result<bool, Error> {
         let other = reference.peel_to_commit()?.id();
-        let is_descendant = self.repo_ref.graph_descendant_of(other, *oid)?;
-
-        Ok(other == *oid || is_descendant)
+        self.is_ancestor(*oid, other)
     }
 
     /// Get the history of the file system where the head of the [`NonEmpty`] is
     /// the latest commit.
     pub(super) fn file_history(
         &self,
-        path: &file_system::Path,
+        pathspec: &Pathspec,
         commit_history: CommitHistory,
         commit: Commit,
     ) -> Result<Vec<Commit>, Error> {
-        let mut revwalk = self.repo_ref.revwalk()?;
-        let mut commits = vec![];
+        let operation = match commit_history {
+            CommitHistory::Last => Operation::LastCommit,
+            CommitHistory::Full => Operation::FileHistory,
+        };
+        self.measure(operation, || {
+            let matcher = pathspec.matcher();
+            let mut revwalk = self.revwalk()?;
+            let mut commits = vec![];
 
-        // Set the revwalk to the head commit
-        revwalk.push(commit.id)?;
+            // Set the revwalk to the head commit
+            revwalk.push(commit.id)?;
 
-        for commit in revwalk {
-            let parent_id: Oid = commit?;
-            let parent = self.repo_ref.find_commit(parent_id)?;
-            let paths = self.diff_commit_and_parents(path, &parent)?;
-            if let Some(_path) = paths {
-                commits.push(Commit::try_from(parent)?);
-                match &commit_history {
-                    CommitHistory::Last => break,
-                    CommitHistory::Full => {},
+            for commit in revwalk {
+                self.check_deadline()?;
+                let parent_id: Oid = commit?;
+                let parent = self.repo_ref.find_commit(parent_id)?;
+                if self.touches(pathspec, &matcher, &parent)? {
+                    commits.push(self.to_commit(parent)?);
+                    match &commit_history {
+                        CommitHistory::Last => break,
+                        CommitHistory::Full => {},
+                    }
                 }
             }
-        }
 
-        Ok(commits)
+            Ok(commits)
+        })
     }
 
-    fn diff_commit_and_parents(
+    /// Walk the first-parent history from `head`, keeping the commits that
+    /// change the file at `path`, and following the file to its previous path
+    /// whenever a commit renamed it.
+    pub(super) fn follow_file_history(
         &self,
         path: &file_system::Path,
+        head: Oid,
+    ) -> Result<(Vec<Commit>, RenameChain), Error> {
+        self.measure(Operation::FileHistory, || {
+            let mut revwalk = self.revwalk()?;
+            revwalk.push(head)?;
+            revwalk.simplify_first_parent()?;
+            let grafts = self.grafts()?;
+
+            let mut current = path.to_string();
+            let mut commits = vec![];
+            let mut links = vec![];
+            // The oldest commit found so far for `current`, and the commit that
+            // renamed the file away from it.
+            let mut since = None;
+            let mut until = None;
+
+            for oid in revwalk {
+                self.check_deadline()?;
+                let oid = oid?;
+                let commit = self.repo_ref.find_commit(oid)?;
+                let blob = match blob_at(&commit.tree()?, &current)? {
+                    Some(blob) => blob,
+                    None => continue,
+                };
+                // The parents of a shallow clone's boundary were not fetched,
+                // so whether it added the file is unknown.
+                if grafts.contains(&oid) {
+                    commits.push(self.to_commit(commit)?);
+                    since = Some(oid);
+                    break;
+                }
+                let parent = commit.parents().next().map(|parent| parent.id());
+                let parent_blob = match parent {
+                    Some(parent) => blob_at(&self.repo_ref.find_commit(parent)?.tree()?, &current)?,
+                    None => None,
+                };
+                if parent_blob == Some(blob) {
+                    continue;
+                }
+
+                commits.push(self.to_commit(commit)?);
+                since = Some(oid);
+                if parent_blob.is_some() {
+                    continue;
+                }
+
+                // The file is new at this path, so it was either renamed here,
+                // or added.
+                links.push(RenameLink {
+                    path: file_system::Path::try_from(path::PathBuf::from(&current))?,
+                    since: oid,
+                    until,
+                });
+                since = None;
+                match parent {
+                    Some(parent) => match self.renamed_from(parent, oid, &current)? {
+                        Some(previous) => {
+                            current = previous;
+                            until = Some(oid);
+                        },
+                        None => break,
+                    },
+                    None => break,
+                }
+            }
+
+            // The history ran out before the file was added, e.g. in a shallow
+            // clone.
+            if let Some(since) = since {
+                links.push(RenameLink {
+                    path: file_system::Path::try_from(path::PathBuf::from(&current))?,
+                    since,
+                    until,
+                });
+            }
+
+            match NonEmpty::from_vec(links) {
+                Some(links) => Ok((commits, RenameChain::new(links))),
+                None => Err(Error::PathNotFound(path.clone())),
+            }
+        })
+    }
+
+    /// The path in `parent` of the file that the commit `oid` renamed to
+    /// `path`, if it was renamed.
+    fn renamed_from(&self, parent: Oid, oid: Oid, path: &str) -> Result<Option<String>, Error> {
+        let mut diff = self.diff_trees(Some(parent), oid, &mut git2::DiffOptions::new())?;
+        diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)))?;
+
+        for delta in diff.deltas() {
+            let is_rename = delta.status() == git2::Delta::Renamed
+                && delta.new_file().path() == Some(path::Path::new(path));
+            if is_rename {
+                let previous = delta
+                    .old_file()
+                    .path()
+                    .and_then(|previous| previous.to_str());
+                return Ok(previous.map(ToString::to_string));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the history of the file system where the head of the [`NonEmpty`] is
+    /// the latest commit, in the same way as `file_history`, except that the
+    /// diffs of the commits are computed in parallel.
+    ///
+    /// Since a [`git2::Repository`] cannot be shared across threads, each
+    /// worker thread opens its own handle to the repository.
+    #[cfg(feature = "rayon")]
+    pub(super) fn par_file_history(
+        &self,
+        pathspec: &Pathspec,
+        commit: Commit,
+    ) -> Result<Vec<Commit>, Error> {
+        use rayon::prelude::*;
+
+        let mut revwalk = self.revwalk()?;
+        revwalk.push(commit.id)?;
+        let oids = revwalk.collect::<Result<Vec<Oid>, _>>()?;
+
+        let matcher = pathspec.matcher();
+        let location = self.repo_ref.path();
+        let deadline = self.deadline;
+        let touched = oids
+            .into_par_iter()
+            .map_init(
+                || git2::Repository::open(location),
+                |repo, oid| {
+                    check_deadline(deadline)?;
+                    // The handle is opened once per worker, so its error is
+                    // copied for each commit that worker is given.
+                    let repo = repo
+                        .as_ref()
+                        .map_err(|err| git2::Error::new(err.code(), err.class(), err.message()))?;
+                    let repo = RepositoryRef::from(repo);
+                    let commit = repo.repo_ref.find_commit(oid)?;
+                    Ok(repo.touches(pathspec, &matcher, &commit)?.then_some(oid))
+                },
+            )
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        touched
+            .into_iter()
+            .flatten()
+            .map(|oid| self.find_commit(oid))
+            .collect()
+    }
+
+    /// Get the last commit that touched each of the `paths`, walking the
+    /// history starting at `commit` only once.
+    ///
+    /// The result is in the same order as `paths`, with `None` for a path
+    /// that no commit touched.
+    pub(super) fn last_commits(
+        &self,
+        paths: &[file_system::Path],
+        commit: Commit,
+    ) -> Result<Vec<Option<Commit>>, Error> {
+        self.measure(Operation::LastCommit, || {
+            // Paths are looked up in the same form as their pathspecs, i.e.
+            // without the root label, so that the root is the empty string.
+            let mut unresolved = HashMap::<String, Vec<usize>>::new();
+            for (index, path) in paths.iter().enumerate() {
+                let spec = path
+                    .0
+                    .tail
+                    .iter()
+                    .map(|label| label.as_str())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                unresolved.entry(spec).or_default().push(index);
+            }
+            let mut last = vec![None; paths.len()];
+
+            let mut revwalk = self.revwalk()?;
+            revwalk.push(commit.id)?;
+
+            for oid in revwalk {
+                if unresolved.is_empty() {
+                    break;
+                }
+                self.check_deadline()?;
+
+                let oid = oid?;
+                let commit = self.repo_ref.find_commit(oid)?;
+                let parent = commit.parents().next().map(|parent| parent.id());
+
+                let mut opts = git2::DiffOptions::new();
+                // We're skipping the binary pass because we won't be inspecting deltas.
+                opts.skip_binary_check(true);
+                let diff = self.diff_trees(parent, oid, &mut opts)?;
+
+                // A commit touches a path if it touches the path itself or
+                // anything beneath it, so each changed path resolves itself
+                // and all of its parent directories, up to the root.
+                let mut resolved = vec![];
+                for delta in diff.deltas() {
+                    let path = delta.new_file().path().or_else(|| delta.old_file().path());
+                    let mut path = match path.and_then(|path| path.to_str()) {
+                        Some(path) => path,
+                        None => continue,
+                    };
+                    loop {
+                        resolved.extend(unresolved.remove(path).into_iter().flatten());
+                        match path.rfind('/') {
+                            Some(end) => path = &path[..end],
+                            None if path.is_empty() => break,
+                            None => path = "",
+                        }
+                    }
+                }
+
+                if !resolved.is_empty() {
+                    let commit = self.to_commit(commit)?;
+                    for index in resolved {
+                        last[index] = Some(commit.clone());
+                    }
+                }
+            }
+
+            Ok(last)
+        })
+    }
+
+    /// Check whether `commit` changes any of the paths matching the
+    /// `pathspec`, compared to its first parent.
+    pub(super) fn touches(
+        &self,
+        pathspec: &Pathspec,
+        matcher: &Matcher,
         commit: &git2::Commit,
-    ) -> Result<Option<file_system::Path>, Error> {
-        let mut parents = commit.parents();
-        let parent = parents.next().map(|c| c.id());
+    ) -> Result<bool, Error> {
+        let parent = commit.parents().next().map(|c| c.id());
 
-        let diff = self.diff_commits(Some(path), parent, commit.id())?;
-        if let Some(_delta) = diff.deltas().next() {
-            Ok(Some(path.clone()))
-        } else {
-            Ok(None)
+        let mut opts = git2::DiffOptions::new();
+        for include in pathspec.literal_includes().into_iter().flatten() {
+            opts.pathspec(include);
         }
+        // We're skipping the binary pass because we won't be inspecting deltas.
+        opts.skip_binary_check(true);
+
+        let diff = self.diff_trees(parent, commit.id(), &mut opts)?;
+        let touched = diff.deltas().any(|delta| {
+            [delta.new_file().path(), delta.old_file().path()]
+                .iter()
+                .flatten()
+                .filter_map(|path| path.to_str())
+                .any(|path| matcher.is_match(path))
+        });
+        Ok(touched)
     }
 
     fn diff_commits(
@@ -336,11 +2034,6 @@ impl<'a> RepositoryRef<'a> {
         from: Option<Oid>,
         to: Oid,
     ) -> Result<git2::Diff, Error> {
-        let new_tree = self.repo_ref.find_commit(to)?.tree()?;
-        let old_tree = from.map_or(Ok(None), |oid| {
-            self.repo_ref.find_commit(oid)?.tree().map(Some)
-        })?;
-
         let mut opts = git2::DiffOptions::new();
         if let Some(path) = path {
             opts.pathspec(path);
@@ -348,9 +2041,27 @@ impl<'a> RepositoryRef<'a> {
             opts.skip_binary_check(true);
         }
 
+        self.diff_trees(from, to, &mut opts)
+    }
+
+    pub(super) fn diff_trees(
+        &self,
+        from: Option<Oid>,
+        to: Oid,
+        opts: &mut git2::DiffOptions,
+    ) -> Result<git2::Diff<'_>, Error> {
+        let new_tree = self.repo_ref.find_commit(to)?.tree()?;
+        let old_tree = from.map_or(Ok(None), |oid| {
+            self.repo_ref.find_commit(oid)?.tree().map(Some)
+        })?;
+
+        // Report a file becoming a symlink, or similar, as one change rather
+        // than a deletion and a creation.
+        opts.include_typechange(true);
+
         let diff =
             self.repo_ref
-                .diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(&mut opts))?;
+                .diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(opts))?;
 
         Ok(diff)
     }
@@ -361,28 +2072,15 @@ impl<'a> Vcs<Commit, Error> for RepositoryRef<'a> {
     type ArtefactId = Oid;
 
     fn get_history(&self, history_id: Self::HistoryId) -> Result<History, Error> {
-        match history_id {
-            Rev::Ref(reference) => self.reference(reference, |_| None),
-            Rev::Oid(oid) => {
-                let commit = self.get_commit(oid)?;
-                self.commit_to_history(commit)
-            },
-        }
+        self.rev(history_id, |_| None)
     }
 
     fn get_histories(&self) -> Result<Vec<History>, Error> {
-        self.repo_ref
-            .references()
-            .map_err(Error::from)
-            .and_then(|mut references| {
-                references.try_fold(vec![], |mut acc, reference| {
-                    reference.map_err(Error::from).and_then(|r| {
-                        let history = self.to_history(&r)?;
-                        acc.push(history);
-                        Ok(acc)
-                    })
-                })
-            })
+        Ok(self
+            .get_named_histories()?
+            .into_iter()
+            .map(|named| named.history)
+            .collect())
     }
 
     fn get_identifier(artifact: &Commit) -> Self::ArtefactId {
@@ -402,18 +2100,85 @@ impl Repository {
     /// # Errors
     ///
     /// * [`Error::Git`]
-    pub fn new(repo_uri: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+    pub fn new(repo_uri: impl AsRef<path::Path>) -> Result<Self, Error> {
         git2::Repository::open(repo_uri)
             .map(Repository)
             .map_err(Error::from)
     }
 
+    /// Fetch the `refspecs` from `remote`, which is either the name of a
+    /// configured remote or a URL. If `refspecs` is empty, the refspecs
+    /// configured for the remote are used.
+    ///
+    /// The `callbacks` are used for reporting the progress of the transfer and
+    /// for providing credentials, see [`git2::RemoteCallbacks`].
+    ///
+    /// Browsers of the repository can pick up the fetched references with
+    /// [`Browser::refresh`](crate::vcs::git::Browser::refresh).
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn fetch(
+        &self,
+        remote: &str,
+        refspecs: &[&str],
+        callbacks: git2::RemoteCallbacks<'_>,
+    ) -> Result<(), Error> {
+        let mut remote = self
+            .0
+            .find_remote(remote)
+            .or_else(|_| self.0.remote_anonymous(remote))?;
+        let mut options = git2::FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        remote.fetch(refspecs, Some(&mut options), None)?;
+        Ok(())
+    }
+
     /// Since our operations are read-only when it comes to surfing a repository
     /// we have a separate struct called [`RepositoryRef`]. This turns an owned
     /// [`Repository`], the one returend by [`Repository::new`], into a
     /// [`RepositoryRef`].
     pub fn as_ref(&'_ self) -> RepositoryRef<'_> {
-        RepositoryRef { repo_ref: &self.0 }
+        RepositoryRef::from(&self.0)
+    }
+
+    /// Check whether the repository is a shallow clone.
+    ///
+    /// See [`RepositoryRef::is_shallow`].
+    pub fn is_shallow(&self) -> bool {
+        self.as_ref().is_shallow()
+    }
+
+    /// Get the mailmap of the repository.
+    ///
+    /// See [`RepositoryRef::mailmap`].
+    pub fn mailmap(&self) -> Result<git2::Mailmap, Error> {
+        self.as_ref().mailmap()
+    }
+
+    /// Gather the storage statistics of the repository.
+    ///
+    /// See [`RepositoryRef::stats`].
+    pub fn stats(&self) -> Result<StorageStats, Error> {
+        self.as_ref().stats()
+    }
+
+    /// Write a commit-graph file for all the commits reachable from the
+    /// references of the repository, replacing any existing one.
+    ///
+    /// libgit2 reads a valid commit-graph during revwalks, which cuts the time
+    /// spent on them for large histories. It should be rewritten after
+    /// fetching new commits, since commits missing from the graph are read
+    /// from the object database as usual.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`] if a commit cannot be read, or the parent of a commit
+    ///   is missing, e.g. in a shallow clone.
+    /// * [`Error::Io`] if the commit-graph file cannot be written.
+    pub fn write_commit_graph(&self) -> Result<(), Error> {
+        commit_graph::write(&self.0)
     }
 }
 
@@ -444,3 +2209,60 @@ impl std::fmt::Debug for Repository {
         write!(f, ".git")
     }
 }
+
+/// Parse the name of `reference` as a [`Ref`], keeping its namespaces.
+fn parse_ref_name(reference: &git2::Reference) -> Result<Ref, Error> {
+    let name = str::from_utf8(reference.name_bytes())?;
+    name.parse()
+        .map_err(|_| Error::InvalidRefName(name.to_string()))
+}
+
+/// The [`Oid`] of the blob at `path` in the `tree`, if there is one.
+fn blob_at(tree: &git2::Tree, path: &str) -> Result<Option<Oid>, Error> {
+    match tree.get_path(path::Path::new(path)) {
+        Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => Ok(Some(entry.id())),
+        Ok(_) => Ok(None),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub(super) fn io_error(path: &path::Path, err: std::io::Error) -> Error {
+    Error::Io {
+        path: path.to_path_buf(),
+        kind: err.kind(),
+    }
+}
+
+fn metadata(path: &path::Path) -> Result<fs::Metadata, Error> {
+    fs::symlink_metadata(path).map_err(|err| io_error(path, err))
+}
+
+fn read_dir(path: &path::Path) -> Result<Vec<path::PathBuf>, Error> {
+    fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect()
+        })
+        .map_err(|err| io_error(path, err))
+}
+
+/// The total size of the files under `path`, without following symlinks.
+fn disk_size(path: &path::Path) -> Result<u64, Error> {
+    let meta = metadata(path)?;
+    if meta.is_dir() {
+        read_dir(path)?
+            .iter()
+            .try_fold(0, |size, entry| Ok(size + disk_size(entry)?))
+    } else {
+        Ok(meta.len())
+    }
+}
+
+fn check_deadline(deadline: Option<Instant>) -> Result<(), Error> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err(Error::Cancelled),
+        _ => Ok(()),
+    }
+}
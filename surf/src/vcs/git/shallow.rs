@@ -0,0 +1,289 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Shallow clones, whose history is cut at the commits listed in the
+//! `shallow` file of their git directory, the grafts.
+//!
+//! libgit2 does not read that file, so its revwalks fail with "object not
+//! found" on the first parent that was not fetched. The [`Revwalk`] here
+//! stops at the grafts instead.
+
+use crate::vcs::git::error::Error;
+use git2::Oid;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fs,
+    io,
+    vec,
+};
+
+/// The commits of a shallow clone whose parents were not fetched, see
+/// [`RepositoryRef::grafts`](crate::vcs::git::RepositoryRef::grafts).
+///
+/// These are empty for a repository that is not shallow.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grafts(HashSet<Oid>);
+
+impl Grafts {
+    /// Read the grafts from the `shallow` file of `repo`.
+    pub(super) fn read(repo: &git2::Repository) -> Result<Self, Error> {
+        if !repo.is_shallow() {
+            return Ok(Self::default());
+        }
+
+        let path = repo.path().join("shallow");
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(super::repo::io_error(&path, err)),
+        };
+        let grafts = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(Oid::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok(Grafts(grafts))
+    }
+
+    /// Check whether the parents of the commit `oid` were cut off.
+    pub fn contains(&self, oid: &Oid) -> bool {
+        self.0.contains(oid)
+    }
+
+    /// Check whether there are no grafts, i.e. the repository is not shallow.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the grafted commits, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Oid> {
+        self.0.iter()
+    }
+}
+
+/// A revwalk that stops at the [`Grafts`] of a shallow clone, and is
+/// libgit2's own for any other repository.
+pub(super) enum Revwalk<'repo> {
+    Git(git2::Revwalk<'repo>),
+    Shallow(Box<Shallow<'repo>>),
+}
+
+impl<'repo> Revwalk<'repo> {
+    pub(super) fn new(repo: &'repo git2::Repository, grafts: Grafts) -> Result<Self, Error> {
+        if grafts.is_empty() {
+            return Ok(Revwalk::Git(repo.revwalk()?));
+        }
+
+        Ok(Revwalk::Shallow(Box::new(Shallow {
+            repo,
+            grafts,
+            push: vec![],
+            hide: vec![],
+            sorting: git2::Sort::NONE,
+            first_parent: false,
+            walked: None,
+        })))
+    }
+
+    pub(super) fn set_sorting(&mut self, sorting: git2::Sort) -> Result<(), git2::Error> {
+        match self {
+            Revwalk::Git(revwalk) => revwalk.set_sorting(sorting),
+            Revwalk::Shallow(walk) => {
+                walk.sorting = sorting;
+                Ok(())
+            },
+        }
+    }
+
+    pub(super) fn simplify_first_parent(&mut self) -> Result<(), git2::Error> {
+        match self {
+            Revwalk::Git(revwalk) => revwalk.simplify_first_parent(),
+            Revwalk::Shallow(walk) => {
+                walk.first_parent = true;
+                Ok(())
+            },
+        }
+    }
+
+    pub(super) fn push(&mut self, oid: Oid) -> Result<(), git2::Error> {
+        match self {
+            Revwalk::Git(revwalk) => revwalk.push(oid),
+            Revwalk::Shallow(walk) => {
+                walk.repo.find_commit(oid)?;
+                walk.push.push(oid);
+                Ok(())
+            },
+        }
+    }
+
+    pub(super) fn push_head(&mut self) -> Result<(), git2::Error> {
+        match self {
+            Revwalk::Git(revwalk) => revwalk.push_head(),
+            Revwalk::Shallow(walk) => {
+                let head = walk.repo.head()?.peel_to_commit()?.id();
+                walk.push.push(head);
+                Ok(())
+            },
+        }
+    }
+
+    pub(super) fn hide(&mut self, oid: Oid) -> Result<(), git2::Error> {
+        match self {
+            Revwalk::Git(revwalk) => revwalk.hide(oid),
+            Revwalk::Shallow(walk) => {
+                walk.repo.find_commit(oid)?;
+                walk.hide.push(oid);
+                Ok(())
+            },
+        }
+    }
+}
+
+impl<'repo> Iterator for Revwalk<'repo> {
+    type Item = Result<Oid, git2::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Revwalk::Git(revwalk) => revwalk.next(),
+            Revwalk::Shallow(walk) => walk.next(),
+        }
+    }
+}
+
+/// The walk of a shallow clone, which is done in full the first time it is
+/// advanced, as libgit2 does for a topological walk.
+pub(super) struct Shallow<'repo> {
+    repo: &'repo git2::Repository,
+    grafts: Grafts,
+    push: Vec<Oid>,
+    hide: Vec<Oid>,
+    sorting: git2::Sort,
+    first_parent: bool,
+    walked: Option<vec::IntoIter<Oid>>,
+}
+
+/// A commit found by a [`Shallow`] walk.
+struct Node {
+    time: i64,
+    parents: Vec<Oid>,
+}
+
+impl<'repo> Shallow<'repo> {
+    fn next(&mut self) -> Option<Result<Oid, git2::Error>> {
+        if self.walked.is_none() {
+            match self.walk() {
+                Ok(oids) => self.walked = Some(oids.into_iter()),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.walked.as_mut()?.next().map(Ok)
+    }
+
+    /// The parents of `commit` that are walked, which are none for a graft.
+    fn parents(&self, commit: &git2::Commit) -> Vec<Oid> {
+        if self.grafts.contains(&commit.id()) {
+            vec![]
+        } else if self.first_parent {
+            commit.parent_ids().take(1).collect()
+        } else {
+            commit.parent_ids().collect()
+        }
+    }
+
+    /// Find the commits reachable from `from`, without going past the grafts
+    /// or into the commits of `hidden`.
+    fn reachable(
+        &self,
+        from: &[Oid],
+        hidden: &HashMap<Oid, Node>,
+    ) -> Result<HashMap<Oid, Node>, git2::Error> {
+        let mut nodes = HashMap::new();
+        let mut stack = from.to_vec();
+        while let Some(oid) = stack.pop() {
+            if nodes.contains_key(&oid) || hidden.contains_key(&oid) {
+                continue;
+            }
+            let commit = self.repo.find_commit(oid)?;
+            let parents = self.parents(&commit);
+            stack.extend(parents.iter().copied());
+            nodes.insert(
+                oid,
+                Node {
+                    time: commit.time().seconds(),
+                    parents,
+                },
+            );
+        }
+        Ok(nodes)
+    }
+
+    /// Walk the commits, newest first, where a topological walk does not
+    /// reach a parent until all of its children were walked.
+    fn walk(&self) -> Result<Vec<Oid>, git2::Error> {
+        let hidden = self.reachable(&self.hide, &HashMap::new())?;
+        let nodes = self.reachable(&self.push, &hidden)?;
+
+        let topological = self.sorting.contains(git2::Sort::TOPOLOGICAL);
+        let mut children = HashMap::<Oid, usize>::new();
+        if topological {
+            for node in nodes.values() {
+                for parent in node.parents.iter().filter(|p| nodes.contains_key(p)) {
+                    *children.entry(*parent).or_default() += 1;
+                }
+            }
+        }
+
+        // Commits with the same time are walked in the order they were found.
+        let mut found = 0;
+        let mut queue = BinaryHeap::new();
+        let mut queued = HashSet::new();
+        for oid in &self.push {
+            if nodes.contains_key(oid) && !children.contains_key(oid) && queued.insert(*oid) {
+                queue.push((nodes[oid].time, Reverse(found), *oid));
+                found += 1;
+            }
+        }
+
+        let mut walked = Vec::with_capacity(nodes.len());
+        while let Some((_, _, oid)) = queue.pop() {
+            walked.push(oid);
+            for parent in &nodes[&oid].parents {
+                let node = match nodes.get(parent) {
+                    Some(node) => node,
+                    None => continue,
+                };
+                if let Some(remaining) = children.get_mut(parent) {
+                    *remaining -= 1;
+                    if *remaining > 0 {
+                        continue;
+                    }
+                }
+                if queued.insert(*parent) {
+                    queue.push((node.time, Reverse(found), *parent));
+                    found += 1;
+                }
+            }
+        }
+
+        if self.sorting.contains(git2::Sort::REVERSE) {
+            walked.reverse();
+        }
+        Ok(walked)
+    }
+}
@@ -0,0 +1,116 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The contents of a file can then be served without copying them out of
+//! libgit2.
+//!
+//! See [`crate::vcs::git::Browser::tree_ref`].
+
+use crate::{
+    file_system::{self, directory::File},
+    vcs::git::error::Error,
+};
+use git2::Oid;
+use std::path;
+
+/// The tree of a commit, whose files are looked up on demand rather than
+/// being copied into a [`file_system::Directory`] up front.
+#[derive(Clone)]
+pub struct TreeRef<'repo> {
+    repo: &'repo git2::Repository,
+    commit: Oid,
+    tree: git2::Tree<'repo>,
+}
+
+impl<'repo> std::fmt::Debug for TreeRef<'repo> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TreeRef")
+            .field("commit", &self.commit)
+            .field("tree", &self.tree.id())
+            .finish()
+    }
+}
+
+impl<'repo> TreeRef<'repo> {
+    pub(crate) fn new(repo: &'repo git2::Repository, commit: Oid) -> Result<Self, Error> {
+        let tree = repo.find_commit(commit)?.tree()?;
+        Ok(TreeRef { repo, commit, tree })
+    }
+
+    /// The [`Oid`] of the commit this is a snapshot of.
+    pub fn commit(&self) -> Oid {
+        self.commit
+    }
+
+    /// The [`Oid`] of the tree of the commit.
+    pub fn id(&self) -> Oid {
+        self.tree.id()
+    }
+
+    /// Get the file at `path`, which is `None` if nothing exists at `path` or
+    /// it is not a file.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn file(&self, path: &file_system::Path) -> Result<Option<FileRef<'repo>>, Error> {
+        let entry = match self.tree.get_path(path::Path::new(&path.to_string())) {
+            Ok(entry) => entry,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        match entry.kind() {
+            Some(git2::ObjectType::Blob) => Ok(Some(FileRef {
+                blob: self.repo.find_blob(entry.id())?,
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// A file of a [`TreeRef`], whose contents are borrowed from the repository.
+#[derive(Debug, Clone)]
+pub struct FileRef<'repo> {
+    blob: git2::Blob<'repo>,
+}
+
+impl<'repo> FileRef<'repo> {
+    /// The [`Oid`] of the blob of the file.
+    pub fn id(&self) -> Oid {
+        self.blob.id()
+    }
+
+    /// The contents of the file, without copying them.
+    pub fn contents(&self) -> &[u8] {
+        self.blob.content()
+    }
+
+    /// The size of the file in bytes.
+    pub fn size(&self) -> usize {
+        self.blob.size()
+    }
+
+    /// Whether libgit2 considers the contents of the file to be binary.
+    pub fn is_binary(&self) -> bool {
+        self.blob.is_binary()
+    }
+
+    /// Copy the contents into an owned [`File`].
+    pub fn to_file(&self) -> File {
+        File::new(self.contents())
+    }
+}
@@ -0,0 +1,170 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Where a [`Repository`] comes from, so that a `Browser` can be pointed at a
+//! URL as well as at a local path.
+//!
+//! ```
+//! use radicle_surf::vcs::git::{fixture::Fixture, Branch, Browser, RepositorySource};
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let upstream = Fixture::new()?;
+//! upstream.commit("master", "Initial commit").file("README.md", "Hi\n").write()?;
+//!
+//! let cache = Fixture::new()?;
+//! let source = RepositorySource::Remote {
+//!     url: upstream.path().to_str().unwrap().to_string(),
+//!     cache_dir: cache.path().join("clones"),
+//! };
+//!
+//! // The first access clones the repository, and later ones reuse the clone.
+//! let repo = source.open()?;
+//! let browser = Browser::new(&repo, Branch::local("master"))?;
+//! assert_eq!(browser.get().len(), 1);
+//! assert!(source.path().exists());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::vcs::git::{
+    error::Error,
+    repo::{io_error, Repository},
+};
+use sha1::{Digest, Sha1};
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The refspec of a mirror, so that the branches and tags of the remote can
+/// be browsed as local ones.
+const MIRROR: &str = "+refs/*:refs/*";
+
+/// Where to find a [`Repository`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepositorySource {
+    /// A repository on the local file system.
+    Local(PathBuf),
+    /// A repository at `url`, which is cloned into `cache_dir` the first time
+    /// it is opened, and reused afterwards.
+    ///
+    /// The clone is a bare mirror of the whole repository, with `url` as its
+    /// `origin` remote, so it can be kept up to date with
+    /// [`Repository::fetch`]. It is never shallow, since the version of
+    /// libgit2 we build against cannot fetch to a depth. A shallow clone made
+    /// with `git clone --depth` can still be browsed as a
+    /// [`RepositorySource::Local`], see
+    /// [`RepositoryRef::is_shallow`](crate::vcs::git::RepositoryRef::is_shallow).
+    Remote {
+        /// The URL of the repository.
+        url: String,
+        /// The directory that holds the clones of remote repositories.
+        cache_dir: PathBuf,
+    },
+}
+
+impl RepositorySource {
+    /// The path the [`Repository`] is opened from. For a
+    /// [`RepositorySource::Remote`], this is the directory of its clone in the
+    /// `cache_dir`, named after the hash of its URL.
+    pub fn path(&self) -> PathBuf {
+        match self {
+            Self::Local(path) => path.clone(),
+            Self::Remote { url, cache_dir } => {
+                let mut name = String::new();
+                for byte in Sha1::digest(url.as_bytes()) {
+                    let _ = write!(name, "{:02x}", byte);
+                }
+                cache_dir.join(name)
+            },
+        }
+    }
+
+    /// Open the [`Repository`], cloning it first if it is a
+    /// [`RepositorySource::Remote`] that is not in the cache yet.
+    ///
+    /// See [`RepositorySource::open_with`] for repositories that need
+    /// credentials.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Io`] if the cache directory cannot be written to.
+    pub fn open(&self) -> Result<Repository, Error> {
+        self.open_with(git2::RemoteCallbacks::new())
+    }
+
+    /// Open the [`Repository`] in the same way as [`RepositorySource::open`],
+    /// using the `callbacks` for reporting the progress of a clone and
+    /// providing credentials, see [`git2::RemoteCallbacks`].
+    ///
+    /// # Errors
+    ///
+    /// See [`RepositorySource::open`].
+    pub fn open_with(&self, callbacks: git2::RemoteCallbacks<'_>) -> Result<Repository, Error> {
+        let path = self.path();
+        match self {
+            Self::Local(_) => Repository::new(path),
+            Self::Remote { url, cache_dir } => {
+                if path.exists() {
+                    return Repository::new(path);
+                }
+
+                fs::create_dir_all(cache_dir).map_err(|err| io_error(cache_dir, err))?;
+                // Clone into a directory of its own next to the final
+                // location and move the clone into place once it is complete,
+                // so that an interrupted clone is never mistaken for a cached
+                // one, and concurrent clones do not get in each other's way.
+                // The directory is removed when it is dropped, unless it was
+                // moved.
+                let partial = tempfile::Builder::new()
+                    .prefix(".partial-")
+                    .tempdir_in(cache_dir)
+                    .map_err(|err| io_error(cache_dir, err))?;
+                mirror_clone(url, partial.path(), callbacks)?;
+
+                if let Err(err) = fs::rename(partial.path(), &path) {
+                    // Someone else may have cloned it in the meantime.
+                    if !path.exists() {
+                        return Err(io_error(&path, err));
+                    }
+                }
+                Repository::new(path)
+            },
+        }
+    }
+}
+
+/// Make a bare mirror of the repository at `url` at `path`.
+fn mirror_clone(url: &str, path: &Path, callbacks: git2::RemoteCallbacks<'_>) -> Result<(), Error> {
+    let repo = git2::Repository::init_bare(path)?;
+    let mut remote = repo.remote_with_fetch("origin", url, MIRROR)?;
+    let mut options = git2::FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    remote.fetch::<&str>(&[], Some(&mut options), None)?;
+
+    // Point HEAD at the default branch of the remote, as `git clone` does.
+    if let Ok(head) = remote.default_branch() {
+        if let Some(head) = head.as_str() {
+            repo.set_head(head)?;
+        }
+    }
+
+    Ok(())
+}
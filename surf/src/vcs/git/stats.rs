@@ -34,3 +34,41 @@ pub struct Stats {
     /// Number of contributors
     pub contributors: usize,
 }
+
+/// Storage statistics for a repository, as found by iterating over its object
+/// database and the files in its git directory.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Number of commit objects
+    pub commits: usize,
+    /// Number of tree objects
+    pub trees: usize,
+    /// Number of blob objects
+    pub blobs: usize,
+    /// Number of annotated tag objects
+    pub tags: usize,
+    /// Number of objects that are stored loose, i.e. outside of a packfile
+    pub loose: usize,
+    /// Number of packfiles
+    pub packs: usize,
+    /// Total size of the packfiles in bytes
+    pub pack_size: u64,
+    /// Total size of the git directory on disk in bytes
+    pub size: u64,
+    /// Whether the repository has a commit-graph file. This only reports
+    /// that the file exists: libgit2 falls back to the object database,
+    /// without an error, for a graph that is invalid or is missing commits
+    pub commit_graph_file: bool,
+}
+
+impl StorageStats {
+    /// Total number of objects in the object database.
+    pub fn objects(&self) -> usize {
+        self.commits + self.trees + self.blobs + self.tags
+    }
+}
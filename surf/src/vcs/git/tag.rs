@@ -24,6 +24,14 @@ use std::{convert::TryFrom, fmt, str};
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TagName(String);
 
+impl str::FromStr for TagName {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::try_new(name)
+    }
+}
+
 impl fmt::Display for TagName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
@@ -55,6 +63,31 @@ impl TagName {
         TagName(name.into())
     }
 
+    /// Create a new `TagName`, checking that it is a valid tag name
+    /// according to git's reference name rules. The name is normalized, e.g.
+    /// repeated slashes are collapsed.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidRefName`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{error::Error, TagName};
+    ///
+    /// assert_eq!(TagName::try_new("v0.1.0"), Ok(TagName::new("v0.1.0")));
+    /// assert_eq!(
+    ///     TagName::try_new("v0.1.0.lock"),
+    ///     Err(Error::InvalidRefName("v0.1.0.lock".to_string()))
+    /// );
+    /// ```
+    pub fn try_new(name: &str) -> Result<Self, Error> {
+        git::ext::normalize_refname("refs/tags/", name)
+            .map(Self)
+            .ok_or_else(|| Error::InvalidRefName(name.to_string()))
+    }
+
     /// Access the string value of the `TagName`.
     pub fn name(&self) -> &str {
         &self.0
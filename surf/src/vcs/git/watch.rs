@@ -0,0 +1,237 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Changes on disk, e.g. when commits are pushed or branches are created and
+//! deleted, are noticed so that long-lived [`Browser`]s can refresh their
+//! views.
+//!
+//! Changes are found by polling: a [`Watcher`](crate::vcs::git::watch::Watcher)
+//! remembers what each reference pointed to, and compares it with what they
+//! point to when it is polled.
+
+use crate::vcs::git::{error::Error, Browser, Oid, RefGlob, RepositoryRef};
+use std::collections::BTreeMap;
+
+/// A change to a reference, see [`Watcher::poll`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefEvent {
+    /// The reference was created.
+    Created {
+        /// The fully qualified name of the reference.
+        name: String,
+        /// What the reference points to.
+        oid: Oid,
+    },
+    /// The reference was moved, e.g. by a push or a fetch.
+    Updated {
+        /// The fully qualified name of the reference.
+        name: String,
+        /// What the reference pointed to.
+        old: Oid,
+        /// What the reference points to now.
+        new: Oid,
+    },
+    /// The reference was deleted.
+    Deleted {
+        /// The fully qualified name of the reference.
+        name: String,
+        /// What the reference pointed to.
+        oid: Oid,
+    },
+}
+
+impl RefEvent {
+    /// The name of the reference that changed.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Created { name, .. }
+            | Self::Updated { name, .. }
+            | Self::Deleted { name, .. } => name,
+        }
+    }
+}
+
+/// Watches the references matching a [`RefGlob`] for changes.
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    glob: RefGlob,
+    refs: BTreeMap<String, Oid>,
+}
+
+impl Watcher {
+    /// Start watching the references of `repo` that match the `glob`, as
+    /// they are now.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn new(repo: &RepositoryRef, glob: RefGlob) -> Result<Self, Error> {
+        let refs = snapshot(repo, &glob)?;
+        Ok(Watcher { glob, refs })
+    }
+
+    /// The [`RefGlob`] being watched.
+    pub fn glob(&self) -> &RefGlob {
+        &self.glob
+    }
+
+    /// Report how the references changed since the `Watcher` was created, or
+    /// last polled, ordered by the names of the references.
+    ///
+    /// Symbolic references are reported by what they resolve to, and the ones
+    /// that cannot be resolved are taken not to exist.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn poll(&mut self, repo: &RepositoryRef) -> Result<Vec<RefEvent>, Error> {
+        let (events, refs) = self.changes(repo)?;
+        self.refs = refs;
+        Ok(events)
+    }
+
+    /// Find how the references changed, along with what they point to now,
+    /// without moving the `Watcher` on.
+    fn changes(
+        &self,
+        repo: &RepositoryRef,
+    ) -> Result<(Vec<RefEvent>, BTreeMap<String, Oid>), Error> {
+        let refs = snapshot(repo, &self.glob)?;
+        let mut events = vec![];
+        for (name, old) in &self.refs {
+            match refs.get(name) {
+                None => events.push(RefEvent::Deleted {
+                    name: name.clone(),
+                    oid: *old,
+                }),
+                Some(new) if new != old => events.push(RefEvent::Updated {
+                    name: name.clone(),
+                    old: *old,
+                    new: *new,
+                }),
+                Some(_) => {},
+            }
+        }
+        for (name, oid) in &refs {
+            if !self.refs.contains_key(name) {
+                events.push(RefEvent::Created {
+                    name: name.clone(),
+                    oid: *oid,
+                });
+            }
+        }
+        events.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok((events, refs))
+    }
+}
+
+impl<'a> Browser<'a> {
+    /// Start watching the references of the `Browser` that match the `glob`.
+    /// See [`Browser::poll_changes`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn watch(&self, glob: RefGlob) -> Result<Watcher, Error> {
+        Watcher::new(&self.repository, glob)
+    }
+
+    /// Poll the `watcher` for changes, and if there are any, [refresh] the
+    /// view of the `Browser`, so that it follows the revision it was last
+    /// pointed at.
+    ///
+    /// The `watcher` only moves on once the refresh succeeded, so if it fails,
+    /// e.g. because the branch being viewed was deleted, the same changes are
+    /// reported by the next poll.
+    ///
+    /// [refresh]: Browser::refresh
+    ///
+    /// # Errors
+    ///
+    /// Any of the errors of [`Browser::refresh`]:
+    ///
+    /// * [`Error::Git`]
+    /// * [`Error::Io`]
+    /// * [`Error::UnbornBranch`]
+    /// * [`Error::RevParseFailure`]
+    /// * [`Error::NamespaceRevParseFailure`]
+    /// * [`Error::EmptyNamespace`]
+    /// * [`Error::Utf8Error`]
+    /// * [`Error::MissingSummary`]
+    /// * [`Error::Cancelled`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{
+    ///     fixture::Fixture,
+    ///     watch::RefEvent,
+    ///     Branch,
+    ///     Browser,
+    ///     RefGlob,
+    ///     RefScope,
+    /// };
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let fixture = Fixture::new()?;
+    /// let first = fixture.commit("master", "Initial commit").file("README.md", "Hi\n").write()?;
+    ///
+    /// let mut browser = Browser::new(fixture.repository(), Branch::local("master"))?;
+    /// let mut watcher = browser.watch(RefGlob::branch(RefScope::Local))?;
+    /// assert_eq!(browser.poll_changes(&mut watcher)?, vec![]);
+    ///
+    /// let second = fixture.commit("master", "Say more").file("README.md", "Hello\n").write()?;
+    /// fixture.branch("dev", first)?;
+    /// assert_eq!(
+    ///     browser.poll_changes(&mut watcher)?,
+    ///     vec![
+    ///         RefEvent::Created { name: "refs/heads/dev".to_string(), oid: first },
+    ///         RefEvent::Updated { name: "refs/heads/master".to_string(), old: first, new: second },
+    ///     ]
+    /// );
+    /// assert_eq!(browser.get().first().id, second);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn poll_changes(&mut self, watcher: &mut Watcher) -> Result<Vec<RefEvent>, Error> {
+        let (events, refs) = watcher.changes(&self.repository)?;
+        if !events.is_empty() {
+            self.refresh()?;
+        }
+        watcher.refs = refs;
+        Ok(events)
+    }
+}
+
+/// What each of the references matching the `glob` resolves to.
+fn snapshot(repo: &RepositoryRef, glob: &RefGlob) -> Result<BTreeMap<String, Oid>, Error> {
+    let mut refs = BTreeMap::new();
+    for reference in glob.references(repo)?.iter() {
+        let reference = reference?;
+        let name = String::from_utf8_lossy(reference.name_bytes()).into_owned();
+        if let Some(oid) = reference
+            .resolve()
+            .ok()
+            .and_then(|resolved| resolved.target())
+        {
+            refs.insert(name, oid);
+        }
+    }
+    Ok(refs)
+}
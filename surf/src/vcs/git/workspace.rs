@@ -0,0 +1,190 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A set of repositories, e.g. all of those of a hosting service, browsed by
+//! their identifiers.
+//!
+//! Repositories are looked up by their [`RepositorySource`], rather than
+//! through `vcs::GetVcs`, whose lookups have no state to find the source of
+//! an identifier in, and which can only open local repositories.
+
+use crate::vcs::git::{error::Error, Browser, Repository, RepositorySource, Rev};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::Hash,
+};
+
+/// The number of repositories a [`Workspace`] keeps open by default.
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// A set of repositories, each known by an `Id`, which are opened when they
+/// are first browsed.
+///
+/// Opening a repository means reading its configuration, references and pack
+/// indexes, so the most recently used ones are kept open, up to the capacity
+/// of the `Workspace`, and the least recently used one is closed to make
+/// room for another.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::vcs::git::{workspace::Workspace, Branch, RepositorySource};
+/// use std::path::PathBuf;
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let mut workspace = Workspace::new(1);
+/// workspace.insert(
+///     "platinum",
+///     RepositorySource::Local(PathBuf::from("./data/git-platinum")),
+/// );
+///
+/// let browser = workspace.browser(&"platinum", Branch::local("master"))?;
+/// assert_eq!(browser.get().len(), 15);
+/// drop(browser);
+/// assert!(workspace.is_open(&"platinum"));
+///
+/// assert!(workspace.browser(&"gold", Branch::local("master")).is_err());
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct Workspace<Id> {
+    capacity: usize,
+    tick: u64,
+    sources: HashMap<Id, RepositorySource>,
+    open: HashMap<Id, (u64, Repository)>,
+    recency: BTreeMap<u64, Id>,
+}
+
+impl<Id> Default for Workspace<Id> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl<Id> Workspace<Id> {
+    /// Create an empty `Workspace` that keeps at most `capacity` repositories
+    /// open. At least one repository is always kept open, i.e. the one that
+    /// was browsed last.
+    pub fn new(capacity: usize) -> Self {
+        Workspace {
+            capacity: capacity.max(1),
+            tick: 0,
+            sources: HashMap::new(),
+            open: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// The maximum number of repositories kept open.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of repositories in the `Workspace`.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Check whether the `Workspace` has no repositories.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+impl<Id> Workspace<Id>
+where
+    Id: Clone + Eq + Hash + fmt::Display,
+{
+    /// Add the repository found at `source` as `id`, returning the source it
+    /// replaces, if any.
+    pub fn insert(&mut self, id: Id, source: RepositorySource) -> Option<RepositorySource> {
+        self.close(&id);
+        self.sources.insert(id, source)
+    }
+
+    /// Remove the repository `id`, returning its source, if any.
+    pub fn remove(&mut self, id: &Id) -> Option<RepositorySource> {
+        self.close(id);
+        self.sources.remove(id)
+    }
+
+    /// Check whether the repository `id` is currently open.
+    pub fn is_open(&self, id: &Id) -> bool {
+        self.open.contains_key(id)
+    }
+
+    /// Get the repository `id`, opening it if it is not open already.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::UnknownRepository`] if there is no repository `id`.
+    /// * [`Error::Git`] if the repository cannot be opened.
+    pub fn repository(&mut self, id: &Id) -> Result<&Repository, Error> {
+        let tick = self.next_tick();
+        match self.open.get_mut(id) {
+            Some((used, _)) => {
+                self.recency.remove(used);
+                *used = tick;
+            },
+            None => {
+                let source = self
+                    .sources
+                    .get(id)
+                    .ok_or_else(|| Error::UnknownRepository(id.to_string()))?;
+                let repository = source.open()?;
+
+                while self.open.len() >= self.capacity {
+                    match self.recency.keys().next().copied() {
+                        Some(oldest) => {
+                            if let Some(id) = self.recency.remove(&oldest) {
+                                self.open.remove(&id);
+                            }
+                        },
+                        None => break,
+                    }
+                }
+                self.open.insert(id.clone(), (tick, repository));
+            },
+        }
+        self.recency.insert(tick, id.clone());
+
+        Ok(&self.open[id].1)
+    }
+
+    /// Create a [`Browser`] for the repository `id`, viewing `rev`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Workspace::repository`] and [`Browser::new`].
+    pub fn browser(&mut self, id: &Id, rev: impl Into<Rev>) -> Result<Browser<'_>, Error> {
+        Browser::new(self.repository(id)?, rev)
+    }
+
+    fn close(&mut self, id: &Id) {
+        if let Some((used, _)) = self.open.remove(id) {
+            self.recency.remove(&used);
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}